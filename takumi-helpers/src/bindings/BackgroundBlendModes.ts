@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MixBlendMode } from "./MixBlendMode";
+
+/**
+ * A list of `background-blend-mode` values, one per background image layer.
+ *
+ * Each layer blends with the layers and the background color beneath it, never with the backdrop.
+ */
+export type BackgroundBlendModes = Array<MixBlendMode> | string;
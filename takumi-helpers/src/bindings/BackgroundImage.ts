@@ -2,8 +2,13 @@
 import type { LinearGradient } from "./LinearGradient";
 import type { NoiseV1 } from "./NoiseV1";
 import type { RadialGradient } from "./RadialGradient";
+import type { UrlImage } from "./UrlImage";
 
 /**
  * Background image variants supported by Takumi.
  */
-export type BackgroundImage = LinearGradient | RadialGradient | NoiseV1;
+export type BackgroundImage =
+  | LinearGradient
+  | RadialGradient
+  | UrlImage
+  | NoiseV1;
@@ -0,0 +1,27 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Film grain painted over the background of a node, above `background-image`.
+ *
+ * Every pixel gets an independent random gray (or color, unless `monochrome`) value, derived
+ * from the seed and the pixel position inside the node so the same style always renders the
+ * same grain.
+ */
+export type BackgroundNoise = {
+  /**
+   * How far the grain strays from mid gray, 0.0 is flat gray and 1.0 spans black to white. Defaults to 1.0
+   */
+  intensity?: number;
+  /**
+   * Opacity of the grain layer, 0.0 is fully transparent and 1.0 is fully opaque. Defaults to 0.1
+   */
+  opacity?: number;
+  /**
+   * Whether the grain is gray instead of varying per color channel. Defaults to true
+   */
+  monochrome?: boolean;
+  /**
+   * Random seed value that determines the grain pattern generated
+   */
+  seed?: number;
+};
@@ -0,0 +1,47 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BackgroundPosition } from "./BackgroundPosition";
+import type { FillRule } from "./FillRule";
+import type { LengthUnit } from "./LengthUnit";
+import type { ShapeRadius } from "./ShapeRadius";
+import type { Sides } from "./Sides";
+
+/**
+ * A basic shape clipping the node, positioned against its border box.
+ */
+export type BasicShape =
+  | {
+      inset: {
+        /**
+         * The distance from the top, right, bottom and left edges
+         */
+        offsets: Sides<LengthUnit>;
+        /**
+         * The corner radii, in the same order as `borderRadius`
+         */
+        radius: Sides<LengthUnit>;
+      };
+    }
+  | {
+      circle: {
+        /**
+         * The radius of the circle
+         */
+        radius: ShapeRadius;
+        /**
+         * The center of the circle
+         */
+        position: BackgroundPosition;
+      };
+    }
+  | {
+      polygon: {
+        /**
+         * The rule deciding which parts of the polygon are inside
+         */
+        fillRule: FillRule;
+        /**
+         * The x and y coordinates of the points
+         */
+        points: Array<[LengthUnit, LengthUnit]>;
+      };
+    };
@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LengthUnit } from "./LengthUnit";
+import type { Sides } from "./Sides";
+
+/**
+ * Represents the `border-radius` shorthand, the horizontal and vertical radius of each corner.
+ *
+ * The corners are in the order top-left, top-right, bottom-right and bottom-left.
+ * Circular corners have the same radius on both axes, `10px / 20px` gives elliptical ones.
+ */
+export type BorderRadius =
+  | {
+      /**
+       * The horizontal radius of each corner
+       */
+      horizontal: Sides<LengthUnit>;
+      /**
+       * The vertical radius of each corner
+       */
+      vertical: Sides<LengthUnit>;
+    }
+  | string
+  | Sides<LengthUnit>;
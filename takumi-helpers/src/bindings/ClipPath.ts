@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BasicShape } from "./BasicShape";
+
+/**
+ * Clips the painting of the node and its descendants to a basic shape, like CSS `clip-path`.
+ */
+export type ClipPath = BasicShape | string;
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A color that can also be `currentColor`, which resolves to the `color` of the node.
+ */
+export type ColorInput =
+  | [number, number, number]
+  | [number, number, number, number]
+  | number
+  | string;
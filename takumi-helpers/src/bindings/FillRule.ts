@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Decides which parts of a self-intersecting `polygon()` are inside.
+ */
+export type FillRule = "nonzero" | "evenodd";
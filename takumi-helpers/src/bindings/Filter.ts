@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LengthUnit } from "./LengthUnit";
+import type { TextShadow } from "./TextShadow";
+
+/**
+ * A single filter function, like `blur(4px)` or `brightness(120%)`.
+ *
+ * Amounts are fractions, `1.0` (or `100%`) leaves the image unchanged.
+ */
+export type Filter =
+  | { blur: LengthUnit }
+  | { brightness: number }
+  | { contrast: number }
+  | { saturate: number }
+  | { "drop-shadow": TextShadow };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Filter } from "./Filter";
+
+/**
+ * A list of filter functions applied in order, like CSS `filter` and `backdrop-filter`.
+ */
+export type Filters = Array<Filter> | string;
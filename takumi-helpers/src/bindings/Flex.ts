@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LengthUnit } from "./LengthUnit";
+
+/**
+ * Parsed `flex` value.
+ *
+ * The longhands `flexGrow`, `flexShrink` and `flexBasis` override the matching part.
+ */
+export type Flex =
+  | { grow: number; shrink: number; basis: LengthUnit }
+  | number
+  | string;
@@ -6,5 +6,9 @@ import type { LengthUnit } from "./LengthUnit";
  *
  * Can be either a single value applied to both axes, or separate values
  * for horizontal and vertical spacing.
+ *
+ * Like the CSS shorthand, the first value is the row gap and the second the column gap.
+ * Percentages of the row gap refer to the height of the container's content box, the ones of
+ * the column gap to its width.
  */
 export type Gap = LengthUnit | [LengthUnit, LengthUnit] | string;
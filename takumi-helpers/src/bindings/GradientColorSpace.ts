@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Defines the color space used to interpolate between gradient stops.
+ *
+ * Corresponds to the `in <color-space>` interpolation hint of CSS gradients.
+ */
+export type GradientColorSpace = "srgb" | "linear-rgb";
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Controls whether words are hyphenated when they are broken across lines.
+ */
+export type Hyphens = "none" | "manual" | "auto";
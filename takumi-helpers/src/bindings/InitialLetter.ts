@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Represents the `initial-letter` property, which draws the first letter of the text as a
+ * drop cap.
+ *
+ * The letter is as tall as `size` lines and sits on the baseline of line `sink`, the lines it
+ * sinks into are shortened to make room for it. A `sink` smaller than `size` raises the letter
+ * above the first line.
+ */
+export type InitialLetter =
+  | number
+  | {
+      /**
+       * The height of the letter in lines
+       */
+      size: number;
+      /**
+       * The number of lines the letter sinks into, defaults to the size
+       */
+      sink: number | null;
+    }
+  | string;
@@ -2,6 +2,10 @@
 import type { LengthUnit } from "./LengthUnit";
 
 /**
- * Represents a line height value, number value is parsed as em.
+ * Represents a line height value.
+ *
+ * A number multiplies the font size of each node it applies to, so children with larger fonts
+ * inherit a proportionally larger line height. A length is computed to pixels on the node that
+ * declares it, and children inherit the pixel value like in CSS.
  */
 export type LineHeight = number | string | LengthUnit;
@@ -1,5 +1,6 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { Angle } from "./Angle";
+import type { GradientColorSpace } from "./GradientColorSpace";
 import type { GradientStop } from "./GradientStop";
 
 /**
@@ -15,5 +16,13 @@ export type LinearGradient =
        * The steps of the gradient.
        */
       stops: Array<GradientStop>;
+      /**
+       * The color space used to interpolate between stops, defaults to sRGB.
+       */
+      colorSpace?: GradientColorSpace;
+      /**
+       * Whether the stops repeat past the last stop, defaults to false.
+       */
+      repeating?: boolean;
     }
   | string;
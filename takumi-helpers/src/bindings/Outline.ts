@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ColorInput } from "./ColorInput";
+import type { LengthUnit } from "./LengthUnit";
+import type { OutlineStyle } from "./OutlineStyle";
+
+/**
+ * Parsed `outline` value.
+ *
+ * The longhands `outlineWidth`, `outlineStyle` and `outlineColor` override the matching part.
+ */
+export type Outline =
+  | { width: LengthUnit; style: OutlineStyle; color: ColorInput | null }
+  | string;
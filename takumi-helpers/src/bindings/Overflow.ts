@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Controls whether the descendants of a node are clipped to its padding box.
+ */
+export type Overflow = "visible" | "hidden";
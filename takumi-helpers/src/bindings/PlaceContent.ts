@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JustifyContent } from "./JustifyContent";
+
+/**
+ * Represents the `place-content` shorthand, the `alignContent` value followed by the `justifyContent` value.
+ *
+ * A single value applies to both axes. The longhands win over the shorthand when both are set.
+ */
+export type PlaceContent =
+  | JustifyContent
+  | [JustifyContent, JustifyContent]
+  | string;
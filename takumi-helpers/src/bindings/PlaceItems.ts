@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AlignItems } from "./AlignItems";
+
+/**
+ * Represents the `place-items` shorthand, the `alignItems` value followed by the `justifyItems` value.
+ *
+ * A single value applies to both axes. The longhands win over the shorthand when both are set.
+ */
+export type PlaceItems = AlignItems | [AlignItems, AlignItems] | string;
@@ -1,4 +1,5 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { GradientColorSpace } from "./GradientColorSpace";
 import type { GradientStop } from "./GradientStop";
 import type { RadialShape } from "./RadialShape";
 import type { RadialSize } from "./RadialSize";
@@ -6,21 +7,31 @@ import type { RadialSize } from "./RadialSize";
 /**
  * Represents a radial gradient.
  */
-export type RadialGradient = {
-  /**
-   * The radial gradient shape
-   */
-  shape: RadialShape;
-  /**
-   * The sizing mode for the gradient
-   */
-  size: RadialSize;
-  /**
-   * Center position in normalized coordinates [0.0, 1.0]
-   */
-  center: [number, number];
-  /**
-   * Gradient stops
-   */
-  stops: Array<GradientStop>;
-};
+export type RadialGradient =
+  | {
+      /**
+       * The shape of the gradient.
+       */
+      shape: RadialShape;
+      /**
+       * The size of the gradient.
+       */
+      size: RadialSize;
+      /**
+       * The center of the gradient in normalized [0,1] coords.
+       */
+      center: [number, number];
+      /**
+       * The steps of the gradient.
+       */
+      stops: Array<GradientStop>;
+      /**
+       * The color space used to interpolate between stops, defaults to sRGB.
+       */
+      colorSpace?: GradientColorSpace;
+      /**
+       * Whether the stops repeat past the last stop, defaults to false.
+       */
+      repeating?: boolean;
+    }
+  | string;
@@ -1,10 +1,23 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LengthUnit } from "./LengthUnit";
 
 /**
- * Supported size keywords for radial gradients
+ * Supported sizes for radial gradients
  */
 export type RadialSize =
   | "closest-side"
   | "farthest-side"
   | "closest-corner"
-  | "farthest-corner";
+  | "farthest-corner"
+  | {
+      explicit: {
+        /**
+         * The horizontal radius
+         */
+        x: LengthUnit;
+        /**
+         * The vertical radius
+         */
+        y: LengthUnit;
+      };
+    };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LengthUnit } from "./LengthUnit";
+
+/**
+ * The radius of a `circle()` shape.
+ */
+export type ShapeRadius =
+  | { length: LengthUnit }
+  | "closest-side"
+  | "farthest-side";
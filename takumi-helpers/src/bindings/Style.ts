@@ -1,15 +1,23 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { AlignItems } from "./AlignItems";
+import type { BackgroundBlendModes } from "./BackgroundBlendModes";
 import type { BackgroundImages } from "./BackgroundImages";
+import type { BackgroundNoise } from "./BackgroundNoise";
 import type { BackgroundPosition } from "./BackgroundPosition";
 import type { BackgroundPositions } from "./BackgroundPositions";
+import type { BackgroundRepeat } from "./BackgroundRepeat";
 import type { BackgroundRepeats } from "./BackgroundRepeats";
 import type { BackgroundSizes } from "./BackgroundSizes";
+import type { BorderRadius } from "./BorderRadius";
 import type { BoxShadows } from "./BoxShadows";
 import type { BoxSizing } from "./BoxSizing";
+import type { ClipPath } from "./ClipPath";
 import type { Color } from "./Color";
+import type { ColorInput } from "./ColorInput";
 import type { CssValue } from "./CssValue";
 import type { Display } from "./Display";
+import type { Filters } from "./Filters";
+import type { Flex } from "./Flex";
 import type { FlexDirection } from "./FlexDirection";
 import type { FlexWrap } from "./FlexWrap";
 import type { FontFamily } from "./FontFamily";
@@ -23,21 +31,33 @@ import type { GridLine } from "./GridLine";
 import type { GridTemplateAreas } from "./GridTemplateAreas";
 import type { GridTemplateComponents } from "./GridTemplateComponents";
 import type { GridTrackSizes } from "./GridTrackSizes";
+import type { Hyphens } from "./Hyphens";
 import type { ImageScalingAlgorithm } from "./ImageScalingAlgorithm";
+import type { InitialLetter } from "./InitialLetter";
 import type { JustifyContent } from "./JustifyContent";
 import type { LengthUnit } from "./LengthUnit";
 import type { LineHeight } from "./LineHeight";
+import type { MixBlendMode } from "./MixBlendMode";
 import type { ObjectFit } from "./ObjectFit";
+import type { Outline } from "./Outline";
+import type { OutlineStyle } from "./OutlineStyle";
+import type { Overflow } from "./Overflow";
 import type { OverflowWrap } from "./OverflowWrap";
+import type { PlaceContent } from "./PlaceContent";
+import type { PlaceItems } from "./PlaceItems";
 import type { Position } from "./Position";
 import type { Sides } from "./Sides";
+import type { TabSize } from "./TabSize";
 import type { TextAlign } from "./TextAlign";
 import type { TextOverflow } from "./TextOverflow";
 import type { TextShadows } from "./TextShadows";
 import type { TextStroke } from "./TextStroke";
 import type { TextTransform } from "./TextTransform";
 import type { Transforms } from "./Transforms";
+import type { VerticalAlign } from "./VerticalAlign";
+import type { Visibility } from "./Visibility";
 import type { WordBreak } from "./WordBreak";
+import type { WritingMode } from "./WritingMode";
 
 /**
  * Defines the style of an element.
@@ -74,9 +94,15 @@ export type Style = {
   justifyItems: CssValue<AlignItems | null>;
   alignItems: CssValue<AlignItems | null>;
   alignSelf: CssValue<AlignItems | null>;
+  placeContent: CssValue<PlaceContent | null>;
+  placeItems: CssValue<PlaceItems | null>;
+  verticalAlign: CssValue<VerticalAlign | null>;
   flexWrap: CssValue<FlexWrap>;
-  flexBasis: CssValue<LengthUnit>;
+  flex: CssValue<Flex | null>;
+  flexBasis: CssValue<LengthUnit | null>;
   position: CssValue<Position>;
+  zIndex: CssValue<number | null>;
+  overflow: CssValue<Overflow>;
   transform: CssValue<Transforms | null>;
   transformOrigin: CssValue<BackgroundPosition | null>;
   maskImage: CssValue<BackgroundImages | null>;
@@ -84,9 +110,9 @@ export type Style = {
   maskPosition: CssValue<BackgroundPositions | null>;
   maskRepeat: CssValue<BackgroundRepeats | null>;
   gap: CssValue<Gap>;
-  flexGrow: CssValue<number>;
-  flexShrink: CssValue<number>;
-  borderRadius: CssValue<Sides<LengthUnit>>;
+  flexGrow: CssValue<number | null>;
+  flexShrink: CssValue<number | null>;
+  borderRadius: CssValue<BorderRadius>;
   borderTopLeftRadius: CssValue<LengthUnit | null>;
   borderTopRightRadius: CssValue<LengthUnit | null>;
   borderBottomRightRadius: CssValue<LengthUnit | null>;
@@ -96,26 +122,41 @@ export type Style = {
   borderRightWidth: CssValue<LengthUnit | null>;
   borderBottomWidth: CssValue<LengthUnit | null>;
   borderLeftWidth: CssValue<LengthUnit | null>;
+  borderImage: CssValue<BackgroundImages | null>;
+  outline: CssValue<Outline | null>;
+  outlineWidth: CssValue<LengthUnit | null>;
+  outlineStyle: CssValue<OutlineStyle | null>;
+  outlineColor: CssValue<ColorInput | null>;
+  outlineOffset: CssValue<LengthUnit>;
   objectFit: CssValue<ObjectFit>;
   objectPosition: CssValue<BackgroundPosition>;
+  objectRepeat: CssValue<BackgroundRepeat>;
   backgroundImage: CssValue<BackgroundImages | null>;
   backgroundPosition: CssValue<BackgroundPositions | null>;
   backgroundSize: CssValue<BackgroundSizes | null>;
   backgroundRepeat: CssValue<BackgroundRepeats | null>;
+  backgroundBlendMode: CssValue<BackgroundBlendModes | null>;
+  backgroundNoise: CssValue<BackgroundNoise | null>;
   backgroundColor: CssValue<Color>;
   boxShadow: CssValue<BoxShadows | null>;
+  backdropFilter: CssValue<Filters | null>;
+  filter: CssValue<Filters | null>;
+  clipPath: CssValue<ClipPath | null>;
+  mixBlendMode: CssValue<MixBlendMode>;
   gridAutoColumns: CssValue<GridTrackSizes | null>;
   gridAutoRows: CssValue<GridTrackSizes | null>;
   gridAutoFlow: CssValue<GridAutoFlow | null>;
   gridColumn: CssValue<GridLine | null>;
   gridRow: CssValue<GridLine | null>;
+  gridArea: CssValue<string | null>;
   gridTemplateColumns: CssValue<GridTemplateComponents | null>;
   gridTemplateRows: CssValue<GridTemplateComponents | null>;
   gridTemplateAreas: CssValue<GridTemplateAreas | null>;
   textOverflow: CssValue<TextOverflow>;
   textTransform: CssValue<TextTransform>;
+  visibility: CssValue<Visibility>;
   fontStyle: CssValue<FontStyle>;
-  borderColor: CssValue<Color>;
+  borderColor: CssValue<ColorInput>;
   color: CssValue<Color>;
   fontSize: CssValue<LengthUnit>;
   fontFamily: CssValue<FontFamily | null>;
@@ -131,7 +172,13 @@ export type Style = {
   textShadow: CssValue<TextShadows | null>;
   letterSpacing: CssValue<LengthUnit | null>;
   wordSpacing: CssValue<LengthUnit | null>;
+  textIndent: CssValue<LengthUnit | null>;
+  writingMode: CssValue<WritingMode>;
   imageRendering: CssValue<ImageScalingAlgorithm>;
   overflowWrap: CssValue<OverflowWrap>;
   wordBreak: CssValue<WordBreak>;
+  hyphens: CssValue<Hyphens>;
+  lang: CssValue<string | null>;
+  tabSize: CssValue<TabSize>;
+  initialLetter: CssValue<InitialLetter | null>;
 };
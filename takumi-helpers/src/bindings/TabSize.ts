@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LengthUnit } from "./LengthUnit";
+
+/**
+ * Represents the `tab-size` property, the distance between the tab stops tabs advance to.
+ *
+ * A number is a count of space characters, including the letter and word spacing applied to
+ * them. A length is the distance itself.
+ */
+export type TabSize = number | string | LengthUnit;
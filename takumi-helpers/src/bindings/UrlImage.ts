@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An image referenced by url, a data uri or a key of the image store.
+ */
+export type UrlImage = {
+  /**
+   * The url of the image
+   */
+  url: string;
+};
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LengthUnit } from "./LengthUnit";
+
+/**
+ * Aligns a node with the text line of its parent, like CSS `vertical-align`.
+ *
+ * The line is the first line of text the node's font properties (inherited from the parent
+ * unless overridden) would produce, which is where sibling text sits in a row container.
+ * The node is placed at the start of the cross axis before being shifted.
+ */
+export type VerticalAlign = string | LengthUnit;
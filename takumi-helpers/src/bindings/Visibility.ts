@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Controls whether a node is painted, hidden nodes still take up their layout space.
+ */
+export type Visibility = "visible" | "hidden";
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Controls whether lines of text are laid out horizontally or vertically.
+ */
+export type WritingMode = "horizontal-tb" | "vertical-rl" | "vertical-lr";
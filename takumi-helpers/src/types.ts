@@ -34,5 +34,6 @@ export type ImageNode = {
   src: string;
   width?: number;
   height?: number;
+  frameIndex?: number;
   style?: PartialStyle;
 };
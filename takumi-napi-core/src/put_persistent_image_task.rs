@@ -13,7 +13,9 @@ impl Task for PutPersistentImageTask {
   type JsValue = ();
 
   fn compute(&mut self) -> napi::Result<Self::Output> {
-    let image = load_image_source_from_bytes(&self.buffer).unwrap();
+    let image = load_image_source_from_bytes(&self.buffer)
+      .map_err(|e| napi::Error::from_reason(format!("Failed to load image: {e:?}")))?;
+
     self
       .context
       .persistent_image_store
@@ -5,7 +5,7 @@ use std::sync::Arc;
 use takumi::{
   GlobalContext,
   layout::{Viewport, node::NodeKind},
-  rendering::{render, write_image},
+  rendering::{EncodeOptions, render, write_image},
 };
 
 use crate::renderer::OutputFormat;
@@ -15,7 +15,7 @@ pub struct RenderTask {
   pub context: Arc<GlobalContext>,
   pub viewport: Viewport,
   pub format: OutputFormat,
-  pub quality: Option<u8>,
+  pub encode_options: EncodeOptions,
 }
 
 impl Task for RenderTask {
@@ -26,7 +26,7 @@ impl Task for RenderTask {
     let node = self.node.take().unwrap();
 
     let image = render(self.viewport, &self.context, node)
-      .map_err(|e| napi::Error::from_reason(format!("Failed to render: {e:?}")))?;
+      .map_err(|e| napi::Error::from_reason(format!("Failed to render: {e}")))?;
 
     if self.format == OutputFormat::raw {
       return Ok(image.into_raw());
@@ -35,8 +35,13 @@ impl Task for RenderTask {
     let mut buffer = Vec::new();
     let mut cursor = Cursor::new(&mut buffer);
 
-    write_image(&image, &mut cursor, self.format.into(), self.quality)
-      .map_err(|e| napi::Error::from_reason(format!("Failed to write to buffer: {e:?}")))?;
+    write_image(
+      &image,
+      &mut cursor,
+      self.format.into(),
+      self.encode_options.clone(),
+    )
+    .map_err(|e| napi::Error::from_reason(format!("Failed to write to buffer: {e}")))?;
 
     Ok(buffer)
   }
@@ -4,8 +4,11 @@ use takumi::{
   GlobalContext,
   layout::{Viewport, node::NodeKind},
   parley::{FontWeight, GenericFamily, fontique::FontInfoOverride},
-  rendering::{ImageOutputFormat, render, write_image},
-  resources::image::load_image_source_from_bytes,
+  rendering::{DebugOptions, EncodeOptions, ImageOutputFormat, render, write_image},
+  resources::{
+    font::FontContext,
+    image::{PersistentImageStore, load_image_source_from_bytes},
+  },
 };
 
 use crate::{
@@ -24,6 +27,33 @@ pub struct RenderOptions {
   pub height: u32,
   pub format: Option<OutputFormat>,
   pub quality: Option<u8>,
+  pub progressive: Option<bool>,
+  /// Defaults to `true`, lossy WebP can't be encoded.
+  pub lossless: Option<bool>,
+  pub dpi: Option<u32>,
+  pub embed_srgb_profile: Option<bool>,
+  pub scale_factor: Option<f64>,
+}
+
+impl RenderOptions {
+  fn viewport(&self) -> Viewport {
+    Viewport::new(self.width, self.height).with_scale_factor(
+      self
+        .scale_factor
+        .map_or(1.0, |scale_factor| scale_factor as f32),
+    )
+  }
+
+  fn encode_options(&self) -> EncodeOptions {
+    EncodeOptions {
+      quality: self.quality,
+      progressive: self.progressive.unwrap_or_default(),
+      lossless: self.lossless.unwrap_or(true),
+      dpi: self.dpi,
+      embed_srgb_profile: self.embed_srgb_profile.unwrap_or_default(),
+      icc_profile: None,
+    }
+  }
 }
 
 #[napi(string_enum)]
@@ -34,10 +64,16 @@ pub enum OutputFormat {
   avif,
   png,
   jpeg,
+  gif,
+  tiff,
+  bmp,
   WebP,
   Avif,
   Jpeg,
   Png,
+  Gif,
+  Tiff,
+  Bmp,
   raw,
 }
 
@@ -52,6 +88,9 @@ impl From<OutputFormat> for ImageOutputFormat {
       OutputFormat::jpeg => ImageOutputFormat::Jpeg,
       OutputFormat::webp => ImageOutputFormat::WebP,
       OutputFormat::avif => ImageOutputFormat::Avif,
+      OutputFormat::Gif | OutputFormat::gif => ImageOutputFormat::Gif,
+      OutputFormat::Tiff | OutputFormat::tiff => ImageOutputFormat::Tiff,
+      OutputFormat::Bmp | OutputFormat::bmp => ImageOutputFormat::Bmp,
       // SAFETY: It's handled in the render task
       OutputFormat::raw => unreachable!(),
     }
@@ -73,6 +112,7 @@ pub struct ConstructRendererOptions<'ctx> {
   #[napi(ts_type = "Font[] | undefined")]
   pub fonts: Option<Vec<Object<'ctx>>>,
   pub load_default_fonts: Option<bool>,
+  pub image_store_capacity: Option<u32>,
 }
 
 const EMBEDDED_FONTS: &[(&[u8], &str, GenericFamily)] = &[
@@ -91,7 +131,7 @@ const EMBEDDED_FONTS: &[(&[u8], &str, GenericFamily)] = &[
 #[napi]
 impl Renderer {
   #[napi(constructor)]
-  pub fn new(env: Env, options: Option<ConstructRendererOptions>) -> Self {
+  pub fn new(env: Env, options: Option<ConstructRendererOptions>) -> Result<Self> {
     let options = options.unwrap_or_default();
 
     let load_default_fonts = options
@@ -99,30 +139,31 @@ impl Renderer {
       .unwrap_or_else(|| options.fonts.is_none());
 
     let renderer = Self(Arc::new(GlobalContext {
-      draw_debug_border: options.debug.unwrap_or_default(),
+      debug: options
+        .debug
+        .unwrap_or_default()
+        .then(DebugOptions::default),
+      persistent_image_store: Box::new(
+        options
+          .image_store_capacity
+          .map(|capacity| PersistentImageStore::with_capacity(capacity as usize))
+          .unwrap_or_default(),
+      ),
+      font_context: if load_default_fonts {
+        FontContext::with_embedded_defaults(EMBEDDED_FONTS).map_err(|e| {
+          napi::Error::from_reason(format!("Failed to load the default fonts: {e:?}"))
+        })?
+      } else {
+        FontContext::default()
+      },
       ..Default::default()
     }));
 
-    if load_default_fonts {
-      for (font, name, generic) in EMBEDDED_FONTS {
-        renderer
-          .0
-          .font_context
-          .load_and_store(
-            font,
-            Some(FontInfoOverride {
-              family_name: Some(name),
-              ..Default::default()
-            }),
-            Some(*generic),
-          )
-          .unwrap();
-      }
-    }
-
     if let Some(images) = options.persistent_images {
       for image in images {
-        let image_source = load_image_source_from_bytes(&image.data).unwrap();
+        let image_source = load_image_source_from_bytes(&image.data).map_err(|e| {
+          napi::Error::from_reason(format!("Failed to load image {}: {e:?}", image.src))
+        })?;
 
         renderer
           .0
@@ -133,20 +174,20 @@ impl Renderer {
 
     if let Some(fonts) = options.fonts {
       for font in fonts {
-        if font.is_arraybuffer().unwrap() || font.is_buffer().unwrap() {
+        if font.is_arraybuffer()? || font.is_buffer()? {
           // SAFETY: We know the font is a buffer
-          let buffer = unsafe { BufferSlice::from_napi_value(env.raw(), font.raw()).unwrap() };
+          let buffer = unsafe { BufferSlice::from_napi_value(env.raw(), font.raw())? };
 
           renderer
             .0
             .font_context
             .load_and_store(&buffer, None, None)
-            .unwrap();
+            .map_err(|e| napi::Error::from_reason(format!("Failed to load font: {e:?}")))?;
 
           continue;
         }
 
-        let font: FontInput = unsafe { FontInput::from_napi_value(env.raw(), font.raw()).unwrap() };
+        let font: FontInput = unsafe { FontInput::from_napi_value(env.raw(), font.raw())? };
 
         let font_override = FontInfoOverride {
           family_name: font.name.as_deref(),
@@ -160,11 +201,11 @@ impl Renderer {
           .0
           .font_context
           .load_and_store(&font.data, Some(font_override), None)
-          .unwrap();
+          .map_err(|e| napi::Error::from_reason(format!("Failed to load font: {e:?}")))?;
       }
     }
 
-    renderer
+    Ok(renderer)
   }
 
   #[napi]
@@ -201,7 +242,7 @@ impl Renderer {
     env: Env,
     data: Object,
     signal: Option<AbortSignal>,
-  ) -> AsyncTask<LoadFontTask> {
+  ) -> Result<AsyncTask<LoadFontTask>> {
     self.load_fonts_async(env, vec![data], signal)
   }
 
@@ -214,31 +255,31 @@ impl Renderer {
     env: Env,
     fonts: Vec<Object>,
     signal: Option<AbortSignal>,
-  ) -> AsyncTask<LoadFontTask> {
+  ) -> Result<AsyncTask<LoadFontTask>> {
     let fonts = fonts
       .into_iter()
       .map(|font| {
-        if font.is_arraybuffer().unwrap() || font.is_buffer().unwrap() {
-          FontInputOwned {
+        if font.is_arraybuffer()? || font.is_buffer()? {
+          Ok(FontInputOwned {
             name: None,
             // SAFETY: We know the font is a buffer
-            data: unsafe { Buffer::from_napi_value(env.raw(), font.raw()).unwrap() },
+            data: unsafe { Buffer::from_napi_value(env.raw(), font.raw())? },
             weight: None,
             style: None,
-          }
+          })
         } else {
-          unsafe { FontInputOwned::from_napi_value(env.raw(), font.raw()).unwrap() }
+          unsafe { FontInputOwned::from_napi_value(env.raw(), font.raw()) }
         }
       })
-      .collect();
+      .collect::<Result<_>>()?;
 
-    AsyncTask::with_optional_signal(
+    Ok(AsyncTask::with_optional_signal(
       LoadFontTask {
         context: Arc::clone(&self.0),
         buffers: fonts,
       },
       signal,
-    )
+    ))
   }
 
   #[napi]
@@ -263,9 +304,9 @@ impl Renderer {
       RenderTask {
         node: Some(node),
         context: Arc::clone(&self.0),
-        viewport: Viewport::new(options.width, options.height),
+        viewport: options.viewport(),
         format: options.format.unwrap_or(OutputFormat::png),
-        quality: options.quality,
+        encode_options: options.encode_options(),
       },
       signal,
     ))
@@ -275,8 +316,8 @@ impl Renderer {
   pub fn render(&self, env: Env, source: Object, options: RenderOptions) -> Result<Buffer> {
     let node: NodeKind = env.from_js_value(source)?;
 
-    let viewport = Viewport::new(options.width, options.height);
-    let image = render(viewport, &self.0, node).unwrap();
+    let image = render(options.viewport(), &self.0, node)
+      .map_err(|e| napi::Error::from_reason(format!("Failed to render: {e}")))?;
 
     let format = options.format.unwrap_or(OutputFormat::png);
 
@@ -287,7 +328,8 @@ impl Renderer {
     let mut buffer = Vec::new();
     let mut cursor = Cursor::new(&mut buffer);
 
-    write_image(&image, &mut cursor, format.into(), options.quality).unwrap();
+    write_image(&image, &mut cursor, format.into(), options.encode_options())
+      .map_err(|e| napi::Error::from_reason(format!("Failed to write to buffer: {e}")))?;
 
     Ok(buffer.into())
   }
@@ -97,6 +97,31 @@ test("no crash without fonts and images", () => {
   new Renderer();
 });
 
+describe("corrupt input", () => {
+  const corrupt = Buffer.from("not a font or an image");
+
+  test("constructor throws on a corrupt font", () => {
+    expect(() => new Renderer({ fonts: [corrupt] })).toThrow(
+      "Failed to load font",
+    );
+  });
+
+  test("constructor throws on a corrupt image", () => {
+    expect(
+      () =>
+        new Renderer({
+          persistentImages: [{ src: "corrupt.png", data: corrupt }],
+        }),
+    ).toThrow("Failed to load image");
+  });
+
+  test("putPersistentImageAsync rejects a corrupt image", async () => {
+    await expect(
+      renderer.putPersistentImageAsync("corrupt.png", corrupt),
+    ).rejects.toThrow("Failed to load image");
+  });
+});
+
 describe("setup", () => {
   test("loadFontsAsync", async () => {
     const count = await renderer.loadFontsAsync(fontBuffers);
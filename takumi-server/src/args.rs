@@ -1,4 +1,6 @@
-use clap::{Parser, command};
+use std::path::PathBuf;
+
+use clap::Parser;
 
 /// Command line arguments for the image generator server.
 ///
@@ -25,7 +27,49 @@ pub struct Args {
   #[arg(short, long)]
   pub font_glob: Option<String>,
 
+  /// Directory that `file://` image sources are loaded from.
+  ///
+  /// Files outside of this directory are never read. When not specified,
+  /// `file://` sources are not loaded.
+  #[arg(long)]
+  pub asset_root: Option<PathBuf>,
+
+  /// Maximum size in bytes of request bodies, like the JSON node tree of `POST /image`.
+  ///
+  /// Defaults to 2 MiB if not specified.
+  #[arg(long)]
+  pub body_limit: Option<usize>,
+
+  /// The `max-age` in seconds of the `Cache-Control` header sent with images.
+  ///
+  /// When not specified, no `Cache-Control` header is sent.
+  #[arg(long)]
+  pub cache_max_age: Option<u64>,
+
+  /// Number of encoded images kept in memory, repeated requests are served from it
+  /// without rendering again.
+  ///
+  /// When not specified or zero, rendered images are not cached.
+  #[arg(long)]
+  pub cache_size: Option<usize>,
+
+  /// Maximum number of images rendered at the same time, other requests wait for a slot.
+  ///
+  /// When not specified or zero, renders are not limited.
+  #[arg(long)]
+  pub max_concurrency: Option<usize>,
+
+  /// Maximum number of pixels of a rendered image, larger requests are rejected.
+  ///
+  /// Defaults to 8192 × 8192 if not specified.
+  #[arg(long)]
+  pub max_pixels: Option<u64>,
+
   /// The HMAC key for integrity checking. Can be any valid UTF-8 string.
+  ///
+  /// `POST` requests must be signed with a timestamp within 5 minutes of the server time,
+  /// and their signature also covers the query string.
+  /// `POST /fonts` is only available when a key is set.
   #[cfg_attr(feature = "hmac_verify", arg(long))]
   #[cfg(feature = "hmac_verify")]
   pub hmac_key: Option<String>,
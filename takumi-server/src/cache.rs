@@ -0,0 +1,63 @@
+use axum::http::{HeaderMap, header::IF_NONE_MATCH};
+use serde::Serialize;
+use takumi::{
+  layout::{Viewport, node::NodeKind},
+  rendering::{EncodeOptions, ImageOutputFormat},
+};
+
+/// Everything that affects the rendered bytes, serialized in a fixed field order.
+#[derive(Serialize)]
+struct NormalizedRequest<'a> {
+  node: &'a NodeKind,
+  width: u32,
+  height: Option<u32>,
+  format: ImageOutputFormat,
+  options: &'a EncodeOptions,
+  font_generation: u64,
+}
+
+/// Computes a strong ETag from the parsed request, so formatting differences in the payload
+/// JSON don't change it and the same request always maps to the same tag across restarts.
+///
+/// `font_generation` counts the fonts registered at runtime, so a request rendered again
+/// after a font upload gets a new tag even though its payload is unchanged.
+pub fn request_etag(
+  node: &NodeKind,
+  viewport: Viewport,
+  format: ImageOutputFormat,
+  options: &EncodeOptions,
+  font_generation: u64,
+) -> String {
+  let normalized = serde_json::to_vec(&NormalizedRequest {
+    node,
+    width: viewport.width,
+    height: viewport.height,
+    format,
+    options,
+    font_generation,
+  })
+  .unwrap_or_default();
+
+  format!("\"{:016x}\"", fnv1a(&normalized))
+}
+
+/// Checks if the `If-None-Match` header lists the ETag, or is `*`.
+pub fn is_not_modified(headers: &HeaderMap, etag: &str) -> bool {
+  headers
+    .get_all(IF_NONE_MATCH)
+    .iter()
+    .filter_map(|value| value.to_str().ok())
+    .flat_map(|value| value.split(','))
+    .map(|tag| tag.trim().trim_start_matches("W/"))
+    .any(|tag| tag == "*" || tag == etag)
+}
+
+/// 64-bit FNV-1a, unlike the std hashers its output is fixed across builds.
+fn fnv1a(bytes: &[u8]) -> u64 {
+  const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+  const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+  bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+    (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+  })
+}
@@ -0,0 +1,92 @@
+use std::sync::atomic::Ordering;
+
+use axum::{
+  Json,
+  body::Bytes,
+  extract::{Query, State},
+  http::StatusCode,
+};
+use serde::Deserialize;
+use takumi::{
+  layout::style::FontStyle,
+  parley::{FontWeight, fontique::FontInfoOverride},
+  resources::font::{FamilyInfo, FontError},
+};
+use tokio::task::spawn_blocking;
+use tracing::info;
+
+use crate::{AxumResult, AxumState};
+
+/// Overrides of the metadata stored in an uploaded font.
+#[derive(Deserialize, Default)]
+pub struct LoadFontQuery {
+  /// The family name to register the font under instead of its own.
+  pub family: Option<String>,
+  /// The weight to register the font with, e.g. `700`.
+  pub weight: Option<f32>,
+  /// The style to register the font with, e.g. `italic`.
+  pub style: Option<FontStyle>,
+}
+
+/// Registers the font in the request body, making it available to subsequent renders.
+///
+/// Responds with every font family loaded after the registration. The route is only mounted
+/// when `--hmac-key` is set, the `family`, `weight` and `style` overrides are signed along
+/// with the font bytes.
+pub async fn load_font_handler(
+  Query(query): Query<LoadFontQuery>,
+  State(state): AxumState,
+  body: Bytes,
+) -> AxumResult<(StatusCode, Json<Vec<FamilyInfo>>)> {
+  let families = spawn_blocking(move || -> AxumResult<Vec<FamilyInfo>> {
+    state
+      .context
+      .font_context
+      .load_and_store(
+        &body,
+        Some(FontInfoOverride {
+          family_name: query.family.as_deref(),
+          width: None,
+          style: query.style.map(Into::into),
+          weight: query.weight.map(FontWeight::new),
+          axes: None,
+        }),
+        None,
+      )
+      .map_err(|err| match err {
+        FontError::UnsupportedFormat => (
+          StatusCode::UNSUPPORTED_MEDIA_TYPE,
+          "Unsupported font format".to_string(),
+        ),
+        err => (
+          StatusCode::BAD_REQUEST,
+          format!("Failed to load font: {err:?}"),
+        ),
+      })?;
+
+    info!(
+      "Registered font (family override: {})",
+      query.family.as_deref().unwrap_or("none")
+    );
+
+    // cached images may have been rendered with a fallback for the new family
+    state.font_generation.fetch_add(1, Ordering::AcqRel);
+
+    if let Some(cache) = &state.render_cache
+      && let Ok(mut cache) = cache.lock()
+    {
+      cache.clear();
+    }
+
+    Ok(state.context.font_context.loaded_families())
+  })
+  .await
+  .map_err(|err| {
+    (
+      StatusCode::INTERNAL_SERVER_ERROR,
+      format!("Font loading task panicked: {err}"),
+    )
+  })??;
+
+  Ok((StatusCode::CREATED, Json(families)))
+}
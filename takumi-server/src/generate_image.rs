@@ -1,8 +1,16 @@
-use std::io::Cursor;
+use std::{
+  io::Cursor,
+  sync::{Arc, atomic::Ordering},
+};
 
 use axum::{
+  Json,
+  body::Bytes,
   extract::{Query, State},
-  http::StatusCode,
+  http::{
+    HeaderMap, HeaderValue, StatusCode,
+    header::{ACCEPT, CACHE_CONTROL, CONTENT_TYPE, ETAG, VARY},
+  },
   response::{IntoResponse, Response},
 };
 use serde::Deserialize;
@@ -13,22 +21,112 @@ use takumi::{
     node::{Node, NodeKind},
     style::{CssValue, LengthUnit},
   },
-  rendering::{ImageOutputFormat, render, write_image},
+  rendering::{EncodeOptions, ImageOutputFormat, render, write_image},
 };
 use tokio::task::spawn_blocking;
+use tracing::debug;
 
-use crate::{AxumResult, AxumState};
+use crate::{
+  AxumResult, AxumState, AxumStateInner,
+  cache::{is_not_modified, request_etag},
+};
 
 #[derive(Deserialize)]
 pub struct GenerateImageQuery {
   pub format: Option<ImageOutputFormat>,
   pub quality: Option<u8>,
+  #[serde(default)]
+  pub progressive: bool,
+  /// Defaults to `true`, lossy WebP can't be encoded.
+  pub lossless: Option<bool>,
+  pub dpi: Option<u32>,
+  #[serde(default)]
+  pub embed_srgb_profile: bool,
   pub payload: String,
 }
 
+/// JSON body of `POST /image`.
+///
+/// `width` and `height` fall back to the pixel width and height of the root node.
+#[derive(Deserialize)]
+pub struct GenerateImageBody {
+  pub width: Option<u32>,
+  pub height: Option<u32>,
+  pub format: Option<ImageOutputFormat>,
+  pub quality: Option<u8>,
+  #[serde(default)]
+  pub progressive: bool,
+  /// Defaults to `true`, lossy WebP can't be encoded.
+  pub lossless: Option<bool>,
+  pub dpi: Option<u32>,
+  #[serde(default)]
+  pub embed_srgb_profile: bool,
+  pub node: NodeKind,
+}
+
+/// Query of `POST /image`, values here override the ones in the body.
+#[derive(Deserialize, Default)]
+pub struct GenerateImagePostQuery {
+  pub format: Option<ImageOutputFormat>,
+  pub quality: Option<u8>,
+}
+
+/// Picks the output format from an `Accept` header, preferring the highest quality value.
+///
+/// Wildcards like `image/*` and headers without a supported format fall back to WebP.
+pub fn negotiate_format(accept: &str) -> ImageOutputFormat {
+  let mut best: Option<(ImageOutputFormat, f32)> = None;
+
+  for range in accept.split(',') {
+    let mut params = range.split(';');
+    let Some(format) = params.next().and_then(ImageOutputFormat::from_content_type) else {
+      continue;
+    };
+
+    let quality = params
+      .filter_map(|param| param.trim().strip_prefix("q="))
+      .find_map(|quality| quality.trim().parse::<f32>().ok())
+      .unwrap_or(1.0);
+
+    // earlier ranges win ties, like browsers listing their preferred formats first
+    if quality > 0.0 && best.is_none_or(|(_, best_quality)| quality > best_quality) {
+      best = Some((format, quality));
+    }
+  }
+
+  best.map_or(ImageOutputFormat::WebP, |(format, _)| format)
+}
+
+/// Uses the explicit format or negotiates one from the `Accept` header.
+fn resolve_format(format: Option<ImageOutputFormat>, headers: &HeaderMap) -> ResolvedFormat {
+  if let Some(format) = format {
+    return ResolvedFormat {
+      format,
+      negotiated: false,
+    };
+  }
+
+  let accept = headers
+    .get(ACCEPT)
+    .and_then(|accept| accept.to_str().ok())
+    .unwrap_or_default();
+
+  ResolvedFormat {
+    format: negotiate_format(accept),
+    negotiated: true,
+  }
+}
+
+struct ResolvedFormat {
+  format: ImageOutputFormat,
+  /// Whether the format depends on the `Accept` header, so caches need to vary on it.
+  negotiated: bool,
+}
+
 pub async fn generate_image_handler(
   Query(query): Query<GenerateImageQuery>,
   State(state): AxumState,
+  headers: HeaderMap,
 ) -> AxumResult<Response> {
   let root_node: NodeKind = from_str(&query.payload).map_err(|err| {
     (
@@ -37,8 +135,59 @@ pub async fn generate_image_handler(
     )
   })?;
 
-  let width = match root_node.get_style().width {
-    CssValue::Value(LengthUnit::Px(px)) => px,
+  let viewport = resolve_viewport(&root_node, None, None)?;
+
+  let options = EncodeOptions {
+    quality: query.quality,
+    progressive: query.progressive,
+    lossless: query.lossless.unwrap_or(true),
+    dpi: query.dpi,
+    embed_srgb_profile: query.embed_srgb_profile,
+    icc_profile: None,
+  };
+
+  render_image_response(
+    state,
+    &headers,
+    root_node,
+    viewport,
+    resolve_format(query.format, &headers),
+    options,
+  )
+  .await
+}
+
+pub async fn generate_image_post_handler(
+  Query(query): Query<GenerateImagePostQuery>,
+  State(state): AxumState,
+  headers: HeaderMap,
+  Json(body): Json<GenerateImageBody>,
+) -> AxumResult<Response> {
+  let viewport = resolve_viewport(&body.node, body.width, body.height)?;
+
+  let format = resolve_format(query.format.or(body.format), &headers);
+
+  let options = EncodeOptions {
+    quality: query.quality.or(body.quality),
+    progressive: body.progressive,
+    lossless: body.lossless.unwrap_or(true),
+    dpi: body.dpi,
+    embed_srgb_profile: body.embed_srgb_profile,
+    icc_profile: None,
+  };
+
+  render_image_response(state, &headers, body.node, viewport, format, options).await
+}
+
+/// Uses the explicit dimensions or the pixel width and height of the root node.
+fn resolve_viewport(
+  root_node: &NodeKind,
+  width: Option<u32>,
+  height: Option<u32>,
+) -> AxumResult<Viewport> {
+  let width = match (width, root_node.get_style().width) {
+    (Some(width), _) => width,
+    (None, CssValue::Value(LengthUnit::Px(px))) => px as u32,
     _ => {
       return Err((
         StatusCode::BAD_REQUEST,
@@ -47,8 +196,9 @@ pub async fn generate_image_handler(
     }
   };
 
-  let height = match root_node.get_style().height {
-    CssValue::Value(LengthUnit::Px(px)) => px,
+  let height = match (height, root_node.get_style().height) {
+    (Some(height), _) => height,
+    (None, CssValue::Value(LengthUnit::Px(px))) => px as u32,
     _ => {
       return Err((
         StatusCode::BAD_REQUEST,
@@ -57,22 +207,109 @@ pub async fn generate_image_handler(
     }
   };
 
-  let format = query.format.unwrap_or(ImageOutputFormat::WebP);
+  Ok(Viewport::new(width, height))
+}
 
-  let buffer = spawn_blocking(move || -> AxumResult<Vec<u8>> {
-    let viewport = Viewport::new(width as u32, height as u32);
+async fn render_image_response(
+  state: Arc<AxumStateInner>,
+  request_headers: &HeaderMap,
+  root_node: NodeKind,
+  viewport: Viewport,
+  format: ResolvedFormat,
+  options: EncodeOptions,
+) -> AxumResult<Response> {
+  let ResolvedFormat { format, negotiated } = format;
 
-    let image = render(viewport, &state.context, root_node).map_err(|err| {
+  if format == ImageOutputFormat::WebP && !options.lossless {
+    return Err((
+      StatusCode::BAD_REQUEST,
+      "Lossy WebP encoding is not supported".to_string(),
+    ));
+  }
+
+  let etag = request_etag(
+    &root_node,
+    viewport,
+    format,
+    &options,
+    state.font_generation.load(Ordering::Acquire),
+  );
+  let cache_max_age = state.cache_max_age;
+
+  let apply_cache_headers = |response: &mut Response| {
+    let headers = response.headers_mut();
+
+    if let Ok(etag) = HeaderValue::from_str(&etag) {
+      headers.insert(ETAG, etag);
+    }
+
+    if let Some(max_age) = cache_max_age
+      && let Ok(cache_control) = HeaderValue::from_str(&format!("public, max-age={max_age}"))
+    {
+      headers.insert(CACHE_CONTROL, cache_control);
+    }
+
+    if negotiated {
+      headers.insert(VARY, HeaderValue::from_static("accept"));
+    }
+  };
+
+  if is_not_modified(request_headers, &etag) {
+    let mut response = StatusCode::NOT_MODIFIED.into_response();
+    apply_cache_headers(&mut response);
+
+    return Ok(response);
+  }
+
+  let cached = state.render_cache.as_ref().and_then(|cache| {
+    let buffer = cache.lock().ok()?.get(&etag).cloned();
+
+    if buffer.is_some() {
+      debug!(%etag, "render cache hit");
+    } else {
+      debug!(%etag, "render cache miss");
+    }
+
+    buffer
+  });
+
+  if let Some(buffer) = cached {
+    return Ok(create_image_response(buffer, format, apply_cache_headers));
+  }
+
+  // held until the image is encoded
+  let _permit = match &state.render_permits {
+    Some(permits) => Some(permits.acquire().await.map_err(|err| {
       (
-        StatusCode::INTERNAL_SERVER_ERROR,
-        format!("Failed to render image: {err:?}"),
+        StatusCode::SERVICE_UNAVAILABLE,
+        format!("Failed to wait for a render slot: {err}"),
       )
+    })?),
+    None => None,
+  };
+
+  let render_state = state.clone();
+
+  // layout, image fetches from the image stores and rasterization are all synchronous,
+  // so they run on the blocking pool to keep the runtime workers serving other connections
+  let buffer = spawn_blocking(move || -> AxumResult<Vec<u8>> {
+    let state = render_state;
+    let image = render(viewport, &state.context, root_node).map_err(|err| {
+      // the requested size is the client's to fix
+      let status = match err {
+        takumi::Error::InvalidViewport { .. } | takumi::Error::CanvasTooLarge { .. } => {
+          StatusCode::BAD_REQUEST
+        }
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+      };
+
+      (status, format!("Failed to render image: {err}"))
     })?;
 
     let mut buffer = Vec::new();
     let mut cursor = Cursor::new(&mut buffer);
 
-    write_image(&image, &mut cursor, format, query.quality).map_err(|err| {
+    write_image(&image, &mut cursor, format, options).map_err(|err| {
       (
         StatusCode::INTERNAL_SERVER_ERROR,
         format!("Failed to write image: {err}"),
@@ -89,5 +326,29 @@ pub async fn generate_image_handler(
     )
   })??;
 
-  Ok(([("content-type", format.content_type())], buffer).into_response())
+  let buffer = Bytes::from(buffer);
+
+  if let Some(cache) = &state.render_cache
+    && let Ok(mut cache) = cache.lock()
+  {
+    cache.put(etag.clone(), buffer.clone());
+  }
+
+  Ok(create_image_response(buffer, format, apply_cache_headers))
+}
+
+fn create_image_response(
+  buffer: Bytes,
+  format: ImageOutputFormat,
+  apply_cache_headers: impl FnOnce(&mut Response),
+) -> Response {
+  let mut response = buffer.into_response();
+
+  response.headers_mut().insert(
+    CONTENT_TYPE,
+    HeaderValue::from_static(format.content_type()),
+  );
+  apply_cache_headers(&mut response);
+
+  response
 }
@@ -1,6 +1,9 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
 use axum::{
+  body::{Body, to_bytes},
   extract::{Query, Request, State},
-  http::StatusCode,
+  http::{Method, StatusCode, Uri},
   middleware::Next,
   response::Response,
 };
@@ -17,9 +20,16 @@ pub struct HmacQuery {
   pub payload: String,
 }
 
+/// Signature of a `POST` request, the payload is the request body and the signed message
+/// also covers the [`canonical_query`] of the request.
+#[derive(Deserialize)]
+pub struct HmacSignatureQuery {
+  pub hash: String,
+  pub timestamp: u64,
+}
+
 pub async fn hmac_verify_middleware(
   State(state): AxumState,
-  Query(query): Query<HmacQuery>,
   request: Request,
   next: Next,
 ) -> AxumResult<Response> {
@@ -27,28 +37,133 @@ pub async fn hmac_verify_middleware(
     return Ok(next.run(request).await);
   };
 
-  if let Err(err) = verify_payload(&query, secret) {
-    return Err((StatusCode::BAD_REQUEST, err.to_string()));
+  // signed `GET` URLs are often embedded in pages for a long time, so they don't expire
+  if request.method() != Method::POST {
+    let Query(query) = Query::<HmacQuery>::try_from_uri(request.uri())
+      .map_err(|err| (StatusCode::BAD_REQUEST, err.body_text()))?;
+
+    verify_payload(&query, secret).map_err(|err| (StatusCode::BAD_REQUEST, err.to_string()))?;
+
+    return Ok(next.run(request).await);
   }
 
-  Ok(next.run(request).await)
+  let Query(signature) = Query::<HmacSignatureQuery>::try_from_uri(request.uri())
+    .map_err(|err| (StatusCode::BAD_REQUEST, err.body_text()))?;
+
+  let query = canonical_query(request.uri())?;
+
+  verify_timestamp(signature.timestamp, SystemTime::now())
+    .map_err(|err| (StatusCode::BAD_REQUEST, err.to_string()))?;
+
+  let (parts, body) = request.into_parts();
+  let body = to_bytes(body, state.body_limit)
+    .await
+    .map_err(|err| (StatusCode::PAYLOAD_TOO_LARGE, err.to_string()))?;
+
+  verify_signed_request(&body, signature.timestamp, &query, &signature.hash, secret)
+    .map_err(|err| (StatusCode::BAD_REQUEST, err.to_string()))?;
+
+  Ok(next.run(Request::from_parts(parts, Body::from(body))).await)
+}
+
+/// How far the signed timestamp of a `POST` request may be from the server clock, in either
+/// direction.
+pub const MAX_TIMESTAMP_SKEW: Duration = Duration::from_secs(5 * 60);
+
+/// The query parameters that carry the signature, they are not part of the signed message.
+const SIGNATURE_PARAMS: [&str; 2] = ["hash", "timestamp"];
+
+/// Builds the query string covered by the signature of a `POST` request from its URI.
+///
+/// Every parameter except `hash` and `timestamp` is kept, sorted by name and
+/// then value, with `%`, `&` and `=` percent-encoded, e.g. `family=Brand&weight=700`.
+pub fn canonical_query(uri: &Uri) -> AxumResult<String> {
+  let Query(mut params) = Query::<Vec<(String, String)>>::try_from_uri(uri)
+    .map_err(|err| (StatusCode::BAD_REQUEST, err.body_text()))?;
+
+  params.retain(|(name, _)| !SIGNATURE_PARAMS.contains(&name.as_str()));
+  params.sort();
+
+  let escape = |part: &str| {
+    part
+      .replace('%', "%25")
+      .replace('&', "%26")
+      .replace('=', "%3D")
+  };
+
+  Ok(
+    params
+      .iter()
+      .map(|(name, value)| format!("{}={}", escape(name), escape(value)))
+      .collect::<Vec<_>>()
+      .join("&"),
+  )
+}
+
+/// Rejects timestamps, in seconds since the Unix epoch, further than
+/// [`MAX_TIMESTAMP_SKEW`] from `now`, so captured requests stop working after a few minutes.
+pub fn verify_timestamp(timestamp: u64, now: SystemTime) -> Result<(), &'static str> {
+  let now = now
+    .duration_since(UNIX_EPOCH)
+    .map_err(|_| ERROR_STALE_TIMESTAMP)?
+    .as_secs();
+
+  if now.abs_diff(timestamp) > MAX_TIMESTAMP_SKEW.as_secs() {
+    return Err(ERROR_STALE_TIMESTAMP);
+  }
+
+  Ok(())
 }
 
 pub const ERROR_HASH_LENGTH: &str = "Hash must be a 64-character hexadecimal string";
 pub const ERROR_INVALID_HEX: &str = "Invalid hexadecimal hash";
 pub const ERROR_HMAC_VERIFICATION: &str = "HMAC verification failed";
+pub const ERROR_STALE_TIMESTAMP: &str = "Timestamp is too far from the server time";
 
 pub fn verify_payload(query: &HmacQuery, secret: &[u8]) -> Result<(), &'static str> {
-  if query.hash.len() != 64 {
+  verify_signature(
+    query.payload.as_bytes(),
+    query.timestamp,
+    &query.hash,
+    secret,
+  )
+}
+
+/// Verifies the hex encoded HMAC-SHA256 of `payload;timestamp`.
+pub fn verify_signature(
+  payload: &[u8],
+  timestamp: u64,
+  hash: &str,
+  secret: &[u8],
+) -> Result<(), &'static str> {
+  verify_signed_request(payload, timestamp, "", hash, secret)
+}
+
+/// Verifies the hex encoded HMAC-SHA256 of `payload;timestamp;query`, where `query` is the
+/// [`canonical_query`] of the request. Without other parameters the message is
+/// `payload;timestamp`, same as [`verify_signature`].
+pub fn verify_signed_request(
+  payload: &[u8],
+  timestamp: u64,
+  query: &str,
+  hash: &str,
+  secret: &[u8],
+) -> Result<(), &'static str> {
+  if hash.len() != 64 {
     return Err(ERROR_HASH_LENGTH);
   }
 
-  let decoded_hash = hex::decode(&query.hash).map_err(|_| ERROR_INVALID_HEX)?;
+  let decoded_hash = hex::decode(hash).map_err(|_| ERROR_INVALID_HEX)?;
 
   let mut mac = Hmac::<Sha256>::new_from_slice(secret).unwrap();
-  mac.update(query.payload.as_bytes());
+  mac.update(payload);
   mac.update(b";");
-  mac.update(query.timestamp.to_string().as_bytes());
+  mac.update(timestamp.to_string().as_bytes());
+
+  if !query.is_empty() {
+    mac.update(b";");
+    mac.update(query.as_bytes());
+  }
 
   mac
     .verify_slice(&decoded_hash)
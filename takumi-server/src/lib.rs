@@ -1,14 +1,29 @@
-use std::{fs::read, net::SocketAddr, sync::Arc};
-
-use axum::{Router, extract::State, http::StatusCode, response::Response, routing::get};
+use std::{
+  fs::read,
+  net::SocketAddr,
+  num::NonZeroUsize,
+  sync::{Arc, Mutex, atomic::AtomicU64},
+};
+
+use axum::{
+  Json, Router,
+  body::Bytes,
+  extract::{DefaultBodyLimit, State},
+  http::StatusCode,
+  response::Response,
+  routing::get,
+};
 use globwalk::glob;
-use takumi::GlobalContext;
-use tokio::net::TcpListener;
+use lru::LruCache;
+use takumi::{GlobalContext, layout::node::node_schema};
+use tokio::{net::TcpListener, signal, sync::Semaphore};
 use tracing::{error, info};
 
-pub use crate::{args::Args, generate_image::*};
+pub use crate::{args::Args, fonts::load_font_handler, generate_image::*};
 
 pub mod args;
+pub mod cache;
+pub mod fonts;
 pub mod generate_image;
 #[cfg(feature = "hmac_verify")]
 pub mod hmac_verify;
@@ -16,8 +31,20 @@ pub mod hmac_verify;
 pub type AxumState = State<Arc<AxumStateInner>>;
 pub type AxumResult<T = Response> = Result<T, (StatusCode, String)>;
 
+/// The request body size limit used when `--body-limit` is not specified.
+pub const DEFAULT_BODY_LIMIT: usize = 2 * 1024 * 1024;
+
 pub struct AxumStateInner {
   pub context: GlobalContext,
+  pub body_limit: usize,
+  pub cache_max_age: Option<u64>,
+  /// Encoded images keyed by their ETag.
+  pub render_cache: Option<Mutex<LruCache<String, Bytes>>>,
+  /// Limits the number of renders running at the same time.
+  pub render_permits: Option<Semaphore>,
+  /// Bumped each time a font is registered through `/fonts`, part of the ETag so tags of
+  /// images rendered with the previous fonts stop matching.
+  pub font_generation: AtomicU64,
   #[cfg(feature = "hmac_verify")]
   pub hmac_key: Option<Vec<u8>>,
 }
@@ -25,6 +52,17 @@ pub struct AxumStateInner {
 pub fn create_state(args: Args, context: GlobalContext) -> AxumState {
   let state = Arc::new(AxumStateInner {
     context,
+    body_limit: args.body_limit.unwrap_or(DEFAULT_BODY_LIMIT),
+    cache_max_age: args.cache_max_age,
+    render_cache: args
+      .cache_size
+      .and_then(NonZeroUsize::new)
+      .map(|size| Mutex::new(LruCache::new(size))),
+    render_permits: args
+      .max_concurrency
+      .filter(|&permits| permits > 0)
+      .map(Semaphore::new),
+    font_generation: AtomicU64::new(0),
     #[cfg(feature = "hmac_verify")]
     hmac_key: args.hmac_key.map(|key| {
       use sha2::{Digest, Sha256};
@@ -39,8 +77,19 @@ pub fn create_state(args: Args, context: GlobalContext) -> AxumState {
 }
 
 pub fn create_app(state: AxumState) -> Router {
-  let mut app = Router::new()
-    .route("/image", get(generate_image_handler))
+  let mut routes = Router::new().route(
+    "/image",
+    get(generate_image_handler).post(generate_image_post_handler),
+  );
+
+  // registering fonts changes every later render, so it is only exposed to signed requests
+  #[cfg(feature = "hmac_verify")]
+  if state.hmac_key.is_some() {
+    routes = routes.route("/fonts", axum::routing::post(load_font_handler));
+  }
+
+  let mut app = routes
+    .layer(DefaultBodyLimit::max(state.body_limit))
     .with_state(state.0.clone());
 
   #[cfg(feature = "hmac_verify")]
@@ -51,7 +100,20 @@ pub fn create_app(state: AxumState) -> Router {
     ));
   }
 
+  // added after the hmac layer, these routes don't depend on any payload
   app
+    .route("/schema", get(schema_handler))
+    .route("/healthz", get(health_handler))
+}
+
+/// Responds once the server accepts requests, used by liveness and readiness probes.
+pub async fn health_handler() -> &'static str {
+  "ok"
+}
+
+/// Returns the JSON Schema of the node tree accepted by `/image`.
+pub async fn schema_handler() -> Json<serde_json::Value> {
+  Json(node_schema())
 }
 
 pub async fn run_server(args: Args, context: GlobalContext) {
@@ -77,12 +139,49 @@ pub async fn run_server(args: Args, context: GlobalContext) {
     }
   }
 
+  for family in context.font_context.loaded_families() {
+    info!(
+      "Font family available: {} (weights: {:?}, styles: {:?})",
+      family.name, family.weights, family.styles
+    );
+  }
+
   let addr = SocketAddr::from(([0, 0, 0, 0], args.port));
   let listener = TcpListener::bind(addr).await.unwrap();
 
   info!("Image generator server running on http://{addr}");
 
   axum::serve(listener, create_app(create_state(args, context)))
+    .with_graceful_shutdown(shutdown_signal())
     .await
     .unwrap();
+
+  info!("Image generator server stopped");
+}
+
+/// Resolves on SIGINT or SIGTERM, in-flight requests are drained before the server stops.
+async fn shutdown_signal() {
+  let ctrl_c = async {
+    signal::ctrl_c()
+      .await
+      .expect("Failed to install the SIGINT handler");
+  };
+
+  #[cfg(unix)]
+  let terminate = async {
+    signal::unix::signal(signal::unix::SignalKind::terminate())
+      .expect("Failed to install the SIGTERM handler")
+      .recv()
+      .await;
+  };
+
+  #[cfg(not(unix))]
+  let terminate = std::future::pending::<()>();
+
+  tokio::select! {
+    _ = ctrl_c => {},
+    _ = terminate => {},
+  }
+
+  info!("Shutdown signal received, waiting for in-flight requests");
 }
@@ -1,6 +1,9 @@
 use clap::Parser;
 use mimalloc::MiMalloc;
-use takumi::GlobalContext;
+use takumi::{
+  DEFAULT_MAX_CANVAS_PIXELS, GlobalContext, rendering::DebugOptions,
+  resources::image::FileSystemImageStore,
+};
 use tracing::Level;
 use tracing_subscriber::fmt;
 
@@ -15,10 +18,17 @@ async fn main() {
 
   let args = Args::parse();
 
-  let context = GlobalContext {
-    draw_debug_border: args.draw_debug_border,
+  let mut context = GlobalContext {
+    debug: args.draw_debug_border.then(DebugOptions::default),
+    max_canvas_pixels: args.max_pixels.unwrap_or(DEFAULT_MAX_CANVAS_PIXELS),
     ..Default::default()
   };
 
+  if let Some(asset_root) = args.asset_root.as_ref() {
+    context.persistent_image_store = Box::new(
+      FileSystemImageStore::new(asset_root).expect("Failed to open the asset root directory"),
+    );
+  }
+
   run_server(args, context).await;
 }
@@ -1,4 +1,11 @@
-use axum::extract::Query;
+use axum::{
+  body::{Bytes, to_bytes},
+  extract::Query,
+  http::{
+    HeaderMap, HeaderValue, StatusCode,
+    header::{ACCEPT, IF_NONE_MATCH},
+  },
+};
 use takumi::{
   GlobalContext,
   layout::{
@@ -7,7 +14,14 @@ use takumi::{
   },
 };
 
-use takumi_server::{GenerateImageQuery, args::Args, create_state, generate_image_handler};
+use takumi::rendering::ImageOutputFormat;
+use takumi_server::{
+  GenerateImageQuery,
+  args::Args,
+  create_state,
+  fonts::{LoadFontQuery, load_font_handler},
+  generate_image_handler, negotiate_format,
+};
 
 #[tokio::test]
 async fn test_generate_image_handler() {
@@ -26,9 +40,14 @@ async fn test_generate_image_handler() {
     Query(GenerateImageQuery {
       format: None,
       quality: None,
+      progressive: false,
+      lossless: None,
+      dpi: None,
+      embed_srgb_profile: false,
       payload: serde_json::to_string(&node).unwrap(),
     }),
     state,
+    HeaderMap::new(),
   )
   .await
   .unwrap();
@@ -48,12 +67,279 @@ async fn test_generate_image_handler_invalid_width() {
     Query(GenerateImageQuery {
       format: None,
       quality: None,
+      progressive: false,
+      lossless: None,
+      dpi: None,
+      embed_srgb_profile: false,
       payload: serde_json::to_string(&node).unwrap(),
     }),
     state,
+    HeaderMap::new(),
   )
   .await
   .unwrap_err();
 
   assert_eq!(status, 400);
 }
+
+#[test]
+fn test_negotiate_format() {
+  assert_eq!(
+    negotiate_format("image/avif,image/webp,image/apng,*/*;q=0.8"),
+    ImageOutputFormat::Avif
+  );
+  assert_eq!(
+    negotiate_format("image/jpeg;q=0.5, image/png"),
+    ImageOutputFormat::Png
+  );
+  assert_eq!(
+    negotiate_format("image/png;q=0, image/jpeg;q=0.1"),
+    ImageOutputFormat::Jpeg
+  );
+  assert_eq!(negotiate_format("*/*"), ImageOutputFormat::WebP);
+  assert_eq!(negotiate_format(""), ImageOutputFormat::WebP);
+}
+
+#[tokio::test]
+async fn test_generate_image_handler_accept_header() {
+  let node: NodeKind = ContainerNode {
+    style: StyleBuilder::default()
+      .width(Px(10.0))
+      .height(Px(10.0))
+      .build()
+      .unwrap(),
+    children: None,
+  }
+  .into();
+
+  let mut headers = HeaderMap::new();
+  headers.insert(ACCEPT, HeaderValue::from_static("image/png"));
+
+  let create_query = |format| GenerateImageQuery {
+    format,
+    quality: None,
+    progressive: false,
+    lossless: None,
+    dpi: None,
+    embed_srgb_profile: false,
+    payload: serde_json::to_string(&node).unwrap(),
+  };
+
+  let state = create_state(Args::default(), GlobalContext::default());
+  let response = generate_image_handler(Query(create_query(None)), state, headers.clone())
+    .await
+    .unwrap();
+
+  assert_eq!(response.headers()["content-type"], "image/png");
+  assert_eq!(response.headers()["vary"], "accept");
+
+  // an explicit format wins over the header
+  let state = create_state(Args::default(), GlobalContext::default());
+  let response = generate_image_handler(
+    Query(create_query(Some(ImageOutputFormat::Jpeg))),
+    state,
+    headers,
+  )
+  .await
+  .unwrap();
+
+  assert_eq!(response.headers()["content-type"], "image/jpeg");
+  assert!(response.headers().get("vary").is_none());
+}
+
+#[tokio::test]
+async fn test_generate_image_handler_rejects_lossy_webp() {
+  let node: NodeKind = ContainerNode {
+    style: StyleBuilder::default()
+      .width(Px(10.0))
+      .height(Px(10.0))
+      .build()
+      .unwrap(),
+    children: None,
+  }
+  .into();
+
+  let query = GenerateImageQuery {
+    format: Some(ImageOutputFormat::WebP),
+    quality: None,
+    progressive: false,
+    lossless: Some(false),
+    dpi: None,
+    embed_srgb_profile: false,
+    payload: serde_json::to_string(&node).unwrap(),
+  };
+
+  let state = create_state(Args::default(), GlobalContext::default());
+  let result = generate_image_handler(Query(query), state, HeaderMap::new()).await;
+
+  assert!(matches!(result, Err((StatusCode::BAD_REQUEST, _))));
+}
+
+#[tokio::test]
+async fn test_generate_image_handler_etag() {
+  let node: NodeKind = ContainerNode {
+    style: StyleBuilder::default()
+      .width(Px(10.0))
+      .height(Px(10.0))
+      .build()
+      .unwrap(),
+    children: None,
+  }
+  .into();
+
+  let create_query = |payload: String| GenerateImageQuery {
+    format: Some(ImageOutputFormat::Png),
+    quality: None,
+    progressive: false,
+    lossless: None,
+    dpi: None,
+    embed_srgb_profile: false,
+    payload,
+  };
+
+  let args = Args {
+    cache_max_age: Some(3600),
+    ..Default::default()
+  };
+  let state = create_state(args, GlobalContext::default());
+
+  let response = generate_image_handler(
+    Query(create_query(serde_json::to_string(&node).unwrap())),
+    state.clone(),
+    HeaderMap::new(),
+  )
+  .await
+  .unwrap();
+
+  let etag = response.headers()["etag"].clone();
+  assert_eq!(response.headers()["cache-control"], "public, max-age=3600");
+
+  // the same tree written differently maps to the same tag
+  let mut headers = HeaderMap::new();
+  headers.insert(IF_NONE_MATCH, etag.clone());
+
+  let response = generate_image_handler(
+    Query(create_query(serde_json::to_string_pretty(&node).unwrap())),
+    state.clone(),
+    headers,
+  )
+  .await
+  .unwrap();
+
+  assert_eq!(response.status(), 304);
+  assert_eq!(response.headers()["etag"], etag);
+
+  let mut headers = HeaderMap::new();
+  headers.insert(IF_NONE_MATCH, HeaderValue::from_static("\"other\""));
+
+  let response = generate_image_handler(
+    Query(create_query(serde_json::to_string(&node).unwrap())),
+    state,
+    headers,
+  )
+  .await
+  .unwrap();
+
+  assert_eq!(response.status(), 200);
+}
+
+#[tokio::test]
+async fn test_generate_image_handler_etag_changes_after_font_load() {
+  let node: NodeKind = ContainerNode {
+    style: StyleBuilder::default()
+      .width(Px(10.0))
+      .height(Px(10.0))
+      .build()
+      .unwrap(),
+    children: None,
+  }
+  .into();
+
+  let create_query = || GenerateImageQuery {
+    format: Some(ImageOutputFormat::Png),
+    quality: None,
+    progressive: false,
+    lossless: None,
+    dpi: None,
+    embed_srgb_profile: false,
+    payload: serde_json::to_string(&node).unwrap(),
+  };
+
+  let state = create_state(Args::default(), GlobalContext::default());
+
+  let response = generate_image_handler(Query(create_query()), state.clone(), HeaderMap::new())
+    .await
+    .unwrap();
+  let etag = response.headers()["etag"].clone();
+
+  let (status, _) = load_font_handler(
+    Query(LoadFontQuery::default()),
+    state.clone(),
+    Bytes::from_static(include_bytes!(
+      "../../assets/fonts/noto-sans/NotoSans-Regular.ttf"
+    )),
+  )
+  .await
+  .unwrap();
+  assert_eq!(status, 201);
+
+  // the image may now render with the new font, so the old tag must not match
+  let mut headers = HeaderMap::new();
+  headers.insert(IF_NONE_MATCH, etag.clone());
+
+  let response = generate_image_handler(Query(create_query()), state, headers)
+    .await
+    .unwrap();
+
+  assert_eq!(response.status(), 200);
+  assert_ne!(response.headers()["etag"], etag);
+}
+
+#[tokio::test]
+async fn test_generate_image_handler_render_cache() {
+  let create_query = |size: f32| GenerateImageQuery {
+    format: Some(ImageOutputFormat::Png),
+    quality: None,
+    progressive: false,
+    lossless: None,
+    dpi: None,
+    embed_srgb_profile: false,
+    payload: serde_json::to_string(&NodeKind::from(ContainerNode {
+      style: StyleBuilder::default()
+        .width(Px(size))
+        .height(Px(size))
+        .build()
+        .unwrap(),
+      children: None,
+    }))
+    .unwrap(),
+  };
+
+  let args = Args {
+    cache_size: Some(1),
+    ..Default::default()
+  };
+  let state = create_state(args, GlobalContext::default());
+
+  let render =
+    |size: f32| generate_image_handler(Query(create_query(size)), state.clone(), HeaderMap::new());
+
+  let first = to_bytes(render(10.0).await.unwrap().into_body(), usize::MAX)
+    .await
+    .unwrap();
+  let cache = state.render_cache.as_ref().unwrap();
+  assert_eq!(cache.lock().unwrap().len(), 1);
+
+  let second = to_bytes(render(10.0).await.unwrap().into_body(), usize::MAX)
+    .await
+    .unwrap();
+  assert_eq!(first, second);
+
+  // the least recently used image is evicted
+  let etag = cache.lock().unwrap().peek_lru().unwrap().0.clone();
+  render(20.0).await.unwrap();
+
+  let cache = cache.lock().unwrap();
+  assert_eq!(cache.len(), 1);
+  assert!(!cache.contains(&etag));
+}
@@ -220,3 +220,62 @@ fn test_verify_payload_non_hex_characters() {
     ERROR_HASH_LENGTH
   );
 }
+
+#[test]
+#[cfg(feature = "hmac_verify")]
+fn test_canonical_query() {
+  use axum::http::Uri;
+  use takumi_server::hmac_verify::*;
+
+  let uri: Uri = "/fonts?weight=700&hash=abc&family=A%26b%3Dc&timestamp=1&style=italic"
+    .parse()
+    .unwrap();
+
+  assert_eq!(
+    canonical_query(&uri).unwrap(),
+    "family=A%26b%3Dc&style=italic&weight=700"
+  );
+
+  let uri: Uri = "/fonts?timestamp=1&hash=abc".parse().unwrap();
+  assert_eq!(canonical_query(&uri).unwrap(), "");
+}
+
+#[test]
+#[cfg(feature = "hmac_verify")]
+fn test_verify_signed_request_covers_query() {
+  use takumi_server::hmac_verify::*;
+
+  let secret = b"secret";
+  let mut mac = Hmac::<Sha256>::new_from_slice(secret).unwrap();
+  mac.update(b"font;1672531200;family=Brand");
+  let hash = hex::encode(mac.finalize().into_bytes());
+
+  assert!(verify_signed_request(b"font", 1672531200, "family=Brand", &hash, secret).is_ok());
+  assert_eq!(
+    verify_signed_request(b"font", 1672531200, "family=Other", &hash, secret).unwrap_err(),
+    ERROR_HMAC_VERIFICATION
+  );
+  assert!(verify_signature(b"font", 1672531200, &hash, secret).is_err());
+}
+
+#[test]
+#[cfg(feature = "hmac_verify")]
+fn test_verify_timestamp() {
+  use std::time::{Duration, UNIX_EPOCH};
+
+  use takumi_server::hmac_verify::*;
+
+  let now = UNIX_EPOCH + Duration::from_secs(1672531200);
+
+  assert!(verify_timestamp(1672531200, now).is_ok());
+  assert!(verify_timestamp(1672531200 - 60, now).is_ok());
+  assert!(verify_timestamp(1672531200 + 60, now).is_ok());
+  assert_eq!(
+    verify_timestamp(1672531200 - 3600, now).unwrap_err(),
+    ERROR_STALE_TIMESTAMP
+  );
+  assert_eq!(
+    verify_timestamp(1672531200 + 3600, now).unwrap_err(),
+    ERROR_STALE_TIMESTAMP
+  );
+}
@@ -7,11 +7,34 @@ use takumi_server::{Args, create_app, create_state};
 #[test]
 fn test_create_state() {
   let state = create_state(Args::default(), GlobalContext::default());
-  assert!(!state.context.draw_debug_border);
+  assert!(state.context.debug.is_none());
   #[cfg(feature = "hmac_verify")]
   assert!(state.hmac_key.is_none());
 }
 
+#[tokio::test]
+async fn test_schema_handler() {
+  use axum::{body::Body, http::StatusCode};
+
+  let app = create_app(create_state(Args::default(), GlobalContext::default()));
+
+  let request = Request::builder()
+    .uri("/schema")
+    .body(Body::empty())
+    .unwrap();
+
+  let response = app.oneshot(request).await.unwrap();
+  assert_eq!(response.status(), StatusCode::OK);
+
+  let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+    .await
+    .unwrap();
+  let schema: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+  assert_eq!(schema["title"], "NodeKind");
+  assert!(schema["$defs"]["Style"]["properties"]["flexDirection"].is_object());
+}
+
 #[tokio::test]
 #[cfg(feature = "hmac_verify")]
 async fn test_generate_image_handler_with_hmac_verify() {
@@ -67,3 +90,348 @@ async fn test_generate_image_handler_with_hmac_verify() {
     "image/webp"
   );
 }
+
+#[tokio::test]
+#[cfg(feature = "hmac_verify")]
+async fn test_generate_image_handler_with_hmac_key_keeps_old_urls() {
+  use axum::body::Body;
+  use hmac::{Hmac, Mac};
+  use query_string_builder::QueryString;
+  use sha2::Sha256;
+  use takumi::layout::{
+    node::{ContainerNode, NodeKind},
+    style::{LengthUnit::Px, StyleBuilder},
+  };
+
+  let args = Args {
+    hmac_key: Some("secret".to_string()),
+    ..Default::default()
+  };
+  let state = create_state(args, GlobalContext::default());
+  let secret = state.hmac_key.clone().unwrap();
+  let app = create_app(state);
+
+  let node: NodeKind = ContainerNode {
+    style: StyleBuilder::default()
+      .width(Px(100.0))
+      .height(Px(100.0))
+      .build()
+      .unwrap(),
+    children: None,
+  }
+  .into();
+
+  let payload = serde_json::to_string(&node).unwrap();
+  // signed long ago, `GET` signatures only cover `payload;timestamp` and never expire
+  let timestamp = 1_600_000_000u64;
+
+  let mut mac = Hmac::<Sha256>::new_from_slice(&secret).unwrap();
+  mac.update(payload.as_bytes());
+  mac.update(b";");
+  mac.update(timestamp.to_string().as_bytes());
+  let hash = mac.finalize().into_bytes();
+
+  let uri = QueryString::dynamic()
+    .with_value("format", "png")
+    .with_value("payload", payload)
+    .with_value("timestamp", timestamp)
+    .with_value("hash", hex::encode(hash));
+
+  let request = Request::builder()
+    .uri(format!("/image{uri}"))
+    .body(Body::empty())
+    .unwrap();
+
+  let response = app.oneshot(request).await.unwrap();
+  assert_eq!(response.status(), 200);
+  assert_eq!(response.headers().get("content-type").unwrap(), "image/png");
+}
+
+fn create_post_body() -> String {
+  use takumi::layout::node::{ContainerNode, NodeKind};
+
+  let node: NodeKind = ContainerNode {
+    style: Default::default(),
+    children: None,
+  }
+  .into();
+
+  serde_json::json!({
+    "width": 100,
+    "height": 50,
+    "format": "png",
+    "node": node,
+  })
+  .to_string()
+}
+
+#[tokio::test]
+async fn test_generate_image_post_handler() {
+  use axum::body::Body;
+
+  let app = create_app(create_state(Args::default(), GlobalContext::default()));
+
+  let request = Request::builder()
+    .method("POST")
+    .uri("/image")
+    .header("content-type", "application/json")
+    .body(Body::from(create_post_body()))
+    .unwrap();
+
+  let response = app.oneshot(request).await.unwrap();
+  assert_eq!(response.status(), 200);
+  assert_eq!(response.headers().get("content-type").unwrap(), "image/png");
+}
+
+#[tokio::test]
+async fn test_generate_image_post_handler_query_format() {
+  use axum::body::Body;
+
+  let app = create_app(create_state(Args::default(), GlobalContext::default()));
+
+  let request = Request::builder()
+    .method("POST")
+    .uri("/image?format=jpeg&quality=80")
+    .header("content-type", "application/json")
+    .body(Body::from(create_post_body()))
+    .unwrap();
+
+  let response = app.oneshot(request).await.unwrap();
+  assert_eq!(response.status(), 200);
+  assert_eq!(
+    response.headers().get("content-type").unwrap(),
+    "image/jpeg"
+  );
+}
+
+#[tokio::test]
+async fn test_generate_image_post_handler_body_limit() {
+  use axum::body::Body;
+
+  let args = Args {
+    body_limit: Some(16),
+    ..Default::default()
+  };
+  let app = create_app(create_state(args, GlobalContext::default()));
+
+  let request = Request::builder()
+    .method("POST")
+    .uri("/image")
+    .header("content-type", "application/json")
+    .body(Body::from(create_post_body()))
+    .unwrap();
+
+  let response = app.oneshot(request).await.unwrap();
+  assert_eq!(response.status(), 413);
+}
+
+#[tokio::test]
+#[cfg(feature = "hmac_verify")]
+async fn test_generate_image_post_handler_with_hmac_verify() {
+  use axum::body::Body;
+  use hmac::{Hmac, Mac};
+  use sha2::Sha256;
+
+  let args = Args {
+    hmac_key: Some("secret".to_string()),
+    ..Default::default()
+  };
+  let state = create_state(args, GlobalContext::default());
+  let secret = state.hmac_key.clone().unwrap();
+  let app = create_app(state);
+
+  let body = create_post_body();
+  let sign = |timestamp: u64| {
+    let mut mac = Hmac::<Sha256>::new_from_slice(&secret).unwrap();
+    mac.update(body.as_bytes());
+    mac.update(b";");
+    mac.update(timestamp.to_string().as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+  };
+
+  let request = |timestamp: u64, hash: &str| {
+    Request::builder()
+      .method("POST")
+      .uri(format!("/image?timestamp={timestamp}&hash={hash}"))
+      .header("content-type", "application/json")
+      .body(Body::from(body.clone()))
+      .unwrap()
+  };
+
+  let timestamp = now();
+
+  let response = app
+    .clone()
+    .oneshot(request(timestamp, &sign(timestamp)))
+    .await
+    .unwrap();
+  assert_eq!(response.status(), 200);
+
+  let response = app
+    .clone()
+    .oneshot(request(timestamp, &"0".repeat(64)))
+    .await
+    .unwrap();
+  assert_eq!(response.status(), 400);
+
+  // a valid signature from an hour ago can't be replayed
+  let stale = timestamp - 3600;
+  let response = app.oneshot(request(stale, &sign(stale))).await.unwrap();
+  assert_eq!(response.status(), 400);
+}
+
+#[cfg(feature = "hmac_verify")]
+fn now() -> u64 {
+  use std::time::{SystemTime, UNIX_EPOCH};
+
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .unwrap()
+    .as_secs()
+}
+
+#[tokio::test]
+#[cfg(feature = "hmac_verify")]
+async fn test_load_font_handler() {
+  use axum::body::{Body, to_bytes};
+  use hmac::{Hmac, Mac};
+  use sha2::Sha256;
+
+  let args = Args {
+    hmac_key: Some("secret".to_string()),
+    ..Default::default()
+  };
+  let state = create_state(args, GlobalContext::default());
+  let secret = state.hmac_key.clone().unwrap();
+  let app = create_app(state);
+
+  let font = include_bytes!("../../assets/fonts/noto-sans/NotoSans-Regular.ttf").as_slice();
+  let timestamp = now();
+
+  let request = |query: &str, signed_query: &str, body: &'static [u8]| {
+    let mut mac = Hmac::<Sha256>::new_from_slice(&secret).unwrap();
+    mac.update(body);
+    mac.update(b";");
+    mac.update(timestamp.to_string().as_bytes());
+
+    if !signed_query.is_empty() {
+      mac.update(b";");
+      mac.update(signed_query.as_bytes());
+    }
+
+    let hash = hex::encode(mac.finalize().into_bytes());
+    let separator = if query.is_empty() { "" } else { "&" };
+
+    Request::builder()
+      .method("POST")
+      .uri(format!(
+        "/fonts?{query}{separator}timestamp={timestamp}&hash={hash}"
+      ))
+      .body(Body::from(body))
+      .unwrap()
+  };
+
+  // the overrides are part of the signature, changing them invalidates it
+  let response = app
+    .clone()
+    .oneshot(request(
+      "weight=700&family=Other",
+      "family=Brand&weight=700",
+      font,
+    ))
+    .await
+    .unwrap();
+  assert_eq!(response.status(), 400);
+
+  let response = app
+    .clone()
+    .oneshot(request(
+      "weight=700&family=Brand",
+      "family=Brand&weight=700",
+      font,
+    ))
+    .await
+    .unwrap();
+  assert_eq!(response.status(), 201);
+
+  let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+  let families: serde_json::Value = serde_json::from_slice(&body).unwrap();
+  assert_eq!(
+    families,
+    serde_json::json!([{ "name": "Brand", "weights": [700.0], "styles": ["normal"] }])
+  );
+
+  let response = app.oneshot(request("", "", b"not a font")).await.unwrap();
+  assert_eq!(response.status(), 415);
+}
+
+#[tokio::test]
+async fn test_load_font_handler_requires_hmac_key() {
+  use axum::body::Body;
+
+  let app = create_app(create_state(Args::default(), GlobalContext::default()));
+
+  let request = Request::builder()
+    .method("POST")
+    .uri("/fonts")
+    .body(Body::from(
+      include_bytes!("../../assets/fonts/noto-sans/NotoSans-Regular.ttf").as_slice(),
+    ))
+    .unwrap();
+
+  let response = app.oneshot(request).await.unwrap();
+  assert_eq!(response.status(), 404);
+}
+
+#[tokio::test]
+#[cfg(feature = "hmac_verify")]
+async fn test_health_handler_with_hmac_verify() {
+  use axum::body::{Body, to_bytes};
+
+  let args = Args {
+    hmac_key: Some("secret".to_string()),
+    ..Default::default()
+  };
+  let app = create_app(create_state(args, GlobalContext::default()));
+
+  let request = Request::builder()
+    .uri("/healthz")
+    .body(Body::empty())
+    .unwrap();
+
+  let response = app.oneshot(request).await.unwrap();
+  assert_eq!(response.status(), 200);
+
+  let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+  assert_eq!(body.as_ref(), b"ok");
+}
+
+#[tokio::test]
+async fn test_generate_image_post_handler_max_concurrency() {
+  use axum::body::Body;
+
+  let args = Args {
+    max_concurrency: Some(1),
+    ..Default::default()
+  };
+  let state = create_state(args, GlobalContext::default());
+  let app = create_app(state.clone());
+
+  let request = || {
+    Request::builder()
+      .method("POST")
+      .uri("/image")
+      .header("content-type", "application/json")
+      .body(Body::from(create_post_body()))
+      .unwrap()
+  };
+
+  let (first, second) = tokio::join!(app.clone().oneshot(request()), app.oneshot(request()));
+
+  assert_eq!(first.unwrap().status(), 200);
+  assert_eq!(second.unwrap().status(), 200);
+  assert_eq!(
+    state.render_permits.as_ref().unwrap().available_permits(),
+    1
+  );
+}
@@ -1,13 +1,13 @@
 use std::{io::Cursor, sync::Arc};
 
 use base64::{Engine, prelude::BASE64_STANDARD};
-use serde_wasm_bindgen::from_value;
+use serde_wasm_bindgen::{from_value, to_value};
 use takumi::{
   GlobalContext,
   image::load_from_memory,
   layout::{Viewport, node::NodeKind},
   parley::{FontWeight, fontique::FontInfoOverride},
-  rendering::{render, write_image},
+  rendering::{DebugOptions, EncodeOptions, render, write_image},
   resources::image::ImageSource,
 };
 use wasm_bindgen::prelude::*;
@@ -36,6 +36,9 @@ pub enum ImageOutputFormat {
   WebP = "webp",
   Png = "png",
   Jpeg = "jpeg",
+  Gif = "gif",
+  Tiff = "tiff",
+  Bmp = "bmp",
 }
 
 #[wasm_bindgen]
@@ -68,6 +71,9 @@ impl From<ImageOutputFormat> for takumi::rendering::ImageOutputFormat {
     match format {
       ImageOutputFormat::WebP => takumi::rendering::ImageOutputFormat::WebP,
       ImageOutputFormat::Jpeg => takumi::rendering::ImageOutputFormat::Jpeg,
+      ImageOutputFormat::Gif => takumi::rendering::ImageOutputFormat::Gif,
+      ImageOutputFormat::Tiff => takumi::rendering::ImageOutputFormat::Tiff,
+      ImageOutputFormat::Bmp => takumi::rendering::ImageOutputFormat::Bmp,
       ImageOutputFormat::Png | ImageOutputFormat::__Invalid => {
         takumi::rendering::ImageOutputFormat::Png
       }
@@ -83,17 +89,21 @@ pub struct Renderer {
 #[wasm_bindgen]
 impl Renderer {
   #[wasm_bindgen(constructor)]
-  pub fn new(debug: Option<bool>) -> Renderer {
-    Renderer {
-      context: GlobalContext {
-        draw_debug_border: debug.unwrap_or_default(),
-        ..Default::default()
-      },
+  pub fn new(debug: Option<bool>, glyph_cache_budget: Option<usize>) -> Renderer {
+    let context = GlobalContext {
+      debug: debug.unwrap_or_default().then(DebugOptions::default),
+      ..Default::default()
+    };
+
+    if let Some(budget) = glyph_cache_budget {
+      context.font_context.set_glyph_cache_budget(budget);
     }
+
+    Renderer { context }
   }
 
   #[wasm_bindgen(js_name = loadFontWithInfo)]
-  pub fn load_font_with_info(&self, font_data: FontInfo) {
+  pub fn load_font_with_info(&self, font_data: FontInfo) -> Result<(), JsError> {
     self
       .context
       .font_context
@@ -110,26 +120,39 @@ impl Renderer {
         }),
         None,
       )
-      .unwrap();
+      .map_err(|error| JsError::new(&format!("Failed to load font: {error:?}")))?;
+
+    Ok(())
   }
 
   #[wasm_bindgen(js_name = loadFont)]
-  pub fn load_font(&self, buffer: &[u8]) {
+  pub fn load_font(&self, buffer: &[u8]) -> Result<(), JsError> {
     self
       .context
       .font_context
       .load_and_store(buffer, None, None)
-      .unwrap();
+      .map_err(|error| JsError::new(&format!("Failed to load font: {error:?}")))?;
+
+    Ok(())
+  }
+
+  #[wasm_bindgen(js_name = loadedFamilies)]
+  pub fn loaded_families(&self) -> Result<JsValue, JsError> {
+    to_value(&self.context.font_context.loaded_families())
+      .map_err(|error| JsError::new(&format!("Failed to list font families: {error}")))
   }
 
   #[wasm_bindgen(js_name = putPersistentImage)]
-  pub fn put_persistent_image(&self, src: String, data: &[u8]) {
-    self.context.persistent_image_store.insert(
-      &src,
-      Arc::new(ImageSource::Bitmap(
-        load_from_memory(data).unwrap().into_rgba8(),
-      )),
-    );
+  pub fn put_persistent_image(&self, src: String, data: &[u8]) -> Result<(), JsError> {
+    let image = load_from_memory(data)
+      .map_err(|error| JsError::new(&format!("Failed to load image: {error}")))?;
+
+    self
+      .context
+      .persistent_image_store
+      .insert(&src, Arc::new(ImageSource::Bitmap(image.into_rgba8())));
+
+    Ok(())
   }
 
   #[wasm_bindgen(js_name = clearImageStore)]
@@ -138,6 +161,7 @@ impl Renderer {
   }
 
   #[wasm_bindgen]
+  #[allow(clippy::too_many_arguments)]
   pub fn render(
     &self,
     node: AnyNode,
@@ -145,12 +169,15 @@ impl Renderer {
     height: u32,
     format: Option<ImageOutputFormat>,
     quality: Option<u8>,
-  ) -> Vec<u8> {
-    let node = node.dyn_into().unwrap();
-    let node: NodeKind = from_value(node).unwrap();
+    progressive: Option<bool>,
+    lossless: Option<bool>,
+  ) -> Result<Vec<u8>, JsError> {
+    let node: NodeKind =
+      from_value(node.into()).map_err(|error| JsError::new(&format!("Invalid node: {error}")))?;
 
     let viewport = Viewport::new(width, height);
-    let image = render(viewport, &self.context, node).unwrap();
+    let image = render(viewport, &self.context, node)
+      .map_err(|error| JsError::new(&format!("Failed to render: {error}")))?;
 
     let mut buffer = Vec::new();
     let mut cursor = Cursor::new(&mut buffer);
@@ -159,14 +186,20 @@ impl Renderer {
       &image,
       &mut cursor,
       format.unwrap_or(ImageOutputFormat::Png).into(),
-      quality,
+      EncodeOptions {
+        quality,
+        progressive: progressive.unwrap_or_default(),
+        lossless: lossless.unwrap_or(true),
+        ..Default::default()
+      },
     )
-    .unwrap();
+    .map_err(|error| JsError::new(&format!("Failed to write the image: {error}")))?;
 
-    buffer
+    Ok(buffer)
   }
 
   #[wasm_bindgen(js_name = "renderAsDataUrl")]
+  #[allow(clippy::too_many_arguments)]
   pub fn render_as_data_url(
     &self,
     node: AnyNode,
@@ -174,8 +207,10 @@ impl Renderer {
     height: u32,
     format: Option<ImageOutputFormat>,
     quality: Option<u8>,
-  ) -> String {
-    let buffer = self.render(node, width, height, format, quality);
+    progressive: Option<bool>,
+    lossless: Option<bool>,
+  ) -> Result<String, JsError> {
+    let buffer = self.render(node, width, height, format, quality, progressive, lossless)?;
     let format: takumi::rendering::ImageOutputFormat =
       format.unwrap_or(ImageOutputFormat::Png).into();
 
@@ -186,6 +221,6 @@ impl Renderer {
     data_uri.push_str(";base64,");
     data_uri.push_str(&BASE64_STANDARD.encode(buffer));
 
-    data_uri
+    Ok(data_uri)
   }
 }
@@ -66,6 +66,24 @@ describe("setup", () => {
   });
 });
 
+describe("corrupt input", () => {
+  const corrupt = Buffer.from("not a font or an image");
+
+  test("loadFont throws on a corrupt font", () => {
+    expect(() => renderer.loadFont(corrupt)).toThrow("Failed to load font");
+  });
+
+  test("putPersistentImage throws on a corrupt image", () => {
+    expect(() => renderer.putPersistentImage("corrupt.png", corrupt)).toThrow(
+      "Failed to load image",
+    );
+  });
+
+  test("renderer is still usable afterwards", () => {
+    expect(renderer.loadedFamilies().length).toBeGreaterThan(0);
+  });
+});
+
 describe("render", () => {
   test("webp", () => {
     const result = renderer.render(node, 1200, 630, "webp");
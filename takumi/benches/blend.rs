@@ -0,0 +1,65 @@
+//! Compares compositing an image pixel by pixel with the row fast path.
+//!
+//! Run with `cargo bench -p takumi --features test-utils --bench blend`.
+
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use takumi::{
+  image::{Rgba, RgbaImage},
+  rendering::{blend_pixel, blend_row},
+};
+
+const WIDTH: u32 = 1920;
+const HEIGHT: u32 = 1080;
+
+/// A full-screen image with varying colors, like a photo, and the same `alpha` everywhere.
+fn create_image(alpha: u8) -> RgbaImage {
+  RgbaImage::from_fn(WIDTH, HEIGHT, |x, y| {
+    Rgba([
+      (x % 256) as u8,
+      (y % 256) as u8,
+      ((x + y) % 256) as u8,
+      alpha,
+    ])
+  })
+}
+
+fn bench_blend(c: &mut Criterion) {
+  let mut group = c.benchmark_group("blend");
+  let row_len = WIDTH as usize * 4;
+
+  for (name, alpha) in [("opaque", u8::MAX), ("translucent", 128)] {
+    let source = create_image(alpha);
+    let mut destination = create_image(u8::MAX);
+
+    group.bench_function(BenchmarkId::new("blend_pixel", name), |b| {
+      b.iter(|| {
+        for (destination, source) in destination.pixels_mut().zip(source.pixels()) {
+          blend_pixel(destination, *source);
+        }
+
+        black_box(&mut destination);
+      })
+    });
+
+    group.bench_function(BenchmarkId::new("blend_row", name), |b| {
+      b.iter(|| {
+        let rows = destination
+          .chunks_exact_mut(row_len)
+          .zip(source.chunks_exact(row_len));
+
+        for (destination, source) in rows {
+          blend_row(destination, source);
+        }
+
+        black_box(&mut destination);
+      })
+    });
+  }
+
+  group.finish();
+}
+
+criterion_group!(benches, bench_blend);
+criterion_main!(benches);
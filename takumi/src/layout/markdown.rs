@@ -0,0 +1,490 @@
+//! Converts Markdown documents into node trees.
+//!
+//! Inline text is split into words that wrap inside a flex container, so inline formatting like
+//! bold, italic, inline code and links can be mixed within the same line.
+
+use std::mem::take;
+
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+
+use crate::layout::{
+  node::{ContainerNode, NodeKind, TextNode},
+  style::{LengthUnit, Style, StyleBuilder},
+};
+
+/// Styles applied to the nodes generated from Markdown, the defaults resemble GitHub's.
+///
+/// Text properties are inherited as usual, so inline styles only need to set what they change.
+#[derive(Debug, Clone)]
+pub struct MarkdownStyles {
+  /// The container of the top-level blocks and blockquote contents.
+  pub block: Style,
+  /// The wrapping container of the words of a paragraph.
+  pub paragraph: Style,
+  /// The wrapping containers of the words of headings from level 1 to 6.
+  pub headings: [Style; 6],
+  /// The container of a blockquote.
+  pub blockquote: Style,
+  /// The container of a fenced or indented code block.
+  pub code_block: Style,
+  /// The container of the items of a list.
+  pub list: Style,
+  /// The row containing the marker and the content of a list item.
+  pub list_item: Style,
+  /// The container of the content of a list item.
+  pub list_item_content: Style,
+  /// A thematic break.
+  pub rule: Style,
+  /// `**strong**` text.
+  pub strong: Style,
+  /// `*emphasized*` text.
+  pub emphasis: Style,
+  /// `` `inline code` ``.
+  pub code: Style,
+  /// Link text.
+  pub link: Style,
+}
+
+impl Default for MarkdownStyles {
+  fn default() -> Self {
+    let css = |declarations: &str| Style::from_css_declarations(declarations).unwrap();
+    let heading = |font_size: &str| {
+      css(&format!(
+        "flex-wrap: wrap; gap: 0 0.25em; font-weight: 600; font-size: {font_size}"
+      ))
+    };
+
+    Self {
+      block: css("flex-direction: column; flex-grow: 1; gap: 0.75em"),
+      paragraph: css("flex-wrap: wrap; gap: 0 0.25em"),
+      headings: [
+        heading("2em"),
+        heading("1.5em"),
+        heading("1.25em"),
+        heading("1em"),
+        heading("0.875em"),
+        heading("0.85em"),
+      ],
+      blockquote: css("flex-direction: column; gap: 0.75em; padding: 0 1em; color: #59636e"),
+      code_block: css(
+        "padding: 1em; border-radius: 6px; background-color: #f6f8fa; font-family: monospace; font-size: 0.85em",
+      ),
+      list: css("flex-direction: column; gap: 0.25em"),
+      list_item: css("gap: 0.5em"),
+      list_item_content: css("flex-direction: column; flex-grow: 1; gap: 0.75em"),
+      rule: css("height: 0.25em; background-color: #d1d9e0"),
+      strong: css("font-weight: 600"),
+      emphasis: css("font-style: italic"),
+      code: css(
+        "padding: 0 0.2em; border-radius: 6px; background-color: #eff1f3; font-family: monospace; font-size: 0.85em",
+      ),
+      link: css("color: #0969da"),
+    }
+  }
+}
+
+/// Converts a Markdown document to a node tree using the default [`MarkdownStyles`].
+///
+/// `base_style` is applied to the root container, which is the place for the font, color and
+/// size of the document.
+pub fn markdown_to_node(md: &str, base_style: Style) -> NodeKind {
+  markdown_to_node_with_styles(md, base_style, &MarkdownStyles::default())
+}
+
+/// Converts a Markdown document to a node tree with custom styles.
+pub fn markdown_to_node_with_styles(
+  md: &str,
+  base_style: Style,
+  styles: &MarkdownStyles,
+) -> NodeKind {
+  let mut builder = MarkdownTreeBuilder {
+    styles,
+    frames: vec![Frame::new(FrameKind::Root)],
+    inline: Vec::new(),
+  };
+
+  for event in Parser::new(md) {
+    builder.handle_event(event);
+  }
+
+  while builder.frames.len() > 1 {
+    builder.end_frame();
+  }
+
+  let mut root = builder.frames.pop().unwrap();
+  root.flush_words(styles);
+
+  ContainerNode {
+    style: base_style,
+    children: Some(vec![
+      ContainerNode {
+        style: styles.block.clone(),
+        children: Some(root.children),
+      }
+      .into(),
+    ]),
+  }
+  .into()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Inline {
+  Strong,
+  Emphasis,
+  Code,
+  Link,
+}
+
+enum FrameKind {
+  Root,
+  Paragraph,
+  Heading(usize),
+  BlockQuote,
+  List { next_number: Option<u64> },
+  Item { marker: String },
+  CodeBlock { text: String },
+}
+
+/// A block that is being built.
+struct Frame {
+  kind: FrameKind,
+  /// Finished block children.
+  children: Vec<NodeKind>,
+  /// Finished words that haven't been placed in a paragraph yet.
+  words: Vec<NodeKind>,
+  /// Pieces of the current word, each with the inline formatting it was written with.
+  word: Vec<(Vec<Inline>, String)>,
+}
+
+impl Frame {
+  fn new(kind: FrameKind) -> Self {
+    Self {
+      kind,
+      children: Vec::new(),
+      words: Vec::new(),
+      word: Vec::new(),
+    }
+  }
+
+  fn end_word(&mut self, styles: &MarkdownStyles) {
+    let mut pieces = take(&mut self.word)
+      .into_iter()
+      .map(|(inline, text)| create_piece_node(styles, &inline, text));
+
+    let node = match pieces.len() {
+      0 => return,
+      1 => pieces.next().unwrap(),
+      _ => ContainerNode {
+        style: Style::default(),
+        children: Some(pieces.collect()),
+      }
+      .into(),
+    };
+
+    self.words.push(node);
+  }
+
+  /// Places loose words, like the content of tight list items, in a paragraph.
+  fn flush_words(&mut self, styles: &MarkdownStyles) {
+    self.end_word(styles);
+
+    if self.words.is_empty() {
+      return;
+    }
+
+    self.children.push(
+      ContainerNode {
+        style: styles.paragraph.clone(),
+        children: Some(take(&mut self.words)),
+      }
+      .into(),
+    );
+  }
+}
+
+/// Creates the node of a piece of text, nested inline styles are applied through wrapping
+/// containers so they combine by inheritance.
+fn create_piece_node(styles: &MarkdownStyles, inline: &[Inline], text: String) -> NodeKind {
+  let style_of = |inline: Inline| match inline {
+    Inline::Strong => styles.strong.clone(),
+    Inline::Emphasis => styles.emphasis.clone(),
+    Inline::Code => styles.code.clone(),
+    Inline::Link => styles.link.clone(),
+  };
+
+  let Some((innermost, outer)) = inline.split_last() else {
+    return TextNode {
+      style: Style::default(),
+      text,
+    }
+    .into();
+  };
+
+  outer.iter().rev().fold(
+    TextNode {
+      style: style_of(*innermost),
+      text,
+    }
+    .into(),
+    |node, inline| {
+      ContainerNode {
+        style: style_of(*inline),
+        children: Some(vec![node]),
+      }
+      .into()
+    },
+  )
+}
+
+struct MarkdownTreeBuilder<'a> {
+  styles: &'a MarkdownStyles,
+  frames: Vec<Frame>,
+  inline: Vec<Inline>,
+}
+
+impl MarkdownTreeBuilder<'_> {
+  fn top(&mut self) -> &mut Frame {
+    self.frames.last_mut().unwrap()
+  }
+
+  fn start_frame(&mut self, kind: FrameKind) {
+    let styles = self.styles;
+    self.top().flush_words(styles);
+    self.frames.push(Frame::new(kind));
+  }
+
+  fn end_frame(&mut self) {
+    let styles = self.styles;
+    let mut frame = self.frames.pop().unwrap();
+
+    let node: NodeKind = match frame.kind {
+      FrameKind::Paragraph | FrameKind::Heading(_) => {
+        frame.end_word(styles);
+
+        let style = match frame.kind {
+          FrameKind::Heading(level) => styles.headings[level - 1].clone(),
+          _ => styles.paragraph.clone(),
+        };
+
+        ContainerNode {
+          style,
+          children: Some(frame.words),
+        }
+        .into()
+      }
+      FrameKind::CodeBlock { text } => ContainerNode {
+        style: styles.code_block.clone(),
+        children: Some(vec![
+          TextNode {
+            style: Style::default(),
+            text: text.trim_end_matches('\n').to_string(),
+          }
+          .into(),
+        ]),
+      }
+      .into(),
+      FrameKind::Item { ref marker } => {
+        let marker = marker.clone();
+        frame.flush_words(styles);
+
+        ContainerNode {
+          style: styles.list_item.clone(),
+          children: Some(vec![
+            TextNode {
+              style: Style::default(),
+              text: marker,
+            }
+            .into(),
+            ContainerNode {
+              style: styles.list_item_content.clone(),
+              children: Some(frame.children),
+            }
+            .into(),
+          ]),
+        }
+        .into()
+      }
+      FrameKind::BlockQuote | FrameKind::List { .. } | FrameKind::Root => {
+        frame.flush_words(styles);
+
+        let style = match frame.kind {
+          FrameKind::BlockQuote => styles.blockquote.clone(),
+          FrameKind::List { .. } => styles.list.clone(),
+          _ => styles.block.clone(),
+        };
+
+        ContainerNode {
+          style,
+          children: Some(frame.children),
+        }
+        .into()
+      }
+    };
+
+    self.top().children.push(node);
+  }
+
+  fn push_text(&mut self, text: &str) {
+    let styles = self.styles;
+
+    for (index, part) in text.split(char::is_whitespace).enumerate() {
+      if index > 0 {
+        self.top().end_word(styles);
+      }
+
+      if !part.is_empty() {
+        self.push_piece(part);
+      }
+    }
+  }
+
+  fn push_piece(&mut self, text: &str) {
+    let inline = self.inline.clone();
+    let word = &mut self.top().word;
+
+    match word.last_mut() {
+      Some((last_inline, last_text)) if *last_inline == inline => last_text.push_str(text),
+      _ => word.push((inline, text.to_string())),
+    }
+  }
+
+  fn handle_event(&mut self, event: Event) {
+    let styles = self.styles;
+
+    match event {
+      Event::Start(tag) => match tag {
+        Tag::Paragraph => self.start_frame(FrameKind::Paragraph),
+        Tag::Heading { level, .. } => self.start_frame(FrameKind::Heading(level as usize)),
+        Tag::BlockQuote(_) => self.start_frame(FrameKind::BlockQuote),
+        Tag::CodeBlock(_) => self.start_frame(FrameKind::CodeBlock {
+          text: String::new(),
+        }),
+        Tag::List(start) => self.start_frame(FrameKind::List { next_number: start }),
+        Tag::Item => {
+          let marker = match &mut self.top().kind {
+            FrameKind::List {
+              next_number: Some(number),
+            } => {
+              *number += 1;
+              format!("{}.", *number - 1)
+            }
+            _ => "•".to_string(),
+          };
+
+          self.start_frame(FrameKind::Item { marker });
+        }
+        Tag::Strong => self.inline.push(Inline::Strong),
+        Tag::Emphasis => self.inline.push(Inline::Emphasis),
+        Tag::Link { .. } => self.inline.push(Inline::Link),
+        _ => {}
+      },
+      Event::End(tag) => match tag {
+        TagEnd::Paragraph
+        | TagEnd::Heading(_)
+        | TagEnd::BlockQuote(_)
+        | TagEnd::CodeBlock
+        | TagEnd::List(_)
+        | TagEnd::Item => self.end_frame(),
+        TagEnd::Strong | TagEnd::Emphasis | TagEnd::Link => {
+          self.inline.pop();
+        }
+        _ => {}
+      },
+      Event::Text(text) => {
+        if let FrameKind::CodeBlock { text: code } = &mut self.top().kind {
+          code.push_str(&text);
+        } else {
+          self.push_text(&text);
+        }
+      }
+      Event::Code(code) => {
+        self.inline.push(Inline::Code);
+        self.push_piece(&code);
+        self.inline.pop();
+      }
+      Event::SoftBreak => self.top().end_word(styles),
+      Event::HardBreak => {
+        let frame = self.top();
+        frame.end_word(styles);
+
+        // takes a whole line so the following words start on the next one
+        frame.words.push(
+          ContainerNode {
+            style: StyleBuilder::default()
+              .width(LengthUnit::Percentage(100.0))
+              .build()
+              .unwrap(),
+            children: None,
+          }
+          .into(),
+        );
+      }
+      Event::Rule => {
+        self.top().flush_words(styles);
+        self.top().children.push(
+          ContainerNode {
+            style: styles.rule.clone(),
+            children: None,
+          }
+          .into(),
+        );
+      }
+      _ => {}
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn texts(node: &NodeKind) -> Vec<String> {
+    match node {
+      NodeKind::Text(text) => vec![text.text.clone()],
+      NodeKind::Container(container) => container
+        .children
+        .iter()
+        .flatten()
+        .flat_map(texts)
+        .collect(),
+      NodeKind::Image(_) => Vec::new(),
+    }
+  }
+
+  fn blocks(node: &NodeKind) -> &[NodeKind] {
+    let NodeKind::Container(root) = node else {
+      unreachable!()
+    };
+    let NodeKind::Container(block) = &root.children.as_ref().unwrap()[0] else {
+      unreachable!()
+    };
+
+    block.children.as_deref().unwrap()
+  }
+
+  #[test]
+  fn test_markdown_words() {
+    let node = markdown_to_node("# Title\n\nHello **bold** and *it*alic", Style::default());
+
+    assert_eq!(blocks(&node).len(), 2);
+    assert_eq!(
+      texts(&node),
+      vec!["Title", "Hello", "bold", "and", "it", "alic"]
+    );
+  }
+
+  #[test]
+  fn test_markdown_lists() {
+    let node = markdown_to_node("3. first\n4. `a b`\n\n- item", Style::default());
+
+    assert_eq!(blocks(&node).len(), 2);
+    assert_eq!(texts(&node), vec!["3.", "first", "4.", "a b", "•", "item"]);
+  }
+
+  #[test]
+  fn test_markdown_code_block() {
+    let node = markdown_to_node("```\nlet a = 1;\n```", Style::default());
+
+    assert_eq!(texts(&node), vec!["let a = 1;"]);
+  }
+}
@@ -6,4 +6,8 @@ pub mod node;
 /// CSS-like styling system with colors, units, and properties
 pub mod style;
 
+/// Markdown to node tree conversion
+#[cfg(feature = "markdown")]
+pub mod markdown;
+
 pub use viewport::*;
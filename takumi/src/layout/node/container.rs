@@ -14,6 +14,7 @@ use crate::layout::{node::Node, style::Style};
 /// Container nodes are used to group other nodes and apply layout
 /// properties like flexbox layout to arrange their children.
 #[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ContainerNode<Nodes: Node<Nodes>> {
   /// The styling properties for this container
   #[serde(default)]
@@ -30,4 +31,8 @@ impl<Nodes: Node<Nodes>> Node<Nodes> for ContainerNode<Nodes> {
   fn get_style(&self) -> &Style {
     &self.style
   }
+
+  fn node_type(&self) -> &'static str {
+    "container"
+  }
 }
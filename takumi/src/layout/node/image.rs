@@ -6,13 +6,11 @@
 use serde::{Deserialize, Serialize};
 use taffy::{AvailableSpace, Layout, Size};
 
-#[cfg(feature = "image_data_uri")]
-use crate::resources::image::ImageResult;
 use crate::{
   GlobalContext,
   layout::{node::Node, style::Style},
   rendering::{Canvas, RenderContext, draw_image},
-  resources::image::{ImageResourceError, ImageSource, is_svg},
+  resources::image::{ImageResourceError, ImageResult, ImageSource, is_svg},
 };
 
 /// A node that renders image content.
@@ -20,6 +18,8 @@ use crate::{
 /// Image nodes display images loaded from URLs or file paths,
 /// with support for async loading and caching.
 #[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
 pub struct ImageNode {
   /// The styling properties for this image node
   #[serde(default)]
@@ -30,6 +30,11 @@ pub struct ImageNode {
   pub width: Option<f32>,
   /// The height of the image
   pub height: Option<f32>,
+  /// The frame of an animated GIF or WebP to draw, defaults to the first frame.
+  ///
+  /// Only applies to images decoded from data URIs, images from the image store
+  /// are drawn as the store loaded them, which is the first frame for the built-in stores.
+  pub frame_index: Option<u32>,
 }
 
 impl<Nodes: Node<Nodes>> Node<Nodes> for ImageNode {
@@ -47,7 +52,13 @@ impl<Nodes: Node<Nodes>> Node<Nodes> for ImageNode {
       return Size { width, height };
     }
 
-    let Ok(image) = resolve_image(&self.src, context.global) else {
+    // like the `width` and `height` attributes of `<img>`, the style takes precedence
+    let known_dimensions = Size {
+      width: known_dimensions.width.or(self.width),
+      height: known_dimensions.height.or(self.height),
+    };
+
+    let Ok(image) = resolve_image(&self.src, self.frame_index, context.global) else {
       return Size::zero();
     };
 
@@ -63,11 +74,16 @@ impl<Nodes: Node<Nodes>> Node<Nodes> for ImageNode {
       },
     };
 
-    measure_image(size, known_dimensions, available_space)
+    measure_image_with_aspect_ratio(
+      size,
+      context.style.aspect_ratio,
+      known_dimensions,
+      available_space,
+    )
   }
 
   fn draw_content(&self, context: &RenderContext, canvas: &Canvas, layout: Layout) {
-    let Ok(image) = resolve_image(&self.src, context.global) else {
+    let Ok(image) = resolve_image(&self.src, self.frame_index, context.global) else {
       return;
     };
 
@@ -77,6 +93,26 @@ impl<Nodes: Node<Nodes>> Node<Nodes> for ImageNode {
   fn has_draw_content(&self) -> bool {
     true
   }
+
+  fn check_resources(&self, context: &RenderContext) -> Result<(), crate::Error> {
+    // usually resolved when measuring already, the store returns the image or the remembered
+    // failure without fetching again
+    match resolve_image(&self.src, self.frame_index, context.global) {
+      Err(err @ ImageResourceError::FetchFailed { .. }) => {
+        Err(crate::Error::ImageResolveError(err))
+      }
+      _ => Ok(()),
+    }
+  }
+
+  fn paints_box_sized_image(&self, _context: &RenderContext) -> bool {
+    // most `object-fit` values resize the image to the content box before it is drawn
+    true
+  }
+
+  fn node_type(&self) -> &'static str {
+    "image"
+  }
 }
 
 const DATA_URI_PREFIX: &str = "data:";
@@ -86,8 +122,10 @@ fn is_data_uri(src: &str) -> bool {
 }
 
 #[cfg(feature = "image_data_uri")]
-fn parse_data_uri_image(src: &str) -> ImageResult {
-  use crate::resources::image::load_image_source_from_bytes;
+fn parse_data_uri_image(src: &str, frame_index: u32) -> ImageResult {
+  use crate::resources::image::{
+    load_image_frame_from_bytes, load_image_source_from_bytes_with_content_type,
+  };
   use base64::{Engine as _, engine::general_purpose};
 
   let comma_pos = src
@@ -97,21 +135,60 @@ fn parse_data_uri_image(src: &str) -> ImageResult {
   let metadata = &src[DATA_URI_PREFIX.len()..comma_pos];
   let data = &src[comma_pos + 1..];
 
-  if !metadata.contains("base64") {
-    return Err(ImageResourceError::InvalidDataUriFormat);
+  // the media type picks the decoder like a `Content-Type` header, frames are always sniffed
+  let load = |image_bytes: &[u8]| {
+    if frame_index == 0 {
+      load_image_source_from_bytes_with_content_type(image_bytes, Some(metadata))
+    } else {
+      load_image_frame_from_bytes(image_bytes, frame_index)
+    }
+  };
+
+  // `data:[<mediatype>][;base64],<data>`, without `;base64` the payload is percent-encoded
+  if !metadata.split(';').any(|param| param == "base64") {
+    let image_bytes = percent_decode(data).ok_or(ImageResourceError::MalformedDataUri)?;
+
+    return load(&image_bytes);
   }
 
   let image_bytes = general_purpose::STANDARD
-    .decode(data)
+    .decode(data.trim())
     .map_err(|_| ImageResourceError::MalformedDataUri)?;
 
-  load_image_source_from_bytes(&image_bytes)
+  load(&image_bytes)
 }
 
-fn resolve_image(src: &str, context: &GlobalContext) -> ImageResult {
+/// Decodes `%XX` escapes of a percent-encoded string, returns `None` for invalid escapes.
+#[cfg(feature = "image_data_uri")]
+fn percent_decode(data: &str) -> Option<Vec<u8>> {
+  let bytes = data.as_bytes();
+  let mut decoded = Vec::with_capacity(bytes.len());
+  let mut i = 0;
+
+  while i < bytes.len() {
+    if bytes[i] == b'%' {
+      let hex = data.get(i + 1..i + 3)?;
+      decoded.push(u8::from_str_radix(hex, 16).ok()?);
+      i += 3;
+    } else {
+      decoded.push(bytes[i]);
+      i += 1;
+    }
+  }
+
+  Some(decoded)
+}
+
+/// Resolves an image from a data uri, an inline svg or the image store.
+#[cfg_attr(not(feature = "image_data_uri"), allow(unused_variables))]
+pub(crate) fn resolve_image(
+  src: &str,
+  frame_index: Option<u32>,
+  context: &GlobalContext,
+) -> ImageResult {
   if is_data_uri(src) {
     #[cfg(feature = "image_data_uri")]
-    return parse_data_uri_image(src);
+    return parse_data_uri_image(src, frame_index.unwrap_or_default());
     #[cfg(not(feature = "image_data_uri"))]
     return Err(ImageResourceError::DataUriParseNotSupported);
   }
@@ -127,7 +204,7 @@ fn resolve_image(src: &str, context: &GlobalContext) -> ImageResult {
     return Ok(img);
   }
 
-  Err(ImageResourceError::Unknown)
+  context.persistent_image_store.fetch(src)
 }
 
 /// Measures the size of image based on known dimensions and available space.
@@ -135,6 +212,21 @@ pub fn measure_image(
   image_size: Size<f32>,
   known_dimensions: Size<Option<f32>>,
   available_space: Size<AvailableSpace>,
+) -> Size<f32> {
+  measure_image_with_aspect_ratio(image_size, None, known_dimensions, available_space)
+}
+
+/// Measures the size of image like [`measure_image`], preferring a declared `aspect-ratio`.
+///
+/// As in CSS, a declared ratio takes precedence over the image's intrinsic ratio when
+/// deriving a missing dimension, `width: 200px; aspect-ratio: 16 / 9` is 112.5px tall
+/// whatever the image's own ratio is. Without any known dimension the intrinsic width is
+/// kept and the height follows the declared ratio.
+pub fn measure_image_with_aspect_ratio(
+  image_size: Size<f32>,
+  declared_aspect_ratio: Option<f32>,
+  known_dimensions: Size<Option<f32>>,
+  available_space: Size<AvailableSpace>,
 ) -> Size<f32> {
   let mut width = known_dimensions.width;
   let mut height = known_dimensions.height;
@@ -147,7 +239,12 @@ pub fn measure_image(
   }
 
   // If only one dimension is specified, calculate the other maintaining aspect ratio
-  let aspect_ratio = if image_size.height != 0.0 {
+  let declared_aspect_ratio =
+    declared_aspect_ratio.filter(|ratio| ratio.is_finite() && *ratio > 0.0);
+
+  let aspect_ratio = if let Some(ratio) = declared_aspect_ratio {
+    ratio
+  } else if image_size.height != 0.0 {
     image_size.width / image_size.height
   } else {
     1.0
@@ -168,7 +265,10 @@ pub fn measure_image(
   // If neither dimension is specified, use intrinsic size but constrain to available space
   if width.is_none() && height.is_none() {
     width = Some(image_size.width);
-    height = Some(image_size.height);
+    height = Some(match declared_aspect_ratio {
+      Some(ratio) => image_size.width / ratio,
+      None => image_size.height,
+    });
   }
 
   let mut final_width = width.unwrap();
@@ -215,9 +315,133 @@ pub fn measure_image(
 
 #[cfg(test)]
 mod tests {
+  use std::sync::Arc;
+
+  use image::RgbaImage;
   use taffy::{AvailableSpace, geometry::Size};
 
   use super::*;
+  use crate::resources::image::ImageStore;
+
+  // A store that generates images on demand instead of holding them
+  struct GeneratedImageStore;
+
+  impl ImageStore for GeneratedImageStore {
+    fn get(&self, _src: &str) -> Option<Arc<ImageSource>> {
+      None
+    }
+
+    fn insert(&self, _src: &str, _image: Arc<ImageSource>) {}
+
+    fn clear(&self) {}
+
+    fn fetch(&self, src: &str) -> ImageResult {
+      match src {
+        "generated://square" => Ok(Arc::new(RgbaImage::new(32, 32).into())),
+        _ => Err(ImageResourceError::Unknown),
+      }
+    }
+  }
+
+  #[cfg(feature = "image_data_uri")]
+  #[test]
+  fn test_resolve_base64_data_uri() {
+    use std::io::Cursor;
+
+    use base64::{Engine as _, engine::general_purpose};
+    use image::ImageFormat;
+
+    let mut png = Cursor::new(Vec::new());
+    RgbaImage::new(3, 2)
+      .write_to(&mut png, ImageFormat::Png)
+      .unwrap();
+
+    let src = format!(
+      "data:image/png;base64,{}",
+      general_purpose::STANDARD.encode(png.get_ref())
+    );
+
+    let image = resolve_image(&src, None, &GlobalContext::default()).unwrap();
+    assert_eq!(image.size(), (3.0, 2.0));
+  }
+
+  #[cfg(all(feature = "image_data_uri", feature = "svg"))]
+  #[test]
+  fn test_resolve_percent_encoded_svg_data_uri() {
+    let src = "data:image/svg+xml;charset=utf-8,%3Csvg%20xmlns=%22http://www.w3.org/2000/svg%22%20width=%2240%22%20height=%2220%22%3E%3C/svg%3E";
+
+    let image = resolve_image(src, None, &GlobalContext::default()).unwrap();
+    assert_eq!(image.size(), (40.0, 20.0));
+  }
+
+  #[cfg(feature = "svg")]
+  #[test]
+  fn test_svg_intrinsic_ratio_from_view_box() {
+    let svg = |attributes: &str| {
+      format!(r#"<svg xmlns="http://www.w3.org/2000/svg" {attributes} viewBox="0 0 100 50"></svg>"#)
+    };
+
+    let context = GlobalContext::default();
+
+    let image = resolve_image(&svg(""), None, &context).unwrap();
+    assert_eq!(image.size(), (100.0, 50.0));
+
+    // a width-only constraint derives the height from the view box
+    let (width, height) = image.size();
+    let result = measure_image(
+      create_image_size(width, height),
+      create_known_dimensions(Some(200.0), None),
+      create_available_space(AvailableSpace::MaxContent, AvailableSpace::MaxContent),
+    );
+
+    assert_eq!(
+      result,
+      Size {
+        width: 200.0,
+        height: 100.0
+      }
+    );
+
+    // a single size attribute keeps the ratio too
+    let image = resolve_image(&svg(r#"width="200""#), None, &context).unwrap();
+    assert_eq!(image.size(), (200.0, 100.0));
+
+    let image = resolve_image(&svg(r#"height="100px""#), None, &context).unwrap();
+    assert_eq!(image.size(), (200.0, 100.0));
+
+    // both attributes win over the view box
+    let image = resolve_image(&svg(r#"width="40" height="40""#), None, &context).unwrap();
+    assert_eq!(image.size(), (40.0, 40.0));
+  }
+
+  #[cfg(feature = "image_data_uri")]
+  #[test]
+  fn test_resolve_malformed_data_uri() {
+    assert!(matches!(
+      resolve_image("data:image/png;base64,@@@", None, &GlobalContext::default()),
+      Err(ImageResourceError::MalformedDataUri)
+    ));
+    assert!(matches!(
+      resolve_image("data:image/png", None, &GlobalContext::default()),
+      Err(ImageResourceError::InvalidDataUriFormat)
+    ));
+  }
+
+  #[test]
+  fn test_resolve_image_from_custom_store() {
+    let context = GlobalContext {
+      persistent_image_store: Box::new(GeneratedImageStore),
+      ..Default::default()
+    };
+
+    let image = resolve_image("generated://square", None, &context).unwrap();
+    assert_eq!(image.size(), (32.0, 32.0));
+
+    assert!(matches!(
+      resolve_image("generated://missing", None, &context),
+      Err(ImageResourceError::Unknown)
+    ));
+  }
 
   // Helper function to create image size for testing
   fn create_image_size(width: f32, height: f32) -> Size<f32> {
@@ -337,6 +561,60 @@ mod tests {
     assert_eq!(result.height, 20.0); // 400 / 20 = 20
   }
 
+  #[test]
+  fn test_measure_image_declared_aspect_ratio_wins() {
+    let available_space =
+      create_available_space(AvailableSpace::MaxContent, AvailableSpace::MaxContent);
+
+    let result = measure_image_with_aspect_ratio(
+      create_image_size(500.0, 500.0),
+      Some(16.0 / 9.0),
+      create_known_dimensions(Some(200.0), None),
+      available_space,
+    );
+    assert_eq!(result.width, 200.0);
+    assert_eq!(result.height, 112.5);
+
+    let result = measure_image_with_aspect_ratio(
+      create_image_size(500.0, 500.0),
+      Some(2.0),
+      create_known_dimensions(None, None),
+      available_space,
+    );
+    assert_eq!(result.width, 500.0);
+    assert_eq!(result.height, 250.0);
+  }
+
+  #[test]
+  fn test_image_node_width_attribute_with_aspect_ratio() {
+    use crate::{
+      layout::{Viewport, node::NodeKind, style::StyleBuilder},
+      rendering::compute_layout_tree,
+    };
+
+    let context = GlobalContext {
+      persistent_image_store: Box::new(GeneratedImageStore),
+      ..Default::default()
+    };
+
+    let node: NodeKind = ImageNode {
+      style: StyleBuilder::default()
+        .aspect_ratio(Some(16.0 / 9.0))
+        .build()
+        .unwrap(),
+      src: "generated://square".to_string(),
+      width: Some(160.0),
+      height: None,
+      frame_index: None,
+    }
+    .into();
+
+    let tree = compute_layout_tree(Viewport::new(400, 400), &context, node);
+    let size = tree.get(&[]).unwrap().size;
+
+    assert_eq!((size.width, size.height), (160.0, 90.0));
+  }
+
   #[test]
   fn test_measure_image_zero_dimensions() {
     let result = measure_image(
@@ -6,15 +6,18 @@ pub use container::*;
 pub use image::*;
 pub use text::*;
 
+use std::sync::Arc;
+
 use serde::{Deserialize, Serialize};
 use taffy::{AvailableSpace, Layout, Point, Size};
 use zeno::Mask;
 
 use crate::{
-  layout::style::Style,
+  layout::style::{ColorInput, Gradient, ImageScalingAlgorithm, OutlineStyle, Style},
   rendering::{
-    BorderProperties, Canvas, RenderContext, SizedShadow, draw_background_layers, draw_border,
-    resolve_layers_tiles,
+    BorderProperties, Canvas, RenderContext, SizedFilter, SizedShadow,
+    compose_blended_background_layers, compose_layers_tiles, draw_background_layers, draw_border,
+    resolve_indexed_layers_tiles, resolve_layers_tiles,
   },
 };
 
@@ -45,6 +48,12 @@ macro_rules! impl_node_enum {
         }
       }
 
+      fn first_baseline(&self, context: &$crate::rendering::RenderContext, layout: $crate::taffy::Layout) -> Option<f32> {
+        match self {
+          $( $name::$variant(inner) => <_ as $crate::layout::node::Node<$name>>::first_baseline(inner, context, layout), )*
+        }
+      }
+
       fn draw_on_canvas(&self, context: &$crate::rendering::RenderContext, canvas: &$crate::rendering::Canvas, layout: $crate::taffy::Layout) {
         match self {
           $( $name::$variant(inner) => <_ as $crate::layout::node::Node<$name>>::draw_on_canvas(inner, context, canvas, layout), )*
@@ -69,6 +78,12 @@ macro_rules! impl_node_enum {
         }
       }
 
+      fn draw_outline(&self, context: &$crate::rendering::RenderContext, canvas: &$crate::rendering::Canvas, layout: $crate::taffy::Layout) {
+        match self {
+          $( $name::$variant(inner) => <_ as $crate::layout::node::Node<$name>>::draw_outline(inner, context, canvas, layout), )*
+        }
+      }
+
       fn draw_outset_box_shadow(&self, context: &$crate::rendering::RenderContext, canvas: &$crate::rendering::Canvas, layout: $crate::taffy::Layout) {
         match self {
           $( $name::$variant(inner) => <_ as $crate::layout::node::Node<$name>>::draw_outset_box_shadow(inner, context, canvas, layout), )*
@@ -86,6 +101,24 @@ macro_rules! impl_node_enum {
           $( $name::$variant(inner) => <_ as $crate::layout::node::Node<$name>>::has_draw_content(inner), )*
         }
       }
+
+      fn check_resources(&self, context: &$crate::rendering::RenderContext) -> Result<(), $crate::Error> {
+        match self {
+          $( $name::$variant(inner) => <_ as $crate::layout::node::Node<$name>>::check_resources(inner, context), )*
+        }
+      }
+
+      fn paints_box_sized_image(&self, context: &$crate::rendering::RenderContext) -> bool {
+        match self {
+          $( $name::$variant(inner) => <_ as $crate::layout::node::Node<$name>>::paints_box_sized_image(inner, context), )*
+        }
+      }
+
+      fn node_type(&self) -> &'static str {
+        match self {
+          $( $name::$variant(inner) => <_ as $crate::layout::node::Node<$name>>::node_type(inner), )*
+        }
+      }
     }
 
     $(
@@ -124,20 +157,46 @@ pub trait Node<N: Node<N>>: Send + Sync + Clone {
     Size::ZERO
   }
 
+  /// Returns the distance from the top of the border box to the first baseline of the content,
+  /// used to align the items of a flex row with `align-items: baseline`.
+  ///
+  /// Nodes without one are aligned on the bottom of their box, containers on the first baseline
+  /// of their first child.
+  fn first_baseline(&self, _context: &RenderContext, _layout: Layout) -> Option<f32> {
+    None
+  }
+
   /// Draws the node onto the canvas using the computed layout.
   fn draw_on_canvas(&self, context: &RenderContext, canvas: &Canvas, layout: Layout) {
+    self.draw_backdrop_filter(context, canvas, layout);
     self.draw_outset_box_shadow(context, canvas, layout);
     self.draw_background_color(context, canvas, layout);
     self.draw_background_image(context, canvas, layout);
     self.draw_inset_box_shadow(context, canvas, layout);
     self.draw_border(context, canvas, layout);
+    self.draw_outline(context, canvas, layout);
     self.draw_content(context, canvas, layout);
   }
 
+  /// Filters what is already painted under the node, like CSS `backdrop-filter`.
+  fn draw_backdrop_filter(&self, context: &RenderContext, canvas: &Canvas, layout: Layout) {
+    if let Some(backdrop_filter) = context.style.backdrop_filter.as_ref() {
+      canvas.backdrop_filter(
+        SizedFilter::from_filters(backdrop_filter, context, layout.size),
+        Point {
+          x: layout.location.x as i32,
+          y: layout.location.y as i32,
+        },
+        context.border_properties(&layout),
+        context.transform,
+      );
+    }
+  }
+
   /// Draws the outset box shadow of the node.
   fn draw_outset_box_shadow(&self, context: &RenderContext, canvas: &Canvas, layout: Layout) {
     if let Some(box_shadow) = context.style.box_shadow.as_ref() {
-      let border_radius = BorderProperties::from_context(context, &layout);
+      let border_radius = context.border_properties(&layout);
 
       for shadow in box_shadow.0.iter() {
         if shadow.inset {
@@ -151,13 +210,18 @@ pub trait Node<N: Node<N>>: Send + Sync + Clone {
         border_radius
           .expand_by(shadow.spread_radius)
           .append_mask_commands(&mut paths);
-        border_radius.transform.apply_on_paths(&mut paths);
 
         context.transform.apply_on_paths(&mut paths);
 
         let (mask, placement) = Mask::new(&paths).render();
 
-        shadow.draw_outset(canvas, mask.into(), placement, layout.location);
+        // the spread grows the box before the transform, the rest is drawn in image pixels
+        shadow.scaled(context.viewport.scale_factor).draw_outset(
+          canvas,
+          mask.into(),
+          placement,
+          layout.location,
+        );
       }
     }
   }
@@ -165,7 +229,7 @@ pub trait Node<N: Node<N>>: Send + Sync + Clone {
   /// Draws the inset box shadow of the node.
   fn draw_inset_box_shadow(&self, context: &RenderContext, canvas: &Canvas, layout: Layout) {
     if let Some(box_shadow) = context.style.box_shadow.as_ref() {
-      let border_radius = BorderProperties::from_context(context, &layout);
+      let border_radius = context.border_properties(&layout);
 
       for shadow in box_shadow.0.iter() {
         if !shadow.inset {
@@ -180,7 +244,12 @@ pub trait Node<N: Node<N>>: Send + Sync + Clone {
 
   /// Draws the background color of the node.
   fn draw_background_color(&self, context: &RenderContext, canvas: &Canvas, layout: Layout) {
-    let radius = BorderProperties::from_context(context, &layout);
+    // blended backgrounds paint the color along with the layers in `draw_background_image`
+    if context.style.has_blended_backgrounds() {
+      return;
+    }
+
+    let radius = context.border_properties(&layout);
 
     canvas.fill_color(
       Point {
@@ -197,24 +266,78 @@ pub trait Node<N: Node<N>>: Send + Sync + Clone {
     );
   }
 
-  /// Draws the background image(s) of the node.
+  /// Draws the background image(s) of the node, followed by the background noise.
   fn draw_background_image(&self, context: &RenderContext, canvas: &Canvas, layout: Layout) {
-    let Some(background_image) = context.style.background_image.as_ref() else {
+    let mut tiles = context
+      .style
+      .background_image
+      .as_ref()
+      .map(|background_image| {
+        resolve_indexed_layers_tiles(
+          background_image,
+          context.style.background_position.as_ref(),
+          context.style.background_size.as_ref(),
+          context.style.background_repeat.as_ref(),
+          context,
+          layout,
+        )
+      })
+      .unwrap_or_default();
+
+    if let Some(noise) = context.style.background_noise.as_ref() {
+      let (width, height) = (layout.size.width as u32, layout.size.height as u32);
+      let index = context
+        .style
+        .background_image
+        .as_ref()
+        .map_or(0, |images| images.0.len());
+
+      if width > 0 && height > 0 {
+        tiles.push((
+          index,
+          (noise.to_image(width, height, context), vec![0], vec![0]),
+        ));
+      }
+    }
+
+    if let Some(blend_modes) = context
+      .style
+      .background_blend_mode
+      .as_ref()
+      .filter(|_| context.style.has_blended_backgrounds())
+    {
+      let border = context.border_properties(&layout);
+
+      let composed = compose_blended_background_layers(
+        tiles,
+        blend_modes,
+        context.style.background_color,
+        border.inset_by_border_width(),
+        layout.size.width as u32,
+        layout.size.height as u32,
+      );
+
+      canvas.overlay_image(
+        Arc::new(composed),
+        Point {
+          x: layout.location.x as i32,
+          y: layout.location.y as i32,
+        },
+        border,
+        context.transform,
+        ImageScalingAlgorithm::Auto,
+      );
+
       return;
-    };
+    }
 
-    let tiles = resolve_layers_tiles(
-      background_image,
-      context.style.background_position.as_ref(),
-      context.style.background_size.as_ref(),
-      context.style.background_repeat.as_ref(),
-      context,
-      layout,
-    );
+    if tiles.is_empty() {
+      return;
+    }
 
     draw_background_layers(
-      tiles,
-      BorderProperties::from_context(context, &layout).inset_by_border_width(),
+      tiles.into_iter().map(|(_, tiles)| tiles).collect(),
+      context.border_properties(&layout).inset_by_border_width(),
       context,
       canvas,
       layout,
@@ -231,18 +354,94 @@ pub trait Node<N: Node<N>>: Send + Sync + Clone {
     false
   }
 
+  /// Returns the name of the node type, used in layout reports.
+  fn node_type(&self) -> &'static str {
+    "node"
+  }
+
+  /// Checks that the resources used by the node can be loaded before painting.
+  ///
+  /// Returning an error aborts [`render`](crate::rendering::render).
+  fn check_resources(&self, _context: &RenderContext) -> Result<(), crate::Error> {
+    Ok(())
+  }
+
+  /// Returns true if painting the node allocates an image the size of its box, like for
+  /// background, mask and border images. Such nodes are checked against
+  /// [`GlobalContext::max_canvas_pixels`](crate::GlobalContext::max_canvas_pixels) before painting.
+  fn paints_box_sized_image(&self, context: &RenderContext) -> bool {
+    let style = &context.style;
+
+    style.background_image.is_some()
+      || style.background_noise.is_some()
+      || style.mask_image.is_some()
+      || style.border_image.is_some()
+  }
+
   /// Draws the border of the node.
   fn draw_border(&self, context: &RenderContext, canvas: &Canvas, layout: Layout) {
+    let fill = context.style.border_image.as_ref().map(|images| {
+      compose_layers_tiles(
+        resolve_layers_tiles(images, None, None, None, context, layout),
+        layout.size.width as u32,
+        layout.size.height as u32,
+      )
+    });
+
     draw_border(
       canvas,
       layout.location,
-      BorderProperties::from_context(context, &layout),
+      context.border_properties(&layout),
+      fill.as_ref(),
+    );
+  }
+
+  /// Draws the outline of the node outside of its border box, it doesn't take up layout space.
+  fn draw_outline(&self, context: &RenderContext, canvas: &Canvas, layout: Layout) {
+    let outline = context.style.resolved_outline();
+
+    if outline.style == OutlineStyle::None {
+      return;
+    }
+
+    let width = outline.width.resolve_to_px(context, layout.size.width);
+
+    if width <= 0.0 {
+      return;
+    }
+
+    let offset = context
+      .style
+      .outline_offset
+      .resolve_to_px(context, layout.size.width);
+
+    // the outer edge of the outline, drawn as a border so it follows the border radius
+    let border = context.border_properties(&layout).expand_by(offset + width);
+
+    draw_border(
+      canvas,
+      layout.location,
+      BorderProperties {
+        width: taffy::Rect {
+          left: width,
+          right: width,
+          top: width,
+          bottom: width,
+        },
+        color: outline
+          .color
+          .unwrap_or(ColorInput::CurrentColor)
+          .resolve(context.style.color),
+        ..border
+      },
+      None,
     );
   }
 }
 
 /// Represents the nodes enum.
 #[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type", rename_all = "camelCase")]
 pub enum NodeKind {
   /// A node that contains other nodes.
@@ -259,3 +458,38 @@ impl_node_enum!(
   Image => ImageNode,
   Text => TextNode
 );
+
+/// Generates a JSON Schema describing the JSON form of [`NodeKind`], including all styling properties.
+///
+/// Editors can use it to autocomplete and validate node trees before rendering them.
+#[cfg(feature = "schema")]
+pub fn node_schema() -> serde_json::Value {
+  schemars::schema_for!(NodeKind).to_value()
+}
+
+#[cfg(all(test, feature = "schema"))]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_node_schema_flex_direction() {
+    let schema = node_schema();
+    let definitions = &schema["$defs"];
+
+    let flex_direction = definitions["FlexDirection"]["oneOf"]
+      .as_array()
+      .unwrap()
+      .iter()
+      .map(|variant| variant["const"].as_str().unwrap())
+      .collect::<Vec<_>>();
+
+    assert_eq!(
+      flex_direction,
+      ["row", "column", "row-reverse", "column-reverse"]
+    );
+    assert_eq!(
+      definitions["Style"]["properties"]["flexDirection"]["$ref"],
+      "#/$defs/CssValue_for_FlexDirection"
+    );
+  }
+}
@@ -13,7 +13,8 @@ use crate::{
     style::{SizedFontStyle, Style},
   },
   rendering::{
-    Canvas, MaxHeight, RenderContext, apply_text_transform, create_text_layout, draw_text,
+    Canvas, MaxHeight, RenderContext, apply_text_transform, create_initial_letter,
+    create_text_layout, draw_text, resolve_hyphens,
   },
 };
 
@@ -22,6 +23,7 @@ use crate::{
 /// Text nodes display text with configurable font properties,
 /// alignment, and styling options.
 #[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct TextNode {
   /// The styling properties for this text node
   #[serde(default)]
@@ -54,15 +56,40 @@ impl<Nodes: Node<Nodes>> Node<Nodes> for TextNode {
     )
   }
 
+  fn first_baseline(&self, context: &RenderContext, layout: Layout) -> Option<f32> {
+    let baseline = measure_text_baseline(
+      context.global,
+      &self.text,
+      context.style.to_sized_font_style(context),
+      layout.content_box_width(),
+    )?;
+
+    Some(layout.border.top + layout.padding.top + baseline)
+  }
+
   fn has_draw_content(&self) -> bool {
     true
   }
+
+  fn check_resources(&self, context: &RenderContext) -> Result<(), crate::Error> {
+    if !self.text.trim().is_empty() && !context.global.font_context.has_fonts() {
+      return Err(crate::Error::FontMissing);
+    }
+
+    Ok(())
+  }
+
+  fn node_type(&self) -> &'static str {
+    "text"
+  }
 }
 
 /// Measures the size of text based on font style and available space.
 ///
 /// This function handles text wrapping, line height, and respects both explicit
 /// dimensions and available space constraints.
+///
+/// With a vertical `writing-mode` the lines are measured along the height instead.
 pub(crate) fn measure_text(
   global: &GlobalContext,
   text: &str,
@@ -80,6 +107,24 @@ pub(crate) fn measure_text(
     };
   }
 
+  // vertical text is laid out as horizontal lines and rotated when drawn
+  let vertical = style.parent.writing_mode.is_vertical();
+
+  let (known_dimensions, available_space) = if vertical {
+    (
+      Size {
+        width: known_dimensions.height,
+        height: known_dimensions.width,
+      },
+      Size {
+        width: available_space.height,
+        height: available_space.width,
+      },
+    )
+  } else {
+    (known_dimensions, available_space)
+  };
+
   let width_constraint = known_dimensions.width.or(match available_space.width {
     AvailableSpace::MinContent => Some(0.0),
     AvailableSpace::MaxContent => None,
@@ -100,6 +145,17 @@ pub(crate) fn measure_text(
   };
 
   let text = apply_text_transform(text, style.parent.text_transform);
+  let (initial_letter, text, style) = match create_initial_letter(&text, &style, global) {
+    Some((letter, rest, rest_style)) => (Some(letter.size), rest, rest_style),
+    None => (None, &*text, style),
+  };
+  let text = resolve_hyphens(
+    text,
+    &style,
+    global,
+    width_constraint.unwrap_or(f32::MAX),
+    height_constraint_with_max_lines,
+  );
 
   let buffer = create_text_layout(
     &text,
@@ -120,12 +176,59 @@ pub(crate) fn measure_text(
         )
       });
 
-  taffy::Size {
+  // negative spacing is taken after the last glyph of a line too, which would cut it off
+  let max_run_width = max_run_width - style.letter_spacing.unwrap_or_default().min(0.0);
+
+  // the drop cap doesn't take part in the lines, a short text still makes room for it
+  let (max_run_width, total_height) = match initial_letter {
+    Some(letter) => (
+      max_run_width.max(letter.width),
+      total_height.max(letter.height),
+    ),
+    None => (max_run_width, total_height),
+  };
+
+  let size = taffy::Size {
     width: max_run_width
       .ceil()
       .min(width_constraint.unwrap_or(f32::MAX)),
     height: total_height.ceil(),
+  };
+
+  if vertical {
+    return Size {
+      width: size.height,
+      height: size.width,
+    };
+  }
+
+  size
+}
+
+/// Measures the baseline of the first line of text laid out in the width, from the top of the
+/// first line.
+///
+/// Returns `None` for empty and vertical text, which has no horizontal baseline.
+pub(crate) fn measure_text_baseline(
+  global: &GlobalContext,
+  text: &str,
+  style: SizedFontStyle,
+  width: f32,
+) -> Option<f32> {
+  if text.trim().is_empty() || style.parent.writing_mode.is_vertical() {
+    return None;
   }
+
+  let text = apply_text_transform(text, style.parent.text_transform);
+  let (text, style) = match create_initial_letter(&text, &style, global) {
+    Some((_, rest, rest_style)) => (rest, rest_style),
+    None => (&*text, style),
+  };
+  let text = resolve_hyphens(text, &style, global, width, None);
+
+  let layout = create_text_layout(&text, &style, global, width, None);
+
+  layout.lines().next().map(|line| line.metrics().baseline)
 }
 
 #[cfg(test)]
@@ -138,7 +241,7 @@ mod tests {
     layout::{
       DEFAULT_FONT_SIZE, DEFAULT_LINE_HEIGHT_SCALER, Viewport,
       node::measure_text,
-      style::{Affine, InheritedStyle, LengthUnit, LineHeight, SizedFontStyle},
+      style::{Affine, InheritedStyle, LengthUnit, LineHeight, SizedFontStyle, WritingMode},
     },
     rendering::RenderContext,
   };
@@ -168,11 +271,13 @@ mod tests {
       parent_font_size: DEFAULT_FONT_SIZE,
       viewport: Viewport {
         width: VIEWPORT_WIDTH,
-        height: VIEWPORT_HEIGHT,
+        height: Some(VIEWPORT_HEIGHT),
         font_size: DEFAULT_FONT_SIZE,
+        scale_factor: 1.0,
       },
       transform: Affine::identity(),
       style: InheritedStyle::default(),
+      border: None,
     }
   }
 
@@ -387,7 +492,7 @@ mod tests {
   fn test_measure_text_with_different_line_height() {
     let context = create_test_context();
     let parent = InheritedStyle {
-      line_height: LineHeight(LengthUnit::Em(1.5)),
+      line_height: LineHeight::Length(LengthUnit::Em(1.5)),
       ..Default::default()
     };
 
@@ -404,6 +509,101 @@ mod tests {
     assert_eq!(result.height, DEFAULT_FONT_SIZE * 1.5);
   }
 
+  #[test]
+  fn test_measure_text_with_text_indent() {
+    let context = create_test_context();
+    let measure = |text_indent: Option<LengthUnit>| {
+      let parent = InheritedStyle {
+        text_indent,
+        ..Default::default()
+      };
+
+      measure_text_with_style(
+        "Indent",
+        parent.to_sized_font_style(&context),
+        None,
+        None,
+        AvailableSpace::MaxContent,
+        AvailableSpace::MaxContent,
+      )
+    };
+
+    let plain = measure(None);
+    let indented = measure(Some(LengthUnit::Px(40.0)));
+    let hanging = measure(Some(LengthUnit::Px(-10.0)));
+
+    // the first line accounts for the indent in both directions
+    assert_eq!(indented.width, plain.width + 40.0);
+    assert_eq!(hanging.width, plain.width - 10.0);
+    assert_eq!(indented.height, plain.height);
+  }
+
+  #[test]
+  fn test_measure_vertical_text_swaps_axes() {
+    let context = create_test_context();
+    let parent = InheritedStyle {
+      writing_mode: WritingMode::VerticalRl,
+      ..Default::default()
+    };
+
+    let horizontal = measure_text_helper(
+      "Hello world",
+      None,
+      Some(40.0),
+      AvailableSpace::Definite(300.0),
+      AvailableSpace::Definite(40.0),
+    );
+
+    // the height constrains the line length, like the width does for horizontal text
+    let vertical = measure_text_with_style(
+      "Hello world",
+      parent.to_sized_font_style(&context),
+      Some(40.0),
+      None,
+      AvailableSpace::Definite(40.0),
+      AvailableSpace::Definite(300.0),
+    );
+
+    assert_eq!(vertical.width, horizontal.height);
+    assert_eq!(vertical.height, horizontal.width);
+  }
+
+  #[test]
+  fn test_measure_text_with_text_indent_wraps_first_line() {
+    let context = create_test_context();
+    let parent = InheritedStyle {
+      text_indent: Some(LengthUnit::Percentage(50.0)),
+      ..Default::default()
+    };
+
+    let plain = measure_text_helper(
+      "Hello world",
+      Some(100.0),
+      None,
+      AvailableSpace::Definite(100.0),
+      AvailableSpace::MaxContent,
+    );
+
+    let indented = measure_text_with_style(
+      "Hello world",
+      parent.to_sized_font_style(&context),
+      Some(100.0),
+      None,
+      AvailableSpace::Definite(100.0),
+      AvailableSpace::MaxContent,
+    );
+
+    // half of the width is taken by the indent, so the second word moves to a new line
+    assert_eq!(
+      plain.height,
+      (DEFAULT_FONT_SIZE * DEFAULT_LINE_HEIGHT_SCALER).ceil()
+    );
+    assert_eq!(
+      indented.height,
+      (DEFAULT_FONT_SIZE * DEFAULT_LINE_HEIGHT_SCALER * 2.0).ceil()
+    );
+  }
+
   #[test]
   fn test_measure_text_whitespace_only() {
     let result = measure_text_helper(
@@ -431,4 +631,53 @@ mod tests {
     assert!(result.width <= 100.0);
     assert!(result.height >= 19.0);
   }
+
+  #[test]
+  fn test_measure_text_letter_spacing() {
+    let context = create_test_context();
+
+    let measure = |letter_spacing: f32| {
+      let parent = InheritedStyle {
+        letter_spacing: Some(LengthUnit::Px(letter_spacing)),
+        ..Default::default()
+      };
+
+      measure_text_with_style(
+        "AVAV",
+        parent.to_sized_font_style(&context),
+        None,
+        None,
+        AvailableSpace::MaxContent,
+        AvailableSpace::MaxContent,
+      )
+    };
+
+    let default = measure_text_helper(
+      "AVAV",
+      None,
+      None,
+      AvailableSpace::MaxContent,
+      AvailableSpace::MaxContent,
+    );
+
+    let tight = measure(-2.0);
+
+    // the spacing after the last glyph is left out, so it isn't cut off
+    assert!(tight.width < default.width);
+    assert!(tight.width >= default.width - 3.0 * 2.0 - 1.0);
+    assert_eq!(tight.height, default.height);
+
+    assert!(measure(4.0).width > default.width);
+
+    // pathological values are clamped instead of producing widths that aren't numbers
+    for letter_spacing in [-1e30, f32::NEG_INFINITY, 1e30, f32::INFINITY, f32::NAN] {
+      let size = measure(letter_spacing);
+
+      assert!(
+        size.width.is_finite() && size.width >= 0.0,
+        "{letter_spacing}"
+      );
+      assert!(size.height.is_finite(), "{letter_spacing}");
+    }
+  }
 }
@@ -0,0 +1,428 @@
+use cssparser::{
+  AtRuleParser, CowRcStr, DeclarationParser, ParseError, Parser, ParserInput, ParserState,
+  QualifiedRuleParser, RuleBodyItemParser, RuleBodyParser, parse_important,
+};
+use serde::{
+  Deserialize,
+  de::{self, DeserializeSeed, IntoDeserializer, MapAccess, Visitor, value::Error as ValueError},
+  forward_to_deserialize_any,
+};
+
+use crate::layout::style::{Color, FromCss, Style};
+
+/// Represents an error that occurs while parsing a CSS declaration block into a [`Style`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum StyleDeclarationError {
+  /// The declaration could not be tokenized, e.g. a missing colon after the property name.
+  InvalidSyntax(String),
+  /// The value of the property could not be parsed.
+  InvalidValue {
+    /// The property name as written in the declaration block.
+    property: String,
+    /// The reason the value was rejected.
+    message: String,
+  },
+}
+
+impl Style {
+  /// Parses a CSS declaration block like `display: flex; padding: 10px 20px` into a [`Style`].
+  ///
+  /// Each value goes through the same parsing as its JSON string form, so shorthands like
+  /// `padding` accept one to four values. `background` sets `background-color` when the value
+  /// is a single color and `background-image` otherwise. `!important` is accepted and ignored,
+  /// and later declarations override earlier ones.
+  pub fn from_css_declarations(css: &str) -> Result<Style, StyleDeclarationError> {
+    let mut input = ParserInput::new(css);
+    let mut parser = Parser::new(&mut input);
+
+    let mut declarations: Vec<Declaration> = Vec::new();
+
+    for declaration in RuleBodyParser::new(&mut parser, &mut DeclarationCollector) {
+      let (name, value) = declaration.map_err(|(error, slice)| {
+        StyleDeclarationError::InvalidSyntax(format!("{slice}: {error:?}"))
+      })?;
+
+      let field = resolve_field_name(&name, value);
+
+      declarations.retain(|declaration| declaration.field != field);
+      declarations.push(Declaration {
+        field,
+        property: name.to_string(),
+        value,
+      });
+    }
+
+    Style::deserialize(DeclarationMapAccess {
+      declarations: declarations.into_iter(),
+      current: None,
+    })
+    .map_err(|error| match error {
+      DeclarationError::Value { property, message } => {
+        StyleDeclarationError::InvalidValue { property, message }
+      }
+      DeclarationError::Custom(message) => StyleDeclarationError::InvalidSyntax(message),
+    })
+  }
+}
+
+/// A single `property: value` pair of a declaration block.
+struct Declaration<'a> {
+  /// The camelCase field name of [`Style`] the declaration sets.
+  field: String,
+  /// The property name as written.
+  property: String,
+  value: &'a str,
+}
+
+/// Converts a kebab-case property name to the camelCase field name of [`Style`].
+fn resolve_field_name(name: &str, value: &str) -> String {
+  if name.eq_ignore_ascii_case("background") {
+    let mut input = ParserInput::new(value);
+    let mut parser = Parser::new(&mut input);
+
+    let is_color = Color::from_css(&mut parser).is_ok() && parser.is_exhausted();
+
+    return if is_color {
+      "backgroundColor"
+    } else {
+      "backgroundImage"
+    }
+    .to_string();
+  }
+
+  let mut property = String::with_capacity(name.len());
+  let mut uppercase_next = false;
+
+  for char in name.chars() {
+    if char == '-' {
+      uppercase_next = !property.is_empty();
+    } else if uppercase_next {
+      property.push(char.to_ascii_uppercase());
+      uppercase_next = false;
+    } else {
+      property.push(char.to_ascii_lowercase());
+    }
+  }
+
+  property
+}
+
+struct DeclarationCollector;
+
+impl<'i> DeclarationParser<'i> for DeclarationCollector {
+  type Declaration = (CowRcStr<'i>, &'i str);
+  type Error = ();
+
+  fn parse_value<'t>(
+    &mut self,
+    name: CowRcStr<'i>,
+    input: &mut Parser<'i, 't>,
+    _declaration_start: &ParserState,
+  ) -> Result<Self::Declaration, ParseError<'i, Self::Error>> {
+    input.skip_whitespace();
+
+    let start = input.position();
+    let mut end = start;
+
+    while !input.is_exhausted() {
+      if input.try_parse(parse_important).is_ok() {
+        break;
+      }
+
+      input.next()?;
+      end = input.position();
+    }
+
+    Ok((name, input.slice(start..end).trim()))
+  }
+}
+
+impl<'i> AtRuleParser<'i> for DeclarationCollector {
+  type Prelude = ();
+  type AtRule = (CowRcStr<'i>, &'i str);
+  type Error = ();
+}
+
+impl<'i> QualifiedRuleParser<'i> for DeclarationCollector {
+  type Prelude = ();
+  type QualifiedRule = (CowRcStr<'i>, &'i str);
+  type Error = ();
+}
+
+impl<'i> RuleBodyItemParser<'i, (CowRcStr<'i>, &'i str), ()> for DeclarationCollector {
+  fn parse_declarations(&self) -> bool {
+    true
+  }
+
+  fn parse_qualified(&self) -> bool {
+    false
+  }
+}
+
+/// Deserialization error that remembers which property failed.
+#[derive(Debug)]
+enum DeclarationError {
+  Value { property: String, message: String },
+  Custom(String),
+}
+
+impl std::fmt::Display for DeclarationError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      DeclarationError::Value { property, message } => write!(f, "{property}: {message}"),
+      DeclarationError::Custom(message) => f.write_str(message),
+    }
+  }
+}
+
+impl std::error::Error for DeclarationError {}
+
+impl de::Error for DeclarationError {
+  fn custom<T: std::fmt::Display>(message: T) -> Self {
+    DeclarationError::Custom(message.to_string())
+  }
+}
+
+struct DeclarationMapAccess<'a, I: Iterator<Item = Declaration<'a>>> {
+  declarations: I,
+  current: Option<Declaration<'a>>,
+}
+
+impl<'de, 'a, I: Iterator<Item = Declaration<'a>>> MapAccess<'de> for DeclarationMapAccess<'a, I> {
+  type Error = DeclarationError;
+
+  fn next_key_seed<K: DeserializeSeed<'de>>(
+    &mut self,
+    seed: K,
+  ) -> Result<Option<K::Value>, Self::Error> {
+    let Some(declaration) = self.declarations.next() else {
+      return Ok(None);
+    };
+
+    let key = seed
+      .deserialize(declaration.field.as_str().into_deserializer())
+      .map_err(|error: ValueError| DeclarationError::Custom(error.to_string()))?;
+
+    self.current = Some(declaration);
+
+    Ok(Some(key))
+  }
+
+  fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+    let declaration = self
+      .current
+      .take()
+      .ok_or_else(|| DeclarationError::Custom("value is missing".to_string()))?;
+
+    seed
+      .deserialize(DeclarationValue(declaration.value))
+      .map_err(|error| DeclarationError::Value {
+        property: declaration.property,
+        message: error.to_string(),
+      })
+  }
+}
+
+impl<'de, 'a, I: Iterator<Item = Declaration<'a>>> de::Deserializer<'de>
+  for DeclarationMapAccess<'a, I>
+{
+  type Error = DeclarationError;
+
+  fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+    visitor.visit_map(self)
+  }
+
+  forward_to_deserialize_any! {
+    bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+    bytes byte_buf option unit unit_struct newtype_struct seq tuple
+    tuple_struct map struct enum identifier ignored_any
+  }
+}
+
+/// A declaration value, presented as a number when it is a plain number and as a string otherwise.
+///
+/// This matches how the same value would be written in JSON, so the property types can
+/// parse it with their existing deserializers.
+struct DeclarationValue<'a>(&'a str);
+
+impl<'de> de::Deserializer<'de> for DeclarationValue<'_> {
+  type Error = ValueError;
+
+  fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+    // integers are kept as integers, like JSON numbers without a fraction
+    if let Ok(number) = self.0.parse::<i64>() {
+      return visitor.visit_i64(number);
+    }
+
+    match self.0.parse::<f64>() {
+      Ok(number) if number.is_finite() => visitor.visit_f64(number),
+      _ => visitor.visit_str(self.0),
+    }
+  }
+
+  fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+    visitor.visit_str(self.0)
+  }
+
+  fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+    visitor.visit_str(self.0)
+  }
+
+  fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+    visitor.visit_some(self)
+  }
+
+  fn deserialize_enum<V: Visitor<'de>>(
+    self,
+    _name: &'static str,
+    _variants: &'static [&'static str],
+    visitor: V,
+  ) -> Result<V::Value, Self::Error> {
+    visitor.visit_enum(self.0.into_deserializer())
+  }
+
+  forward_to_deserialize_any! {
+    bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char
+    bytes byte_buf unit unit_struct newtype_struct seq tuple
+    tuple_struct map struct identifier ignored_any
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::layout::style::{
+    AlignItems, BackgroundImage, ColorInput, CssValue, Display, Flex, FontWeight, InheritedStyle,
+    JustifyContent, LengthUnit::Px, LineHeight, PlaceContent, PlaceItems, Sides,
+  };
+
+  #[test]
+  fn test_parse_declarations() {
+    let style = Style::from_css_declarations(
+      "display: flex; padding: 10px 20px; flex-grow: 1; font-weight: 600; color: #fff !important; line-height: 1.5",
+    )
+    .unwrap();
+
+    assert_eq!(style.display, CssValue::Value(Display::Flex));
+    assert_eq!(
+      style.padding,
+      CssValue::Value(Sides([Px(10.0), Px(20.0), Px(10.0), Px(20.0)]))
+    );
+    assert_eq!(style.flex_grow, CssValue::Value(Some(1.0)));
+    assert_eq!(style.font_weight, CssValue::Value(FontWeight::from(600.0)));
+    assert_eq!(style.color, CssValue::Value(Color([255, 255, 255, 255])));
+    assert_eq!(style.line_height, CssValue::Value(LineHeight::Number(1.5)));
+  }
+
+  #[test]
+  fn test_parse_flex_shorthand() {
+    let style = Style::from_css_declarations("flex: 1; flex-shrink: 0").unwrap();
+
+    assert_eq!(style.flex, CssValue::Value(Some(Flex::grow(1.0))));
+    assert_eq!(style.flex_shrink, CssValue::Value(Some(0.0)));
+
+    // the longhand overrides the matching part of the shorthand
+    let flex = style.inherit(&InheritedStyle::default()).resolved_flex();
+
+    assert_eq!(
+      flex,
+      Flex {
+        grow: 1.0,
+        shrink: 0.0,
+        basis: Px(0.0),
+      }
+    );
+  }
+
+  #[test]
+  fn test_individual_sides_override_inset() {
+    // the order doesn't matter, the individual side always wins
+    for css in [
+      "position: absolute; inset: 10px; left: 20px",
+      "position: absolute; left: 20px; inset: 10px",
+    ] {
+      let inset = Style::from_css_declarations(css)
+        .unwrap()
+        .inherit(&InheritedStyle::default())
+        .resolved_inset();
+
+      assert_eq!(
+        inset,
+        taffy::Rect {
+          top: Px(10.0),
+          right: Px(10.0),
+          bottom: Px(10.0),
+          left: Px(20.0),
+        }
+      );
+    }
+  }
+
+  #[test]
+  fn test_parse_place_shorthands() {
+    let style =
+      Style::from_css_declarations("place-items: center start; place-content: center").unwrap();
+
+    assert_eq!(
+      style.place_items,
+      CssValue::Value(Some(PlaceItems(AlignItems::Center, AlignItems::Start)))
+    );
+    assert_eq!(
+      style.place_content,
+      CssValue::Value(Some(PlaceContent(
+        JustifyContent::Center,
+        JustifyContent::Center
+      )))
+    );
+  }
+
+  #[test]
+  fn test_parse_background_shorthand() {
+    let style = Style::from_css_declarations("background: red").unwrap();
+    assert_eq!(
+      style.background_color,
+      CssValue::Value(Color([255, 0, 0, 255]))
+    );
+
+    let style =
+      Style::from_css_declarations("background: linear-gradient(90deg, red, blue)").unwrap();
+    let CssValue::Value(Some(images)) = style.background_image else {
+      panic!("background-image should be set");
+    };
+    assert!(matches!(images.0[0], BackgroundImage::Linear(_)));
+  }
+
+  #[test]
+  fn test_later_declaration_wins() {
+    let style = Style::from_css_declarations("width: 10px; width: 20px; width: inherit").unwrap();
+
+    assert_eq!(style.width, CssValue::Inherit);
+  }
+
+  #[test]
+  fn test_border_color_current_color() {
+    let style = Style::from_css_declarations("color: red; border-color: currentColor").unwrap();
+
+    assert_eq!(
+      style.border_color,
+      CssValue::Value(ColorInput::CurrentColor)
+    );
+
+    let style = style.inherit(&InheritedStyle::default());
+
+    assert_eq!(
+      style.border_color.resolve(style.color),
+      Color([255, 0, 0, 255])
+    );
+  }
+
+  #[test]
+  fn test_invalid_value_reports_property() {
+    let error = Style::from_css_declarations("display: flex; max-width: wide").unwrap_err();
+
+    assert!(matches!(
+      error,
+      StyleDeclarationError::InvalidValue { ref property, .. } if property == "max-width"
+    ));
+  }
+}
@@ -1,7 +1,9 @@
+mod declarations;
 mod properties;
 mod stylesheets;
 
 use cssparser::Parser;
+pub use declarations::*;
 pub use properties::*;
 use serde::{Deserialize, Serialize};
 pub use stylesheets::*;
@@ -9,6 +11,8 @@ use ts_rs::TS;
 
 /// Represents a CSS property value that can be explicitly set, inherited from parent, or reset to initial value.
 #[derive(Debug, Clone, Deserialize, Serialize, TS, PartialEq, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "CssValue_for_{T}"))]
 #[serde(rename_all = "kebab-case")]
 pub enum CssValue<T> {
   /// Use the initial value of the property
@@ -7,16 +7,35 @@ use crate::layout::style::{FromCss, LinearGradient, NoiseV1, ParseResult, Radial
 
 /// Background image variants supported by Takumi.
 #[derive(Debug, Clone, PartialEq, TS, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum BackgroundImage {
   /// CSS linear-gradient(...)
   Linear(LinearGradient),
   /// CSS radial-gradient(...)
   Radial(RadialGradient),
+  /// CSS url(...), loaded like the `src` of an image node
+  Url(UrlImage),
   /// Custom noise-v1(...)
   Noise(NoiseV1),
 }
 
+/// An image referenced by url, a data uri or a key of the image store.
+#[derive(Debug, Clone, PartialEq, TS, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct UrlImage {
+  /// The url of the image
+  pub url: String,
+}
+
+impl<'i> FromCss<'i> for UrlImage {
+  fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
+    Ok(UrlImage {
+      url: input.expect_url()?.to_string(),
+    })
+  }
+}
+
 impl<'i> FromCss<'i> for BackgroundImage {
   fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, BackgroundImage> {
     if let Ok(gradient) = input.try_parse(LinearGradient::from_css) {
@@ -28,13 +47,16 @@ impl<'i> FromCss<'i> for BackgroundImage {
     if let Ok(noise) = input.try_parse(NoiseV1::from_css) {
       return Ok(BackgroundImage::Noise(noise));
     }
-    // TODO: url(...) images can be supported here later
+    if let Ok(image) = input.try_parse(UrlImage::from_css) {
+      return Ok(BackgroundImage::Url(image));
+    }
     Err(input.new_error(cssparser::BasicParseErrorKind::QualifiedRuleInvalid))
   }
 }
 
 /// Proxy type to deserialize CSS background images as either a list or CSS string
 #[derive(Debug, Clone, PartialEq, TS, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 #[allow(clippy::large_enum_variant)]
 pub enum BackgroundImagesValue {
@@ -47,6 +69,7 @@ pub enum BackgroundImagesValue {
 
 /// A collection of background images.
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, TS)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[ts(as = "BackgroundImagesValue")]
 #[serde(try_from = "BackgroundImagesValue")]
 pub struct BackgroundImages(pub SmallVec<[BackgroundImage; 4]>);
@@ -73,3 +96,38 @@ impl TryFrom<BackgroundImagesValue> for BackgroundImages {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_url_images() {
+    let images = BackgroundImages::try_from(BackgroundImagesValue::Css(
+      "url(mask.png), url(\"data:image/png;base64,AAAA\")".to_string(),
+    ))
+    .unwrap();
+
+    assert_eq!(
+      images.0.as_slice(),
+      [
+        BackgroundImage::Url(UrlImage {
+          url: "mask.png".to_string()
+        }),
+        BackgroundImage::Url(UrlImage {
+          url: "data:image/png;base64,AAAA".to_string()
+        }),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_deserialize_url_image() {
+    assert_eq!(
+      serde_json::from_str::<BackgroundImage>(r#"{"url": "mask.png"}"#).unwrap(),
+      BackgroundImage::Url(UrlImage {
+        url: "mask.png".to_string()
+      })
+    );
+  }
+}
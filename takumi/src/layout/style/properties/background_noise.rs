@@ -0,0 +1,152 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::{
+  layout::style::{Color, Gradient},
+  rendering::RenderContext,
+};
+
+/// Film grain painted over the background of a node, above `background-image`.
+///
+/// Every pixel gets an independent random gray (or color, unless `monochrome`) value, derived
+/// from the seed and the pixel position inside the node so the same style always renders the
+/// same grain.
+#[derive(Debug, Clone, Copy, PartialEq, TS, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+#[ts(optional_fields)]
+pub struct BackgroundNoise {
+  /// How far the grain strays from mid gray, 0.0 is flat gray and 1.0 spans black to white. Defaults to 1.0
+  pub intensity: Option<f32>,
+  /// Opacity of the grain layer, 0.0 is fully transparent and 1.0 is fully opaque. Defaults to 0.1
+  pub opacity: Option<f32>,
+  /// Whether the grain is gray instead of varying per color channel. Defaults to true
+  pub monochrome: Option<bool>,
+  /// Random seed value that determines the grain pattern generated
+  pub seed: Option<u32>,
+}
+
+/// Resolved parameters of a [`BackgroundNoise`].
+pub struct BackgroundNoiseDrawContext {
+  intensity: f32,
+  alpha: u8,
+  monochrome: bool,
+  seed: u32,
+}
+
+impl Gradient for BackgroundNoise {
+  type DrawContext = BackgroundNoiseDrawContext;
+
+  fn at(&self, x: u32, y: u32, ctx: &Self::DrawContext) -> Color {
+    let channel = |index: u32| {
+      let noise = hash_to_unit(ctx.seed, x, y, index) * 2.0 - 1.0;
+
+      (127.5 + noise * 127.5 * ctx.intensity).round() as u8
+    };
+
+    if ctx.monochrome {
+      let value = channel(0);
+
+      Color([value, value, value, ctx.alpha])
+    } else {
+      Color([channel(0), channel(1), channel(2), ctx.alpha])
+    }
+  }
+
+  fn to_draw_context(
+    &self,
+    _width: f32,
+    _height: f32,
+    _context: &RenderContext,
+  ) -> Self::DrawContext {
+    BackgroundNoiseDrawContext {
+      intensity: self.intensity.unwrap_or(1.0).clamp(0.0, 1.0),
+      alpha: (self.opacity.unwrap_or(0.1).clamp(0.0, 1.0) * 255.0).round() as u8,
+      monochrome: self.monochrome.unwrap_or(true),
+      seed: self.seed.unwrap_or(0),
+    }
+  }
+}
+
+/// Hashes the pixel position and channel into a uniform value in `[0, 1)`.
+fn hash_to_unit(seed: u32, x: u32, y: u32, channel: u32) -> f32 {
+  let mut hash = seed
+    ^ x.wrapping_mul(0x27d4_eb2d)
+    ^ y.wrapping_mul(0x1656_67b1)
+    ^ channel.wrapping_mul(0x9e37_79b9);
+
+  // lowbias32 finalizer
+  hash ^= hash >> 16;
+  hash = hash.wrapping_mul(0x7feb_352d);
+  hash ^= hash >> 15;
+  hash = hash.wrapping_mul(0x846c_a68b);
+  hash ^= hash >> 16;
+
+  (hash >> 8) as f32 / (1 << 24) as f32
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    GlobalContext,
+    layout::{
+      DEFAULT_FONT_SIZE, Viewport,
+      style::{Affine, InheritedStyle},
+    },
+  };
+
+  use super::*;
+
+  fn to_image(noise: BackgroundNoise) -> image::RgbaImage {
+    let context = RenderContext {
+      global: &GlobalContext::default(),
+      viewport: Viewport::new(16, 16),
+      parent_font_size: DEFAULT_FONT_SIZE,
+      transform: Affine::identity(),
+      style: InheritedStyle::default(),
+      border: None,
+    };
+
+    noise.to_image(16, 16, &context)
+  }
+
+  #[test]
+  fn test_background_noise_is_deterministic() {
+    let noise = BackgroundNoise {
+      seed: Some(42),
+      ..Default::default()
+    };
+
+    assert_eq!(to_image(noise), to_image(noise));
+    assert_ne!(
+      to_image(noise),
+      to_image(BackgroundNoise {
+        seed: Some(7),
+        ..noise
+      })
+    );
+  }
+
+  #[test]
+  fn test_background_noise_options() {
+    let image = to_image(BackgroundNoise {
+      opacity: Some(0.5),
+      ..Default::default()
+    });
+
+    assert!(image.pixels().all(|pixel| pixel.0[3] == 128));
+    assert!(
+      image
+        .pixels()
+        .all(|pixel| pixel.0[0] == pixel.0[1] && pixel.0[1] == pixel.0[2])
+    );
+
+    let image = to_image(BackgroundNoise {
+      intensity: Some(0.0),
+      monochrome: Some(false),
+      ..Default::default()
+    });
+
+    assert!(image.pixels().all(|pixel| pixel.0[..3] == [128, 128, 128]));
+  }
+}
@@ -6,6 +6,7 @@ use crate::layout::style::{FromCss, LengthUnit, ParseResult};
 
 /// Horizontal keywords for `background-position`.
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "kebab-case")]
 pub enum PositionKeywordX {
   /// Align to the left edge.
@@ -18,6 +19,7 @@ pub enum PositionKeywordX {
 
 /// Vertical keywords for `background-position`.
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "kebab-case")]
 pub enum PositionKeywordY {
   /// Align to the top edge.
@@ -30,6 +32,7 @@ pub enum PositionKeywordY {
 
 /// A single `background-position` component for an axis.
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "kebab-case")]
 pub enum PositionComponent {
   /// A horizontal keyword.
@@ -60,6 +63,7 @@ impl PositionComponent {
 
 /// Parsed `background-position` value for one layer.
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(try_from = "BackgroundPositionValue")]
 #[ts(as = "BackgroundPositionValue")]
 pub struct BackgroundPosition {
@@ -71,6 +75,7 @@ pub struct BackgroundPosition {
 
 /// Proxy type for deserializing `BackgroundPosition`
 #[derive(Debug, Clone, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum BackgroundPositionValue {
   /// Parsed positions for one or two dimensions.
@@ -146,6 +151,7 @@ impl<'i> FromCss<'i> for PositionComponent {
 
 /// A value representing either a list of parsed positions or a raw CSS string.
 #[derive(Debug, Clone, PartialEq, TS, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum BackgroundPositionsValue {
   /// Parsed positions for one or more layers.
@@ -156,6 +162,7 @@ pub enum BackgroundPositionsValue {
 
 /// A list of `background-position` values (one per layer).
 #[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, TS)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[ts(as = "BackgroundPositionsValue")]
 #[serde(try_from = "BackgroundPositionsValue")]
 pub struct BackgroundPositions(pub Vec<BackgroundPosition>);
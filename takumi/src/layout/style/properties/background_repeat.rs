@@ -6,6 +6,7 @@ use crate::layout::style::{FromCss, ParseResult};
 
 /// Per-axis repeat style.
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, TS, PartialEq, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "kebab-case")]
 pub enum BackgroundRepeatStyle {
   /// Tile as many times as needed with no extra spacing
@@ -21,7 +22,9 @@ pub enum BackgroundRepeatStyle {
 
 /// Combined repeat for X and Y axes.
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, TS, PartialEq, Default)]
-#[serde(rename_all = "kebab-case")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(try_from = "BackgroundRepeatValue")]
+#[ts(as = "BackgroundRepeatValue")]
 pub struct BackgroundRepeat {
   /// Repeat style along the X axis.
   pub x: BackgroundRepeatStyle,
@@ -29,6 +32,38 @@ pub struct BackgroundRepeat {
   pub y: BackgroundRepeatStyle,
 }
 
+/// Proxy type to deserialize a single repeat as either structured axes or a CSS string.
+#[derive(Debug, Clone, PartialEq, TS, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(untagged)]
+pub enum BackgroundRepeatValue {
+  /// Repeat styles for each axis.
+  Structured {
+    /// Repeat style along the X axis.
+    x: BackgroundRepeatStyle,
+    /// Repeat style along the Y axis.
+    y: BackgroundRepeatStyle,
+  },
+  /// Raw CSS to be parsed at runtime, like `repeat-x`.
+  Css(String),
+}
+
+impl TryFrom<BackgroundRepeatValue> for BackgroundRepeat {
+  type Error = String;
+
+  fn try_from(value: BackgroundRepeatValue) -> Result<Self, Self::Error> {
+    match value {
+      BackgroundRepeatValue::Structured { x, y } => Ok(Self { x, y }),
+      BackgroundRepeatValue::Css(css) => {
+        let mut input = ParserInput::new(&css);
+        let mut parser = Parser::new(&mut input);
+
+        BackgroundRepeat::from_css(&mut parser).map_err(|e| e.to_string())
+      }
+    }
+  }
+}
+
 impl BackgroundRepeat {
   /// Returns a repeat value that tiles on both the X and Y axes.
   pub const fn repeat() -> Self {
@@ -101,6 +136,7 @@ impl<'i> FromCss<'i> for BackgroundRepeat {
 
 /// Proxy type to deserialize CSS background-repeat as either a list or CSS string.
 #[derive(Debug, Clone, PartialEq, TS, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum BackgroundRepeatsValue {
   /// Parsed repeats for one or more layers.
@@ -111,6 +147,7 @@ pub enum BackgroundRepeatsValue {
 
 /// A list of background-repeat values (layered).
 #[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, TS)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[ts(as = "BackgroundRepeatsValue")]
 #[serde(try_from = "BackgroundRepeatsValue")]
 pub struct BackgroundRepeats(pub Vec<BackgroundRepeat>);
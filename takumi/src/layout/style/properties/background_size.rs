@@ -6,6 +6,7 @@ use crate::layout::style::{FromCss, LengthUnit, ParseResult};
 
 /// Parsed `background-size` for one layer.
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum BackgroundSize {
   /// Scale the image to cover the container (may crop).
   Cover,
@@ -62,6 +63,7 @@ impl<'i> FromCss<'i> for BackgroundSize {
 
 /// A value representing either a list of parsed sizes or a raw CSS string.
 #[derive(Debug, Clone, PartialEq, TS, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum BackgroundSizesValue {
   /// Parsed sizes for one or more layers.
@@ -72,6 +74,7 @@ pub enum BackgroundSizesValue {
 
 /// A list of `background-size` values (one per layer).
 #[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, TS)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[ts(as = "BackgroundSizesValue")]
 #[serde(try_from = "BackgroundSizesValue")]
 pub struct BackgroundSizes(pub Vec<BackgroundSize>);
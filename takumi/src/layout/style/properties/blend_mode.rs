@@ -0,0 +1,151 @@
+use cssparser::{Parser, ParserInput, Token, match_ignore_ascii_case};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::layout::style::{FromCss, ParseResult};
+
+/// How a node and its children are blended with what's painted beneath them.
+///
+/// Similar to CSS mix-blend-mode property, the blending is done on straight sRGB values.
+#[derive(Default, Debug, Clone, Deserialize, Serialize, Copy, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum MixBlendMode {
+  /// The node is painted over the backdrop
+  #[default]
+  Normal,
+  /// Multiplies the colors, the result is always darker
+  Multiply,
+  /// Multiplies the inverted colors, the result is always lighter
+  Screen,
+  /// `multiply` or `screen` depending on the backdrop color
+  Overlay,
+  /// Keeps the darker color of each channel
+  Darken,
+  /// Keeps the lighter color of each channel
+  Lighten,
+  /// Brightens the backdrop to reflect the node
+  ColorDodge,
+  /// Darkens the backdrop to reflect the node
+  ColorBurn,
+  /// `multiply` or `screen` depending on the node color
+  HardLight,
+  /// A softer version of `hard-light`
+  SoftLight,
+  /// Subtracts the darker color from the lighter one
+  Difference,
+  /// Like `difference` with lower contrast
+  Exclusion,
+  /// The hue of the node with the saturation and luminosity of the backdrop
+  Hue,
+  /// The saturation of the node with the hue and luminosity of the backdrop
+  Saturation,
+  /// The hue and saturation of the node with the luminosity of the backdrop
+  Color,
+  /// The luminosity of the node with the hue and saturation of the backdrop
+  Luminosity,
+}
+
+impl<'i> FromCss<'i> for MixBlendMode {
+  fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
+    let location = input.current_source_location();
+    let ident = input.expect_ident()?;
+
+    match_ignore_ascii_case! {&ident,
+      "normal" => Ok(MixBlendMode::Normal),
+      "multiply" => Ok(MixBlendMode::Multiply),
+      "screen" => Ok(MixBlendMode::Screen),
+      "overlay" => Ok(MixBlendMode::Overlay),
+      "darken" => Ok(MixBlendMode::Darken),
+      "lighten" => Ok(MixBlendMode::Lighten),
+      "color-dodge" => Ok(MixBlendMode::ColorDodge),
+      "color-burn" => Ok(MixBlendMode::ColorBurn),
+      "hard-light" => Ok(MixBlendMode::HardLight),
+      "soft-light" => Ok(MixBlendMode::SoftLight),
+      "difference" => Ok(MixBlendMode::Difference),
+      "exclusion" => Ok(MixBlendMode::Exclusion),
+      "hue" => Ok(MixBlendMode::Hue),
+      "saturation" => Ok(MixBlendMode::Saturation),
+      "color" => Ok(MixBlendMode::Color),
+      "luminosity" => Ok(MixBlendMode::Luminosity),
+      _ => Err(location.new_basic_unexpected_token_error(Token::Ident(ident.clone())).into()),
+    }
+  }
+}
+
+/// Proxy type to deserialize CSS background-blend-mode as either a list or CSS string.
+#[derive(Debug, Clone, PartialEq, TS, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(untagged)]
+pub enum BackgroundBlendModesValue {
+  /// Blend modes for one or more layers.
+  Modes(Vec<MixBlendMode>),
+  /// Raw CSS to be parsed at runtime.
+  Css(String),
+}
+
+/// A list of `background-blend-mode` values, one per background image layer.
+///
+/// Each layer blends with the layers and the background color beneath it, never with the backdrop.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, TS)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[ts(as = "BackgroundBlendModesValue")]
+#[serde(try_from = "BackgroundBlendModesValue")]
+pub struct BackgroundBlendModes(pub Vec<MixBlendMode>);
+
+impl BackgroundBlendModes {
+  /// Returns true if any layer blends with something other than `normal`.
+  pub fn is_blended(&self) -> bool {
+    self.0.iter().any(|mode| *mode != MixBlendMode::Normal)
+  }
+}
+
+impl TryFrom<BackgroundBlendModesValue> for BackgroundBlendModes {
+  type Error = String;
+
+  fn try_from(value: BackgroundBlendModesValue) -> Result<Self, Self::Error> {
+    match value {
+      BackgroundBlendModesValue::Modes(modes) => Ok(Self(modes)),
+      BackgroundBlendModesValue::Css(css) => {
+        let mut input = ParserInput::new(&css);
+        let mut parser = Parser::new(&mut input);
+
+        let modes = parser
+          .parse_comma_separated(MixBlendMode::from_css)
+          .map_err(|e| e.to_string())?;
+
+        parser.expect_exhausted().map_err(|e| format!("{e:?}"))?;
+
+        Ok(Self(modes))
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_background_blend_modes() {
+    assert_eq!(
+      BackgroundBlendModes::try_from(BackgroundBlendModesValue::Css(
+        "multiply, normal, color-dodge".to_string()
+      )),
+      Ok(BackgroundBlendModes(vec![
+        MixBlendMode::Multiply,
+        MixBlendMode::Normal,
+        MixBlendMode::ColorDodge,
+      ]))
+    );
+
+    assert!(
+      BackgroundBlendModes::try_from(BackgroundBlendModesValue::Css("plus-darker".to_string()))
+        .is_err()
+    );
+    assert!(
+      BackgroundBlendModes::try_from(BackgroundBlendModesValue::Css("multiply,".to_string()))
+        .is_err()
+    );
+  }
+}
@@ -0,0 +1,172 @@
+use cssparser::{Parser, ParserInput};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::layout::style::{CssValue, FromCss, LengthUnit, ParseResult, Sides};
+
+/// Represents the `border-radius` shorthand, the horizontal and vertical radius of each corner.
+///
+/// The corners are in the order top-left, top-right, bottom-right and bottom-left.
+/// Circular corners have the same radius on both axes, `10px / 20px` gives elliptical ones.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(try_from = "BorderRadiusValue")]
+#[ts(as = "BorderRadiusValue")]
+pub struct BorderRadius {
+  /// The horizontal radius of each corner, percentages resolve against the width
+  pub horizontal: Sides<LengthUnit>,
+  /// The vertical radius of each corner, percentages resolve against the height
+  pub vertical: Sides<LengthUnit>,
+}
+
+/// Represents a value for the `border-radius` property.
+#[derive(Debug, Clone, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(untagged)]
+pub enum BorderRadiusValue {
+  /// Separate horizontal and vertical radii for elliptical corners
+  Elliptical {
+    /// The horizontal radius of each corner
+    horizontal: Sides<LengthUnit>,
+    /// The vertical radius of each corner
+    vertical: Sides<LengthUnit>,
+  },
+  /// CSS string representation, like `10px 20px / 5px`
+  Css(String),
+  /// The same radius on both axes of each corner
+  Circular(Sides<LengthUnit>),
+}
+
+impl BorderRadius {
+  /// Square corners.
+  pub const fn zero() -> Self {
+    Self {
+      horizontal: Sides([LengthUnit::Px(0.0); 4]),
+      vertical: Sides([LengthUnit::Px(0.0); 4]),
+    }
+  }
+}
+
+impl Default for BorderRadius {
+  fn default() -> Self {
+    Self::zero()
+  }
+}
+
+impl From<Sides<LengthUnit>> for BorderRadius {
+  fn from(radius: Sides<LengthUnit>) -> Self {
+    Self {
+      horizontal: radius,
+      vertical: radius,
+    }
+  }
+}
+
+impl From<Sides<LengthUnit>> for CssValue<BorderRadius> {
+  fn from(radius: Sides<LengthUnit>) -> Self {
+    CssValue::Value(radius.into())
+  }
+}
+
+impl TryFrom<BorderRadiusValue> for BorderRadius {
+  type Error = String;
+
+  fn try_from(value: BorderRadiusValue) -> Result<Self, Self::Error> {
+    match value {
+      BorderRadiusValue::Elliptical {
+        horizontal,
+        vertical,
+      } => Ok(Self {
+        horizontal,
+        vertical,
+      }),
+      BorderRadiusValue::Circular(radius) => Ok(radius.into()),
+      BorderRadiusValue::Css(css) => {
+        let mut input = ParserInput::new(&css);
+        let mut parser = Parser::new(&mut input);
+
+        let radius = BorderRadius::from_css(&mut parser).map_err(|e| e.to_string())?;
+
+        parser.expect_exhausted().map_err(|e| format!("{e:?}"))?;
+
+        Ok(radius)
+      }
+    }
+  }
+}
+
+impl<'i> FromCss<'i> for BorderRadius {
+  /// Parses 1 to 4 horizontal radii, optionally followed by `/` and 1 to 4 vertical radii.
+  fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
+    let horizontal = Sides::from_css(input)?;
+
+    let vertical = if input.try_parse(|input| input.expect_delim('/')).is_ok() {
+      Sides::from_css(input)?
+    } else {
+      horizontal
+    };
+
+    Ok(Self {
+      horizontal,
+      vertical,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use LengthUnit::{Percentage, Px};
+
+  fn parse(css: &str) -> Result<BorderRadius, String> {
+    BorderRadius::try_from(BorderRadiusValue::Css(css.to_string()))
+  }
+
+  #[test]
+  fn test_parse_circular_radius() {
+    assert_eq!(parse("8px"), Ok(Sides([Px(8.0); 4]).into()));
+    assert_eq!(
+      parse("10px 20px"),
+      Ok(Sides([Px(10.0), Px(20.0), Px(10.0), Px(20.0)]).into())
+    );
+  }
+
+  #[test]
+  fn test_parse_elliptical_radius() {
+    assert_eq!(
+      parse("10px / 20px"),
+      Ok(BorderRadius {
+        horizontal: Sides([Px(10.0); 4]),
+        vertical: Sides([Px(20.0); 4]),
+      })
+    );
+    assert_eq!(
+      parse("50% 10px / 20px 30px 40px"),
+      Ok(BorderRadius {
+        horizontal: Sides([Percentage(50.0), Px(10.0), Percentage(50.0), Px(10.0)]),
+        vertical: Sides([Px(20.0), Px(30.0), Px(40.0), Px(30.0)]),
+      })
+    );
+
+    assert!(parse("10px /").is_err());
+    assert!(parse("/ 10px").is_err());
+    assert!(parse("10px / 20px / 30px").is_err());
+  }
+
+  #[test]
+  fn test_deserialize_border_radius() {
+    assert_eq!(
+      serde_json::from_str::<BorderRadius>("12").unwrap(),
+      Sides([Px(12.0); 4]).into()
+    );
+    assert_eq!(
+      serde_json::from_str::<BorderRadius>(r#"{"horizontal": 10, "vertical": "5px 10px"}"#)
+        .unwrap(),
+      BorderRadius {
+        horizontal: Sides([Px(10.0); 4]),
+        vertical: Sides([Px(5.0), Px(10.0), Px(5.0), Px(10.0)]),
+      }
+    );
+  }
+}
@@ -15,6 +15,7 @@ use crate::layout::style::{Color, FromCss, LengthUnit, ParseResult};
 /// - Spread radius (optional, defaults to 0)
 /// - Color (optional, defaults to transparent)
 #[derive(Debug, Clone, PartialEq, Copy, Serialize, Deserialize, TS)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[ts(as = "BoxShadowValue")]
 #[serde(try_from = "BoxShadowValue")]
 pub struct BoxShadow {
@@ -34,6 +35,7 @@ pub struct BoxShadow {
 
 /// Proxy type for `BoxShadow` Css deserialization.
 #[derive(Debug, Clone, PartialEq, TS, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum BoxShadowValue {
   /// Represents a structured box shadow.
@@ -88,11 +90,13 @@ impl TryFrom<BoxShadowValue> for BoxShadow {
 
 /// Represents a collection of box shadows, have custom `FromCss` implementation for comma-separated values.
 #[derive(Debug, Clone, PartialEq, TS, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[ts(as = "BoxShadowsValue")]
 #[serde(try_from = "BoxShadowsValue")]
 pub struct BoxShadows(pub SmallVec<[BoxShadow; 4]>);
 
 #[derive(Debug, Clone, PartialEq, TS, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub(crate) enum BoxShadowsValue {
   #[ts(as = "Vec<BoxShadow>")]
@@ -112,11 +116,7 @@ impl TryFrom<BoxShadowsValue> for BoxShadows {
 
         let mut shadows = SmallVec::new();
 
-        loop {
-          let Ok(shadow) = BoxShadow::from_css(&mut parser).map_err(|e| e.to_string()) else {
-            break;
-          };
-
+        while let Ok(shadow) = BoxShadow::from_css(&mut parser) {
           if parser.expect_comma().is_err() {
             break;
           }
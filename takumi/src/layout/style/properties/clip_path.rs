@@ -0,0 +1,263 @@
+use cssparser::{Parser, ParserInput, Token, match_ignore_ascii_case};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::layout::style::{BackgroundPosition, FromCss, LengthUnit, ParseResult, Sides};
+
+/// The radius of a `circle()` shape.
+#[derive(Debug, Clone, Copy, PartialEq, TS, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum ShapeRadius {
+  /// An explicit radius, percentages resolve against the diagonal of the box divided by `sqrt(2)`
+  Length(LengthUnit),
+  /// The distance from the center to the nearest side of the box
+  #[default]
+  ClosestSide,
+  /// The distance from the center to the farthest side of the box
+  FarthestSide,
+}
+
+impl<'i> FromCss<'i> for ShapeRadius {
+  fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
+    if let Ok(length) = input.try_parse(LengthUnit::from_css) {
+      return Ok(ShapeRadius::Length(length));
+    }
+
+    let location = input.current_source_location();
+    let ident = input.expect_ident()?;
+
+    match_ignore_ascii_case! {&ident,
+      "closest-side" => Ok(ShapeRadius::ClosestSide),
+      "farthest-side" => Ok(ShapeRadius::FarthestSide),
+      _ => Err(location.new_basic_unexpected_token_error(Token::Ident(ident.clone())).into()),
+    }
+  }
+}
+
+/// Decides which parts of a self-intersecting `polygon()` are inside.
+#[derive(Debug, Clone, Copy, PartialEq, TS, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum FillRule {
+  /// Inside when the path winds around the point a non-zero number of times
+  #[default]
+  Nonzero,
+  /// Inside when a ray from the point crosses the path an odd number of times
+  Evenodd,
+}
+
+impl<'i> FromCss<'i> for FillRule {
+  fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
+    let location = input.current_source_location();
+    let ident = input.expect_ident()?;
+
+    match_ignore_ascii_case! {&ident,
+      "nonzero" => Ok(FillRule::Nonzero),
+      "evenodd" => Ok(FillRule::Evenodd),
+      _ => Err(location.new_basic_unexpected_token_error(Token::Ident(ident.clone())).into()),
+    }
+  }
+}
+
+/// A basic shape clipping the node, positioned against its border box.
+#[derive(Debug, Clone, PartialEq, TS, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum BasicShape {
+  /// A rectangle inset from the edges of the box, like `inset(10px round 8px)`
+  Inset {
+    /// The distance from the top, right, bottom and left edges
+    offsets: Sides<LengthUnit>,
+    /// The corner radii, in the same order as `borderRadius`
+    radius: Sides<LengthUnit>,
+  },
+  /// A circle, like `circle(50% at center)`
+  Circle {
+    /// The radius of the circle
+    radius: ShapeRadius,
+    /// The center of the circle
+    position: BackgroundPosition,
+  },
+  /// A polygon through the points in order, like `polygon(50% 0, 100% 100%, 0 100%)`
+  Polygon {
+    /// The rule deciding which parts of the polygon are inside
+    #[serde(rename = "fillRule")]
+    fill_rule: FillRule,
+    /// The x and y coordinates of the points
+    points: Vec<(LengthUnit, LengthUnit)>,
+  },
+}
+
+impl<'i> FromCss<'i> for BasicShape {
+  fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
+    let location = input.current_source_location();
+    let function = input.expect_function()?.clone();
+
+    input.parse_nested_block(|input| {
+      match_ignore_ascii_case! {&function,
+        "inset" => {
+          let offsets = Sides::from_css(input)?;
+          let radius = if input.try_parse(|input| input.expect_ident_matching("round")).is_ok() {
+            Sides::from_css(input)?
+          } else {
+            Sides::zero()
+          };
+
+          Ok(BasicShape::Inset { offsets, radius })
+        },
+        "circle" => {
+          let radius = input.try_parse(ShapeRadius::from_css).unwrap_or_default();
+          let position = if input.try_parse(|input| input.expect_ident_matching("at")).is_ok() {
+            BackgroundPosition::from_css(input)?
+          } else {
+            BackgroundPosition::default()
+          };
+
+          Ok(BasicShape::Circle { radius, position })
+        },
+        "polygon" => {
+          let fill_rule = match input.try_parse(FillRule::from_css) {
+            Ok(fill_rule) => {
+              input.expect_comma()?;
+              fill_rule
+            }
+            Err(_) => FillRule::default(),
+          };
+
+          let points = input.parse_comma_separated(|input| {
+            Ok((LengthUnit::from_css(input)?, LengthUnit::from_css(input)?))
+          })?;
+
+          Ok(BasicShape::Polygon { fill_rule, points })
+        },
+        _ => Err(location.new_basic_unexpected_token_error(Token::Function(function.clone())).into()),
+      }
+    })
+  }
+}
+
+/// Proxy type for `ClipPath` Css deserialization.
+#[derive(Debug, Clone, PartialEq, TS, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(untagged)]
+pub enum ClipPathValue {
+  /// A structured shape.
+  Shape(BasicShape),
+  /// CSS string representation.
+  Css(String),
+}
+
+/// Clips the painting of the node and its descendants to a basic shape, like CSS `clip-path`.
+#[derive(Debug, Clone, PartialEq, TS, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[ts(as = "ClipPathValue")]
+#[serde(try_from = "ClipPathValue")]
+pub struct ClipPath(pub BasicShape);
+
+impl TryFrom<ClipPathValue> for ClipPath {
+  type Error = String;
+
+  fn try_from(value: ClipPathValue) -> Result<Self, Self::Error> {
+    match value {
+      ClipPathValue::Shape(shape) => Ok(Self(shape)),
+      ClipPathValue::Css(css) => {
+        let mut input = ParserInput::new(&css);
+        let mut parser = Parser::new(&mut input);
+
+        let shape = BasicShape::from_css(&mut parser).map_err(|e| e.to_string())?;
+
+        parser.expect_exhausted().map_err(|e| format!("{e:?}"))?;
+
+        Ok(Self(shape))
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::layout::style::{PositionComponent, PositionKeywordX};
+
+  fn parse(css: &str) -> Result<BasicShape, String> {
+    ClipPath::try_from(ClipPathValue::Css(css.to_string())).map(|clip_path| clip_path.0)
+  }
+
+  #[test]
+  fn test_parse_inset() {
+    assert_eq!(
+      parse("inset(10px 20%)"),
+      Ok(BasicShape::Inset {
+        offsets: Sides([
+          LengthUnit::Px(10.0),
+          LengthUnit::Percentage(20.0),
+          LengthUnit::Px(10.0),
+          LengthUnit::Percentage(20.0),
+        ]),
+        radius: Sides::zero(),
+      })
+    );
+    assert_eq!(
+      parse("inset(0 round 8px)"),
+      Ok(BasicShape::Inset {
+        offsets: Sides([LengthUnit::Px(0.0); 4]),
+        radius: Sides([LengthUnit::Px(8.0); 4]),
+      })
+    );
+  }
+
+  #[test]
+  fn test_parse_circle() {
+    assert_eq!(
+      parse("circle()"),
+      Ok(BasicShape::Circle {
+        radius: ShapeRadius::ClosestSide,
+        position: BackgroundPosition::default(),
+      })
+    );
+    assert_eq!(
+      parse("circle(50% at left 20px)"),
+      Ok(BasicShape::Circle {
+        radius: ShapeRadius::Length(LengthUnit::Percentage(50.0)),
+        position: BackgroundPosition {
+          x: PositionComponent::KeywordX(PositionKeywordX::Left),
+          y: PositionComponent::Length(LengthUnit::Px(20.0)),
+        },
+      })
+    );
+    assert_eq!(
+      parse("circle(farthest-side)"),
+      Ok(BasicShape::Circle {
+        radius: ShapeRadius::FarthestSide,
+        position: BackgroundPosition::default(),
+      })
+    );
+  }
+
+  #[test]
+  fn test_parse_polygon() {
+    assert_eq!(
+      parse("polygon(50% 0, 100% 100%, 0 100%)"),
+      Ok(BasicShape::Polygon {
+        fill_rule: FillRule::Nonzero,
+        points: vec![
+          (LengthUnit::Percentage(50.0), LengthUnit::Px(0.0)),
+          (LengthUnit::Percentage(100.0), LengthUnit::Percentage(100.0)),
+          (LengthUnit::Px(0.0), LengthUnit::Percentage(100.0)),
+        ],
+      })
+    );
+    assert!(matches!(
+      parse("polygon(evenodd, 0 0, 10px 0, 10px 10px)"),
+      Ok(BasicShape::Polygon {
+        fill_rule: FillRule::Evenodd,
+        ..
+      })
+    ));
+
+    assert!(parse("polygon()").is_err());
+    assert!(parse("polygon(0 0, 10px)").is_err());
+    assert!(parse("ellipse(10px 20px)").is_err());
+  }
+}
@@ -6,10 +6,11 @@ use image::Rgba;
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
-use crate::layout::style::{FromCss, ParseResult};
+use crate::layout::style::{CssValue, FromCss, ParseResult};
 
 /// `Color` proxy type for deserializing CSS color values.
 #[derive(Debug, Clone, Deserialize, TS)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum ColorValue {
   /// RGB color with 8-bit components
@@ -18,12 +19,13 @@ pub enum ColorValue {
   Rgba(u8, u8, u8, f32),
   /// Single 32-bit integer containing RGB values
   RgbInt(u32),
-  /// CSS color string
+  /// CSS color string, like `#ff0099`, `rgb()`, `hsl()` or `hwb()`
   Css(String),
 }
 
 /// Represents a color with 8-bit RGBA components.
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize, TS, Copy)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(try_from = "ColorValue")]
 #[ts(as = "ColorValue")]
 pub struct Color(pub [u8; 4]);
@@ -76,7 +78,10 @@ impl TryFrom<ColorValue> for Color {
   fn try_from(value: ColorValue) -> Result<Self, Self::Error> {
     match value {
       ColorValue::Rgb(r, g, b) => Ok(Color([r, g, b, 255])),
-      ColorValue::Rgba(r, g, b, a) => Ok(Color([r, g, b, (a * 255.0) as u8])),
+      // rounded like the alpha of parsed CSS colors
+      ColorValue::Rgba(r, g, b, a) => {
+        Ok(Color([r, g, b, (a.clamp(0.0, 1.0) * 255.0).round() as u8]))
+      }
       ColorValue::RgbInt(rgb) => {
         let r = ((rgb >> 16) & 0xFF) as u8;
         let g = ((rgb >> 8) & 0xFF) as u8;
@@ -89,6 +94,75 @@ impl TryFrom<ColorValue> for Color {
   }
 }
 
+/// A color that can also be `currentColor`, which resolves to the `color` of the node.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, TS)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(try_from = "ColorValue")]
+#[ts(as = "ColorValue")]
+pub enum ColorInput {
+  /// The `color` property of the node
+  CurrentColor,
+  /// An explicit color
+  Value(Color),
+}
+
+impl ColorInput {
+  /// Resolves the color with `current_color` as the value of `currentColor`.
+  pub fn resolve(self, current_color: Color) -> Color {
+    match self {
+      ColorInput::CurrentColor => current_color,
+      ColorInput::Value(color) => color,
+    }
+  }
+}
+
+impl From<Color> for ColorInput {
+  fn from(color: Color) -> Self {
+    ColorInput::Value(color)
+  }
+}
+
+impl From<Color> for CssValue<ColorInput> {
+  fn from(color: Color) -> Self {
+    CssValue::Value(color.into())
+  }
+}
+
+impl Serialize for ColorInput {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    match self {
+      ColorInput::CurrentColor => serializer.serialize_str("currentColor"),
+      ColorInput::Value(color) => color.serialize(serializer),
+    }
+  }
+}
+
+impl TryFrom<ColorValue> for ColorInput {
+  type Error = ParseColorError;
+
+  fn try_from(value: ColorValue) -> Result<Self, Self::Error> {
+    match value {
+      ColorValue::Css(css) if css.trim().eq_ignore_ascii_case("currentcolor") => {
+        Ok(ColorInput::CurrentColor)
+      }
+      value => Color::try_from(value).map(ColorInput::Value),
+    }
+  }
+}
+
+impl<'i> FromCss<'i> for ColorInput {
+  fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
+    if input
+      .try_parse(|input| input.expect_ident_matching("currentcolor"))
+      .is_ok()
+    {
+      return Ok(ColorInput::CurrentColor);
+    }
+
+    Color::from_css(input).map(ColorInput::Value)
+  }
+}
+
 impl<'i> FromCss<'i> for Color {
   fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
     let location = input.current_source_location();
@@ -176,6 +250,22 @@ mod tests {
     assert_eq!(result, Color([255, 0, 153, 255]));
   }
 
+  #[test]
+  fn test_parse_hex_color_4_digits() {
+    // Each digit is doubled, like the 3-digit form
+    let result = parse_color_str("#f098").unwrap();
+    assert_eq!(result, Color([255, 0, 153, 136]));
+  }
+
+  #[test]
+  fn test_parse_hex_color_8_digits() {
+    let result = parse_color_str("#ff009980").unwrap();
+    assert_eq!(result, Color([255, 0, 153, 128]));
+
+    let result = parse_color_str("#FF0099FF").unwrap();
+    assert_eq!(result, Color([255, 0, 153, 255]));
+  }
+
   #[test]
   fn test_parse_color_transparent() {
     // Test parsing transparent keyword
@@ -211,6 +301,73 @@ mod tests {
     assert_eq!(result, Color([255, 0, 153, 128]));
   }
 
+  #[test]
+  fn test_parse_color_hsl_function() {
+    // Legacy comma-separated and modern space-separated syntax
+    let result = parse_color_str("hsl(210, 50%, 40%)").unwrap();
+    assert_eq!(result, Color([51, 102, 153, 255]));
+
+    let result = parse_color_str("hsl(210 50% 40%)").unwrap();
+    assert_eq!(result, Color([51, 102, 153, 255]));
+
+    let result = parse_color_str("hsl(210deg 50% 40% / 50%)").unwrap();
+    assert_eq!(result, Color([51, 102, 153, 128]));
+  }
+
+  #[test]
+  fn test_parse_color_hsla_function() {
+    let result = parse_color_str("hsla(0, 100%, 50%, 0.5)").unwrap();
+    assert_eq!(result, Color([255, 0, 0, 128]));
+  }
+
+  #[test]
+  fn test_parse_color_hwb_function() {
+    let result = parse_color_str("hwb(120 0% 0%)").unwrap();
+    assert_eq!(result, Color([0, 255, 0, 255]));
+
+    let result = parse_color_str("hwb(0 40% 40% / 0.5)").unwrap();
+    assert_eq!(result, Color([153, 102, 102, 128]));
+  }
+
+  #[test]
+  fn test_parse_color_input_current_color() {
+    let mut parser_input = ParserInput::new("currentColor");
+    let mut parser = Parser::new(&mut parser_input);
+
+    assert_eq!(
+      ColorInput::from_css(&mut parser),
+      Ok(ColorInput::CurrentColor)
+    );
+
+    assert_eq!(
+      ColorInput::try_from(ColorValue::Css("rebeccapurple".to_string())),
+      Ok(ColorInput::Value(Color([102, 51, 153, 255])))
+    );
+    assert!(parse_color_str("currentColor").is_err());
+  }
+
+  #[test]
+  fn test_parse_color_rgb_with_alpha_percentage() {
+    let result = parse_color_str("rgb(255 0 0 / 50%)").unwrap();
+    assert_eq!(result, Color([255, 0, 0, 128]));
+
+    // out of range channels and alpha are clamped
+    let result = parse_color_str("rgb(300 -10 0 / 150%)").unwrap();
+    assert_eq!(result, Color([255, 0, 0, 255]));
+  }
+
+  #[test]
+  fn test_structured_rgba_alpha_matches_css() {
+    assert_eq!(
+      Color::try_from(ColorValue::Rgba(255, 0, 0, 0.5)),
+      Ok(parse_color_str("rgb(255 0 0 / 50%)").unwrap())
+    );
+    assert_eq!(
+      Color::try_from(ColorValue::Rgba(255, 0, 0, 2.0)),
+      Ok(Color([255, 0, 0, 255]))
+    );
+  }
+
   #[test]
   fn test_parse_named_color_grey() {
     let result = parse_color_str("grey").unwrap();
@@ -0,0 +1,143 @@
+use cssparser::{Parser, ParserInput, Token, match_ignore_ascii_case};
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+use ts_rs::TS;
+
+use crate::layout::style::{FromCss, LengthUnit, ParseResult, TextShadow};
+
+/// A single filter function, like `blur(4px)` or `brightness(120%)`.
+///
+/// Amounts are fractions, `1.0` (or `100%`) leaves the image unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, TS, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum Filter {
+  /// Gaussian blur with the length as standard deviation
+  Blur(LengthUnit),
+  /// Multiplies the color channels by the amount
+  Brightness(f32),
+  /// Scales the distance of the color channels from mid gray by the amount
+  Contrast(f32),
+  /// Scales the saturation by the amount, `0.0` is grayscale
+  Saturate(f32),
+  /// Shadows the painted pixels instead of the box, with the same syntax as `text-shadow`
+  DropShadow(TextShadow),
+}
+
+impl<'i> FromCss<'i> for Filter {
+  fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
+    let location = input.current_source_location();
+    let function = input.expect_function()?.clone();
+
+    input.parse_nested_block(|input| {
+      match_ignore_ascii_case! {&function,
+        "blur" => Ok(Filter::Blur(
+          input.try_parse(LengthUnit::from_css).unwrap_or(LengthUnit::zero()),
+        )),
+        "brightness" => Ok(Filter::Brightness(parse_amount(input)?)),
+        "contrast" => Ok(Filter::Contrast(parse_amount(input)?)),
+        "saturate" => Ok(Filter::Saturate(parse_amount(input)?)),
+        "drop-shadow" => Ok(Filter::DropShadow(TextShadow::from_css(input)?)),
+        _ => Err(location.new_basic_unexpected_token_error(Token::Function(function.clone())).into()),
+      }
+    })
+  }
+}
+
+/// Parses a filter amount written as a number or a percentage, defaulting to `1.0`.
+fn parse_amount<'i>(input: &mut Parser<'i, '_>) -> ParseResult<'i, f32> {
+  if input.is_exhausted() {
+    return Ok(1.0);
+  }
+
+  let location = input.current_source_location();
+  let token = input.next()?;
+
+  match *token {
+    Token::Number { value, .. } => Ok(value.max(0.0)),
+    Token::Percentage { unit_value, .. } => Ok(unit_value.max(0.0)),
+    _ => Err(
+      location
+        .new_basic_unexpected_token_error(token.clone())
+        .into(),
+    ),
+  }
+}
+
+/// A list of filter functions applied in order, like CSS `filter` and `backdrop-filter`.
+#[derive(Debug, Clone, PartialEq, TS, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[ts(as = "FiltersValue")]
+#[serde(try_from = "FiltersValue")]
+pub struct Filters(pub SmallVec<[Filter; 4]>);
+
+/// Proxy type for `Filters` Css deserialization.
+#[derive(Debug, Clone, PartialEq, TS, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(untagged)]
+pub enum FiltersValue {
+  /// An explicit list of filters.
+  #[ts(as = "Vec<Filter>")]
+  Structured(SmallVec<[Filter; 4]>),
+  /// A CSS string, like `blur(12px) saturate(180%)`.
+  Css(String),
+}
+
+impl TryFrom<FiltersValue> for Filters {
+  type Error = String;
+
+  fn try_from(value: FiltersValue) -> Result<Self, Self::Error> {
+    match value {
+      FiltersValue::Structured(filters) => Ok(Filters(filters)),
+      FiltersValue::Css(css) => {
+        let mut input = ParserInput::new(&css);
+        let mut parser = Parser::new(&mut input);
+
+        let mut filters = SmallVec::new();
+
+        while !parser.is_exhausted() {
+          filters.push(Filter::from_css(&mut parser).map_err(|e| e.to_string())?);
+        }
+
+        Ok(Filters(filters))
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::layout::style::Color;
+
+  use super::*;
+
+  #[test]
+  fn test_parse_filters() {
+    let filters = Filters::try_from(FiltersValue::Css(
+      "blur(12px) brightness(120%) contrast(0.8) saturate() drop-shadow(2px 4px 6px red)"
+        .to_string(),
+    ));
+
+    assert_eq!(
+      filters,
+      Ok(Filters(
+        [
+          Filter::Blur(LengthUnit::Px(12.0)),
+          Filter::Brightness(1.2),
+          Filter::Contrast(0.8),
+          Filter::Saturate(1.0),
+          Filter::DropShadow(TextShadow {
+            offset_x: LengthUnit::Px(2.0),
+            offset_y: LengthUnit::Px(4.0),
+            blur_radius: LengthUnit::Px(6.0),
+            color: Color([255, 0, 0, 255]),
+          }),
+        ]
+        .into_iter()
+        .collect()
+      ))
+    );
+
+    assert!(Filters::try_from(FiltersValue::Css("sepia(1)".to_string())).is_err());
+  }
+}
@@ -0,0 +1,185 @@
+use cssparser::{Parser, ParserInput};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::layout::style::{FromCss, LengthUnit, ParseResult};
+
+/// Represents the `flex` shorthand which accepts a grow factor, a shrink factor and a basis.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(untagged)]
+pub(crate) enum FlexValue {
+  /// Structured representation when provided as JSON.
+  Structured {
+    grow: f32,
+    shrink: f32,
+    basis: LengthUnit,
+  },
+  /// A single grow factor, like `flex: 1`.
+  Number(f32),
+  /// Raw CSS string representation.
+  Css(String),
+}
+
+/// Parsed `flex` value.
+///
+/// The longhands `flexGrow`, `flexShrink` and `flexBasis` override the matching part.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(try_from = "FlexValue")]
+#[ts(as = "FlexValue")]
+pub struct Flex {
+  /// The flex grow factor.
+  pub grow: f32,
+  /// The flex shrink factor.
+  pub shrink: f32,
+  /// The flex basis.
+  pub basis: LengthUnit,
+}
+
+impl Default for Flex {
+  /// The initial value, `0 1 auto`.
+  fn default() -> Self {
+    Self {
+      grow: 0.0,
+      shrink: 1.0,
+      basis: LengthUnit::Auto,
+    }
+  }
+}
+
+impl Flex {
+  /// A single grow factor, the shrink factor is `1` and the basis is `0` like in CSS.
+  pub fn grow(grow: f32) -> Self {
+    Self {
+      grow,
+      shrink: 1.0,
+      basis: LengthUnit::Px(0.0),
+    }
+  }
+}
+
+impl TryFrom<FlexValue> for Flex {
+  type Error = String;
+
+  fn try_from(value: FlexValue) -> Result<Self, Self::Error> {
+    match value {
+      FlexValue::Structured {
+        grow,
+        shrink,
+        basis,
+      } => Ok(Flex {
+        grow,
+        shrink,
+        basis,
+      }),
+      FlexValue::Number(grow) => Ok(Flex::grow(grow)),
+      FlexValue::Css(css) => {
+        let mut input = ParserInput::new(&css);
+        let mut parser = Parser::new(&mut input);
+
+        let flex = Flex::from_css(&mut parser).map_err(|e| e.to_string())?;
+
+        parser.expect_exhausted().map_err(|e| format!("{e:?}"))?;
+
+        Ok(flex)
+      }
+    }
+  }
+}
+
+impl<'i> FromCss<'i> for Flex {
+  fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
+    if input
+      .try_parse(|input| input.expect_ident_matching("none"))
+      .is_ok()
+    {
+      return Ok(Flex {
+        grow: 0.0,
+        shrink: 0.0,
+        basis: LengthUnit::Auto,
+      });
+    }
+
+    let mut factors = None;
+    let mut basis = None;
+
+    // the factors and the basis can come in either order
+    loop {
+      if factors.is_none()
+        && let Ok(grow) = input.try_parse(Parser::expect_number)
+      {
+        let shrink = input.try_parse(Parser::expect_number).ok();
+
+        factors = Some((grow, shrink));
+        continue;
+      }
+
+      if basis.is_none()
+        && let Ok(length) = input.try_parse(LengthUnit::from_css)
+      {
+        basis = Some(length);
+        continue;
+      }
+
+      break;
+    }
+
+    if factors.is_none() && basis.is_none() {
+      return Err(input.new_error_for_next_token());
+    }
+
+    // omitted factors are 1 and an omitted basis is 0, so `flex: 1` and `flex: auto` match CSS
+    let (grow, shrink) = factors.unwrap_or((1.0, None));
+
+    Ok(Flex {
+      grow,
+      shrink: shrink.unwrap_or(1.0),
+      basis: basis.unwrap_or(LengthUnit::Px(0.0)),
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn parse(css: &str) -> Result<Flex, String> {
+    Flex::try_from(FlexValue::Css(css.to_string()))
+  }
+
+  fn flex(grow: f32, shrink: f32, basis: LengthUnit) -> Flex {
+    Flex {
+      grow,
+      shrink,
+      basis,
+    }
+  }
+
+  #[test]
+  fn test_parse_flex_shorthand() {
+    assert_eq!(parse("1"), Ok(flex(1.0, 1.0, LengthUnit::Px(0.0))));
+    assert_eq!(
+      parse("1 1 0%"),
+      Ok(flex(1.0, 1.0, LengthUnit::Percentage(0.0)))
+    );
+    assert_eq!(parse("2 3"), Ok(flex(2.0, 3.0, LengthUnit::Px(0.0))));
+    assert_eq!(parse("auto"), Ok(flex(1.0, 1.0, LengthUnit::Auto)));
+    assert_eq!(parse("none"), Ok(flex(0.0, 0.0, LengthUnit::Auto)));
+    assert_eq!(parse("100px"), Ok(flex(1.0, 1.0, LengthUnit::Px(100.0))));
+    assert_eq!(parse("10em 2"), Ok(flex(2.0, 1.0, LengthUnit::Em(10.0))));
+
+    assert!(parse("").is_err());
+    assert!(parse("1 2 3 4").is_err());
+    assert!(parse("grow").is_err());
+  }
+
+  #[test]
+  fn test_deserialize_flex_number() {
+    assert_eq!(serde_json::from_str::<Flex>("1").unwrap(), Flex::grow(1.0));
+    assert_eq!(
+      serde_json::from_str::<Flex>("\"0 0 auto\"").unwrap(),
+      flex(0.0, 0.0, LengthUnit::Auto)
+    );
+  }
+}
@@ -11,6 +11,20 @@ use ts_rs::TS;
 #[ts(type = "string")]
 pub struct FontFeatureSettings(pub SmallVec<[FontFeature; 4]>);
 
+#[cfg(feature = "schema")]
+impl schemars::JsonSchema for FontFeatureSettings {
+  fn schema_name() -> std::borrow::Cow<'static, str> {
+    "FontFeatureSettings".into()
+  }
+
+  fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+    schemars::json_schema!({
+      "description": "CSS font-feature-settings value, e.g. '\"liga\" 1'",
+      "type": "string"
+    })
+  }
+}
+
 impl<'de> Deserialize<'de> for FontFeatureSettings {
   fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
   where
@@ -13,6 +13,20 @@ impl From<FontStyle> for ParleyFontStyle {
   }
 }
 
+#[cfg(feature = "schema")]
+impl schemars::JsonSchema for FontStyle {
+  fn schema_name() -> std::borrow::Cow<'static, str> {
+    "FontStyle".into()
+  }
+
+  fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+    schemars::json_schema!({
+      "description": "\"normal\", \"italic\", \"oblique\" or \"oblique <angle>deg\"",
+      "type": "string"
+    })
+  }
+}
+
 impl<'de> Deserialize<'de> for FontStyle {
   fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
   where
@@ -11,6 +11,20 @@ use ts_rs::TS;
 #[ts(type = "string")]
 pub struct FontVariationSettings(pub SmallVec<[FontVariation; 4]>);
 
+#[cfg(feature = "schema")]
+impl schemars::JsonSchema for FontVariationSettings {
+  fn schema_name() -> std::borrow::Cow<'static, str> {
+    "FontVariationSettings".into()
+  }
+
+  fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+    schemars::json_schema!({
+      "description": "CSS font-variation-settings value, e.g. '\"wght\" 700'",
+      "type": "string"
+    })
+  }
+}
+
 impl<'de> Deserialize<'de> for FontVariationSettings {
   fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
   where
@@ -21,6 +21,20 @@ impl From<f32> for FontWeight {
   }
 }
 
+#[cfg(feature = "schema")]
+impl schemars::JsonSchema for FontWeight {
+  fn schema_name() -> std::borrow::Cow<'static, str> {
+    "FontWeight".into()
+  }
+
+  fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+    schemars::json_schema!({
+      "description": "\"normal\", \"bold\" or a number from 0 to 1000",
+      "type": ["string", "number"]
+    })
+  }
+}
+
 impl<'de> Deserialize<'de> for FontWeight {
   fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
   where
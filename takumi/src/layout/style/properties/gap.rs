@@ -12,7 +12,12 @@ use crate::{
 ///
 /// Can be either a single value applied to both axes, or separate values
 /// for horizontal and vertical spacing.
+///
+/// Like the CSS shorthand, the first value is the row gap and the second the column gap.
+/// Percentages of the row gap refer to the height of the container's content box, the ones of
+/// the column gap to its width.
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(try_from = "GapValue")]
 #[ts(as = "GapValue")]
 pub struct Gap(pub LengthUnit, pub LengthUnit);
@@ -22,6 +27,7 @@ pub struct Gap(pub LengthUnit, pub LengthUnit);
 /// Can be either a single value applied to both axes, or separate values
 /// for horizontal and vertical spacing.
 #[derive(Debug, Clone, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum GapValue {
   /// Same gap value for both horizontal and vertical spacing
@@ -66,6 +72,9 @@ impl Gap {
   ///
   /// This method converts the gap value to a size in length percentages,
   /// which can be used to set the size of flex items in a flex container.
+  ///
+  /// Percentages are kept for the layout, which resolves them against the container size of
+  /// their own axis.
   pub(crate) fn resolve_to_size(self, context: &RenderContext) -> Size<LengthPercentage> {
     Size {
       height: self.0.resolve_to_length_percentage(context),
@@ -76,6 +85,19 @@ impl Gap {
 
 #[cfg(test)]
 mod tests {
+  use crate::{
+    GlobalContext,
+    layout::{
+      Viewport,
+      node::{ContainerNode, NodeKind},
+      style::{
+        Display, GridLengthUnit, GridTemplateComponent, GridTemplateComponents, GridTrackSize,
+        Style, StyleBuilder,
+      },
+    },
+    rendering::compute_layout_tree,
+  };
+
   use super::*;
 
   #[test]
@@ -106,9 +128,60 @@ mod tests {
     assert_eq!(gap_two, Gap(LengthUnit::Px(10.0), LengthUnit::Px(20.0)));
   }
 
+  #[test]
+  fn test_gap_deserialize_css_string() {
+    let gap: Gap = serde_json::from_str("\"0 0.25em\"").expect("css string deserializes");
+    assert_eq!(gap, Gap(LengthUnit::Px(0.0), LengthUnit::Em(0.25)));
+  }
+
   #[test]
   fn test_gap_from_css_invalid() {
     let res = Gap::try_from(GapValue::Css("invalid".to_string()));
     assert!(res.is_err());
   }
+
+  #[test]
+  fn test_gap_percentages_resolve_against_container() {
+    let two_tracks = || {
+      Some(GridTemplateComponents(vec![
+        GridTemplateComponent::Single(
+          GridTrackSize::Fixed(GridLengthUnit::Fr(1.0))
+        );
+        2
+      ]))
+    };
+
+    let cell = || -> NodeKind {
+      ContainerNode {
+        style: Style::default(),
+        children: None,
+      }
+      .into()
+    };
+
+    let node: NodeKind = ContainerNode {
+      style: StyleBuilder::default()
+        .width(LengthUnit::Px(400.0))
+        .height(LengthUnit::Px(200.0))
+        .display(Display::Grid)
+        .grid_template_columns(two_tracks())
+        .grid_template_rows(two_tracks())
+        .gap(Gap(
+          LengthUnit::Percentage(5.0),
+          LengthUnit::Percentage(5.0),
+        ))
+        .build()
+        .unwrap(),
+      children: Some(vec![cell(), cell(), cell(), cell()]),
+    }
+    .into();
+
+    let tree = compute_layout_tree(Viewport::new(800, 600), &GlobalContext::default(), node);
+
+    // the column gap is 5% of the width, the row gap 5% of the height
+    assert_eq!(tree.get(&[1]).unwrap().location.x, 190.0 + 20.0);
+    assert_eq!(tree.get(&[2]).unwrap().location.y, 95.0 + 10.0);
+    assert_eq!(tree.get(&[0]).unwrap().size.width, 190.0);
+    assert_eq!(tree.get(&[0]).unwrap().size.height, 95.0);
+  }
 }
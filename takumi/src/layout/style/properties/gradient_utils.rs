@@ -1,6 +1,6 @@
 use smallvec::SmallVec;
 
-use super::{Color, GradientStop, LengthUnit, ResolvedGradientStop};
+use super::{Color, GradientColorSpace, GradientStop, LengthUnit, ResolvedGradientStop};
 use crate::rendering::RenderContext;
 
 /// Interpolates between two colors in RGBA space, if t is 0.0 or 1.0, returns the first or second color.
@@ -21,8 +21,72 @@ pub(crate) fn interpolate_rgba(c1: Color, c2: Color, t: f32) -> Color {
   Color(out)
 }
 
+/// Converts a gamma-encoded sRGB channel (0-255) to linear light (0.0-1.0).
+fn srgb_to_linear(value: u8) -> f32 {
+  let c = value as f32 / 255.0;
+
+  if c <= 0.04045 {
+    c / 12.92
+  } else {
+    ((c + 0.055) / 1.055).powf(2.4)
+  }
+}
+
+/// Converts a linear light channel (0.0-1.0) back to gamma-encoded sRGB (0-255).
+fn linear_to_srgb(value: f32) -> u8 {
+  let c = value.clamp(0.0, 1.0);
+
+  let encoded = if c <= 0.0031308 {
+    c * 12.92
+  } else {
+    1.055 * c.powf(1.0 / 2.4) - 0.055
+  };
+
+  (encoded * 255.0).round() as u8
+}
+
+/// Interpolates between two colors in linear-light RGB, alpha is interpolated linearly.
+pub(crate) fn interpolate_linear_rgba(c1: Color, c2: Color, t: f32) -> Color {
+  if t <= f32::EPSILON {
+    return c1;
+  }
+  if t >= 1.0 - f32::EPSILON {
+    return c2;
+  }
+
+  let mut out = [0u8; 4];
+
+  for (i, value) in out.iter_mut().take(3).enumerate() {
+    let from = srgb_to_linear(c1.0[i]);
+    let to = srgb_to_linear(c2.0[i]);
+
+    *value = linear_to_srgb(from * (1.0 - t) + to * t);
+  }
+
+  out[3] = (c1.0[3] as f32 * (1.0 - t) + c2.0[3] as f32 * t).round() as u8;
+
+  Color(out)
+}
+
+/// Interpolates between two colors in the given color space.
+pub(crate) fn interpolate_color(
+  c1: Color,
+  c2: Color,
+  t: f32,
+  color_space: GradientColorSpace,
+) -> Color {
+  match color_space {
+    GradientColorSpace::Srgb => interpolate_rgba(c1, c2, t),
+    GradientColorSpace::LinearRgb => interpolate_linear_rgba(c1, c2, t),
+  }
+}
+
 /// Returns the color for a pixel-space position along the resolved stops.
-pub(crate) fn color_from_stops(position: f32, resolved_stops: &[ResolvedGradientStop]) -> Color {
+pub(crate) fn color_from_stops(
+  position: f32,
+  resolved_stops: &[ResolvedGradientStop],
+  color_space: GradientColorSpace,
+) -> Color {
   // Find the two stops that bracket the current position.
   // We want the last stop with position <= current position.
   let left_index = resolved_stops
@@ -50,10 +114,32 @@ pub(crate) fn color_from_stops(position: f32, resolved_stops: &[ResolvedGradient
       ((position - left_stop.position) / denom).clamp(0.0, 1.0)
     };
 
-    interpolate_rgba(left_stop.color, right_stop.color, interpolation_position)
+    interpolate_color(
+      left_stop.color,
+      right_stop.color,
+      interpolation_position,
+      color_space,
+    )
   }
 }
 
+/// Wraps a position into the range between the first and last stop, for repeating gradients.
+///
+/// Stops spanning no distance have nothing to repeat, the position is returned unchanged.
+pub(crate) fn repeat_position(position: f32, resolved_stops: &[ResolvedGradientStop]) -> f32 {
+  let (Some(first), Some(last)) = (resolved_stops.first(), resolved_stops.last()) else {
+    return position;
+  };
+
+  let period = last.position - first.position;
+
+  if period <= f32::EPSILON {
+    return position;
+  }
+
+  first.position + (position - first.position).rem_euclid(period)
+}
+
 /// Resolve a length value used in a gradient stop to an absolute pixel position along an axis.
 pub(crate) fn stop_length_to_axis_px(
   length: LengthUnit,
@@ -210,6 +296,7 @@ mod tests {
       parent_font_size: DEFAULT_FONT_SIZE,
       transform: Affine::identity(),
       style: InheritedStyle::default(),
+      border: None,
     };
 
     let resolved = resolve_stops_along_axis(&stops, ctx.viewport.width as f32, &ctx);
@@ -262,6 +349,7 @@ mod tests {
       parent_font_size: DEFAULT_FONT_SIZE,
       transform: Affine::identity(),
       style: InheritedStyle::default(),
+      border: None,
     };
 
     let resolved = resolve_stops_along_axis(&stops, ctx.viewport.width as f32, &ctx);
@@ -311,6 +399,7 @@ mod tests {
       parent_font_size: DEFAULT_FONT_SIZE,
       transform: Affine::identity(),
       style: InheritedStyle::default(),
+      border: None,
     };
 
     let resolved = resolve_stops_along_axis(&stops, ctx.viewport.width as f32, &ctx);
@@ -340,4 +429,32 @@ mod tests {
       },
     );
   }
+
+  #[test]
+  fn test_linear_rgb_interpolation_midpoint() {
+    let red = Color([255, 0, 0, 255]);
+    let green = Color([0, 255, 0, 255]);
+
+    // straight sRGB lerp goes through a dark muddy midpoint
+    assert_eq!(interpolate_rgba(red, green, 0.5), Color([128, 128, 0, 255]));
+
+    // linear-light lerp keeps the midpoint bright
+    assert_eq!(
+      interpolate_linear_rgba(red, green, 0.5),
+      Color([188, 188, 0, 255])
+    );
+  }
+
+  #[test]
+  fn test_linear_rgb_interpolation_endpoints() {
+    let c1 = Color([12, 34, 56, 78]);
+    let c2 = Color([210, 180, 150, 120]);
+
+    assert_eq!(interpolate_linear_rgba(c1, c2, 0.0), c1);
+    assert_eq!(interpolate_linear_rgba(c1, c2, 1.0), c2);
+    assert_eq!(
+      interpolate_linear_rgba(c1, c2, 0.5).0[3],
+      interpolate_rgba(c1, c2, 0.5).0[3]
+    );
+  }
 }
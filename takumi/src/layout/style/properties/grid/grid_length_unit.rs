@@ -10,6 +10,7 @@ use crate::{
 
 /// Represents a grid track sizing function with serde support
 #[derive(Debug, Clone, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(try_from = "GridLengthUnitValue")]
 #[ts(as = "GridLengthUnitValue")]
 pub enum GridLengthUnit {
@@ -22,6 +23,7 @@ pub enum GridLengthUnit {
 
 /// Represents a grid length unit value with serde support
 #[derive(Debug, Clone, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "kebab-case")]
 pub enum GridLengthUnitValue {
   /// A fraction of the available space
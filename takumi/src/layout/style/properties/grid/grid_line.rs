@@ -8,6 +8,7 @@ use super::GridPlacement;
 
 /// Represents a grid line placement with serde support
 #[derive(Debug, Clone, Deserialize, Serialize, TS, Default, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[ts(optional_fields)]
 pub struct GridLine {
   /// The start line placement
@@ -18,9 +19,15 @@ pub struct GridLine {
 
 impl From<GridLine> for taffy::Line<taffy::GridPlacement> {
   fn from(line: GridLine) -> Self {
+    // a lone name is used for both ends like in CSS, so `gridColumn: header` covers the whole area
+    let end = match (&line.start, line.end) {
+      (Some(GridPlacement::Named(name)), None) => GridPlacement::Named(name.clone()),
+      (_, end) => end.unwrap_or_default(),
+    };
+
     Self {
       start: line.start.unwrap_or_default().into(),
-      end: line.end.unwrap_or_default().into(),
+      end: end.into(),
     }
   }
 }
@@ -67,4 +74,22 @@ mod tests {
     assert_eq!(line.start, Some(GridPlacement::Span(2)));
     assert_eq!(line.end, Some(GridPlacement::Line(3)));
   }
+
+  #[test]
+  fn test_lone_name_resolves_both_ends() {
+    let line: taffy::Line<taffy::GridPlacement> = GridLine {
+      start: Some(GridPlacement::Named("header".to_string())),
+      end: None,
+    }
+    .into();
+
+    assert_eq!(
+      line.start,
+      taffy::GridPlacement::NamedLine("header".to_string(), 0)
+    );
+    assert_eq!(
+      line.end,
+      taffy::GridPlacement::NamedLine("header".to_string(), 0)
+    );
+  }
 }
@@ -6,6 +6,7 @@ use crate::layout::style::{FromCss, GridLengthUnit, ParseResult};
 
 /// Represents a grid minmax()
 #[derive(Debug, Clone, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct GridMinMaxSize {
   /// The minimum size of the grid item
   pub min: GridLengthUnit,
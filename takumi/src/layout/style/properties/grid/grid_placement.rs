@@ -6,6 +6,7 @@ use crate::layout::style::{FromCss, ParseResult};
 
 /// Represents a grid placement with serde support
 #[derive(Debug, Clone, Deserialize, Serialize, TS, Default, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "kebab-case")]
 pub enum GridPlacement {
   /// Auto placement
@@ -17,7 +18,7 @@ pub enum GridPlacement {
   #[serde(untagged)]
   Line(i16),
   #[serde(untagged)]
-  /// Named grid area
+  /// Named grid line or area, `header` matches `header-start` or `header-end` from the template areas
   Named(String),
 }
 
@@ -28,7 +29,7 @@ impl From<GridPlacement> for taffy::GridPlacement {
       GridPlacement::Auto => taffy::GridPlacement::Auto,
       GridPlacement::Line(line) => taffy::GridPlacement::Line(line.into()),
       GridPlacement::Span(span) => taffy::GridPlacement::Span(span),
-      GridPlacement::Named(_) => taffy::GridPlacement::Auto,
+      GridPlacement::Named(name) => taffy::GridPlacement::NamedLine(name, 0),
     }
   }
 }
@@ -6,6 +6,7 @@ use crate::layout::style::{FromCss, GridTrackSize, ParseResult};
 
 /// Represents a grid repeat track
 #[derive(Debug, Clone, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[ts(optional_fields)]
 pub struct GridRepeatTrack {
@@ -6,6 +6,7 @@ use crate::layout::style::{FromCss, ParseResult};
 
 /// Represents a grid track repetition pattern
 #[derive(Debug, Clone, Deserialize, Serialize, TS, Copy, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "kebab-case")]
 pub enum GridRepetitionCount {
   /// Automatically fills the available space with as many tracks as possible
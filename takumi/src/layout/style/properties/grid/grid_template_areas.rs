@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use cssparser::{Parser, ParserInput, Token};
 use serde::{Deserialize, Serialize};
@@ -11,12 +11,14 @@ use crate::layout::style::{FromCss, ParseResult};
 /// Supports either a 2D matrix of area names (use "." for empty) or a CSS string value
 /// like: "a a ." "b b c"
 #[derive(Default, Debug, Clone, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(try_from = "GridTemplateAreasValue")]
 #[ts(as = "GridTemplateAreasValue")]
 pub struct GridTemplateAreas(pub Vec<Vec<String>>);
 
 /// Serde helper that accepts either a matrix or a CSS string
 #[derive(Debug, Clone, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum GridTemplateAreasValue {
   /// A 2D matrix representation (use "." for empty)
@@ -55,7 +57,8 @@ impl From<GridTemplateAreas> for Vec<taffy::GridTemplateArea<String>> {
       return Vec::new();
     }
 
-    let mut bounds: HashMap<&str, (usize, usize, usize, usize)> = HashMap::new();
+    // ordered by name, so the same template always produces the same areas
+    let mut bounds: BTreeMap<&str, (usize, usize, usize, usize)> = BTreeMap::new();
     for (r, row) in value.0.iter().enumerate() {
       for (c, cell) in row.iter().enumerate() {
         if cell == "." {
@@ -11,6 +11,7 @@ use super::{GridRepeatTrack, GridRepetitionCount, GridTrackSize};
 /// This exists to provide a distinct type for template component lists while
 /// preserving JSON compatibility (serialized as a plain array) and clean TS types.
 #[derive(Debug, Clone, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(try_from = "GridTemplateComponentsValue")]
 #[ts(as = "GridTemplateComponentsValue")]
 pub struct GridTemplateComponents(pub Vec<GridTemplateComponent>);
@@ -18,6 +19,7 @@ pub struct GridTemplateComponents(pub Vec<GridTemplateComponent>);
 /// Serializable input for `GridTemplateComponents` that accepts either a
 /// pre-parsed component list or a CSS string to be parsed at runtime.
 #[derive(Debug, Clone, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum GridTemplateComponentsValue {
   /// Explicit list of template components.
@@ -50,6 +52,7 @@ impl TryFrom<GridTemplateComponentsValue> for GridTemplateComponents {
 
 /// Represents a track sizing function or a list of line names between tracks
 #[derive(Debug, Clone, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum GridTemplateComponent {
   /// A list of line names that apply to the current grid line (e.g., [a b])
@@ -10,6 +10,7 @@ use crate::{
 
 /// A wrapper around a list of `GridTrackSize` that can also be parsed from a CSS string.
 #[derive(Debug, Clone, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(try_from = "GridTrackSizesValue")]
 #[ts(as = "GridTrackSizesValue")]
 pub struct GridTrackSizes(pub Vec<GridTrackSize>);
@@ -17,6 +18,7 @@ pub struct GridTrackSizes(pub Vec<GridTrackSize>);
 /// Serializable input for `GridTrackSizes` that accepts either a list of
 /// pre-parsed `GridTrackSize` values or a CSS string to parse.
 #[derive(Debug, Clone, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum GridTrackSizesValue {
   /// Explicit list of track sizes.
@@ -48,6 +50,7 @@ impl TryFrom<GridTrackSizesValue> for GridTrackSizes {
 
 /// Represents a grid track size
 #[derive(Debug, Clone, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum GridTrackSize {
   /// A minmax() track size
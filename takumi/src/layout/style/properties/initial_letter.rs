@@ -0,0 +1,124 @@
+use cssparser::{Parser, ParserInput};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::layout::style::{FromCss, ParseResult};
+
+/// Represents the `initial-letter` property, which draws the first letter of the text as a
+/// drop cap.
+///
+/// The letter is as tall as `size` lines and sits on the baseline of line `sink`, the lines it
+/// sinks into are shortened to make room for it. A `sink` smaller than `size` raises the letter
+/// above the first line.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(try_from = "InitialLetterValue")]
+#[ts(as = "InitialLetterValue")]
+pub struct InitialLetter {
+  /// The height of the letter in lines
+  pub size: f32,
+  /// The number of lines the letter sinks into
+  pub sink: u32,
+}
+
+/// Represents a value for the `initial-letter` property.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(untagged)]
+pub enum InitialLetterValue {
+  /// The height in lines, the letter sinks as deep as it is tall
+  Size(f32),
+  /// Structured representation when provided as JSON
+  Structured {
+    /// The height of the letter in lines
+    size: f32,
+    /// The number of lines the letter sinks into, defaults to the size
+    sink: Option<u32>,
+  },
+  /// CSS string representation, like `3` or `3 2`
+  Css(String),
+}
+
+impl InitialLetter {
+  fn new(size: f32, sink: Option<u32>) -> Result<Self, String> {
+    if size.is_nan() || size < 1.0 {
+      return Err("initial-letter size must be at least 1".to_string());
+    }
+
+    let sink = sink.unwrap_or(size.floor() as u32);
+
+    if sink == 0 {
+      return Err("initial-letter sink must be at least 1".to_string());
+    }
+
+    Ok(Self { size, sink })
+  }
+}
+
+impl TryFrom<InitialLetterValue> for InitialLetter {
+  type Error = String;
+
+  fn try_from(value: InitialLetterValue) -> Result<Self, Self::Error> {
+    match value {
+      InitialLetterValue::Size(size) => Self::new(size, None),
+      InitialLetterValue::Structured { size, sink } => Self::new(size, sink),
+      InitialLetterValue::Css(css) => {
+        let mut input = ParserInput::new(&css);
+        let mut parser = Parser::new(&mut input);
+
+        let initial_letter = InitialLetter::from_css(&mut parser).map_err(|e| e.to_string())?;
+
+        parser.expect_exhausted().map_err(|e| format!("{e:?}"))?;
+
+        Self::new(initial_letter.size, Some(initial_letter.sink))
+      }
+    }
+  }
+}
+
+impl<'i> FromCss<'i> for InitialLetter {
+  /// Parses the size in lines, optionally followed by the number of lines the letter sinks into.
+  fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
+    let size = input.expect_number()?;
+
+    let sink = input
+      .try_parse(Parser::expect_integer)
+      .ok()
+      .map(|sink| sink.max(0) as u32)
+      .unwrap_or(size.max(0.0).floor() as u32);
+
+    Ok(Self { size, sink })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn parse(css: &str) -> Result<InitialLetter, String> {
+    InitialLetter::try_from(InitialLetterValue::Css(css.to_string()))
+  }
+
+  #[test]
+  fn test_parse_initial_letter() {
+    assert_eq!(parse("3"), Ok(InitialLetter { size: 3.0, sink: 3 }));
+    assert_eq!(parse("3 2"), Ok(InitialLetter { size: 3.0, sink: 2 }));
+    assert_eq!(parse("2.5"), Ok(InitialLetter { size: 2.5, sink: 2 }));
+
+    assert!(parse("0.5").is_err());
+    assert!(parse("3 0").is_err());
+    assert!(parse("3 2 1").is_err());
+  }
+
+  #[test]
+  fn test_deserialize_initial_letter() {
+    assert_eq!(
+      serde_json::from_str::<InitialLetter>("3").unwrap(),
+      InitialLetter { size: 3.0, sink: 3 }
+    );
+    assert_eq!(
+      serde_json::from_str::<InitialLetter>(r#"{"size": 4, "sink": 2}"#).unwrap(),
+      InitialLetter { size: 4.0, sink: 2 }
+    );
+  }
+}
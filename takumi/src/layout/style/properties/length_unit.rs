@@ -13,6 +13,7 @@ use crate::{
 /// This corresponds to CSS values that can be specified as pixels, percentages,
 /// or the 'auto' keyword for automatic sizing.
 #[derive(Default, Debug, Clone, Deserialize, Serialize, PartialEq, Copy, TS)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(try_from = "LengthUnitValue", into = "LengthUnitValue")]
 #[ts(as = "LengthUnitValue")]
 pub enum LengthUnit {
@@ -47,6 +48,7 @@ pub enum LengthUnit {
 
 /// Proxy type for CSS `LengthUnit` serialization/deserialization.
 #[derive(Debug, Deserialize, Serialize, TS)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "kebab-case")]
 pub enum LengthUnitValue {
   /// Automatic sizing based on content
@@ -108,6 +110,9 @@ impl TryFrom<LengthUnitValue> for LengthUnit {
 
         let unit = LengthUnit::from_css(&mut parser).map_err(|e| e.to_string())?;
 
+        // so untagged proxies containing a length, like gap, can fall through to their css variant
+        parser.expect_exhausted().map_err(|e| format!("{e:?}"))?;
+
         Ok(unit)
       }
     }
@@ -217,7 +222,7 @@ impl LengthUnit {
       LengthUnit::Rem(value) => CompactLength::length(value * context.viewport.font_size),
       LengthUnit::Em(value) => CompactLength::length(value * context.parent_font_size),
       LengthUnit::Vh(value) => {
-        CompactLength::length(context.viewport.height as f32 * value / 100.0)
+        CompactLength::length(context.viewport.height.unwrap_or_default() as f32 * value / 100.0)
       }
       LengthUnit::Vw(value) => CompactLength::length(context.viewport.width as f32 * value / 100.0),
       _ => CompactLength::length(self.resolve_to_px(context, context.viewport.width as f32)),
@@ -251,7 +256,7 @@ impl LengthUnit {
       LengthUnit::Percentage(value) => (value / 100.0) * percentage_full_px,
       LengthUnit::Rem(value) => value * context.viewport.font_size,
       LengthUnit::Em(value) => value * context.parent_font_size,
-      LengthUnit::Vh(value) => value * context.viewport.height as f32 / 100.0,
+      LengthUnit::Vh(value) => value * context.viewport.height.unwrap_or_default() as f32 / 100.0,
       LengthUnit::Vw(value) => value * context.viewport.width as f32 / 100.0,
       LengthUnit::Cm(value) => value * ONE_CM_IN_PX,
       LengthUnit::Mm(value) => value * ONE_MM_IN_PX,
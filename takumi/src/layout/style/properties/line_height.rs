@@ -10,20 +10,40 @@ use crate::{
   rendering::RenderContext,
 };
 
-/// Represents a line height value, number value is parsed as em.
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, TS, Copy)]
+/// Represents a line height value.
+///
+/// A number multiplies the font size of each node it applies to, so children with larger fonts
+/// inherit a proportionally larger line height. A length is computed to pixels on the node that
+/// declares it, and children inherit the pixel value like in CSS.
+#[derive(Debug, Clone, PartialEq, Deserialize, TS, Copy)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(try_from = "LineHeightValue")]
 #[ts(as = "LineHeightValue")]
-pub struct LineHeight(pub LengthUnit);
+pub enum LineHeight {
+  /// A multiplier of the font size.
+  Number(f32),
+  /// A length, `em` and percentages are relative to the font size.
+  Length(LengthUnit),
+}
+
+impl Serialize for LineHeight {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    match self {
+      LineHeight::Number(number) => serializer.serialize_f32(*number),
+      LineHeight::Length(length) => length.serialize(serializer),
+    }
+  }
+}
 
 impl Default for LineHeight {
   fn default() -> Self {
-    Self(LengthUnit::Em(DEFAULT_LINE_HEIGHT_SCALER)) // Default line height
+    Self::Number(DEFAULT_LINE_HEIGHT_SCALER) // Default line height
   }
 }
 
 /// Proxy type for `LineHeight` Css deserialization.
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize, TS)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum LineHeightValue {
   /// A number value.
@@ -39,14 +59,14 @@ impl TryFrom<LineHeightValue> for LineHeight {
 
   fn try_from(value: LineHeightValue) -> Result<Self, Self::Error> {
     match value {
-      LineHeightValue::Number(number) => Ok(LineHeight(LengthUnit::Em(number))),
+      LineHeightValue::Number(number) => Ok(LineHeight::Number(number)),
       LineHeightValue::Css(css) => {
         let mut input = ParserInput::new(&css);
         let mut parser = Parser::new(&mut input);
 
         LineHeight::from_css(&mut parser).map_err(|e| e.to_string())
       }
-      LineHeightValue::Length(length) => Ok(LineHeight(length)),
+      LineHeightValue::Length(length) => Ok(LineHeight::Length(length)),
     }
   }
 }
@@ -54,21 +74,40 @@ impl TryFrom<LineHeightValue> for LineHeight {
 impl<'i> FromCss<'i> for LineHeight {
   fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
     let Ok(number) = input.try_parse(Parser::expect_number) else {
-      return LengthUnit::from_css(input).map(LineHeight);
+      return LengthUnit::from_css(input).map(LineHeight::Length);
     };
 
-    Ok(LineHeight(LengthUnit::Em(number)))
+    Ok(LineHeight::Number(number))
   }
 }
 
 impl LineHeight {
+  /// Returns the value inherited by the children, lengths are resolved to pixels against the
+  /// font size of the node declaring them.
+  pub(crate) fn to_computed(self, context: &RenderContext, font_size: f32) -> Self {
+    match self {
+      LineHeight::Number(_) | LineHeight::Length(LengthUnit::Px(_)) => self,
+      LineHeight::Length(LengthUnit::Em(value)) => {
+        LineHeight::Length(LengthUnit::Px(value * font_size))
+      }
+      LineHeight::Length(unit) => {
+        LineHeight::Length(LengthUnit::Px(unit.resolve_to_px(context, font_size)))
+      }
+    }
+  }
+
   /// Converts the line height to a parley line height.
   pub(crate) fn into_parley(self, context: &RenderContext) -> parley::LineHeight {
-    match self.0 {
-      LengthUnit::Px(value) => parley::LineHeight::Absolute(value),
-      LengthUnit::Em(value) => parley::LineHeight::FontSizeRelative(value),
-      LengthUnit::Percentage(value) => parley::LineHeight::FontSizeRelative(value / 100.0),
-      unit => parley::LineHeight::Absolute(unit.resolve_to_px(context, context.parent_font_size)),
+    match self {
+      LineHeight::Number(value) => parley::LineHeight::FontSizeRelative(value),
+      LineHeight::Length(LengthUnit::Px(value)) => parley::LineHeight::Absolute(value),
+      LineHeight::Length(LengthUnit::Em(value)) => parley::LineHeight::FontSizeRelative(value),
+      LineHeight::Length(LengthUnit::Percentage(value)) => {
+        parley::LineHeight::FontSizeRelative(value / 100.0)
+      }
+      LineHeight::Length(unit) => {
+        parley::LineHeight::Absolute(unit.resolve_to_px(context, context.parent_font_size))
+      }
     }
   }
 }
@@ -5,7 +5,7 @@ use smallvec::SmallVec;
 use std::ops::Deref;
 use ts_rs::TS;
 
-use super::gradient_utils::{color_from_stops, resolve_stops_along_axis};
+use super::gradient_utils::{color_from_stops, repeat_position, resolve_stops_along_axis};
 use crate::{
   layout::style::{Color, FromCss, LengthUnit, ParseResult},
   rendering::RenderContext,
@@ -38,6 +38,7 @@ pub trait Gradient: Send + Sync {
 
 /// Represents a linear gradient.
 #[derive(Debug, Clone, PartialEq, TS, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(try_from = "LinearGradientValue")]
 #[ts(as = "LinearGradientValue")]
 pub struct LinearGradient {
@@ -45,19 +46,31 @@ pub struct LinearGradient {
   pub angle: Angle,
   /// The steps of the gradient.
   pub stops: SmallVec<[GradientStop; 4]>,
+  /// The color space used to interpolate between stops.
+  pub color_space: GradientColorSpace,
+  /// Whether the stops repeat past the last stop, like `repeating-linear-gradient()`.
+  pub repeating: bool,
 }
 
 /// Proxy type for `LinearGradient` Css deserialization.
 #[derive(Debug, Clone, PartialEq, TS, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum LinearGradientValue {
   /// Represents a linear gradient.
+  #[serde(rename_all = "camelCase")]
   Structured {
     /// The angle of the gradient.
     angle: Angle,
     /// The steps of the gradient.
     #[ts(as = "Vec<GradientStop>")]
     stops: SmallVec<[GradientStop; 4]>,
+    /// The color space used to interpolate between stops, defaults to sRGB.
+    #[ts(optional)]
+    color_space: Option<GradientColorSpace>,
+    /// Whether the stops repeat past the last stop, defaults to false.
+    #[ts(optional)]
+    repeating: Option<bool>,
   },
   /// Represents a CSS string.
   Css(String),
@@ -68,7 +81,17 @@ impl TryFrom<LinearGradientValue> for LinearGradient {
 
   fn try_from(value: LinearGradientValue) -> Result<Self, Self::Error> {
     match value {
-      LinearGradientValue::Structured { angle, stops } => Ok(LinearGradient { angle, stops }),
+      LinearGradientValue::Structured {
+        angle,
+        stops,
+        color_space,
+        repeating,
+      } => Ok(LinearGradient {
+        angle,
+        stops,
+        color_space: color_space.unwrap_or_default(),
+        repeating: repeating.unwrap_or_default(),
+      }),
       LinearGradientValue::Css(css) => {
         let mut input = ParserInput::new(&css);
         let mut parser = Parser::new(&mut input);
@@ -96,9 +119,13 @@ impl Gradient for LinearGradient {
     let dx = x as f32 - ctx.cx;
     let dy = y as f32 - ctx.cy;
     let projection = dx * ctx.dir_x + dy * ctx.dir_y;
-    let position_px = (projection + ctx.max_extent).clamp(0.0, ctx.axis_length);
+    let position_px = if self.repeating {
+      repeat_position(projection + ctx.max_extent, &ctx.resolved_stops)
+    } else {
+      (projection + ctx.max_extent).clamp(0.0, ctx.axis_length)
+    };
 
-    color_from_stops(position_px, &ctx.resolved_stops)
+    color_from_stops(position_px, &ctx.resolved_stops, self.color_space)
   }
 
   fn to_draw_context(&self, width: f32, height: f32, context: &RenderContext) -> Self::DrawContext {
@@ -171,12 +198,14 @@ impl LinearGradientDrawContext {
 /// Represents a gradient stop position.
 /// If a percentage or number (0.0-1.0) is provided, it is treated as a percentage.
 #[derive(Debug, Clone, Copy, PartialEq, TS, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[ts(as = "StopPositionValue")]
 #[serde(try_from = "StopPositionValue")]
 pub struct StopPosition(pub LengthUnit);
 
 /// Proxy type for `StopPosition` Css deserialization.
 #[derive(Debug, Clone, PartialEq, TS, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum StopPositionValue {
   /// Length value, percentage or number (0.0-1.0) is treated as a percentage.
@@ -187,6 +216,7 @@ pub enum StopPositionValue {
 
 /// Represents a gradient stop.
 #[derive(Debug, Clone, PartialEq, TS, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum GradientStop {
   /// A color gradient stop.
@@ -201,8 +231,40 @@ pub enum GradientStop {
   Hint(StopPosition),
 }
 
+/// Defines the color space used to interpolate between gradient stops.
+///
+/// Corresponds to the `in <color-space>` interpolation hint of CSS gradients.
+#[derive(Debug, Clone, Copy, PartialEq, TS, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum GradientColorSpace {
+  /// Interpolate gamma-encoded sRGB channels directly, this is the CSS default.
+  #[default]
+  Srgb,
+  /// Interpolate in linear-light sRGB, which avoids muddy midpoints between saturated hues.
+  #[serde(alias = "srgb-linear")]
+  LinearRgb,
+}
+
+impl<'i> FromCss<'i> for GradientColorSpace {
+  fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
+    input.expect_ident_matching("in")?;
+
+    let location = input.current_source_location();
+    let ident = input.expect_ident()?;
+
+    match_ignore_ascii_case! {&ident,
+      "srgb" => Ok(GradientColorSpace::Srgb),
+      "srgb-linear" => Ok(GradientColorSpace::LinearRgb),
+      "linear-rgb" => Ok(GradientColorSpace::LinearRgb),
+      _ => Err(location.new_basic_unexpected_token_error(Token::Ident(ident.clone())).into()),
+    }
+  }
+}
+
 /// Represents a resolved gradient stop with a position.
 #[derive(Debug, Clone, PartialEq, TS, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ResolvedGradientStop {
   /// The color of the gradient stop.
   pub color: Color,
@@ -269,6 +331,7 @@ impl<'i> FromCss<'i> for GradientStop {
 
 /// Represents an angle value in degrees.
 #[derive(Debug, Default, Clone, Copy, PartialEq, TS, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Angle(f32);
 
 impl Deref for Angle {
@@ -358,15 +421,33 @@ impl VerticalKeyword {
 
 impl<'i> FromCss<'i> for LinearGradient {
   fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, LinearGradient> {
-    input.expect_function_matching("linear-gradient")?;
+    let location = input.current_source_location();
+    let function = input.expect_function()?;
+
+    let repeating = match_ignore_ascii_case! {&function,
+      "linear-gradient" => false,
+      "repeating-linear-gradient" => true,
+      _ => return Err(location.new_basic_unexpected_token_error(Token::Function(function.clone())).into()),
+    };
 
     input.parse_nested_block(|input| {
-      let angle = if let Ok(angle) = Angle::from_css(input) {
+      // [ <angle> || in <color-space> ]?
+      let leading_color_space = input.try_parse(GradientColorSpace::from_css).ok();
+
+      let angle = if leading_color_space.is_some() {
+        input
+          .try_parse(Angle::from_css)
+          .unwrap_or_else(|_| Angle::new(180.0))
+      } else if let Ok(angle) = Angle::from_css(input) {
         angle
       } else {
         Angle::new(180.0)
       };
 
+      let color_space = leading_color_space
+        .or_else(|| input.try_parse(GradientColorSpace::from_css).ok())
+        .unwrap_or_default();
+
       let mut steps = SmallVec::new();
 
       loop {
@@ -380,6 +461,8 @@ impl<'i> FromCss<'i> for LinearGradient {
       Ok(LinearGradient {
         angle,
         stops: steps,
+        color_space,
+        repeating,
       })
     })
   }
@@ -491,7 +574,9 @@ mod tests {
             color: Color([0, 0, 255, 255]),
             hint: None,
           },
-        ]
+        ],
+        color_space: GradientColorSpace::Srgb,
+        repeating: false,
       })
     );
   }
@@ -640,7 +725,9 @@ mod tests {
             color: Color([0, 0, 255, 255]),
             hint: None,
           },
-        ]
+        ],
+        color_space: GradientColorSpace::Srgb,
+        repeating: false,
       })
     );
   }
@@ -664,7 +751,9 @@ mod tests {
             color: Color([0, 0, 255, 255]),
             hint: Some(StopPosition(LengthUnit::Percentage(100.0))),
           },
-        ]
+        ],
+        color_space: GradientColorSpace::Srgb,
+        repeating: false,
       })
     );
   }
@@ -689,7 +778,9 @@ mod tests {
             color: Color([0, 0, 255, 255]),
             hint: None,
           },
-        ]
+        ],
+        color_space: GradientColorSpace::Srgb,
+        repeating: false,
       })
     );
   }
@@ -707,7 +798,9 @@ mod tests {
         stops: smallvec![GradientStop::ColorHint {
           color: Color([255, 0, 0, 255]),
           hint: None,
-        },]
+        },],
+        color_space: GradientColorSpace::Srgb,
+        repeating: false,
       })
     );
   }
@@ -727,7 +820,9 @@ mod tests {
         stops: smallvec![GradientStop::ColorHint {
           color: Color([0, 0, 255, 255]), // Only the last color is parsed due to the parsing logic
           hint: None,
-        },]
+        },],
+        color_space: GradientColorSpace::Srgb,
+        repeating: false,
       })
     );
   }
@@ -833,7 +928,9 @@ mod tests {
             color: Color([0, 0, 255, 255]),
             hint: None,
           },
-        ]
+        ],
+        color_space: GradientColorSpace::Srgb,
+        repeating: false,
       })
     );
   }
@@ -852,6 +949,8 @@ mod tests {
           hint: Some(StopPosition(LengthUnit::Percentage(100.0))),
         },
       ],
+      color_space: GradientColorSpace::Srgb,
+      repeating: false,
     };
 
     // Test at the top (should be red)
@@ -861,6 +960,7 @@ mod tests {
       parent_font_size: DEFAULT_FONT_SIZE,
       transform: Affine::identity(),
       style: InheritedStyle::default(),
+      border: None,
     };
     let ctx = gradient.to_draw_context(100.0, 100.0, &dummy_context);
     let color_top = gradient.at(50, 0, &ctx);
@@ -894,6 +994,8 @@ mod tests {
           hint: Some(StopPosition(LengthUnit::Percentage(100.0))),
         },
       ],
+      color_space: GradientColorSpace::Srgb,
+      repeating: false,
     };
 
     // Test at the left (should be red)
@@ -903,6 +1005,7 @@ mod tests {
       parent_font_size: DEFAULT_FONT_SIZE,
       transform: Affine::identity(),
       style: InheritedStyle::default(),
+      border: None,
     };
     let ctx = gradient.to_draw_context(100.0, 100.0, &dummy_context);
     let color_left = gradient.at(0, 50, &ctx);
@@ -921,6 +1024,8 @@ mod tests {
         color: Color([255, 0, 0, 255]), // Red
         hint: None,
       }],
+      color_space: GradientColorSpace::Srgb,
+      repeating: false,
     };
 
     // Should always return the same color
@@ -930,6 +1035,7 @@ mod tests {
       parent_font_size: DEFAULT_FONT_SIZE,
       transform: Affine::identity(),
       style: InheritedStyle::default(),
+      border: None,
     };
     let ctx = gradient.to_draw_context(100.0, 100.0, &dummy_context);
     let color = gradient.at(50, 50, &ctx);
@@ -941,6 +1047,8 @@ mod tests {
     let gradient = LinearGradient {
       angle: Angle::new(0.0),
       stops: smallvec![],
+      color_space: GradientColorSpace::Srgb,
+      repeating: false,
     };
 
     // Should return transparent
@@ -950,6 +1058,7 @@ mod tests {
       parent_font_size: DEFAULT_FONT_SIZE,
       transform: Affine::identity(),
       style: InheritedStyle::default(),
+      border: None,
     };
     let ctx = gradient.to_draw_context(100.0, 100.0, &dummy_context);
     let color = gradient.at(50, 50, &ctx);
@@ -968,6 +1077,7 @@ mod tests {
       parent_font_size: DEFAULT_FONT_SIZE,
       transform: Affine::identity(),
       style: InheritedStyle::default(),
+      border: None,
     };
     let ctx = gradient.to_draw_context(40.0, 40.0, &dummy_context);
 
@@ -984,6 +1094,38 @@ mod tests {
     assert_eq!(c2, Color([0, 0, 0, 0]));
   }
 
+  #[test]
+  fn test_repeating_linear_gradient_tiles_stops() {
+    let mut input = ParserInput::new(
+      "repeating-linear-gradient(to right, red 0px, red 5px, blue 5px, blue 10px)",
+    );
+    let mut parser = Parser::new(&mut input);
+    let gradient = LinearGradient::from_css(&mut parser).unwrap();
+
+    assert!(gradient.repeating);
+
+    let dummy_context = RenderContext {
+      global: &GlobalContext::default(),
+      viewport: Viewport::new(40, 40),
+      parent_font_size: DEFAULT_FONT_SIZE,
+      transform: Affine::identity(),
+      style: InheritedStyle::default(),
+      border: None,
+    };
+    let ctx = gradient.to_draw_context(40.0, 40.0, &dummy_context);
+
+    let red = Color([255, 0, 0, 255]);
+    let blue = Color([0, 0, 255, 255]);
+
+    for x in [1, 3, 11, 13, 31] {
+      assert_eq!(gradient.at(x, 0, &ctx), red, "x = {x}");
+    }
+
+    for x in [6, 8, 16, 38] {
+      assert_eq!(gradient.at(x, 0, &ctx), blue, "x = {x}");
+    }
+  }
+
   #[test]
   fn test_linear_gradient_vertical_px_stops_top_pixel() {
     let mut input = ParserInput::new("linear-gradient(to bottom, grey 1px, transparent 1px)");
@@ -996,6 +1138,7 @@ mod tests {
       parent_font_size: DEFAULT_FONT_SIZE,
       transform: Affine::identity(),
       style: InheritedStyle::default(),
+      border: None,
     };
     let ctx = gradient.to_draw_context(40.0, 40.0, &dummy_context);
 
@@ -1056,6 +1199,8 @@ mod tests {
           hint: Some(StopPosition(LengthUnit::Px(100.0))),
         },
       ],
+      color_space: GradientColorSpace::Srgb,
+      repeating: false,
     };
 
     let ctx = RenderContext {
@@ -1064,6 +1209,7 @@ mod tests {
       parent_font_size: DEFAULT_FONT_SIZE,
       transform: Affine::identity(),
       style: InheritedStyle::default(),
+      border: None,
     };
 
     let resolved = gradient.resolve_stops_for_axis_size(ctx.viewport.width as f32, &ctx);
@@ -1087,6 +1233,8 @@ mod tests {
           hint: Some(StopPosition(LengthUnit::Px(0.0))),
         },
       ],
+      color_space: GradientColorSpace::Srgb,
+      repeating: false,
     };
     let ctx = RenderContext {
       global: &GlobalContext::default(),
@@ -1094,6 +1242,7 @@ mod tests {
       parent_font_size: DEFAULT_FONT_SIZE,
       transform: Affine::identity(),
       style: InheritedStyle::default(),
+      border: None,
     };
 
     let resolved = gradient.resolve_stops_for_axis_size(ctx.viewport.width as f32, &ctx);
@@ -1101,4 +1250,44 @@ mod tests {
     assert!((resolved[0].position - 0.0).abs() < 1e-3);
     assert!((resolved[1].position - 0.0).abs() < 1e-3);
   }
+
+  #[test]
+  fn test_parse_linear_gradient_color_space() {
+    let mut input = ParserInput::new("linear-gradient(to right in srgb-linear, red 0%, lime 100%)");
+    let mut parser = Parser::new(&mut input);
+    let gradient = LinearGradient::from_css(&mut parser).unwrap();
+
+    assert_eq!(gradient.angle, Angle::new(90.0));
+    assert_eq!(gradient.color_space, GradientColorSpace::LinearRgb);
+    assert_eq!(gradient.stops.len(), 2);
+
+    let mut input = ParserInput::new("linear-gradient(in srgb-linear, red 0%, lime 100%)");
+    let mut parser = Parser::new(&mut input);
+    let gradient = LinearGradient::from_css(&mut parser).unwrap();
+
+    assert_eq!(gradient.angle, Angle::new(180.0));
+    assert_eq!(gradient.color_space, GradientColorSpace::LinearRgb);
+    assert_eq!(gradient.stops.len(), 2);
+  }
+
+  #[test]
+  fn test_linear_gradient_at_linear_rgb_midpoint() {
+    let mut input =
+      ParserInput::new("linear-gradient(to right in srgb-linear, red 0px, lime 100px)");
+    let mut parser = Parser::new(&mut input);
+    let gradient = LinearGradient::from_css(&mut parser).unwrap();
+
+    let dummy_context = RenderContext {
+      global: &GlobalContext::default(),
+      viewport: Viewport::new(100, 1),
+      parent_font_size: DEFAULT_FONT_SIZE,
+      transform: Affine::identity(),
+      style: InheritedStyle::default(),
+      border: None,
+    };
+    let ctx = gradient.to_draw_context(100.0, 1.0, &dummy_context);
+
+    // linear-light midpoint stays bright instead of going through muddy brown
+    assert_eq!(gradient.at(50, 0, &ctx), Color([188, 188, 0, 255]));
+  }
 }
@@ -4,11 +4,17 @@
 //! typography settings, positioning, and visual effects.
 
 mod background_image;
+mod background_noise;
 mod background_position;
 mod background_repeat;
 mod background_size;
+mod blend_mode;
+mod border_radius;
 mod box_shadow;
+mod clip_path;
 mod color;
+mod filter;
+mod flex;
 mod font_feature_settings;
 mod font_style;
 mod font_variation_settings;
@@ -16,44 +22,60 @@ mod font_weight;
 mod gap;
 mod gradient_utils;
 mod grid;
+mod initial_letter;
 mod length_unit;
 mod line_height;
 mod linear_gradient;
 mod noise_v1;
+mod outline;
 mod overflow_wrap;
 mod parser;
+mod place;
 mod radial_gradient;
 mod sides;
+mod tab_size;
 mod text_shadow;
 mod text_stroke;
 mod transform;
+mod vertical_align;
 mod word_break;
 
 use std::borrow::Cow;
 
 pub use background_image::*;
+pub use background_noise::*;
 pub use background_position::*;
 pub use background_repeat::*;
 pub use background_size::*;
+pub use blend_mode::*;
+pub use border_radius::*;
 pub use box_shadow::*;
+pub use clip_path::*;
 pub use color::*;
+pub use filter::*;
+pub use flex::*;
 pub use font_feature_settings::*;
 pub use font_style::*;
 pub use font_variation_settings::*;
 pub use font_weight::*;
 pub use gap::*;
 pub use grid::*;
+pub use initial_letter::*;
 pub use length_unit::*;
 pub use line_height::*;
 pub use linear_gradient::*;
 pub use noise_v1::*;
+pub use outline::*;
 pub use overflow_wrap::*;
 pub use parser::*;
+pub use place::*;
 pub use radial_gradient::*;
 pub use sides::*;
+pub use tab_size::*;
 pub use text_shadow::*;
 pub use text_stroke::*;
 pub use transform::*;
+pub use vertical_align::*;
 pub use word_break::*;
 
 use cssparser::{ParseError, Parser};
@@ -90,6 +112,7 @@ macro_rules! impl_from_taffy_enum {
 ///
 /// Similar to CSS object-fit property.
 #[derive(Debug, Clone, Deserialize, Serialize, Copy, TS, PartialEq, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "kebab-case")]
 pub enum ObjectFit {
   /// The replaced content is sized to fill the element's content box exactly, without maintaining aspect ratio
@@ -109,6 +132,7 @@ pub enum ObjectFit {
 ///
 /// This enum determines whether the width and height properties include padding and border, or just the content area.
 #[derive(Default, Debug, Clone, Deserialize, Serialize, Copy, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "kebab-case")]
 pub enum BoxSizing {
   /// The width and height properties include padding and border, but not the content area
@@ -124,6 +148,7 @@ impl_from_taffy_enum!(BoxSizing, taffy::BoxSizing, ContentBox, BorderBox);
 ///
 /// Corresponds to CSS text-align property values.
 #[derive(Default, Debug, Clone, Deserialize, Serialize, Copy, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "kebab-case")]
 pub enum TextAlign {
   /// Aligns inline content to the left edge of the line box
@@ -158,6 +183,7 @@ impl From<TextAlign> for Alignment {
 ///
 /// This enum determines how an element is positioned within its containing element.
 #[derive(Default, Debug, Clone, Deserialize, Serialize, Copy, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "kebab-case")]
 pub enum Position {
   /// The element is positioned according to the normal flow of the document.
@@ -167,14 +193,27 @@ pub enum Position {
   /// The element is removed from the normal document flow and positioned relative to its nearest positioned ancestor.
   /// Offsets (top, right, bottom, left) specify the distance from the ancestor.
   Absolute,
+  /// The element is removed from the normal document flow and positioned relative to the viewport,
+  /// regardless of its ancestors. Offsets (top, right, bottom, left) specify the distance from the
+  /// viewport edges, like for watermarks and corner badges.
+  Fixed,
 }
 
-impl_from_taffy_enum!(Position, taffy::Position, Relative, Absolute);
+impl From<Position> for taffy::Position {
+  fn from(value: Position) -> Self {
+    match value {
+      Position::Relative => taffy::Position::Relative,
+      // laid out like an absolute box of its parent, then moved to the viewport when painted
+      Position::Absolute | Position::Fixed => taffy::Position::Absolute,
+    }
+  }
+}
 
 /// Defines the direction of flex items within a flex container.
 ///
 /// This enum determines how flex items are laid out along the main axis.
 #[derive(Default, Debug, Clone, Deserialize, Serialize, Copy, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "kebab-case")]
 pub enum FlexDirection {
   /// Items are laid out in the same direction as the text direction (left-to-right for English)
@@ -202,6 +241,7 @@ impl_from_taffy_enum!(
 /// This enum determines how space is distributed between and around flex items
 /// along the main axis of the flex container.
 #[derive(Debug, Clone, Deserialize, Serialize, Copy, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "kebab-case")]
 pub enum JustifyContent {
   /// Items are packed toward the start of the line.
@@ -247,6 +287,7 @@ impl_from_taffy_enum!(
 
 /// This enum determines the layout algorithm used for the children of a node.
 #[derive(Debug, Clone, Deserialize, Serialize, Copy, TS, PartialEq, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "kebab-case")]
 pub enum Display {
   /// The element generates a flex container and its children follow the flexbox layout algorithm
@@ -254,15 +295,18 @@ pub enum Display {
   Flex,
   /// The element generates a grid container and its children follow the CSS Grid layout algorithm
   Grid,
+  /// The element and its children are left out of layout and painting
+  None,
 }
 
-impl_from_taffy_enum!(Display, taffy::Display, Flex, Grid);
+impl_from_taffy_enum!(Display, taffy::Display, Flex, Grid, None);
 
 /// Defines how flex items are aligned along the cross axis.
 ///
 /// This enum determines how items are aligned within the flex container
 /// along the cross axis (perpendicular to the main axis).
 #[derive(Debug, Clone, Deserialize, Serialize, Copy, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "kebab-case")]
 pub enum AlignItems {
   /// Items are aligned to the start of the line in the cross axis
@@ -297,6 +341,7 @@ impl_from_taffy_enum!(
 ///
 /// This enum determines how flex items should wrap within the flex container.
 #[derive(Debug, Clone, Deserialize, Serialize, Copy, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "kebab-case")]
 pub enum FlexWrap {
   /// Flex items will all be displayed in a single line, shrinking as needed
@@ -314,6 +359,7 @@ impl_from_taffy_enum!(FlexWrap, taffy::FlexWrap, NoWrap, Wrap, WrapReverse);
 ///
 /// This enum determines how text should be handled when it exceeds the container width.
 #[derive(Debug, Clone, Deserialize, Serialize, Copy, TS, PartialEq, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "kebab-case")]
 pub enum TextOverflow {
   /// Text is simply clipped at the overflow edge with no visual indication
@@ -321,10 +367,28 @@ pub enum TextOverflow {
   Clip,
   /// Text is truncated with an ellipsis (…) at the end when it overflows
   Ellipsis,
+  /// The end of the last line fades out to transparent when it overflows
+  Fade,
+}
+
+/// Controls whether words are hyphenated when they are broken across lines.
+#[derive(Default, Debug, Clone, Deserialize, Serialize, Copy, TS, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum Hyphens {
+  /// Words are not hyphenated, even at soft hyphens (`\u{AD}`)
+  None,
+  /// Words are only hyphenated at soft hyphens
+  #[default]
+  Manual,
+  /// Words are also hyphenated where the [`Hyphenator`](crate::rendering::Hyphenator) of the
+  /// context allows, like [`Hyphens::Manual`] without one
+  Auto,
 }
 
 /// Controls text case transformation when rendering.
 #[derive(Default, Debug, Clone, Deserialize, Serialize, Copy, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "kebab-case")]
 pub enum TextTransform {
   /// Do not transform text
@@ -334,13 +398,68 @@ pub enum TextTransform {
   Uppercase,
   /// Transform all characters to lowercase
   Lowercase,
-  /// Uppercase the first letter of each word
+  /// Titlecase the first letter of each word, keeping the rest of the word as is
   Capitalize,
 }
 
+/// Controls whether lines of text are laid out horizontally or vertically.
+#[derive(Default, Debug, Clone, Deserialize, Serialize, Copy, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum WritingMode {
+  /// Lines run left to right and stack from top to bottom
+  #[default]
+  HorizontalTb,
+  /// Lines run top to bottom and stack from right to left, like traditional CJK text
+  VerticalRl,
+  /// Lines run top to bottom and stack from left to right
+  VerticalLr,
+}
+
+impl WritingMode {
+  /// Returns whether lines run from top to bottom.
+  pub fn is_vertical(self) -> bool {
+    self != WritingMode::HorizontalTb
+  }
+}
+
+/// Controls whether a node is painted, hidden nodes still take up their layout space.
+#[derive(Default, Debug, Clone, Deserialize, Serialize, Copy, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum Visibility {
+  /// The node is painted
+  #[default]
+  Visible,
+  /// The node is not painted, children can still be painted by setting `visible`
+  Hidden,
+}
+
+/// Controls whether the descendants of a node are clipped to its padding box.
+#[derive(Default, Debug, Clone, Deserialize, Serialize, Copy, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum Overflow {
+  /// Descendants are painted outside of the node
+  #[default]
+  Visible,
+  /// Descendants are clipped to the padding box of the node, following its border radius
+  Hidden,
+}
+
+impl From<Overflow> for taffy::Overflow {
+  fn from(value: Overflow) -> Self {
+    match value {
+      Overflow::Visible => taffy::Overflow::Visible,
+      Overflow::Hidden => taffy::Overflow::Hidden,
+    }
+  }
+}
+
 /// Represents a font family for text rendering.
 /// Multi value fallback is supported.
 #[derive(Debug, Clone, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct FontFamily(String);
 
 impl Default for FontFamily {
@@ -363,6 +482,7 @@ impl<'a> From<&'a FontFamily> for FontStack<'a> {
 
 /// Represents the grid auto flow with serde support
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, TS, Default, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "kebab-case")]
 pub enum GridAutoFlow {
   /// Places grid items by filling each row in turn, adding new rows as needed
@@ -389,6 +509,7 @@ impl_from_taffy_enum!(
 
 /// Defines how images should be scaled when rendered.
 #[derive(Default, Debug, Clone, Copy, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "kebab-case")]
 pub enum ImageScalingAlgorithm {
   /// The image is scaled using Catmull-Rom interpolation.
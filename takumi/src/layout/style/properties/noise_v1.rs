@@ -12,6 +12,7 @@ use crate::{
 /// This creates dynamic textures that can be used as backgrounds or overlays with customizable parameters
 /// for controlling the noise characteristics and visual appearance.
 #[derive(Debug, Clone, PartialEq, TS, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[ts(optional_fields)]
 pub struct NoiseV1 {
   /// Controls the scale of the noise pattern. Higher values create finer, more detailed patterns
@@ -0,0 +1,194 @@
+use cssparser::{Parser, ParserInput};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::layout::style::{ColorInput, FromCss, LengthUnit, ParseResult};
+
+/// Controls whether the outline is painted.
+#[derive(Default, Debug, Clone, Copy, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum OutlineStyle {
+  /// No outline is painted
+  #[default]
+  None,
+  /// A single solid line
+  Solid,
+}
+
+impl<'i> FromCss<'i> for OutlineStyle {
+  fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
+    let location = input.current_source_location();
+    let ident = input.expect_ident()?;
+
+    if ident.eq_ignore_ascii_case("none") {
+      Ok(OutlineStyle::None)
+    } else if ident.eq_ignore_ascii_case("solid") {
+      Ok(OutlineStyle::Solid)
+    } else {
+      Err(
+        location
+          .new_basic_unexpected_token_error(cssparser::Token::Ident(ident.clone()))
+          .into(),
+      )
+    }
+  }
+}
+
+/// Represents the `outline` shorthand which accepts a width, a style and a color.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(untagged)]
+pub(crate) enum OutlineValue {
+  /// Structured representation when provided as JSON.
+  Structured {
+    width: LengthUnit,
+    style: OutlineStyle,
+    color: Option<ColorInput>,
+  },
+  /// Raw CSS string representation.
+  Css(String),
+}
+
+/// Parsed `outline` value.
+///
+/// The longhands `outlineWidth`, `outlineStyle` and `outlineColor` override the matching part.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(try_from = "OutlineValue")]
+#[ts(as = "OutlineValue")]
+pub struct Outline {
+  /// The width of the outline.
+  pub width: LengthUnit,
+  /// Whether the outline is painted.
+  pub style: OutlineStyle,
+  /// The color of the outline, `currentColor` when absent.
+  pub color: Option<ColorInput>,
+}
+
+impl Default for Outline {
+  /// The initial value, a `medium` wide outline with no style.
+  fn default() -> Self {
+    Self {
+      width: LengthUnit::Px(3.0),
+      style: OutlineStyle::None,
+      color: None,
+    }
+  }
+}
+
+impl TryFrom<OutlineValue> for Outline {
+  type Error = String;
+
+  fn try_from(value: OutlineValue) -> Result<Self, Self::Error> {
+    match value {
+      OutlineValue::Structured {
+        width,
+        style,
+        color,
+      } => Ok(Outline {
+        width,
+        style,
+        color,
+      }),
+      OutlineValue::Css(css) => {
+        let mut input = ParserInput::new(&css);
+        let mut parser = Parser::new(&mut input);
+
+        let outline = Outline::from_css(&mut parser).map_err(|e| e.to_string())?;
+
+        parser.expect_exhausted().map_err(|e| format!("{e:?}"))?;
+
+        Ok(outline)
+      }
+    }
+  }
+}
+
+impl<'i> FromCss<'i> for Outline {
+  fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
+    let mut width = None;
+    let mut style = None;
+    let mut color = None;
+
+    // the parts can come in any order, like `solid 2px red`
+    loop {
+      if width.is_none()
+        && let Ok(value) = input.try_parse(LengthUnit::from_css)
+      {
+        width = Some(value);
+        continue;
+      }
+
+      if style.is_none()
+        && let Ok(value) = input.try_parse(OutlineStyle::from_css)
+      {
+        style = Some(value);
+        continue;
+      }
+
+      if color.is_none()
+        && let Ok(value) = input.try_parse(ColorInput::from_css)
+      {
+        color = Some(value);
+        continue;
+      }
+
+      break;
+    }
+
+    if width.is_none() && style.is_none() && color.is_none() {
+      return Err(input.new_error_for_next_token());
+    }
+
+    let initial = Outline::default();
+
+    Ok(Outline {
+      width: width.unwrap_or(initial.width),
+      style: style.unwrap_or(initial.style),
+      color,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::layout::style::Color;
+
+  fn parse(css: &str) -> Result<Outline, String> {
+    Outline::try_from(OutlineValue::Css(css.to_string()))
+  }
+
+  #[test]
+  fn test_parse_outline_shorthand() {
+    assert_eq!(
+      parse("2px solid red"),
+      Ok(Outline {
+        width: LengthUnit::Px(2.0),
+        style: OutlineStyle::Solid,
+        color: Some(ColorInput::Value(Color([255, 0, 0, 255]))),
+      })
+    );
+    assert_eq!(
+      parse("solid currentColor 0.5rem"),
+      Ok(Outline {
+        width: LengthUnit::Rem(0.5),
+        style: OutlineStyle::Solid,
+        color: Some(ColorInput::CurrentColor),
+      })
+    );
+    assert_eq!(
+      parse("solid"),
+      Ok(Outline {
+        style: OutlineStyle::Solid,
+        ..Outline::default()
+      })
+    );
+    assert_eq!(parse("none"), Ok(Outline::default()));
+
+    assert!(parse("").is_err());
+    assert!(parse("2px dashed").is_err());
+    assert!(parse("2px 4px").is_err());
+  }
+}
@@ -3,11 +3,13 @@ use ts_rs::TS;
 
 /// Controls how text should be overflowed.
 #[derive(Debug, Default, Copy, Clone, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(from = "OverflowWrapValue", into = "OverflowWrapValue")]
 #[ts(as = "OverflowWrapValue")]
 pub struct OverflowWrap(parley::OverflowWrap);
 
 #[derive(Debug, Copy, Clone, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "kebab-case")]
 enum OverflowWrapValue {
   Normal,
@@ -0,0 +1,126 @@
+use serde::{
+  Deserialize, Serialize,
+  de::{DeserializeOwned, IntoDeserializer, value::Error as ValueError},
+};
+use ts_rs::TS;
+
+use crate::layout::style::{AlignItems, JustifyContent};
+
+/// Represents the `place-items` shorthand, the `alignItems` value followed by the `justifyItems` value.
+///
+/// A single value applies to both axes. The longhands win over the shorthand when both are set.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(try_from = "PlaceItemsValue")]
+#[ts(as = "PlaceItemsValue")]
+pub struct PlaceItems(pub AlignItems, pub AlignItems);
+
+/// Represents a value for the `place-items` property.
+#[derive(Debug, Clone, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(untagged)]
+pub enum PlaceItemsValue {
+  /// Same alignment for both axes
+  SingleValue(AlignItems),
+  /// Separate values for the block and inline axes (align, justify)
+  Array(AlignItems, AlignItems),
+  /// CSS string representation
+  Css(String),
+}
+
+impl TryFrom<PlaceItemsValue> for PlaceItems {
+  type Error = String;
+
+  fn try_from(value: PlaceItemsValue) -> Result<Self, Self::Error> {
+    match value {
+      PlaceItemsValue::SingleValue(value) => Ok(Self(value, value)),
+      PlaceItemsValue::Array(align, justify) => Ok(Self(align, justify)),
+      PlaceItemsValue::Css(css) => parse_pair(&css).map(|(align, justify)| Self(align, justify)),
+    }
+  }
+}
+
+/// Represents the `place-content` shorthand, the `alignContent` value followed by the `justifyContent` value.
+///
+/// A single value applies to both axes. The longhands win over the shorthand when both are set.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(try_from = "PlaceContentValue")]
+#[ts(as = "PlaceContentValue")]
+pub struct PlaceContent(pub JustifyContent, pub JustifyContent);
+
+/// Represents a value for the `place-content` property.
+#[derive(Debug, Clone, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(untagged)]
+pub enum PlaceContentValue {
+  /// Same distribution for both axes
+  SingleValue(JustifyContent),
+  /// Separate values for the block and inline axes (align, justify)
+  Array(JustifyContent, JustifyContent),
+  /// CSS string representation
+  Css(String),
+}
+
+impl TryFrom<PlaceContentValue> for PlaceContent {
+  type Error = String;
+
+  fn try_from(value: PlaceContentValue) -> Result<Self, Self::Error> {
+    match value {
+      PlaceContentValue::SingleValue(value) => Ok(Self(value, value)),
+      PlaceContentValue::Array(align, justify) => Ok(Self(align, justify)),
+      PlaceContentValue::Css(css) => parse_pair(&css).map(|(align, justify)| Self(align, justify)),
+    }
+  }
+}
+
+/// Parses one or two keywords, the second one defaults to the first.
+fn parse_pair<T: DeserializeOwned + Copy>(css: &str) -> Result<(T, T), String> {
+  let mut keywords = css.split_whitespace().map(|keyword| {
+    T::deserialize(keyword.into_deserializer()).map_err(|e: ValueError| e.to_string())
+  });
+
+  let first = keywords
+    .next()
+    .ok_or_else(|| "expected one or two values".to_string())??;
+  let second = keywords.next().transpose()?.unwrap_or(first);
+
+  if keywords.next().is_some() {
+    return Err("expected one or two values".to_string());
+  }
+
+  Ok((first, second))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_place_items() {
+    assert_eq!(
+      serde_json::from_str::<PlaceItems>("\"center\"").unwrap(),
+      PlaceItems(AlignItems::Center, AlignItems::Center)
+    );
+    assert_eq!(
+      serde_json::from_str::<PlaceItems>("\"center start\"").unwrap(),
+      PlaceItems(AlignItems::Center, AlignItems::Start)
+    );
+    assert_eq!(
+      serde_json::from_str::<PlaceItems>("[\"end\", \"stretch\"]").unwrap(),
+      PlaceItems(AlignItems::End, AlignItems::Stretch)
+    );
+
+    assert!(serde_json::from_str::<PlaceItems>("\"\"").is_err());
+    assert!(serde_json::from_str::<PlaceItems>("\"center start end\"").is_err());
+    assert!(serde_json::from_str::<PlaceItems>("\"middle\"").is_err());
+  }
+
+  #[test]
+  fn test_parse_place_content() {
+    assert_eq!(
+      serde_json::from_str::<PlaceContent>("\"space-between center\"").unwrap(),
+      PlaceContent(JustifyContent::SpaceBetween, JustifyContent::Center)
+    );
+  }
+}
@@ -3,17 +3,20 @@ use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 use ts_rs::TS;
 
-use super::gradient_utils::{color_from_stops, resolve_stops_along_axis};
+use super::gradient_utils::{color_from_stops, repeat_position, resolve_stops_along_axis};
 use crate::{
   layout::style::{
-    Color, FromCss, Gradient, GradientStop, ParseResult, ResolvedGradientStop,
-    parse_length_percentage,
+    Color, FromCss, Gradient, GradientColorSpace, GradientStop, LengthUnit, ParseResult,
+    ResolvedGradientStop, parse_length_percentage,
   },
   rendering::RenderContext,
 };
 
 /// Represents a radial gradient.
 #[derive(Debug, Clone, PartialEq, TS, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(try_from = "RadialGradientValue")]
+#[ts(as = "RadialGradientValue")]
 pub struct RadialGradient {
   /// The radial gradient shape
   pub shape: RadialShape,
@@ -23,10 +26,16 @@ pub struct RadialGradient {
   pub center: (f32, f32),
   /// Gradient stops
   pub stops: Vec<GradientStop>,
+  /// The color space used to interpolate between stops
+  #[serde(rename = "colorSpace")]
+  pub color_space: GradientColorSpace,
+  /// Whether the stops repeat past the last stop, like `repeating-radial-gradient()`
+  pub repeating: bool,
 }
 
 /// Supported shapes for radial gradients
 #[derive(Debug, Clone, Copy, PartialEq, TS, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "kebab-case")]
 pub enum RadialShape {
   /// A circle shape where radii are equal
@@ -36,8 +45,9 @@ pub enum RadialShape {
   Ellipse,
 }
 
-/// Supported size keywords for radial gradients
+/// Supported sizes for radial gradients
 #[derive(Debug, Clone, Copy, PartialEq, TS, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "kebab-case")]
 pub enum RadialSize {
   /// The gradient end stops at the nearest side from the center
@@ -49,6 +59,14 @@ pub enum RadialSize {
   /// The gradient end stops at the farthest corner from the center
   #[default]
   FarthestCorner,
+  /// Explicit radii, percentages resolve against the width and height of the box.
+  /// A circle uses `x` for both radii.
+  Explicit {
+    /// The horizontal radius
+    x: LengthUnit,
+    /// The vertical radius
+    y: LengthUnit,
+  },
 }
 
 /// Precomputed drawing context for repeated sampling of a `RadialGradient`.
@@ -85,8 +103,13 @@ impl Gradient for RadialGradient {
     let dx = (x as f32 - ctx.cx) / ctx.radius_x.max(1e-6);
     let dy = (y as f32 - ctx.cy) / ctx.radius_y.max(1e-6);
     let position = (dx * dx + dy * dy).sqrt() * ctx.radius_x.max(ctx.radius_y);
+    let position = if self.repeating {
+      repeat_position(position, &ctx.resolved_stops)
+    } else {
+      position
+    };
 
-    color_from_stops(position, &ctx.resolved_stops)
+    color_from_stops(position, &ctx.resolved_stops, self.color_space)
   }
 
   fn to_draw_context(&self, width: f32, height: f32, context: &RenderContext) -> Self::DrawContext {
@@ -156,6 +179,14 @@ impl RadialGradientDrawContext {
       (RadialShape::Ellipse, RadialSize::ClosestCorner) => {
         (dx_left.max(dx_right), dy_top.max(dy_bottom))
       }
+      (RadialShape::Ellipse, RadialSize::Explicit { x, y }) => (
+        x.resolve_to_px(context, width).max(0.0),
+        y.resolve_to_px(context, height).max(0.0),
+      ),
+      (RadialShape::Circle, RadialSize::Explicit { x, .. }) => {
+        let r = x.resolve_to_px(context, width).max(0.0);
+        (r, r)
+      }
       (RadialShape::Circle, RadialSize::ClosestCorner) => {
         let candidates = [
           (cx, cy),
@@ -191,33 +222,64 @@ impl RadialGradientDrawContext {
 
 impl<'i> FromCss<'i> for RadialGradient {
   fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, RadialGradient> {
-    input.expect_function_matching("radial-gradient")?;
+    let location = input.current_source_location();
+    let function = input.expect_function()?;
+
+    let repeating = match_ignore_ascii_case! {&function,
+      "radial-gradient" => false,
+      "repeating-radial-gradient" => true,
+      _ => return Err(location.new_basic_unexpected_token_error(Token::Function(function.clone())).into()),
+    };
 
     input.parse_nested_block(|input| {
-      let mut shape = RadialShape::Ellipse;
-      let mut size = RadialSize::FarthestCorner;
+      let mut shape = None;
+      let mut size = None;
+      let mut single_radius = false;
       let mut center = (0.5_f32, 0.5_f32);
+      let mut color_space = GradientColorSpace::Srgb;
 
       // Optional prelude: [<ending-shape> || <size>]? [at <position>]? ,
       // Try to parse up to one shape and one size in any order
-      let mut parsed_any_prelude = false;
+      for _ in 0..2 {
+        if shape.is_none()
+          && let Ok(parsed) = input.try_parse(RadialShape::from_css)
+        {
+          shape = Some(parsed);
+          continue;
+        }
 
-      // Helper closure to try parse shape or size once
-      let mut try_parse_shape_or_size = |input: &mut Parser<'i, '_>| -> bool {
-        if let Ok(s) = input.try_parse(RadialShape::from_css) {
-          shape = s;
-          return true;
+        if size.is_none()
+          && let Ok(x) = input.try_parse(parse_radius)
+        {
+          let y = input.try_parse(parse_radius).ok();
+
+          single_radius = y.is_none();
+          size = Some(RadialSize::Explicit {
+            x,
+            y: y.unwrap_or(x),
+          });
+          continue;
         }
-        if let Ok(s) = input.try_parse(RadialSize::from_css) {
-          size = s;
-          return true;
+
+        if size.is_none()
+          && let Ok(parsed) = input.try_parse(RadialSize::from_css)
+        {
+          size = Some(parsed);
+          continue;
         }
-        false
-      };
 
-      // Attempt to parse any combination
-      parsed_any_prelude |= try_parse_shape_or_size(input);
-      parsed_any_prelude |= try_parse_shape_or_size(input);
+        break;
+      }
+
+      let mut parsed_any_prelude = shape.is_some() || size.is_some();
+
+      // a single radius without a shape is a circle, like `radial-gradient(40px, ...)`
+      let shape = shape.unwrap_or(if single_radius {
+        RadialShape::Circle
+      } else {
+        RadialShape::Ellipse
+      });
+      let size = size.unwrap_or_default();
 
       // Optional position: at <position>
       if input.try_parse(|i| i.expect_ident_matching("at")).is_ok() {
@@ -225,6 +287,12 @@ impl<'i> FromCss<'i> for RadialGradient {
         parsed_any_prelude = true;
       }
 
+      // Optional interpolation color space: in <color-space>
+      if let Ok(space) = input.try_parse(GradientColorSpace::from_css) {
+        color_space = space;
+        parsed_any_prelude = true;
+      }
+
       // If there was any prelude, expect a comma separator before stops when present in the source.
       // The css syntax requires a comma between prelude and first stop.
       // If the author omitted prelude, the next token should already be a color or percentage before comma.
@@ -245,6 +313,8 @@ impl<'i> FromCss<'i> for RadialGradient {
         size,
         center,
         stops: steps,
+        color_space,
+        repeating,
       })
     })
   }
@@ -277,89 +347,127 @@ impl<'i> FromCss<'i> for RadialSize {
   }
 }
 
+/// Parses an explicit radius, which can be any length or percentage but `auto`.
+fn parse_radius<'i>(input: &mut Parser<'i, '_>) -> ParseResult<'i, LengthUnit> {
+  let location = input.current_source_location();
+
+  match LengthUnit::from_css(input)? {
+    LengthUnit::Auto => Err(
+      location
+        .new_basic_unexpected_token_error(Token::Ident("auto".into()))
+        .into(),
+    ),
+    radius => Ok(radius),
+  }
+}
+
 /// Represents radial position keywords or percentages
 #[derive(Debug, Clone, Copy, PartialEq)]
 struct RadialPosition;
 
+/// A single component of a radial position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RadialPositionComponent {
+  /// `left` or `right`
+  X(f32),
+  /// `top` or `bottom`
+  Y(f32),
+  /// `center`
+  Center,
+  /// A fraction or percentage, applying to the axis it is written for
+  Value(f32),
+}
+
+impl RadialPositionComponent {
+  fn from_css<'i>(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
+    if let Ok(value) = input.try_parse(parse_length_percentage) {
+      return Ok(RadialPositionComponent::Value(value));
+    }
+
+    let location = input.current_source_location();
+    let ident = input.expect_ident()?;
+
+    match_ignore_ascii_case! {&ident,
+      "left" => Ok(RadialPositionComponent::X(0.0)),
+      "right" => Ok(RadialPositionComponent::X(1.0)),
+      "top" => Ok(RadialPositionComponent::Y(0.0)),
+      "bottom" => Ok(RadialPositionComponent::Y(1.0)),
+      "center" => Ok(RadialPositionComponent::Center),
+      _ => Err(location.new_basic_unexpected_token_error(Token::Ident(ident.clone())).into()),
+    }
+  }
+
+  fn value(self) -> f32 {
+    match self {
+      RadialPositionComponent::X(value)
+      | RadialPositionComponent::Y(value)
+      | RadialPositionComponent::Value(value) => value,
+      RadialPositionComponent::Center => 0.5,
+    }
+  }
+}
+
 impl RadialPosition {
   /// Parses a position definition into normalized (x, y) in [0,1]
+  ///
+  /// Keywords can be written in either order (`top left`) and mixed with
+  /// percentages (`left 30%`), a single component centers the other axis.
   fn from_css<'i>(input: &mut Parser<'i, '_>) -> ParseResult<'i, (f32, f32)> {
-    // Try keyword-based positions first
-    let mut x: Option<f32> = None;
-    let mut y: Option<f32> = None;
-
-    // Helper to parse a single keyword to percentage
-    let parse_keyword = |ident: &str| -> Option<(Option<f32>, Option<f32>)> {
-      match ident.to_ascii_lowercase().as_str() {
-        "left" => Some((Some(0.0), None)),
-        "right" => Some((Some(1.0), None)),
-        "center" => Some((None, None)),
-        "top" => Some((None, Some(0.0))),
-        "bottom" => Some((None, Some(1.0))),
-        _ => None,
-      }
+    use RadialPositionComponent::{X, Y};
+
+    let Ok(first) = input.try_parse(RadialPositionComponent::from_css) else {
+      // Default center
+      return Ok((0.5, 0.5));
     };
 
-    // Try to read up to two idents
-    for _ in 0..2 {
-      let parsed = input
-        .try_parse(
-          |i| -> Result<(), cssparser::ParseError<'i, cssparser::BasicParseError<'i>>> {
-            let location = i.current_source_location();
-            let ident = i.expect_ident()?;
-            if let Some((maybe_x, maybe_y)) = parse_keyword(ident) {
-              if let Some(v) = maybe_x {
-                x = Some(v);
-              }
-              if let Some(v) = maybe_y {
-                y = Some(v);
-              }
-              Ok(())
-            } else {
-              Err(
-                location
-                  .new_basic_unexpected_token_error(Token::Ident(ident.clone()))
-                  .into(),
-              )
-            }
-          },
-        )
-        .is_ok();
+    let Ok(second) = input.try_parse(RadialPositionComponent::from_css) else {
+      return Ok(match first {
+        Y(y) => (0.5, y),
+        first => (first.value(), 0.5),
+      });
+    };
 
-      if !parsed {
-        break;
-      }
-    }
+    let (x, y) = match (first, second) {
+      (Y(_), _) | (_, X(_)) => (second, first),
+      _ => (first, second),
+    };
 
-    if x.is_some() || y.is_some() {
-      return Ok((x.unwrap_or(0.5), y.unwrap_or(0.5)));
-    }
+    if matches!(x, Y(_)) || matches!(y, X(_)) {
+      let location = input.current_source_location();
 
-    // Numeric/percentage path: parse one or two length/percentage values
-    if let Ok(px) = input.try_parse(parse_length_percentage) {
-      let py = input.try_parse(parse_length_percentage).ok().unwrap_or(0.5);
-      return Ok((px, py));
+      return Err(
+        location
+          .new_basic_unexpected_token_error(Token::Ident("at".into()))
+          .into(),
+      );
     }
 
-    // Default center
-    Ok((0.5, 0.5))
+    Ok((x.value(), y.value()))
   }
 }
 
 /// Proxy type for `RadialGradient` Css deserialization.
 #[derive(Debug, Clone, PartialEq, TS, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum RadialGradientValue {
   /// Represents a radial gradient.
+  #[serde(rename_all = "camelCase")]
   Structured {
     /// The shape of the gradient.
     shape: RadialShape,
-    /// The size keyword of the gradient.
+    /// The size of the gradient.
     size: RadialSize,
     /// The center of the gradient in normalized [0,1] coords.
     center: (f32, f32),
     /// The steps of the gradient.
     stops: Vec<GradientStop>,
+    /// The color space used to interpolate between stops, defaults to sRGB.
+    #[ts(optional)]
+    color_space: Option<GradientColorSpace>,
+    /// Whether the stops repeat past the last stop, defaults to false.
+    #[ts(optional)]
+    repeating: Option<bool>,
   },
   /// Represents a CSS string.
   Css(String),
@@ -375,11 +483,15 @@ impl TryFrom<RadialGradientValue> for RadialGradient {
         size,
         center,
         stops,
+        color_space,
+        repeating,
       } => Ok(RadialGradient {
         shape,
         size,
         center,
         stops,
+        color_space: color_space.unwrap_or_default(),
+        repeating: repeating.unwrap_or_default(),
       }),
       RadialGradientValue::Css(css) => {
         let mut input = ParserInput::new(&css);
@@ -420,6 +532,8 @@ mod tests {
             hint: None,
           },
         ],
+        color_space: GradientColorSpace::Srgb,
+        repeating: false,
       })
     );
   }
@@ -446,6 +560,8 @@ mod tests {
             hint: None,
           },
         ],
+        color_space: GradientColorSpace::Srgb,
+        repeating: false,
       })
     );
   }
@@ -472,6 +588,8 @@ mod tests {
             hint: None,
           },
         ],
+        color_space: GradientColorSpace::Srgb,
+        repeating: false,
       })
     );
   }
@@ -499,6 +617,8 @@ mod tests {
             hint: None,
           },
         ],
+        color_space: GradientColorSpace::Srgb,
+        repeating: false,
       })
     );
   }
@@ -530,10 +650,115 @@ mod tests {
             hint: Some(StopPosition(LengthUnit::Percentage(100.0))),
           },
         ],
+        color_space: GradientColorSpace::Srgb,
+        repeating: false,
       })
     );
   }
 
+  fn parse_prelude(css: &str) -> (RadialShape, RadialSize, (f32, f32)) {
+    let mut input = ParserInput::new(css);
+    let mut parser = Parser::new(&mut input);
+    let gradient = RadialGradient::from_css(&mut parser).unwrap();
+
+    (gradient.shape, gradient.size, gradient.center)
+  }
+
+  #[test]
+  fn test_parse_radial_gradient_keywords_and_position() {
+    assert_eq!(
+      parse_prelude("radial-gradient(circle closest-side at 30% 70%, red, blue)"),
+      (RadialShape::Circle, RadialSize::ClosestSide, (0.3, 0.7))
+    );
+    assert_eq!(
+      parse_prelude("radial-gradient(closest-corner circle at top right, red, blue)"),
+      (RadialShape::Circle, RadialSize::ClosestCorner, (1.0, 0.0))
+    );
+    assert_eq!(
+      parse_prelude("radial-gradient(at left 30%, red, blue)"),
+      (RadialShape::Ellipse, RadialSize::FarthestCorner, (0.0, 0.3))
+    );
+    assert_eq!(
+      parse_prelude("radial-gradient(at bottom, red, blue)"),
+      (RadialShape::Ellipse, RadialSize::FarthestCorner, (0.5, 1.0))
+    );
+  }
+
+  #[test]
+  fn test_parse_radial_gradient_explicit_size() {
+    assert_eq!(
+      parse_prelude("radial-gradient(circle 40px, red, blue)"),
+      (
+        RadialShape::Circle,
+        RadialSize::Explicit {
+          x: LengthUnit::Px(40.0),
+          y: LengthUnit::Px(40.0),
+        },
+        (0.5, 0.5)
+      )
+    );
+    assert_eq!(
+      parse_prelude("radial-gradient(40px at 25% 25%, red, blue)").0,
+      RadialShape::Circle
+    );
+    assert_eq!(
+      parse_prelude("radial-gradient(50% 20px ellipse at center, red, blue)"),
+      (
+        RadialShape::Ellipse,
+        RadialSize::Explicit {
+          x: LengthUnit::Percentage(50.0),
+          y: LengthUnit::Px(20.0),
+        },
+        (0.5, 0.5)
+      )
+    );
+  }
+
+  #[test]
+  fn test_radial_gradient_css_proxy() {
+    let gradient: RadialGradient =
+      serde_json::from_str(r#""radial-gradient(circle 40px at top left, red, blue)""#).unwrap();
+
+    assert_eq!(gradient.center, (0.0, 0.0));
+
+    let ctx = RenderContext {
+      global: &GlobalContext::default(),
+      viewport: Viewport::new(200, 100),
+      parent_font_size: DEFAULT_FONT_SIZE,
+      transform: Affine::identity(),
+      style: InheritedStyle::default(),
+      border: None,
+    };
+    let draw_context = gradient.to_draw_context(200.0, 100.0, &ctx);
+
+    assert_eq!((draw_context.radius_x, draw_context.radius_y), (40.0, 40.0));
+  }
+
+  #[test]
+  fn test_repeating_radial_gradient_rings() {
+    let gradient: RadialGradient = serde_json::from_str(
+      r#""repeating-radial-gradient(circle at 0% 0%, black 0px, black 4px, white 4px, white 8px)""#,
+    )
+    .unwrap();
+
+    assert!(gradient.repeating);
+
+    let ctx = RenderContext {
+      global: &GlobalContext::default(),
+      viewport: Viewport::new(40, 40),
+      parent_font_size: DEFAULT_FONT_SIZE,
+      transform: Affine::identity(),
+      style: InheritedStyle::default(),
+      border: None,
+    };
+    let draw_context = gradient.to_draw_context(40.0, 40.0, &ctx);
+
+    assert_eq!(gradient.at(2, 0, &draw_context), Color::black());
+    assert_eq!(gradient.at(6, 0, &draw_context), Color::white());
+    assert_eq!(gradient.at(0, 10, &draw_context), Color::black());
+    assert_eq!(gradient.at(0, 30, &draw_context), Color::white());
+  }
+
   #[test]
   fn resolve_stops_percentage_and_px_radial() {
     let gradient = RadialGradient {
@@ -554,6 +779,8 @@ mod tests {
           hint: Some(StopPosition(LengthUnit::Px(100.0))),
         },
       ],
+      color_space: GradientColorSpace::Srgb,
+      repeating: false,
     };
 
     let ctx = RenderContext {
@@ -562,6 +789,7 @@ mod tests {
       parent_font_size: DEFAULT_FONT_SIZE,
       transform: Affine::identity(),
       style: InheritedStyle::default(),
+      border: None,
     };
     let resolved = gradient.resolve_stops_for_radius(ctx.viewport.width as f32, &ctx);
 
@@ -590,6 +818,8 @@ mod tests {
           hint: Some(StopPosition(LengthUnit::Px(0.0))),
         },
       ],
+      color_space: GradientColorSpace::Srgb,
+      repeating: false,
     };
 
     let ctx = RenderContext {
@@ -598,6 +828,7 @@ mod tests {
       parent_font_size: DEFAULT_FONT_SIZE,
       transform: Affine::identity(),
       style: InheritedStyle::default(),
+      border: None,
     };
     let resolved = gradient.resolve_stops_for_radius(ctx.viewport.width as f32, &ctx);
 
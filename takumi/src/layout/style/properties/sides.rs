@@ -3,7 +3,7 @@ use serde::{Deserialize, Deserializer, Serialize, de::Error as DeError};
 use taffy::Rect;
 use ts_rs::TS;
 
-use crate::layout::style::{FromCss, LengthUnit};
+use crate::layout::style::{FromCss, LengthUnit, ParseResult};
 
 /// Represents the values for the four sides of a box (top, right, bottom, left).
 #[derive(Debug, Clone, Copy, Serialize, TS, PartialEq)]
@@ -16,6 +16,7 @@ pub struct Sides<T: TS + Copy>(pub [T; 4]);
 /// or border sizes using either a single value for all sides, separate values
 /// for vertical/horizontal axes, or individual values for each side.
 #[derive(Debug, Clone, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum SidesValue<T> {
   /// CSS string representation
@@ -37,36 +38,10 @@ impl<T: TS + Copy + for<'i> FromCss<'i>> TryFrom<SidesValue<T>> for Sides<T> {
         let mut input = ParserInput::new(&string);
         let mut parser = Parser::new(&mut input);
 
-        // Parse between 1 and 4 values of T using FromCss
-        let first = T::from_css(&mut parser).map_err(|e| e.to_string())?;
-
-        // Collect all values by parsing until we can't parse more
-        let mut values = Vec::with_capacity(4);
-
-        values.push(first);
-
-        // Keep parsing values separated by whitespace
-        loop {
-          // Try to parse the next value
-          match parser.try_parse(T::from_css) {
-            Ok(next_value) => values.push(next_value),
-            Err(_) => break,
-          }
-
-          // Don't allow more than 4 values
-          if values.len() >= 4 {
-            break;
-          }
-        }
-
-        // Now create the sides based on how many values we got
-        let sides = match values.len() {
-          1 => Sides([values[0]; 4]),
-          2 => Sides([values[0], values[1], values[0], values[1]]),
-          3 => Sides([values[0], values[1], values[2], values[1]]),
-          4 => Sides([values[0], values[1], values[2], values[3]]),
-          _ => unreachable!(),
-        };
+        let sides = Sides::from_css(&mut parser).map_err(|e| e.to_string())?;
+
+        // Reject anything after the fourth value instead of silently dropping it
+        parser.expect_exhausted().map_err(|e| format!("{e:?}"))?;
 
         Ok(sides)
       }
@@ -79,6 +54,28 @@ impl<T: TS + Copy + for<'i> FromCss<'i>> TryFrom<SidesValue<T>> for Sides<T> {
   }
 }
 
+impl<'i, T: TS + Copy + FromCss<'i>> FromCss<'i> for Sides<T> {
+  /// Parses 1 to 4 whitespace separated values, expanded to top, right, bottom and left
+  /// the same way CSS shorthands like `padding` and `margin` do.
+  fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
+    let top = T::from_css(input)?;
+
+    let Ok(right) = input.try_parse(T::from_css) else {
+      return Ok(Sides([top; 4]));
+    };
+
+    let Ok(bottom) = input.try_parse(T::from_css) else {
+      return Ok(Sides([top, right, top, right]));
+    };
+
+    let Ok(left) = input.try_parse(T::from_css) else {
+      return Ok(Sides([top, right, bottom, right]));
+    };
+
+    Ok(Sides([top, right, bottom, left]))
+  }
+}
+
 impl<'de, T> Deserialize<'de> for Sides<T>
 where
   T: TS + Copy + Deserialize<'de> + for<'i> FromCss<'i>,
@@ -92,6 +89,21 @@ where
   }
 }
 
+#[cfg(feature = "schema")]
+impl<T: TS + Copy + schemars::JsonSchema> schemars::JsonSchema for Sides<T> {
+  fn inline_schema() -> bool {
+    true
+  }
+
+  fn schema_name() -> std::borrow::Cow<'static, str> {
+    SidesValue::<T>::schema_name()
+  }
+
+  fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+    SidesValue::<T>::json_schema(generator)
+  }
+}
+
 impl<T: Copy + TS> From<Sides<T>> for Rect<T> {
   fn from(value: Sides<T>) -> Self {
     Rect {
@@ -192,4 +204,40 @@ mod tests {
       ])
     );
   }
+
+  #[test]
+  fn deserialize_css_axis_values() {
+    let json = "\"10px 20px\"";
+    let sides: Sides<LengthUnit> = serde_json::from_str(json).expect("should deserialize");
+    assert_eq!(
+      sides,
+      Sides([
+        LengthUnit::Px(10.0),
+        LengthUnit::Px(20.0),
+        LengthUnit::Px(10.0),
+        LengthUnit::Px(20.0)
+      ])
+    );
+  }
+
+  #[test]
+  fn deserialize_css_three_values() {
+    let json = "\"10px 20px 30px\"";
+    let sides: Sides<LengthUnit> = serde_json::from_str(json).expect("should deserialize");
+    assert_eq!(
+      sides,
+      Sides([
+        LengthUnit::Px(10.0),
+        LengthUnit::Px(20.0),
+        LengthUnit::Px(30.0),
+        LengthUnit::Px(20.0)
+      ])
+    );
+  }
+
+  #[test]
+  fn deserialize_css_too_many_values() {
+    let json = "\"1px 2px 3px 4px 5px\"";
+    assert!(serde_json::from_str::<Sides<LengthUnit>>(json).is_err());
+  }
 }
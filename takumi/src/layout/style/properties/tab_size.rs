@@ -0,0 +1,121 @@
+use cssparser::{Parser, ParserInput};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::layout::style::{FromCss, LengthUnit, ParseResult};
+
+/// Represents the `tab-size` property, the distance between the tab stops tabs advance to.
+///
+/// A number is a count of space characters, including the letter and word spacing applied to
+/// them. A length is the distance itself.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, TS)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(try_from = "TabSizeValue")]
+#[ts(as = "TabSizeValue")]
+pub enum TabSize {
+  /// A multiple of the advance of a space.
+  Spaces(f32),
+  /// A length, `em` is relative to the font size.
+  Length(LengthUnit),
+}
+
+impl Serialize for TabSize {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    match self {
+      TabSize::Spaces(spaces) => serializer.serialize_f32(*spaces),
+      TabSize::Length(length) => length.serialize(serializer),
+    }
+  }
+}
+
+impl Default for TabSize {
+  fn default() -> Self {
+    Self::Spaces(8.0)
+  }
+}
+
+/// Proxy type for `TabSize` Css deserialization.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, TS)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(untagged)]
+pub enum TabSizeValue {
+  /// A number of spaces.
+  Spaces(f32),
+  /// A CSS string value.
+  Css(String),
+  /// A length value.
+  Length(LengthUnit),
+}
+
+impl TryFrom<TabSizeValue> for TabSize {
+  type Error = String;
+
+  fn try_from(value: TabSizeValue) -> Result<Self, Self::Error> {
+    let tab_size = match value {
+      TabSizeValue::Spaces(spaces) => TabSize::Spaces(spaces),
+      TabSizeValue::Css(css) => {
+        let mut input = ParserInput::new(&css);
+        let mut parser = Parser::new(&mut input);
+
+        let tab_size = TabSize::from_css(&mut parser).map_err(|e| e.to_string())?;
+
+        parser.expect_exhausted().map_err(|e| format!("{e:?}"))?;
+
+        tab_size
+      }
+      TabSizeValue::Length(length) => TabSize::Length(length),
+    };
+
+    match tab_size {
+      TabSize::Spaces(spaces) if spaces < 0.0 => Err("tab-size can't be negative".to_string()),
+      TabSize::Length(LengthUnit::Percentage(_)) => {
+        Err("tab-size doesn't accept percentages".to_string())
+      }
+      _ => Ok(tab_size),
+    }
+  }
+}
+
+impl<'i> FromCss<'i> for TabSize {
+  fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
+    let Ok(spaces) = input.try_parse(Parser::expect_number) else {
+      return LengthUnit::from_css(input).map(TabSize::Length);
+    };
+
+    Ok(TabSize::Spaces(spaces))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn parse(css: &str) -> Result<TabSize, String> {
+    TabSize::try_from(TabSizeValue::Css(css.to_string()))
+  }
+
+  #[test]
+  fn test_parse_tab_size() {
+    assert_eq!(parse("4"), Ok(TabSize::Spaces(4.0)));
+    assert_eq!(parse("2.5"), Ok(TabSize::Spaces(2.5)));
+    assert_eq!(parse("32px"), Ok(TabSize::Length(LengthUnit::Px(32.0))));
+    assert_eq!(parse("2em"), Ok(TabSize::Length(LengthUnit::Em(2.0))));
+
+    assert!(parse("-1").is_err());
+    assert!(parse("50%").is_err());
+    assert!(parse("4 4").is_err());
+  }
+
+  #[test]
+  fn test_deserialize_tab_size() {
+    // a bare number is a count of spaces like in CSS, not pixels
+    assert_eq!(
+      serde_json::from_str::<TabSize>("4").unwrap(),
+      TabSize::Spaces(4.0)
+    );
+    assert_eq!(
+      serde_json::from_str::<TabSize>(r#""20px""#).unwrap(),
+      TabSize::Length(LengthUnit::Px(20.0))
+    );
+  }
+}
@@ -9,6 +9,7 @@ use crate::layout::style::{Color, FromCss, LengthUnit, ParseResult};
 
 /// Represents a text shadow with all its properties.
 #[derive(Debug, Clone, PartialEq, Copy, Serialize, Deserialize, TS)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[ts(as = "TextShadowValue")]
 #[serde(try_from = "TextShadowValue")]
 pub struct TextShadow {
@@ -24,6 +25,7 @@ pub struct TextShadow {
 
 /// Proxy type for `TextShadow` Css deserialization.
 #[derive(Debug, Clone, PartialEq, TS, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub(crate) enum TextShadowValue {
   /// Represents a structured box shadow.
@@ -70,11 +72,13 @@ impl TryFrom<TextShadowValue> for TextShadow {
 
 /// Represents a collection of text shadows; has custom `FromCss` implementation for comma-separated values.
 #[derive(Debug, Clone, PartialEq, Deserialize, TS, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[ts(as = "TextShadowsValue")]
 #[serde(try_from = "TextShadowsValue")]
 pub struct TextShadows(pub SmallVec<[TextShadow; 4]>);
 
 #[derive(Debug, Clone, PartialEq, TS, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub(crate) enum TextShadowsValue {
   #[ts(as = "Vec<TextShadow>")]
@@ -9,6 +9,7 @@ use crate::layout::style::{
 
 /// Represents the `text-stroke` shorthand which accepts a width and an optional color.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub(crate) enum TextStrokeValue {
   /// Structured representation when provided as JSON.
@@ -25,6 +26,7 @@ pub(crate) enum TextStrokeValue {
 ///
 /// `color` is optional; when absent the element's `color` property should be used.
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(try_from = "TextStrokeValue")]
 #[ts(as = "TextStrokeValue")]
 pub struct TextStroke {
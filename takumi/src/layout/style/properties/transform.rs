@@ -18,6 +18,7 @@ const DEFAULT_SCALE: f32 = 1.0;
 
 /// Represents a single CSS transform operation
 #[derive(Debug, Clone, Deserialize, Serialize, Copy, TS)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub enum Transform {
   /// Translates an element along the X-axis and Y-axis by the specified lengths
@@ -34,6 +35,7 @@ pub enum Transform {
 
 /// A collection of transform operations that can be applied together
 #[derive(Debug, Clone, Deserialize, Serialize, TS, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[ts(as = "TransformsValue")]
 #[serde(try_from = "TransformsValue")]
 pub struct Transforms(pub SmallVec<[Transform; 4]>);
@@ -106,6 +108,7 @@ impl Transforms {
 
 /// Represents transform values that can be either a structured list or raw CSS
 #[derive(Debug, Clone, Deserialize, TS)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum TransformsValue {
   /// A structured list of transform operations
@@ -201,6 +204,7 @@ impl<'i> FromCss<'i> for Transform {
 
 /// Represents an affine transform matrix
 #[derive(PartialEq, Deserialize, Serialize, Debug, Clone, Copy, TS)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Affine {
   /// Horizontal scaling / cosine of rotation
   pub a: f32,
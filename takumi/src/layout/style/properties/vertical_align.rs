@@ -0,0 +1,101 @@
+use cssparser::{Parser, ParserInput, match_ignore_ascii_case};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::layout::style::{FromCss, LengthUnit, ParseResult};
+
+/// Aligns a node with the text line of its parent, like CSS `vertical-align`.
+///
+/// The line is the first line of text the node's font properties (inherited from the parent
+/// unless overridden) would produce, which is where sibling text sits in a row container.
+/// The node is placed at the start of the cross axis before being shifted.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize, TS)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(try_from = "VerticalAlignValue")]
+#[ts(as = "VerticalAlignValue")]
+pub enum VerticalAlign {
+  /// Aligns the bottom of the node with the baseline of the line
+  Baseline,
+  /// Aligns the middle of the node with the baseline plus half the x-height of the line
+  Middle,
+  /// Aligns the top of the node with the top of the line
+  Top,
+  /// Aligns the bottom of the node with the bottom of the line
+  Bottom,
+  /// Raises the bottom of the node above the baseline by the length
+  Length(LengthUnit),
+  /// Sets the node in a smaller font and raises its baseline above the baseline of the line
+  Super,
+  /// Sets the node in a smaller font and lowers its baseline below the baseline of the line
+  Sub,
+}
+
+impl VerticalAlign {
+  /// Returns whether the node is a superscript or subscript, which are set in a smaller font
+  /// unless they have a `font-size` of their own.
+  pub(crate) fn is_script(self) -> bool {
+    matches!(self, VerticalAlign::Super | VerticalAlign::Sub)
+  }
+}
+
+/// Proxy type for `VerticalAlign` Css deserialization.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, TS)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(untagged)]
+pub enum VerticalAlignValue {
+  /// A CSS string value.
+  Css(String),
+  /// A length value.
+  Length(LengthUnit),
+}
+
+impl TryFrom<VerticalAlignValue> for VerticalAlign {
+  type Error = String;
+
+  fn try_from(value: VerticalAlignValue) -> Result<Self, Self::Error> {
+    match value {
+      VerticalAlignValue::Css(css) => {
+        let mut input = ParserInput::new(&css);
+        let mut parser = Parser::new(&mut input);
+
+        VerticalAlign::from_css(&mut parser).map_err(|e| e.to_string())
+      }
+      VerticalAlignValue::Length(length) => Ok(VerticalAlign::Length(length)),
+    }
+  }
+}
+
+impl<'i> FromCss<'i> for VerticalAlign {
+  fn from_css(input: &mut Parser<'i, '_>) -> ParseResult<'i, Self> {
+    if let Ok(ident) = input.try_parse(Parser::expect_ident_cloned) {
+      return match_ignore_ascii_case! { &ident,
+        "baseline" => Ok(VerticalAlign::Baseline),
+        "middle" => Ok(VerticalAlign::Middle),
+        "top" => Ok(VerticalAlign::Top),
+        "bottom" => Ok(VerticalAlign::Bottom),
+        "super" => Ok(VerticalAlign::Super),
+        "sub" => Ok(VerticalAlign::Sub),
+        _ => Err(input.new_error_for_next_token()),
+      };
+    }
+
+    LengthUnit::from_css(input).map(VerticalAlign::Length)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_vertical_align() {
+    let parse = |css: &str| VerticalAlign::try_from(VerticalAlignValue::Css(css.to_string()));
+
+    assert_eq!(parse("baseline"), Ok(VerticalAlign::Baseline));
+    assert_eq!(parse("MIDDLE"), Ok(VerticalAlign::Middle));
+    assert_eq!(parse("super"), Ok(VerticalAlign::Super));
+    assert_eq!(parse("Sub"), Ok(VerticalAlign::Sub));
+    assert_eq!(parse("4px"), Ok(VerticalAlign::Length(LengthUnit::Px(4.0))));
+    assert!(parse("sideways").is_err());
+  }
+}
@@ -6,11 +6,13 @@ use ts_rs::TS;
 ///
 /// Corresponds to CSS word-break property.
 #[derive(Debug, Default, Copy, Clone, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(from = "WordBreakValue", into = "WordBreakValue")]
 #[ts(as = "WordBreakValue")]
 pub struct WordBreak(pub WordBreakStrength);
 
 #[derive(Debug, Copy, Clone, Deserialize, Serialize, TS, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "kebab-case")]
 enum WordBreakValue {
   Normal,
@@ -1,7 +1,7 @@
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
-use taffy::{Size, prelude::FromLength};
+use taffy::{Point, Size, prelude::FromLength};
 use ts_rs::TS;
 
 use crate::{
@@ -9,7 +9,7 @@ use crate::{
     DEFAULT_FONT_SIZE,
     style::{CssValue, properties::*},
   },
-  rendering::{RenderContext, SizedShadow},
+  rendering::{LineReservation, RenderContext, SizedShadow},
 };
 
 /// Helper macro to define the `Style` struct and `InheritedStyle` struct.
@@ -17,6 +17,7 @@ macro_rules! define_style {
   ($( $(#[$attr:meta])? $property:ident: $type:ty = $default_global:expr => $initial_value:expr),* $(,)?) => {
     /// Defines the style of an element.
     #[derive(Debug, Clone, Deserialize, Serialize, TS, Builder)]
+    #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
     #[serde(default, rename_all = "camelCase")]
     #[ts(export, optional_fields)]
     #[builder(default, setter(into))]
@@ -92,9 +93,15 @@ define_style!(
   justify_items: Option<AlignItems> = None => None,
   align_items: Option<AlignItems> = None => None,
   align_self: Option<AlignItems> = None => None,
+  place_content: Option<PlaceContent> = None => None,
+  place_items: Option<PlaceItems> = None => None,
+  vertical_align: Option<VerticalAlign> = None => None,
   flex_wrap: FlexWrap = FlexWrap::NoWrap => FlexWrap::NoWrap,
-  flex_basis: LengthUnit = LengthUnit::Auto => LengthUnit::Auto,
+  flex: Option<Flex> = None => None,
+  flex_basis: Option<LengthUnit> = None => None,
   position: Position = Position::Relative => Position::Relative,
+  z_index: Option<i32> = None => None,
+  overflow: Overflow = Overflow::Visible => Overflow::Visible,
   transform: Option<Transforms> = None => None,
   transform_origin: Option<BackgroundPosition> = None => None,
   mask_image: Option<BackgroundImages> = None => None,
@@ -102,9 +109,9 @@ define_style!(
   mask_position: Option<BackgroundPositions> = None => None,
   mask_repeat: Option<BackgroundRepeats> = None => None,
   gap: Gap = Gap::default() => Gap::default(),
-  flex_grow: f32 = 0.0 => 0.0,
-  flex_shrink: f32 = 1.0 => 1.0,
-  border_radius: Sides<LengthUnit> = Sides::zero() => Sides::zero(),
+  flex_grow: Option<f32> = None => None,
+  flex_shrink: Option<f32> = None => None,
+  border_radius: BorderRadius = BorderRadius::zero() => BorderRadius::zero(),
   border_top_left_radius: Option<LengthUnit> = None => None,
   border_top_right_radius: Option<LengthUnit> = None => None,
   border_bottom_right_radius: Option<LengthUnit> = None => None,
@@ -114,26 +121,41 @@ define_style!(
   border_right_width: Option<LengthUnit> = None => None,
   border_bottom_width: Option<LengthUnit> = None => None,
   border_left_width: Option<LengthUnit> = None => None,
+  border_image: Option<BackgroundImages> = None => None,
+  outline: Option<Outline> = None => None,
+  outline_width: Option<LengthUnit> = None => None,
+  outline_style: Option<OutlineStyle> = None => None,
+  outline_color: Option<ColorInput> = None => None,
+  outline_offset: LengthUnit = LengthUnit::Px(0.0) => LengthUnit::Px(0.0),
   object_fit: ObjectFit = CssValue::Inherit => Default::default(),
   object_position: BackgroundPosition = CssValue::Inherit => BackgroundPosition::default(),
+  object_repeat: BackgroundRepeat = CssValue::Inherit => BackgroundRepeat::no_repeat(),
   background_image: Option<BackgroundImages> = None => None,
   background_position: Option<BackgroundPositions> = None => None,
   background_size: Option<BackgroundSizes> = None => None,
   background_repeat: Option<BackgroundRepeats> = None => None,
+  background_blend_mode: Option<BackgroundBlendModes> = None => None,
+  background_noise: Option<BackgroundNoise> = None => None,
   background_color: Color = Color::transparent() => Color::transparent(),
   box_shadow: Option<BoxShadows> = None => None,
+  backdrop_filter: Option<Filters> = None => None,
+  filter: Option<Filters> = None => None,
+  clip_path: Option<ClipPath> = None => None,
+  mix_blend_mode: MixBlendMode = MixBlendMode::Normal => MixBlendMode::Normal,
   grid_auto_columns: Option<GridTrackSizes> = None => None,
   grid_auto_rows: Option<GridTrackSizes> = None => None,
   grid_auto_flow: Option<GridAutoFlow> = None => None,
   grid_column: Option<GridLine> = None => None,
   grid_row: Option<GridLine> = None => None,
+  grid_area: Option<String> = None => None,
   grid_template_columns: Option<GridTemplateComponents> = None => None,
   grid_template_rows: Option<GridTemplateComponents> = None => None,
   grid_template_areas: Option<GridTemplateAreas> = None => None,
   text_overflow: TextOverflow = CssValue::Inherit => Default::default(),
   text_transform: TextTransform = CssValue::Inherit => Default::default(),
+  visibility: Visibility = CssValue::Inherit => Default::default(),
   font_style: FontStyle = CssValue::Inherit => Default::default(),
-  border_color: Color = CssValue::Inherit => Color::black(),
+  border_color: ColorInput = CssValue::Inherit => ColorInput::Value(Color::black()),
   color: Color = CssValue::Inherit => Color::black(),
   font_size: LengthUnit = CssValue::Inherit => LengthUnit::Px(DEFAULT_FONT_SIZE),
   font_family: Option<FontFamily> = CssValue::Inherit => None,
@@ -152,22 +174,75 @@ define_style!(
   text_shadow: Option<TextShadows> = CssValue::Inherit => None,
   letter_spacing: Option<LengthUnit> = CssValue::Inherit => None,
   word_spacing: Option<LengthUnit> = CssValue::Inherit => None,
+  text_indent: Option<LengthUnit> = CssValue::Inherit => None,
+  writing_mode: WritingMode = CssValue::Inherit => Default::default(),
   image_rendering: ImageScalingAlgorithm = CssValue::Inherit => Default::default(),
   overflow_wrap: OverflowWrap = CssValue::Inherit => Default::default(),
   word_break: WordBreak = CssValue::Inherit => Default::default(),
+  hyphens: Hyphens = CssValue::Inherit => Default::default(),
+  lang: Option<String> = CssValue::Inherit => None,
+  tab_size: TabSize = CssValue::Inherit => Default::default(),
+  initial_letter: Option<InitialLetter> = None => None,
 );
 
+/// The widest `letter-spacing` and `word-spacing` relative to the font size, wider spacing runs
+/// the line widths out of float precision.
+const MAX_SPACING_EM: f32 = 1000.0;
+
+/// Resolves a `letter-spacing` or `word-spacing` to pixels.
+///
+/// Spacing can pull the glyphs back by at most the font size, tighter than that the text runs
+/// backwards and the lines lose their width. Values that don't resolve to a finite length are
+/// ignored.
+fn resolve_spacing(spacing: LengthUnit, context: &RenderContext, font_size: f32) -> Option<f32> {
+  let spacing = spacing.resolve_to_px(context, font_size);
+
+  (spacing.is_finite() && font_size.is_finite())
+    .then(|| spacing.clamp(-font_size, font_size * MAX_SPACING_EM))
+}
+
 /// Sized font style with resolved font size and line height.
+#[derive(Clone)]
 pub(crate) struct SizedFontStyle<'s> {
   pub parent: &'s InheritedStyle,
   pub font_size: f32,
   pub line_height: parley::LineHeight,
   pub stroke_width: f32,
+  /// In pixels, clamped by [`resolve_spacing`].
   pub letter_spacing: Option<f32>,
+  /// In pixels, clamped by [`resolve_spacing`].
   pub word_spacing: Option<f32>,
+  /// Every unit except percentages is resolved to pixels, percentages resolve against the line width.
+  pub text_indent: Option<LengthUnit>,
+  /// Lengths are resolved to pixels.
+  pub tab_size: TabSize,
+  /// The room made for a drop cap at the start of the first lines.
+  pub line_reservation: Option<LineReservation>,
   pub text_shadow: Option<SmallVec<[SizedShadow; 4]>>,
 }
 
+impl SizedFontStyle<'_> {
+  /// Resolves the indent of the first line, percentages are ignored without a definite width.
+  pub fn resolve_text_indent(&self, max_width: f32) -> f32 {
+    match self.text_indent {
+      Some(LengthUnit::Px(indent)) => indent,
+      Some(LengthUnit::Percentage(percentage)) if max_width < f32::MAX => {
+        max_width * percentage / 100.0
+      }
+      _ => 0.0,
+    }
+  }
+
+  /// Resolves the distance between tab stops from the advance of a space.
+  pub fn resolve_tab_interval(&self, space: f32) -> f32 {
+    match self.tab_size {
+      TabSize::Spaces(spaces) => spaces * space,
+      TabSize::Length(LengthUnit::Px(length)) => length,
+      TabSize::Length(_) => 0.0,
+    }
+  }
+}
+
 impl InheritedStyle {
   #[inline]
   fn convert_template_components(
@@ -284,11 +359,141 @@ impl InheritedStyle {
     )
   }
 
+  /// Resolves `inset` with the individual `top`, `right`, `bottom` and `left` fields, which win
+  /// over the shorthand when both are set.
   #[inline]
-  fn resolved_inset(&self) -> taffy::Rect<LengthUnit> {
+  pub(crate) fn resolved_inset(&self) -> taffy::Rect<LengthUnit> {
     Self::resolve_rect_with_longhands(self.inset, self.top, self.right, self.bottom, self.left)
   }
 
+  /// Resolves the `inset` of a `position: fixed` node against the viewport rect, `auto` sides
+  /// are `None`.
+  pub(crate) fn resolve_fixed_inset(
+    &self,
+    context: &RenderContext,
+    viewport: Size<f32>,
+  ) -> taffy::Rect<Option<f32>> {
+    let inset = self.resolved_inset();
+    let resolve = |side: LengthUnit, full: f32| {
+      (side != LengthUnit::Auto).then(|| side.resolve_to_px(context, full))
+    };
+
+    taffy::Rect {
+      left: resolve(inset.left, viewport.width),
+      right: resolve(inset.right, viewport.width),
+      top: resolve(inset.top, viewport.height),
+      bottom: resolve(inset.bottom, viewport.height),
+    }
+  }
+
+  /// Resolves the size of a `position: fixed` node, percentages are of the viewport and an `auto`
+  /// size with both insets set stretches between them.
+  ///
+  /// Without a viewport height the height is sized like an absolute box of the parent.
+  fn resolve_fixed_size(&self, context: &RenderContext) -> Size<taffy::Dimension> {
+    let viewport = Size {
+      width: context.viewport.width as f32,
+      height: context.viewport.height.map_or(0.0, |height| height as f32),
+    };
+
+    let inset = self.resolve_fixed_inset(context, viewport);
+    let margin = self.resolved_margin();
+    let padding = self.resolved_padding();
+    let border = self.resolved_border_width();
+
+    let content_box = self.box_sizing == BoxSizing::ContentBox;
+
+    let resolve = |size: LengthUnit,
+                   insets: (Option<f32>, Option<f32>),
+                   edges: [LengthUnit; 6],
+                   full: f32| match (size, insets) {
+      (LengthUnit::Percentage(percentage), _) => {
+        taffy::Dimension::length(full * percentage / 100.0)
+      }
+      (LengthUnit::Auto, (Some(start), Some(end))) => {
+        let [
+          margin_start,
+          margin_end,
+          padding_start,
+          padding_end,
+          border_start,
+          border_end,
+        ] = edges.map(|edge| match edge {
+          LengthUnit::Auto => 0.0,
+          edge => edge.resolve_to_px(context, viewport.width),
+        });
+
+        let mut stretched = full - start - end - margin_start - margin_end;
+
+        if content_box {
+          stretched -= padding_start + padding_end + border_start + border_end;
+        }
+
+        taffy::Dimension::length(stretched.max(0.0))
+      }
+      (size, _) => size.resolve_to_dimension(context),
+    };
+
+    Size {
+      width: resolve(
+        self.width,
+        (inset.left, inset.right),
+        [
+          margin.left,
+          margin.right,
+          padding.left,
+          padding.right,
+          border.left,
+          border.right,
+        ],
+        viewport.width,
+      ),
+      height: if context.viewport.height.is_some() {
+        resolve(
+          self.height,
+          (inset.top, inset.bottom),
+          [
+            margin.top,
+            margin.bottom,
+            padding.top,
+            padding.bottom,
+            border.top,
+            border.bottom,
+          ],
+          viewport.height,
+        )
+      } else {
+        self.height.resolve_to_dimension(context)
+      },
+    }
+  }
+
+  #[inline]
+  pub(crate) fn resolved_flex(&self) -> Flex {
+    let flex = self.flex.unwrap_or_default();
+
+    Flex {
+      grow: self.flex_grow.unwrap_or(flex.grow),
+      shrink: self.flex_shrink.unwrap_or(flex.shrink),
+      basis: self.flex_basis.unwrap_or(flex.basis),
+    }
+  }
+
+  /// Resolves `gridColumn` or `gridRow`, falling back to the `gridArea` name, which covers the
+  /// matching area of the parent's `gridTemplateAreas` in both axes.
+  #[inline]
+  fn resolved_grid_line(&self, line: &Option<GridLine>) -> taffy::Line<taffy::GridPlacement> {
+    line
+      .clone()
+      .or_else(|| {
+        self.grid_area.clone().map(|name| GridLine {
+          start: Some(GridPlacement::Named(name)),
+          end: None,
+        })
+      })
+      .map_or_else(Default::default, Into::into)
+  }
+
   #[inline]
   fn resolved_border_width(&self) -> taffy::Rect<LengthUnit> {
     Self::resolve_rect_with_longhands(
@@ -300,15 +505,51 @@ impl InheritedStyle {
     )
   }
 
+  /// Resolves the `border-radius` shorthand, the longhands set both radii of their corner.
   #[inline]
-  pub(crate) fn resolved_border_radius(&self) -> taffy::Rect<LengthUnit> {
-    Self::resolve_rect_with_longhands(
-      self.border_radius,
+  pub(crate) fn resolved_border_radius(&self) -> BorderRadius {
+    let longhands = [
       self.border_top_left_radius,
       self.border_top_right_radius,
       self.border_bottom_right_radius,
       self.border_bottom_left_radius,
-    )
+    ];
+
+    let with_longhands = |mut radius: Sides<LengthUnit>| {
+      for (radius, longhand) in radius.0.iter_mut().zip(longhands) {
+        if let Some(longhand) = longhand {
+          *radius = longhand;
+        }
+      }
+
+      radius
+    };
+
+    BorderRadius {
+      horizontal: with_longhands(self.border_radius.horizontal),
+      vertical: with_longhands(self.border_radius.vertical),
+    }
+  }
+
+  /// Resolves the `outline` shorthand, the longhands win over the matching part.
+  pub(crate) fn resolved_outline(&self) -> Outline {
+    let outline = self.outline.unwrap_or_default();
+
+    Outline {
+      width: self.outline_width.unwrap_or(outline.width),
+      style: self.outline_style.unwrap_or(outline.style),
+      color: self.outline_color.or(outline.color),
+    }
+  }
+
+  /// Returns true if the background layers blend with each other, so the background color is
+  /// composed together with them instead of being painted on its own.
+  pub(crate) fn has_blended_backgrounds(&self) -> bool {
+    (self.background_image.is_some() || self.background_noise.is_some())
+      && self
+        .background_blend_mode
+        .as_ref()
+        .is_some_and(BackgroundBlendModes::is_blended)
   }
 
   pub fn to_sized_font_style(&'_ self, context: &RenderContext) -> SizedFontStyle<'_> {
@@ -317,11 +558,13 @@ impl InheritedStyle {
       .resolve_to_px(context, context.parent_font_size);
     let line_height = self.line_height.into_parley(context);
 
+    // the stroke is applied to the transformed glyph outlines, so it's in image pixels
     let resolved_stroke_width = self
       .text_stroke
       .map(|stroke| stroke.width)
       .unwrap_or(self.text_stroke_width)
-      .resolve_to_px(context, font_size);
+      .resolve_to_px(context, font_size)
+      * context.viewport.scale_factor;
 
     SizedFontStyle {
       parent: self,
@@ -330,16 +573,28 @@ impl InheritedStyle {
       stroke_width: resolved_stroke_width,
       letter_spacing: self
         .letter_spacing
-        .map(|spacing| spacing.resolve_to_px(context, font_size) / font_size),
+        .and_then(|spacing| resolve_spacing(spacing, context, font_size)),
       word_spacing: self
         .word_spacing
-        .map(|spacing| spacing.resolve_to_px(context, font_size) / font_size),
+        .and_then(|spacing| resolve_spacing(spacing, context, font_size)),
+      text_indent: self.text_indent.map(|indent| match indent {
+        LengthUnit::Percentage(_) => indent,
+        _ => LengthUnit::Px(indent.resolve_to_px(context, font_size)),
+      }),
+      tab_size: match self.tab_size {
+        TabSize::Length(length) => {
+          TabSize::Length(LengthUnit::Px(length.resolve_to_px(context, font_size)))
+        }
+        spaces => spaces,
+      },
+      line_reservation: None,
       text_shadow: self.text_shadow.as_ref().map(|shadows| {
         shadows
           .0
           .iter()
           .map(|shadow| {
             SizedShadow::from_text_shadow(*shadow, context, Size::from_length(font_size))
+              .scaled(context.viewport.scale_factor)
           })
           .collect()
       }),
@@ -353,11 +608,17 @@ impl InheritedStyle {
     let (grid_template_rows, grid_template_row_names) =
       Self::convert_template_components(&self.grid_template_rows, context);
 
+    let flex = self.resolved_flex();
+
     taffy::style::Style {
       box_sizing: self.box_sizing.into(),
-      size: Size {
-        width: self.width.resolve_to_dimension(context),
-        height: self.height.resolve_to_dimension(context),
+      size: if self.position == Position::Fixed {
+        self.resolve_fixed_size(context)
+      } else {
+        Size {
+          width: self.width.resolve_to_dimension(context),
+          height: self.height.resolve_to_dimension(context),
+        }
       },
       border: resolve_length_unit_rect_to_length_percentage(context, self.resolved_border_width()),
       padding: resolve_length_unit_rect_to_length_percentage(context, self.resolved_padding()),
@@ -366,14 +627,30 @@ impl InheritedStyle {
       display: self.display.into(),
       flex_direction: self.flex_direction.into(),
       position: self.position.into(),
-      justify_content: self.justify_content.map(Into::into),
-      align_content: self.align_content.map(Into::into),
-      justify_items: self.justify_items.map(Into::into),
-      flex_grow: self.flex_grow,
-      align_items: self.align_items.map(Into::into),
+      overflow: Point {
+        x: self.overflow.into(),
+        y: self.overflow.into(),
+      },
+      justify_content: self
+        .justify_content
+        .or(self.place_content.map(|place| place.1))
+        .map(Into::into),
+      align_content: self
+        .align_content
+        .or(self.place_content.map(|place| place.0))
+        .map(Into::into),
+      justify_items: self
+        .justify_items
+        .or(self.place_items.map(|place| place.1))
+        .map(Into::into),
+      flex_grow: flex.grow,
+      align_items: self
+        .align_items
+        .or(self.place_items.map(|place| place.0))
+        .map(Into::into),
       gap: self.gap.resolve_to_size(context),
-      flex_basis: self.flex_basis.resolve_to_dimension(context),
-      flex_shrink: self.flex_shrink,
+      flex_basis: flex.basis.resolve_to_dimension(context),
+      flex_shrink: flex.shrink,
       flex_wrap: self.flex_wrap.into(),
       min_size: Size {
         width: self.min_width.resolve_to_dimension(context),
@@ -390,14 +667,8 @@ impl InheritedStyle {
         v.0.iter().map(|s| s.to_min_max(context)).collect()
       }),
       grid_auto_flow: self.grid_auto_flow.unwrap_or_default().into(),
-      grid_column: self
-        .grid_column
-        .as_ref()
-        .map_or_else(Default::default, |line| line.clone().into()),
-      grid_row: self
-        .grid_row
-        .as_ref()
-        .map_or_else(Default::default, |line| line.clone().into()),
+      grid_column: self.resolved_grid_line(&self.grid_column),
+      grid_row: self.resolved_grid_line(&self.grid_row),
       grid_template_columns,
       grid_template_rows,
       grid_template_column_names,
@@ -409,7 +680,11 @@ impl InheritedStyle {
         .unwrap_or_default()
         .into(),
       aspect_ratio: self.aspect_ratio,
-      align_self: self.align_self.map(Into::into),
+      // vertical-align shifts the node from the start of the line
+      align_self: self
+        .align_self
+        .map(Into::into)
+        .or(self.vertical_align.map(|_| taffy::AlignItems::FlexStart)),
       justify_self: self.justify_self.map(Into::into),
       ..Default::default()
     }
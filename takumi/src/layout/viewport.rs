@@ -1,3 +1,5 @@
+use taffy::{AvailableSpace, Size};
+
 /// The default font size in pixels.
 pub const DEFAULT_FONT_SIZE: f32 = 16.0;
 
@@ -10,9 +12,18 @@ pub struct Viewport {
   /// The width of the viewport in pixels.
   pub width: u32,
   /// The height of the viewport in pixels.
-  pub height: u32,
+  ///
+  /// `None` means the height is sized by the content of the root node, see
+  /// [`Viewport::auto_height`]. This used to be a plain `u32`, code building a viewport from its
+  /// fields wraps the height in `Some` or uses [`Viewport::new`] instead.
+  pub height: Option<u32>,
   /// The font size in pixels, used for em and rem units.
   pub font_size: f32,
+  /// The number of image pixels per CSS pixel, like the device pixel ratio of a screen.
+  ///
+  /// Lengths are still resolved in CSS pixels, only the rasterization is scaled, so a
+  /// 600×315 viewport with a scale factor of 2 renders a sharp 1200×630 image.
+  pub scale_factor: f32,
 }
 
 impl Viewport {
@@ -27,8 +38,42 @@ impl Viewport {
   pub fn new_with_font_size(width: u32, height: u32, font_size: f32) -> Self {
     Self {
       width,
-      height,
+      height: Some(height),
       font_size,
+      scale_factor: 1.0,
+    }
+  }
+
+  /// Creates a new viewport with a fixed width, the height is sized by the content.
+  ///
+  /// The content height depends on the lengths it is laid out with, so there is no height for
+  /// `vh` to be relative to and it resolves to zero in this mode, use `vw` or pixels instead.
+  #[must_use]
+  pub fn auto_height(width: u32) -> Self {
+    Self {
+      width,
+      height: None,
+      font_size: DEFAULT_FONT_SIZE,
+      scale_factor: 1.0,
+    }
+  }
+
+  /// Returns the viewport rendered at the given scale factor, see [`Viewport::scale_factor`].
+  #[must_use]
+  pub fn with_scale_factor(self, scale_factor: f32) -> Self {
+    Self {
+      scale_factor,
+      ..self
+    }
+  }
+
+  /// Returns the space available to the root node.
+  pub(crate) fn available_space(&self) -> Size<AvailableSpace> {
+    Size {
+      width: AvailableSpace::Definite(self.width as f32),
+      height: self.height.map_or(AvailableSpace::MaxContent, |height| {
+        AvailableSpace::Definite(height as f32)
+      }),
     }
   }
 }
@@ -41,15 +86,32 @@ mod tests {
   fn test_viewport_new_defaults() {
     let v = Viewport::new(800, 600);
     assert_eq!(v.width, 800);
-    assert_eq!(v.height, 600);
+    assert_eq!(v.height, Some(600));
     assert_eq!(v.font_size, DEFAULT_FONT_SIZE);
+    assert_eq!(v.scale_factor, 1.0);
   }
 
   #[test]
   fn test_viewport_new_with_font_size() {
     let v = Viewport::new_with_font_size(1024, 768, 14.0);
     assert_eq!(v.width, 1024);
-    assert_eq!(v.height, 768);
+    assert_eq!(v.height, Some(768));
     assert_eq!(v.font_size, 14.0);
   }
+
+  #[test]
+  fn test_viewport_auto_height() {
+    let v = Viewport::auto_height(1200);
+    assert_eq!(v.width, 1200);
+    assert_eq!(v.height, None);
+    assert_eq!(v.available_space().height, AvailableSpace::MaxContent);
+  }
+
+  #[test]
+  fn test_viewport_with_scale_factor() {
+    let v = Viewport::new(600, 315).with_scale_factor(2.0);
+    assert_eq!(v.width, 600);
+    assert_eq!(v.scale_factor, 2.0);
+    assert_eq!(v.available_space().width, AvailableSpace::Definite(600.0));
+  }
 }
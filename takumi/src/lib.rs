@@ -73,6 +73,10 @@
 //! - `svg`: Enable SVG support.
 //! - `rayon`: Enable rayon support.
 //! - `avif`: Enable AVIF support.
+//! - `markdown`: Enable converting Markdown to node trees.
+//! - `schema`: Enable generating a JSON Schema for the node tree.
+//! - `test-utils`: Enable the snapshot testing helpers in the `testing` module, and export the
+//!   blending primitives used by the benchmarks.
 //!
 //! # Credits
 //!
@@ -82,6 +86,8 @@
 //! - [wuff](https://github.com/nicoburns/wuff) for woff/woff2 decompression.
 //! - [ts-rs](https://github.com/AlephAlpha/ts-rs) for the type-safe serialization.
 //! - [resvg](https://github.com/linebender/resvg) for SVG parsing and rendering.
+//! - [schemars](https://github.com/GREsau/schemars) for the JSON Schema generation.
+//! - [pulldown-cmark](https://github.com/pulldown-cmark/pulldown-cmark) for Markdown parsing.
 
 /// Layout related modules, including the node tree, style parsing, and layout calculation.
 pub mod layout;
@@ -92,27 +98,96 @@ pub mod rendering;
 /// External resource management (fonts, images)
 pub mod resources;
 
+/// Snapshot testing helpers comparing renders against golden PNG images.
+#[cfg(any(test, feature = "test-utils"))]
+pub mod testing;
+
 pub use image;
 pub use parley;
 pub use taffy;
 
-use crate::resources::{
-  font::FontContext,
-  image::{ImageResourceError, PersistentImageStore},
+use crate::{
+  rendering::{DebugOptions, Hyphenator, MissingGlyph},
+  resources::{
+    font::FontContext,
+    image::{ImageResourceError, ImageStore, ImageStoreUsage, PersistentImageStore},
+  },
 };
 
 /// The main context for image rendering.
 ///
 /// This struct holds all the necessary state for rendering images, including
 /// font management, image storage, and debug options.
-#[derive(Default)]
 pub struct GlobalContext {
-  /// Whether to draw debug borders around nodes
-  pub draw_debug_border: bool,
+  /// Debug overlays drawn over every node, nothing is drawn with `None`
+  pub debug: Option<DebugOptions>,
   /// The font context for text rendering
   pub font_context: FontContext,
-  /// The image store for persisting contents
-  pub persistent_image_store: PersistentImageStore,
+  /// The image store for persisting contents, defaults to an in-memory [`PersistentImageStore`]
+  pub persistent_image_store: Box<dyn ImageStore>,
+  /// How characters without a glyph in any loaded font are drawn
+  pub missing_glyph: MissingGlyph,
+  /// Finds where words are hyphenated with `hyphens: auto`, only soft hyphens are used without one
+  pub hyphenator: Option<Box<dyn Hyphenator>>,
+  /// The most pixels a rendered image may have, defaults to [`DEFAULT_MAX_CANVAS_PIXELS`]
+  ///
+  /// Larger renders fail with [`Error::CanvasTooLarge`] before the image is allocated, which keeps
+  /// a huge requested size from exhausting the memory. The limit also applies to images painted at
+  /// the size of a node, like its background, so a huge node on a small canvas fails the same way.
+  pub max_canvas_pixels: u64,
+}
+
+/// The default [`GlobalContext::max_canvas_pixels`], a 8192×8192 image taking 256 MiB.
+pub const DEFAULT_MAX_CANVAS_PIXELS: u64 = 8192 * 8192;
+
+impl Default for GlobalContext {
+  fn default() -> Self {
+    Self {
+      debug: None,
+      font_context: FontContext::default(),
+      persistent_image_store: Box::new(PersistentImageStore::default()),
+      missing_glyph: MissingGlyph::default(),
+      hyphenator: None,
+      max_canvas_pixels: DEFAULT_MAX_CANVAS_PIXELS,
+    }
+  }
+}
+
+/// The memory held by the caches of a [`GlobalContext`], see [`GlobalContext::memory_report`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryReport {
+  /// The number of cached glyphs
+  pub glyph_entries: usize,
+  /// The approximate bytes of the cached glyphs
+  pub glyph_bytes: usize,
+  /// The byte budget of the glyph cache
+  pub glyph_budget: usize,
+  /// The images held by the image store
+  pub image_store: ImageStoreUsage,
+}
+
+impl GlobalContext {
+  /// Reports the memory held by the glyph cache and the image store.
+  pub fn memory_report(&self) -> MemoryReport {
+    let (glyph_bytes, glyph_budget) = self.font_context.glyph_cache_stats();
+
+    MemoryReport {
+      glyph_entries: self.font_context.glyph_cache_len(),
+      glyph_bytes,
+      glyph_budget,
+      image_store: self.persistent_image_store.usage(),
+    }
+  }
+
+  /// Frees every cache, the glyphs, the font sources and the stored images.
+  ///
+  /// Loaded fonts are kept. Images inserted into the store by hand are dropped too and have to be
+  /// inserted again, fetching stores load them again when they are referenced.
+  pub fn purge_all(&self) {
+    self.font_context.purge_glyph_cache();
+    self.font_context.purge_cache();
+    self.persistent_image_store.clear();
+  }
 }
 
 /// Represents errors that can occur.
@@ -120,4 +195,79 @@ pub struct GlobalContext {
 pub enum Error {
   /// Represents an error that occurs during image resolution.
   ImageResolveError(ImageResourceError),
+  /// Represents an error that occurs while encoding the rendered image.
+  ImageEncodeError(image::ImageError),
+  /// The image passed to [`rendering::render_into`] has a different size than the canvas.
+  CanvasSizeMismatch {
+    /// The width and height of the canvas.
+    expected: (u32, u32),
+    /// The width and height of the image.
+    actual: (u32, u32),
+  },
+  /// The scale factor of the viewport, or a scale passed to [`rendering::render_scaled`], is not
+  /// a positive finite number.
+  InvalidScale(f32),
+  /// The image would have no pixels, like for a viewport with a zero width or height.
+  InvalidViewport {
+    /// The width of the image.
+    width: u32,
+    /// The height of the image.
+    height: u32,
+  },
+  /// The image, or an image painted at the size of a node, would have more pixels than
+  /// [`GlobalContext::max_canvas_pixels`].
+  CanvasTooLarge {
+    /// The width of the image.
+    width: u32,
+    /// The height of the image.
+    height: u32,
+    /// The most pixels allowed.
+    max_pixels: u64,
+  },
+  /// The tree has text to draw, but no font is loaded in the [`FontContext`].
+  FontMissing,
+  /// The layout of the tree couldn't be computed.
+  LayoutFailed(taffy::TaffyError),
+}
+
+impl std::fmt::Display for Error {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Error::ImageResolveError(error) => write!(f, "failed to resolve an image: {error:?}"),
+      Error::ImageEncodeError(error) => write!(f, "failed to encode the image: {error}"),
+      Error::CanvasSizeMismatch { expected, actual } => write!(
+        f,
+        "the image is {}x{}, but the canvas is {}x{}",
+        actual.0, actual.1, expected.0, expected.1
+      ),
+      Error::InvalidScale(scale) => {
+        write!(f, "the scale {scale} is not a positive finite number")
+      }
+      Error::InvalidViewport { width, height } => {
+        write!(
+          f,
+          "the image would be {width}x{height}, which has no pixels"
+        )
+      }
+      Error::CanvasTooLarge {
+        width,
+        height,
+        max_pixels,
+      } => write!(
+        f,
+        "the image would be {width}x{height}, more than the {max_pixels} pixels allowed"
+      ),
+      Error::FontMissing => f.write_str("there is text to draw, but no font is loaded"),
+      Error::LayoutFailed(error) => write!(f, "failed to compute the layout: {error}"),
+    }
+  }
+}
+
+impl std::error::Error for Error {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    match self {
+      Error::ImageEncodeError(error) => Some(error),
+      _ => None,
+    }
+  }
 }
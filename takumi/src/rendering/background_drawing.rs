@@ -5,16 +5,27 @@ use image::{
   imageops::{FilterType, resize},
 };
 use taffy::{Layout, Point};
+use zeno::Mask;
 
 use crate::{
-  layout::style::{
-    BackgroundImage, BackgroundImages, BackgroundPosition, BackgroundPositions, BackgroundRepeat,
-    BackgroundRepeatStyle, BackgroundRepeats, BackgroundSize, BackgroundSizes, Gradient,
-    ImageScalingAlgorithm, LengthUnit, PositionComponent, PositionKeywordX, PositionKeywordY,
+  layout::{
+    node::resolve_image,
+    style::{
+      Affine, BackgroundBlendModes, BackgroundImage, BackgroundImages, BackgroundPosition,
+      BackgroundPositions, BackgroundRepeat, BackgroundRepeatStyle, BackgroundRepeats,
+      BackgroundSize, BackgroundSizes, Color, Gradient, ImageScalingAlgorithm, LengthUnit,
+      PositionComponent, PositionKeywordX, PositionKeywordY,
+    },
   },
-  rendering::{BorderProperties, Canvas, RenderContext},
+  rendering::{
+    BorderProperties, Canvas, ClipMask, RenderContext, blend_pixel_with_mode, overlay_image,
+  },
+  resources::image::ImageSource,
 };
 
+/// A tile image of a background layer along with the X and Y positions it's drawn at.
+pub(crate) type LayerTiles = (RgbaImage, Vec<i32>, Vec<i32>);
+
 pub(crate) fn resolve_length_against_area(
   unit: LengthUnit,
   area: u32,
@@ -29,16 +40,57 @@ pub(crate) fn resolve_length_against_area(
 pub(crate) fn resolve_background_size(
   size: BackgroundSize,
   area: (u32, u32),
+  intrinsic: Option<(f32, f32)>,
   context: &RenderContext,
 ) -> (u32, u32) {
+  // gradients have no intrinsic size, so `auto`, `cover` and `contain` fill the area
+  let Some((image_w, image_h)) = intrinsic.filter(|(w, h)| *w > 0.0 && *h > 0.0) else {
+    return match size {
+      BackgroundSize::Explicit { width, height } => (
+        resolve_length_against_area(width, area.0, context),
+        resolve_length_against_area(height, area.1, context),
+      ),
+      _ => area,
+    };
+  };
+
   match size {
+    BackgroundSize::Cover | BackgroundSize::Contain => {
+      let scale_x = area.0 as f32 / image_w;
+      let scale_y = area.1 as f32 / image_h;
+      let scale = if size == BackgroundSize::Cover {
+        scale_x.max(scale_y)
+      } else {
+        scale_x.min(scale_y)
+      };
+
+      ((image_w * scale) as u32, (image_h * scale) as u32)
+    }
+    BackgroundSize::Explicit {
+      width: LengthUnit::Auto,
+      height: LengthUnit::Auto,
+    } => (image_w as u32, image_h as u32),
+    // a single `auto` keeps the aspect ratio of the image
+    BackgroundSize::Explicit {
+      width: LengthUnit::Auto,
+      height,
+    } => {
+      let height = resolve_length_against_area(height, area.1, context);
+
+      ((height as f32 * image_w / image_h) as u32, height)
+    }
+    BackgroundSize::Explicit {
+      width,
+      height: LengthUnit::Auto,
+    } => {
+      let width = resolve_length_against_area(width, area.0, context);
+
+      (width, (width as f32 * image_h / image_w) as u32)
+    }
     BackgroundSize::Explicit { width, height } => (
       resolve_length_against_area(width, area.0, context),
       resolve_length_against_area(height, area.1, context),
     ),
-    // as we only support gradients for now, we can just use the area size
-    // if we want to support images, we need to resolve based on the image size
-    _ => area,
   }
 }
 
@@ -101,6 +153,8 @@ pub(crate) fn render_gradient_tile(
     BackgroundImage::Linear(gradient) => gradient.to_image(tile_w, tile_h, context),
     BackgroundImage::Radial(gradient) => gradient.to_image(tile_w, tile_h, context),
     BackgroundImage::Noise(noise) => noise.to_image(tile_w, tile_h, context),
+    // urls that resolve are drawn by `resolve_layer_tiles`, the rest are transparent
+    BackgroundImage::Url(_) => RgbaImage::new(tile_w, tile_h),
   }
 }
 
@@ -115,8 +169,18 @@ pub(crate) fn resolve_layer_tiles(
   area_h: u32,
   context: &RenderContext,
 ) -> (RgbaImage, Vec<i32>, Vec<i32>) {
+  let source = match image {
+    BackgroundImage::Url(image) => resolve_image(&image.url, None, context.global).ok(),
+    _ => None,
+  };
+
   // Compute tile size
-  let (mut tile_w, mut tile_h) = resolve_background_size(size, (area_w, area_h), context);
+  let (tile_w, tile_h) = resolve_background_size(
+    size,
+    (area_w, area_h),
+    source.as_deref().map(ImageSource::size),
+    context,
+  );
 
   if tile_w == 0 || tile_h == 0 {
     return (
@@ -127,7 +191,27 @@ pub(crate) fn resolve_layer_tiles(
   }
 
   // Build tile image (use context-aware resolver where possible)
-  let mut tile_image = render_gradient_tile(image, tile_w, tile_h, context);
+  let tile_image = match source {
+    Some(source) => source
+      .render_to_rgba_image(tile_w, tile_h, context.style.image_rendering.into())
+      .into_owned(),
+    None => render_gradient_tile(image, tile_w, tile_h, context),
+  };
+
+  resolve_tile_positions(tile_image, pos, repeat, area_w, area_h, context)
+}
+
+/// Resolve the positions of a tile repeated across an area, along X and Y.
+/// The tile is rescaled for `round`, so the returned tile should be drawn instead.
+pub(crate) fn resolve_tile_positions(
+  mut tile_image: RgbaImage,
+  pos: BackgroundPosition,
+  repeat: BackgroundRepeat,
+  area_w: u32,
+  area_h: u32,
+  context: &RenderContext,
+) -> (RgbaImage, Vec<i32>, Vec<i32>) {
+  let (mut tile_w, mut tile_h) = tile_image.dimensions();
 
   // Handle round adjustment (rescale per axis)
   let xs: Vec<i32> = match repeat.x {
@@ -248,6 +332,22 @@ pub(crate) fn resolve_layers_tiles(
   context: &RenderContext,
   layout: Layout,
 ) -> Vec<(RgbaImage, Vec<i32>, Vec<i32>)> {
+  resolve_indexed_layers_tiles(images, positions, sizes, repeats, context, layout)
+    .into_iter()
+    .map(|(_, tiles)| tiles)
+    .collect()
+}
+
+/// Like [`resolve_layers_tiles`], keeping the index of the image each layer is resolved from
+/// as empty layers are skipped.
+pub(crate) fn resolve_indexed_layers_tiles(
+  images: &BackgroundImages,
+  positions: Option<&BackgroundPositions>,
+  sizes: Option<&BackgroundSizes>,
+  repeats: Option<&BackgroundRepeats>,
+  context: &RenderContext,
+  layout: Layout,
+) -> Vec<(usize, LayerTiles)> {
   let last_position = positions
     .and_then(|p| p.0.last().copied())
     .unwrap_or_default();
@@ -286,12 +386,77 @@ pub(crate) fn resolve_layers_tiles(
       continue;
     }
 
-    tiles.push(resolved);
+    tiles.push((i, resolved));
   }
 
   tiles
 }
 
+/// Composes resolved layer tiles into a single image of the given size.
+///
+/// Used wherever the layers fill a shape instead of the node box, like glyphs or borders.
+pub(crate) fn compose_layers_tiles(
+  tiles: Vec<(RgbaImage, Vec<i32>, Vec<i32>)>,
+  width: u32,
+  height: u32,
+) -> RgbaImage {
+  let mut composed = RgbaImage::new(width, height);
+
+  for (tile_image, xs, ys) in tiles {
+    for y in &ys {
+      for x in &xs {
+        overlay_image(
+          &mut composed,
+          &tile_image,
+          Point { x: *x, y: *y },
+          Default::default(),
+          Affine::identity(),
+          ImageScalingAlgorithm::Auto,
+        )
+      }
+    }
+  }
+
+  composed
+}
+
+/// Composes the background color and layers into a single image, blending each layer with the
+/// ones beneath it by its `background-blend-mode`.
+///
+/// The layers are clipped to the padding box like regular backgrounds, the color fills the whole
+/// image and is clipped to the border box when drawn.
+pub(crate) fn compose_blended_background_layers(
+  tiles: Vec<(usize, LayerTiles)>,
+  blend_modes: &BackgroundBlendModes,
+  background_color: Color,
+  padding_box: BorderProperties,
+  width: u32,
+  height: u32,
+) -> RgbaImage {
+  let mut composed = RgbaImage::from_pixel(width, height, background_color.into());
+
+  let mut paths = Vec::new();
+  padding_box.append_mask_commands(&mut paths);
+
+  let (mask, placement) = Mask::new(&paths).render();
+  let padding_box_mask = ClipMask { mask, placement };
+
+  let last_mode = blend_modes.0.last().copied().unwrap_or_default();
+
+  for (i, tile) in tiles {
+    let mode = blend_modes.0.get(i).copied().unwrap_or(last_mode);
+
+    let mut layer = compose_layers_tiles(vec![tile], width, height);
+    padding_box_mask.apply(&mut layer);
+
+    for (destination, source) in composed.pixels_mut().zip(layer.pixels()) {
+      blend_pixel_with_mode(destination, *source, mode);
+    }
+  }
+
+  composed
+}
+
 /// Draw layered backgrounds (gradients) with support for background-size, -position, and -repeat.
 pub(crate) fn draw_background_layers(
   tiles: Vec<(RgbaImage, Vec<i32>, Vec<i32>)>,
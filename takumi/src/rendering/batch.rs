@@ -0,0 +1,132 @@
+use std::io::Cursor;
+
+use image::RgbaImage;
+use taffy::TaffyTree;
+
+use crate::{
+  GlobalContext,
+  layout::{Viewport, node::Node},
+  rendering::{
+    EncodeOptions, ImageOutputFormat, check_node_resources, check_scale_factor,
+    compute_taffy_layout_with, create_image, paint_taffy_tree, render_with_taffy,
+    resolve_canvas_size, write_image,
+  },
+};
+
+/// Renders every node with the same viewport, the outputs are in the same order as `root_nodes`.
+///
+/// The taffy tree is cleared and reused between nodes instead of being allocated for each one,
+/// and the font and image caches of the context are warmed up by the earlier nodes.
+/// With the `rayon` feature the nodes are rendered in parallel.
+pub fn render_batch<Nodes: Node<Nodes> + Send>(
+  viewport: Viewport,
+  global: &GlobalContext,
+  root_nodes: Vec<Nodes>,
+) -> Vec<Result<RgbaImage, crate::Error>> {
+  #[cfg(feature = "rayon")]
+  {
+    use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+    root_nodes
+      .into_par_iter()
+      .map_init(TaffyTree::new, |taffy, root_node| {
+        render_with_taffy(taffy, viewport, global, root_node, create_image, None)
+      })
+      .collect()
+  }
+
+  #[cfg(not(feature = "rayon"))]
+  {
+    let mut taffy = TaffyTree::new();
+
+    root_nodes
+      .into_iter()
+      .map(|root_node| {
+        render_with_taffy(&mut taffy, viewport, global, root_node, create_image, None)
+      })
+      .collect()
+  }
+}
+
+/// Renders every node like [`render_batch`] and encodes the images with [`write_image`].
+pub fn render_batch_encoded<Nodes: Node<Nodes> + Send>(
+  viewport: Viewport,
+  global: &GlobalContext,
+  root_nodes: Vec<Nodes>,
+  format: ImageOutputFormat,
+  options: impl Into<EncodeOptions>,
+) -> Vec<Result<Vec<u8>, crate::Error>> {
+  let options = options.into();
+
+  render_batch(viewport, global, root_nodes)
+    .into_iter()
+    .map(|image| {
+      let mut buffer = Vec::new();
+
+      write_image(
+        &image?,
+        &mut Cursor::new(&mut buffer),
+        format,
+        options.clone(),
+      )?;
+
+      Ok(buffer)
+    })
+    .collect()
+}
+
+/// Renders a node once for every scale, like the 1x, 2x and 3x variants of an asset.
+///
+/// The layout is computed once in CSS pixels, then rasterized with each scale in place of the
+/// [scale factor](Viewport::scale_factor) of the viewport, so the images share the same
+/// proportions. The outputs are in the same order as `scales`, every scale must be positive and
+/// finite.
+pub fn render_scaled<Nodes: Node<Nodes>>(
+  viewport: Viewport,
+  global: &GlobalContext,
+  root_node: Nodes,
+  scales: &[f32],
+) -> Result<Vec<RgbaImage>, crate::Error> {
+  for &scale in scales {
+    check_scale_factor(scale)?;
+  }
+
+  let mut taffy = TaffyTree::new();
+  let root_node_id = compute_taffy_layout_with(
+    &mut taffy,
+    viewport,
+    global,
+    root_node,
+    viewport.available_space(),
+    None,
+  )?;
+
+  let max_scale = scales.iter().copied().fold(0.0, f32::max);
+
+  check_node_resources(&taffy, root_node_id, max_scale)?;
+
+  // every size is checked before painting any
+  let canvas_sizes = scales
+    .iter()
+    .map(|&scale| {
+      resolve_canvas_size(
+        &taffy,
+        root_node_id,
+        viewport.with_scale_factor(scale),
+        global.max_canvas_pixels,
+      )
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+
+  Ok(
+    scales
+      .iter()
+      .zip(canvas_sizes)
+      .map(|(&scale, canvas_size)| {
+        let image = RgbaImage::new(canvas_size.width, canvas_size.height);
+
+        paint_taffy_tree(&mut taffy, root_node_id, image, None, scale)
+      })
+      .collect(),
+  )
+}
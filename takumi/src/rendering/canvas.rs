@@ -11,19 +11,17 @@ use std::{
   },
 };
 
-use image::{
-  Pixel, Rgba, RgbaImage,
-  imageops::{interpolate_bilinear, interpolate_nearest},
-};
+use image::{Pixel, Rgba, RgbaImage, imageops::interpolate_nearest};
+use smallvec::SmallVec;
 use taffy::{Point, Size};
 use zeno::{Mask, Placement};
 
 use crate::{
-  layout::{
-    Viewport,
-    style::{Affine, Color, ImageScalingAlgorithm},
+  layout::style::{Affine, Color, ImageScalingAlgorithm, MixBlendMode},
+  rendering::{
+    BorderProperties, ClipMask, SizedFilter, apply_backdrop_filter, apply_filters,
+    blend_pixel_with_mode,
   },
-  rendering::BorderProperties,
 };
 
 /// A canvas handle for sending drawing commands asynchronously.
@@ -102,15 +100,55 @@ impl Canvas {
       transform,
     });
   }
+
+  /// Replaces what is painted under the border box with its filtered version.
+  pub(crate) fn backdrop_filter(
+    &self,
+    filters: SmallVec<[SizedFilter; 4]>,
+    offset: Point<i32>,
+    border: BorderProperties,
+    transform: Affine,
+  ) {
+    if filters.is_empty() {
+      return;
+    }
+
+    let _ = self.0.send(DrawCommand::BackdropFilter {
+      filters,
+      offset,
+      border,
+      transform,
+    });
+  }
+
+  /// Draws the commands on a transparent layer, then composites it filtered, clipped and masked onto the canvas.
+  pub(crate) fn draw_layer(
+    &self,
+    commands: Vec<DrawCommand>,
+    filters: SmallVec<[SizedFilter; 4]>,
+    clip: Option<ClipMask>,
+    mask: Option<Box<DrawCommand>>,
+    blend_mode: MixBlendMode,
+  ) {
+    if commands.is_empty() {
+      return;
+    }
+
+    let _ = self.0.send(DrawCommand::Layer {
+      commands,
+      filters,
+      clip,
+      mask,
+      blend_mode,
+    });
+  }
 }
 
 /// A canvas that receives draw tasks from the main rendering thread and draws them to the canvas.
 pub(crate) fn create_blocking_canvas_loop(
-  viewport: Viewport,
+  mut canvas: RgbaImage,
   receiver: Receiver<DrawCommand>,
 ) -> RgbaImage {
-  let mut canvas = RgbaImage::new(viewport.width, viewport.height);
-
   while let Ok(task) = receiver.recv() {
     #[cfg(debug_assertions)]
     println!("{task}");
@@ -163,6 +201,30 @@ pub(crate) enum DrawCommand {
     /// Transform to apply when drawing
     transform: Affine,
   },
+  /// Filter the pixels already painted under a border box.
+  BackdropFilter {
+    /// The filters to apply in order
+    filters: SmallVec<[SizedFilter; 4]>,
+    /// The position of the border box
+    offset: Point<i32>,
+    /// Border properties (including radii) clipping the filtered area
+    border: BorderProperties,
+    /// Transform to apply when drawing
+    transform: Affine,
+  },
+  /// Draw the commands on a layer of their own and composite it with filters applied.
+  Layer {
+    /// The commands painting the layer
+    commands: Vec<DrawCommand>,
+    /// The filters to apply to the layer in order
+    filters: SmallVec<[SizedFilter; 4]>,
+    /// The shape clipping the layer after the filters
+    clip: Option<ClipMask>,
+    /// The command painting the mask, its alpha is multiplied into the layer after the clip
+    mask: Option<Box<DrawCommand>>,
+    /// How the layer is blended with the canvas
+    blend_mode: MixBlendMode,
+  },
 }
 
 impl Display for DrawCommand {
@@ -206,6 +268,29 @@ impl Display for DrawCommand {
 
         write!(f, ")")
       }
+      DrawCommand::BackdropFilter {
+        ref filters,
+        border: radius,
+        transform,
+        ..
+      } => write!(
+        f,
+        "BackdropFilter(filters={filters:?}, radius={radius:?}, transform={})",
+        transform.decompose()
+      ),
+      DrawCommand::Layer {
+        ref commands,
+        ref filters,
+        ref clip,
+        ref mask,
+        blend_mode,
+      } => write!(
+        f,
+        "Layer(commands={}, filters={filters:?}, clip={:?}, mask={}, blend_mode={blend_mode:?})",
+        commands.len(),
+        clip.as_ref().map(|clip| clip.placement),
+        mask.is_some()
+      ),
     }
   }
 }
@@ -237,7 +322,59 @@ impl DrawCommand {
         color,
         ref image,
       } => draw_mask(canvas, mask, placement, color, image.as_ref()),
+      DrawCommand::BackdropFilter {
+        ref filters,
+        offset,
+        border,
+        transform,
+      } => apply_backdrop_filter(canvas, filters, offset, border, transform),
+      DrawCommand::Layer {
+        ref commands,
+        ref filters,
+        ref clip,
+        ref mask,
+        blend_mode,
+      } => {
+        let mut layer = RgbaImage::new(canvas.width(), canvas.height());
+
+        for command in commands {
+          command.draw(&mut layer);
+        }
+
+        apply_filters(&mut layer, filters);
+
+        if let Some(clip) = clip {
+          clip.apply(&mut layer);
+        }
+
+        if let Some(mask) = mask {
+          apply_layer_mask(&mut layer, mask);
+        }
+
+        if blend_mode == MixBlendMode::Normal {
+          overlay_image_rows(canvas, &layer, Point { x: 0, y: 0 });
+        } else {
+          for (destination, source) in canvas.pixels_mut().zip(layer.pixels()) {
+            blend_pixel_with_mode(destination, *source, blend_mode);
+          }
+        }
+      }
+    }
+  }
+}
+
+/// Multiplies the alpha of the layer by the alpha of the mask, pixels the mask doesn't cover are cleared.
+fn apply_layer_mask(layer: &mut RgbaImage, mask: &DrawCommand) {
+  let mut mask_layer = RgbaImage::new(layer.width(), layer.height());
+
+  mask.draw(&mut mask_layer);
+
+  for (pixel, mask_pixel) in layer.pixels_mut().zip(mask_layer.pixels()) {
+    if pixel.0[3] == 0 {
+      continue;
     }
+
+    *pixel = apply_mask_alpha_to_pixel(*pixel, mask_pixel.0[3]);
   }
 }
 
@@ -250,12 +387,103 @@ pub fn draw_pixel(canvas: &mut RgbaImage, x: u32, y: u32, color: Rgba<u8>) {
     return;
   }
 
-  // image-rs blend will skip the operation if the source color is fully transparent
   if let Some(pixel) = canvas.get_pixel_mut_checked(x, y) {
-    pixel.blend(&color);
+    blend_pixel(pixel, color);
   }
 }
 
+/// Composites `source` over `destination` (source-over).
+///
+/// Both pixels are stored with straight alpha, the math is done in premultiplied space
+/// and the result is unpremultiplied with rounding, so semi-transparent edges never
+/// pick up a darker fringe from the destination.
+pub fn blend_pixel(destination: &mut Rgba<u8>, source: Rgba<u8>) {
+  let source_alpha = source.0[3] as f32 / 255.0;
+
+  if source.0[3] == u8::MAX || destination.0[3] == 0 {
+    *destination = source;
+    return;
+  }
+
+  let destination_alpha = destination.0[3] as f32 / 255.0;
+  let destination_weight = destination_alpha * (1.0 - source_alpha);
+  let out_alpha = source_alpha + destination_weight;
+
+  if out_alpha <= f32::EPSILON {
+    *destination = Rgba([0, 0, 0, 0]);
+    return;
+  }
+
+  for i in 0..3 {
+    let premultiplied =
+      source.0[i] as f32 * source_alpha + destination.0[i] as f32 * destination_weight;
+
+    destination.0[i] = (premultiplied / out_alpha).round().clamp(0.0, 255.0) as u8;
+  }
+
+  destination.0[3] = (out_alpha * 255.0).round() as u8;
+}
+
+/// Samples an image with bilinear interpolation in premultiplied space.
+///
+/// Interpolating straight alpha pixels lets the color of fully transparent neighbors
+/// (usually black) bleed into the edges, so each sample is weighted by its alpha.
+fn interpolate_bilinear_premultiplied(image: &RgbaImage, x: f32, y: f32) -> Option<Rgba<u8>> {
+  let (width, height) = image.dimensions();
+
+  if width == 0 || height == 0 || x < 0.0 || y < 0.0 {
+    return None;
+  }
+
+  let max_x = (width - 1) as f32;
+  let max_y = (height - 1) as f32;
+
+  if x > max_x || y > max_y {
+    return None;
+  }
+
+  let x0 = x.floor();
+  let y0 = y.floor();
+  let fx = x - x0;
+  let fy = y - y0;
+
+  let x0 = x0 as u32;
+  let y0 = y0 as u32;
+  let x1 = (x0 + 1).min(width - 1);
+  let y1 = (y0 + 1).min(height - 1);
+
+  let samples = [
+    (image.get_pixel(x0, y0), (1.0 - fx) * (1.0 - fy)),
+    (image.get_pixel(x1, y0), fx * (1.0 - fy)),
+    (image.get_pixel(x0, y1), (1.0 - fx) * fy),
+    (image.get_pixel(x1, y1), fx * fy),
+  ];
+
+  let mut premultiplied = [0.0f32; 3];
+  let mut alpha = 0.0f32;
+
+  for (pixel, weight) in samples {
+    let weighted_alpha = pixel.0[3] as f32 * weight;
+
+    for (channel, value) in premultiplied.iter_mut().enumerate() {
+      *value += pixel.0[channel] as f32 * weighted_alpha;
+    }
+
+    alpha += weighted_alpha;
+  }
+
+  if alpha <= f32::EPSILON {
+    return Some(Rgba([0, 0, 0, 0]));
+  }
+
+  Some(Rgba([
+    (premultiplied[0] / alpha).round().clamp(0.0, 255.0) as u8,
+    (premultiplied[1] / alpha).round().clamp(0.0, 255.0) as u8,
+    (premultiplied[2] / alpha).round().clamp(0.0, 255.0) as u8,
+    alpha.round().clamp(0.0, 255.0) as u8,
+  ]))
+}
+
 pub(crate) fn apply_mask_alpha_to_pixel(pixel: Rgba<u8>, alpha: u8) -> Rgba<u8> {
   if alpha == u8::MAX {
     pixel
@@ -368,6 +596,96 @@ pub(crate) fn draw_mask<C: Into<Rgba<u8>>>(
   }
 }
 
+/// Copies the part of `image` covered by `placement` into an image of the placement size,
+/// so it lines up with the mask when passed as the fill of [`draw_mask`].
+///
+/// Pixels outside of `image` are left transparent.
+pub(crate) fn crop_to_placement(image: &RgbaImage, placement: Placement) -> RgbaImage {
+  let mut cropped = RgbaImage::new(placement.width, placement.height);
+
+  for y in 0..placement.height {
+    let source_y = y as i32 + placement.top;
+
+    if source_y < 0 || source_y >= image.height() as i32 {
+      continue;
+    }
+
+    for x in 0..placement.width {
+      let source_x = x as i32 + placement.left;
+
+      if source_x < 0 || source_x >= image.width() as i32 {
+        continue;
+      }
+
+      cropped.put_pixel(x, y, *image.get_pixel(source_x as u32, source_y as u32));
+    }
+  }
+
+  cropped
+}
+
+/// Composites a row of source pixels over a destination row of the same length.
+///
+/// Runs of opaque source pixels are copied at once and transparent ones are skipped,
+/// only partially transparent pixels go through [`blend_pixel`].
+pub fn blend_row(destination: &mut [u8], source: &[u8]) {
+  let mut i = 0;
+
+  while i < source.len() {
+    match source[i + 3] {
+      u8::MAX => {
+        let start = i;
+
+        while i < source.len() && source[i + 3] == u8::MAX {
+          i += 4;
+        }
+
+        destination[start..i].copy_from_slice(&source[start..i]);
+      }
+      0 => i += 4,
+      _ => {
+        blend_pixel(
+          Rgba::from_slice_mut(&mut destination[i..i + 4]),
+          *Rgba::from_slice(&source[i..i + 4]),
+        );
+
+        i += 4;
+      }
+    }
+  }
+}
+
+/// Overlays an untransformed image row by row, clipped to the canvas.
+fn overlay_image_rows(canvas: &mut RgbaImage, image: &RgbaImage, offset: Point<i32>) {
+  let canvas_width = canvas.width() as i64;
+  let image_width = image.width() as i64;
+
+  let (offset_x, offset_y) = (offset.x as i64, offset.y as i64);
+
+  let x_start = offset_x.max(0);
+  let y_start = offset_y.max(0);
+  let x_end = (offset_x + image_width).min(canvas_width);
+  let y_end = (offset_y + image.height() as i64).min(canvas.height() as i64);
+
+  if x_start >= x_end || y_start >= y_end {
+    return;
+  }
+
+  let row_len = ((x_end - x_start) * 4) as usize;
+  let canvas_raw: &mut [u8] = canvas.as_mut();
+  let image_raw = image.as_raw();
+
+  for dest_y in y_start..y_end {
+    let source_start = (((dest_y - offset_y) * image_width + x_start - offset_x) * 4) as usize;
+    let dest_start = ((dest_y * canvas_width + x_start) * 4) as usize;
+
+    blend_row(
+      &mut canvas_raw[dest_start..dest_start + row_len],
+      &image_raw[source_start..source_start + row_len],
+    );
+  }
+}
+
 pub(crate) fn overlay_image(
   canvas: &mut RgbaImage,
   image: &RgbaImage,
@@ -377,18 +695,7 @@ pub(crate) fn overlay_image(
   algorithm: ImageScalingAlgorithm,
 ) {
   if transform.is_identity() && border.is_zero() {
-    for y in 0..image.height() {
-      for x in 0..image.width() {
-        let dest_x = offset.x + x as i32;
-        let dest_y = offset.y + y as i32;
-
-        if dest_x < 0 || dest_y < 0 {
-          continue;
-        }
-
-        draw_pixel(canvas, dest_x as u32, dest_y as u32, *image.get_pixel(x, y));
-      }
-    }
+    overlay_image_rows(canvas, image, offset);
 
     return;
   }
@@ -429,7 +736,7 @@ pub(crate) fn overlay_image(
 
       let sampled_pixel = match algorithm {
         ImageScalingAlgorithm::Pixelated => interpolate_nearest(image, point.x, point.y),
-        _ => interpolate_bilinear(image, point.x, point.y),
+        _ => interpolate_bilinear_premultiplied(image, point.x, point.y),
       };
 
       if let Some(mut pixel) = sampled_pixel {
@@ -442,3 +749,88 @@ pub(crate) fn overlay_image(
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_blend_pixel_opaque_source_replaces() {
+    let mut destination = Rgba([10, 20, 30, 255]);
+    blend_pixel(&mut destination, Rgba([200, 100, 50, 255]));
+
+    assert_eq!(destination, Rgba([200, 100, 50, 255]));
+  }
+
+  #[test]
+  fn test_blend_pixel_over_transparent_keeps_source_color() {
+    let mut destination = Rgba([0, 0, 0, 0]);
+    blend_pixel(&mut destination, Rgba([255, 255, 255, 64]));
+
+    assert_eq!(destination, Rgba([255, 255, 255, 64]));
+  }
+
+  #[test]
+  fn test_blend_pixel_semi_transparent_over_semi_transparent() {
+    let mut destination = Rgba([255, 255, 255, 128]);
+    blend_pixel(&mut destination, Rgba([255, 255, 255, 128]));
+
+    // white over white never gets darker, regardless of alpha
+    assert_eq!(destination, Rgba([255, 255, 255, 192]));
+  }
+
+  #[test]
+  fn test_blend_row_matches_blend_pixel() {
+    let source = [
+      Rgba([200, 100, 50, 255]),
+      Rgba([200, 100, 50, 255]),
+      Rgba([10, 10, 10, 0]),
+      Rgba([255, 255, 255, 128]),
+      Rgba([0, 0, 255, 255]),
+    ];
+    let destination = Rgba([255, 255, 255, 128]);
+
+    let mut row = [destination.0; 5].concat();
+    blend_row(&mut row, &source.map(|pixel| pixel.0).concat());
+
+    for (index, source) in source.into_iter().enumerate() {
+      let mut expected = destination;
+      if source.0[3] != 0 {
+        blend_pixel(&mut expected, source);
+      }
+
+      assert_eq!(row[index * 4..index * 4 + 4], expected.0);
+    }
+  }
+
+  #[test]
+  fn test_overlay_image_clips_to_canvas() {
+    let mut canvas = RgbaImage::new(4, 4);
+    let image = RgbaImage::from_pixel(3, 3, Rgba([255, 0, 0, 255]));
+
+    overlay_image(
+      &mut canvas,
+      &image,
+      Point { x: -1, y: 2 },
+      BorderProperties::zero(),
+      Affine::identity(),
+      ImageScalingAlgorithm::Auto,
+    );
+
+    for (x, y, pixel) in canvas.enumerate_pixels() {
+      let covered = x < 2 && y >= 2;
+      assert_eq!(pixel.0[3] == 255, covered, "pixel at {x},{y}");
+    }
+  }
+
+  #[test]
+  fn test_bilinear_premultiplied_ignores_transparent_neighbor_color() {
+    let mut image = RgbaImage::new(2, 1);
+    image.put_pixel(0, 0, Rgba([255, 255, 255, 255]));
+    image.put_pixel(1, 0, Rgba([0, 0, 0, 0]));
+
+    let pixel = interpolate_bilinear_premultiplied(&image, 0.5, 0.0).unwrap();
+
+    assert_eq!(pixel, Rgba([255, 255, 255, 128]));
+  }
+}
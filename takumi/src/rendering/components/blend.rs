@@ -0,0 +1,235 @@
+use image::Rgba;
+
+use crate::{layout::style::MixBlendMode, rendering::blend_pixel};
+
+/// Composites `source` over `destination`, blending the colors where they overlap.
+///
+/// Follows the compositing spec on straight sRGB values, the blended color is weighted by the
+/// destination alpha so the node keeps its own color where nothing is painted beneath it.
+pub(crate) fn blend_pixel_with_mode(
+  destination: &mut Rgba<u8>,
+  source: Rgba<u8>,
+  mode: MixBlendMode,
+) {
+  if mode == MixBlendMode::Normal || destination.0[3] == 0 {
+    blend_pixel(destination, source);
+    return;
+  }
+
+  if source.0[3] == 0 {
+    return;
+  }
+
+  let source_alpha = source.0[3] as f32 / 255.0;
+  let destination_alpha = destination.0[3] as f32 / 255.0;
+
+  let source_color = to_unit(source);
+  let destination_color = to_unit(*destination);
+  let blended = blend_colors(mode, destination_color, source_color);
+
+  let out_alpha = source_alpha + destination_alpha * (1.0 - source_alpha);
+
+  for i in 0..3 {
+    let color = (1.0 - destination_alpha) * source_color[i] + destination_alpha * blended[i];
+    let premultiplied =
+      source_alpha * color + (1.0 - source_alpha) * destination_alpha * destination_color[i];
+
+    destination.0[i] = (premultiplied / out_alpha * 255.0)
+      .round()
+      .clamp(0.0, 255.0) as u8;
+  }
+
+  destination.0[3] = (out_alpha * 255.0).round() as u8;
+}
+
+fn to_unit(pixel: Rgba<u8>) -> [f32; 3] {
+  [
+    pixel.0[0] as f32 / 255.0,
+    pixel.0[1] as f32 / 255.0,
+    pixel.0[2] as f32 / 255.0,
+  ]
+}
+
+/// Blends the backdrop color with the source color, channels are in the `0.0..=1.0` range.
+fn blend_colors(mode: MixBlendMode, backdrop: [f32; 3], source: [f32; 3]) -> [f32; 3] {
+  match mode {
+    MixBlendMode::Hue => set_lum(set_sat(source, sat(backdrop)), lum(backdrop)),
+    MixBlendMode::Saturation => set_lum(set_sat(backdrop, sat(source)), lum(backdrop)),
+    MixBlendMode::Color => set_lum(source, lum(backdrop)),
+    MixBlendMode::Luminosity => set_lum(backdrop, lum(source)),
+    _ => [0, 1, 2].map(|i| blend_channel(mode, backdrop[i], source[i])),
+  }
+}
+
+fn blend_channel(mode: MixBlendMode, backdrop: f32, source: f32) -> f32 {
+  match mode {
+    MixBlendMode::Multiply => backdrop * source,
+    MixBlendMode::Screen => screen(backdrop, source),
+    MixBlendMode::Overlay => hard_light(source, backdrop),
+    MixBlendMode::Darken => backdrop.min(source),
+    MixBlendMode::Lighten => backdrop.max(source),
+    MixBlendMode::ColorDodge => {
+      if backdrop == 0.0 {
+        0.0
+      } else if source >= 1.0 {
+        1.0
+      } else {
+        (backdrop / (1.0 - source)).min(1.0)
+      }
+    }
+    MixBlendMode::ColorBurn => {
+      if backdrop >= 1.0 {
+        1.0
+      } else if source == 0.0 {
+        0.0
+      } else {
+        1.0 - ((1.0 - backdrop) / source).min(1.0)
+      }
+    }
+    MixBlendMode::HardLight => hard_light(backdrop, source),
+    MixBlendMode::SoftLight => {
+      if source <= 0.5 {
+        backdrop - (1.0 - 2.0 * source) * backdrop * (1.0 - backdrop)
+      } else {
+        let d = if backdrop <= 0.25 {
+          ((16.0 * backdrop - 12.0) * backdrop + 4.0) * backdrop
+        } else {
+          backdrop.sqrt()
+        };
+
+        backdrop + (2.0 * source - 1.0) * (d - backdrop)
+      }
+    }
+    MixBlendMode::Difference => (backdrop - source).abs(),
+    MixBlendMode::Exclusion => backdrop + source - 2.0 * backdrop * source,
+    _ => source,
+  }
+}
+
+fn screen(backdrop: f32, source: f32) -> f32 {
+  backdrop + source - backdrop * source
+}
+
+fn hard_light(backdrop: f32, source: f32) -> f32 {
+  if source <= 0.5 {
+    backdrop * 2.0 * source
+  } else {
+    screen(backdrop, 2.0 * source - 1.0)
+  }
+}
+
+fn lum(color: [f32; 3]) -> f32 {
+  0.3 * color[0] + 0.59 * color[1] + 0.11 * color[2]
+}
+
+/// Moves the color back into gamut while keeping its luminosity.
+fn clip_color(color: [f32; 3]) -> [f32; 3] {
+  let l = lum(color);
+  let min = color[0].min(color[1]).min(color[2]);
+  let max = color[0].max(color[1]).max(color[2]);
+
+  color.map(|c| {
+    let mut c = c;
+
+    if min < 0.0 {
+      c = l + (c - l) * l / (l - min);
+    }
+
+    if max > 1.0 {
+      c = l + (c - l) * (1.0 - l) / (max - l);
+    }
+
+    c
+  })
+}
+
+fn set_lum(color: [f32; 3], l: f32) -> [f32; 3] {
+  let delta = l - lum(color);
+
+  clip_color(color.map(|c| c + delta))
+}
+
+fn sat(color: [f32; 3]) -> f32 {
+  color[0].max(color[1]).max(color[2]) - color[0].min(color[1]).min(color[2])
+}
+
+fn set_sat(color: [f32; 3], s: f32) -> [f32; 3] {
+  let min = color[0].min(color[1]).min(color[2]);
+  let range = sat(color);
+
+  if range <= 0.0 {
+    return [0.0; 3];
+  }
+
+  // the min channel becomes 0, the max channel `s` and the mid channel is scaled in between
+  color.map(|c| (c - min) * s / range)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn blend(destination: [u8; 4], source: [u8; 4], mode: MixBlendMode) -> [u8; 4] {
+    let mut destination = Rgba(destination);
+
+    blend_pixel_with_mode(&mut destination, Rgba(source), mode);
+
+    destination.0
+  }
+
+  #[test]
+  fn test_blend_separable_modes() {
+    let backdrop = [200, 100, 50, 255];
+    let source = [128, 255, 0, 255];
+
+    assert_eq!(
+      blend(backdrop, source, MixBlendMode::Multiply),
+      [100, 100, 0, 255]
+    );
+    assert_eq!(
+      blend(backdrop, source, MixBlendMode::Screen),
+      [228, 255, 50, 255]
+    );
+    assert_eq!(
+      blend(backdrop, source, MixBlendMode::Darken),
+      [128, 100, 0, 255]
+    );
+    assert_eq!(
+      blend(backdrop, source, MixBlendMode::Lighten),
+      [200, 255, 50, 255]
+    );
+    assert_eq!(
+      blend(backdrop, source, MixBlendMode::Difference),
+      [72, 155, 50, 255]
+    );
+  }
+
+  #[test]
+  fn test_blend_keeps_source_over_transparent_backdrop() {
+    assert_eq!(
+      blend([0, 0, 0, 0], [10, 20, 30, 128], MixBlendMode::Multiply),
+      [10, 20, 30, 128]
+    );
+  }
+
+  #[test]
+  fn test_blend_partially_transparent_source() {
+    // half of the multiplied color mixed with the backdrop
+    assert_eq!(
+      blend([200, 200, 200, 255], [0, 0, 0, 128], MixBlendMode::Multiply),
+      [100, 100, 100, 255]
+    );
+  }
+
+  #[test]
+  fn test_blend_luminosity_keeps_backdrop_hue() {
+    let [r, g, b, a] = blend(
+      [255, 0, 0, 255],
+      [255, 255, 255, 255],
+      MixBlendMode::Luminosity,
+    );
+
+    assert_eq!(a, 255);
+    assert!(r >= g && g == b);
+  }
+}
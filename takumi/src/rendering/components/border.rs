@@ -1,23 +1,14 @@
 use std::f32::consts::SQRT_2;
 
+use image::RgbaImage;
 use taffy::{Layout, Point, Size};
 use zeno::{Command, Fill, Mask, PathBuilder};
 
 use crate::{
-  layout::style::{Affine, Color, LengthUnit, Sides},
-  rendering::{Canvas, RenderContext},
+  layout::style::{Affine, Color, Sides},
+  rendering::{Canvas, RenderContext, crop_to_placement},
 };
 
-fn resolve_border_radius_from_percentage_css(
-  context: &RenderContext,
-  radius: LengthUnit,
-  reference_size: f32,
-) -> f32 {
-  radius
-    .resolve_to_px(context, reference_size)
-    .min(reference_size / 2.0)
-}
-
 /// Represents the properties of a border, including corner radii and drawing metadata.
 #[derive(Debug, Clone, Copy, Default)]
 pub(crate) struct BorderProperties {
@@ -29,8 +20,10 @@ pub(crate) struct BorderProperties {
   pub size: Size<f32>,
   /// The color of the border
   pub color: Color,
-  /// Corner radii: top, right, bottom, left (in pixels)
-  pub radius: Sides<f32>,
+  /// Horizontal corner radii: top-left, top-right, bottom-right, bottom-left (in pixels)
+  pub radius_x: Sides<f32>,
+  /// Vertical corner radii, in the same order as `radius_x` (in pixels)
+  pub radius_y: Sides<f32>,
   /// The transform applied when drawing this border
   pub transform: Affine,
 }
@@ -43,7 +36,8 @@ impl BorderProperties {
       offset: Point::ZERO,
       size: Size::ZERO,
       color: Color([0, 0, 0, 255]),
-      radius: Sides([0.0; 4]),
+      radius_x: Sides([0.0; 4]),
+      radius_y: Sides([0.0; 4]),
       transform: Affine::identity(),
     }
   }
@@ -51,22 +45,41 @@ impl BorderProperties {
   /// Resolves the border radius from the context and layout.
   pub fn from_context(context: &RenderContext, layout: &Layout) -> Self {
     let resolved = context.style.resolved_border_radius();
-    let reference_size = layout.size.width.min(layout.size.height);
-
-    let top_left = resolve_border_radius_from_percentage_css(context, resolved.top, reference_size);
-    let top_right =
-      resolve_border_radius_from_percentage_css(context, resolved.right, reference_size);
-    let bottom_right =
-      resolve_border_radius_from_percentage_css(context, resolved.bottom, reference_size);
-    let bottom_left =
-      resolve_border_radius_from_percentage_css(context, resolved.left, reference_size);
+    let size = layout.size;
+
+    let mut radius_x = resolved
+      .horizontal
+      .0
+      .map(|radius| radius.resolve_to_px(context, size.width).max(0.0));
+    let mut radius_y = resolved
+      .vertical
+      .0
+      .map(|radius| radius.resolve_to_px(context, size.height).max(0.0));
+
+    // like CSS, all radii shrink by the same factor until adjacent corners stop overlapping
+    let scale = [
+      size.width / (radius_x[0] + radius_x[1]),
+      size.height / (radius_y[1] + radius_y[2]),
+      size.width / (radius_x[3] + radius_x[2]),
+      size.height / (radius_y[0] + radius_y[3]),
+    ]
+    .into_iter()
+    .filter(|scale| scale.is_finite())
+    .fold(1.0, f32::min);
+
+    if scale < 1.0 {
+      for radius in radius_x.iter_mut().chain(radius_y.iter_mut()) {
+        *radius *= scale;
+      }
+    }
 
     Self {
       width: layout.border,
       offset: Point::ZERO,
       size: layout.size,
-      color: context.style.border_color,
-      radius: Sides([top_left, top_right, bottom_right, bottom_left]),
+      color: context.style.border_color.resolve(context.style.color),
+      radius_x: Sides(radius_x),
+      radius_y: Sides(radius_y),
       transform: context.transform,
     }
   }
@@ -74,10 +87,12 @@ impl BorderProperties {
   /// Returns true if all corner radii are zero.
   #[inline]
   pub fn is_zero(&self) -> bool {
-    self.radius.0[0] == 0.0
-      && self.radius.0[1] == 0.0
-      && self.radius.0[2] == 0.0
-      && self.radius.0[3] == 0.0
+    self
+      .radius_x
+      .0
+      .iter()
+      .chain(self.radius_y.0.iter())
+      .all(|radius| *radius == 0.0)
   }
 
   /// Expand/shrink all corner radii and adjust radius bounds/offset.
@@ -93,12 +108,8 @@ impl BorderProperties {
         height: (self.size.height + amount * 2.0).max(0.0),
       },
       color: self.color,
-      radius: Sides([
-        (self.radius.0[0] + amount).max(0.0),
-        (self.radius.0[1] + amount).max(0.0),
-        (self.radius.0[2] + amount).max(0.0),
-        (self.radius.0[3] + amount).max(0.0),
-      ]),
+      radius_x: Sides(self.radius_x.0.map(|radius| (radius + amount).max(0.0))),
+      radius_y: Sides(self.radius_y.0.map(|radius| (radius + amount).max(0.0))),
       transform: self.transform,
     }
   }
@@ -110,26 +121,30 @@ impl BorderProperties {
   }
 
   /// Append rounded-rect path commands for this border's corner radii.
+  ///
+  /// Each corner is a quarter ellipse with its horizontal and vertical radius.
   pub fn append_mask_commands(&self, path: &mut Vec<Command>) {
     const KAPPA: f32 = 4.0 / 3.0 * (SQRT_2 - 1.0);
 
-    let top_edge_width = (self.size.width - self.radius.0[0] - self.radius.0[1]).max(0.0);
-    let right_edge_height = (self.size.height - self.radius.0[1] - self.radius.0[2]).max(0.0);
-    let bottom_edge_width = (self.size.width - self.radius.0[3] - self.radius.0[2]).max(0.0);
-    let left_edge_height = (self.size.height - self.radius.0[3] - self.radius.0[0]).max(0.0);
+    let [top_left_x, top_right_x, bottom_right_x, bottom_left_x] = self.radius_x.0;
+    let [top_left_y, top_right_y, bottom_right_y, bottom_left_y] = self.radius_y.0;
 
-    path.move_to((self.offset.x + self.radius.0[0], self.offset.y));
+    let top_edge_width = (self.size.width - top_left_x - top_right_x).max(0.0);
+    let right_edge_height = (self.size.height - top_right_y - bottom_right_y).max(0.0);
+    let bottom_edge_width = (self.size.width - bottom_left_x - bottom_right_x).max(0.0);
+    let left_edge_height = (self.size.height - bottom_left_y - top_left_y).max(0.0);
+
+    path.move_to((self.offset.x + top_left_x, self.offset.y));
 
     if top_edge_width > 0.0 {
       path.rel_line_to((top_edge_width, 0.0));
     }
 
-    if self.radius.0[1] > 0.0 {
-      let control_offset = self.radius.0[1] * KAPPA;
+    if top_right_x > 0.0 || top_right_y > 0.0 {
       path.rel_curve_to(
-        (control_offset, 0.0),
-        (self.radius.0[1], self.radius.0[1] - control_offset),
-        (self.radius.0[1], self.radius.0[1]),
+        (top_right_x * KAPPA, 0.0),
+        (top_right_x, top_right_y - top_right_y * KAPPA),
+        (top_right_x, top_right_y),
       );
     }
 
@@ -137,12 +152,11 @@ impl BorderProperties {
       path.rel_line_to((0.0, right_edge_height));
     }
 
-    if self.radius.0[2] > 0.0 {
-      let control_offset = self.radius.0[2] * KAPPA;
+    if bottom_right_x > 0.0 || bottom_right_y > 0.0 {
       path.rel_curve_to(
-        (0.0, control_offset),
-        (-self.radius.0[2] + control_offset, self.radius.0[2]),
-        (-self.radius.0[2], self.radius.0[2]),
+        (0.0, bottom_right_y * KAPPA),
+        (-bottom_right_x + bottom_right_x * KAPPA, bottom_right_y),
+        (-bottom_right_x, bottom_right_y),
       );
     }
 
@@ -150,12 +164,11 @@ impl BorderProperties {
       path.rel_line_to((-bottom_edge_width, 0.0));
     }
 
-    if self.radius.0[3] > 0.0 {
-      let control_offset = self.radius.0[3] * KAPPA;
+    if bottom_left_x > 0.0 || bottom_left_y > 0.0 {
       path.rel_curve_to(
-        (-control_offset, 0.0),
-        (-self.radius.0[3], -self.radius.0[3] + control_offset),
-        (-self.radius.0[3], -self.radius.0[3]),
+        (-bottom_left_x * KAPPA, 0.0),
+        (-bottom_left_x, -bottom_left_y + bottom_left_y * KAPPA),
+        (-bottom_left_x, -bottom_left_y),
       );
     }
 
@@ -163,12 +176,11 @@ impl BorderProperties {
       path.rel_line_to((0.0, -left_edge_height));
     }
 
-    if self.radius.0[0] > 0.0 {
-      let control_offset = self.radius.0[0] * KAPPA;
+    if top_left_x > 0.0 || top_left_y > 0.0 {
       path.rel_curve_to(
-        (0.0, -control_offset),
-        (self.radius.0[0] - control_offset, -self.radius.0[0]),
-        (self.radius.0[0], -self.radius.0[0]),
+        (0.0, -top_left_y * KAPPA),
+        (top_left_x - top_left_x * KAPPA, -top_left_y),
+        (top_left_x, -top_left_y),
       );
     }
 
@@ -182,7 +194,15 @@ impl BorderProperties {
 ///
 /// This function draws borders with specified size and color. If border_radius is specified,
 /// it creates a rounded border using a custom drawing approach.
-pub(crate) fn draw_border(canvas: &Canvas, canvas_offset: Point<f32>, border: BorderProperties) {
+///
+/// When `fill` is set, it replaces the border color. The image covers the border box, so a
+/// gradient runs across the whole box and each side shows its own part of it.
+pub(crate) fn draw_border(
+  canvas: &Canvas,
+  canvas_offset: Point<f32>,
+  border: BorderProperties,
+  fill: Option<&RgbaImage>,
+) {
   if border.width.left == 0.0
     && border.width.right == 0.0
     && border.width.top == 0.0
@@ -203,8 +223,11 @@ pub(crate) fn draw_border(canvas: &Canvas, canvas_offset: Point<f32>, border: Bo
 
   let (mask, mut placement) = Mask::new(&paths).style(Fill::EvenOdd).render();
 
-  placement.left += border.offset.x as i32 + canvas_offset.x as i32;
-  placement.top += border.offset.y as i32 + canvas_offset.y as i32;
+  // the paths already include the border offset, so the placement is in the node's local space
+  let fill = fill.map(|image| crop_to_placement(image, placement));
+
+  placement.left += canvas_offset.x as i32;
+  placement.top += canvas_offset.y as i32;
 
-  canvas.draw_mask(mask, placement, border.color, None);
+  canvas.draw_mask(mask, placement, border.color, fill);
 }
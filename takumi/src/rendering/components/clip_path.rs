@@ -0,0 +1,179 @@
+use std::f32::consts::SQRT_2;
+
+use image::RgbaImage;
+use taffy::{Layout, Point, Size};
+use zeno::{Command, Fill, Mask, PathBuilder, Placement};
+
+use crate::{
+  layout::style::{Affine, BasicShape, ClipPath, FillRule, ShapeRadius, Sides},
+  rendering::{
+    BorderProperties, RenderContext, apply_mask_alpha_to_pixel, resolve_position_component_x,
+    resolve_position_component_y,
+  },
+};
+
+/// The coverage of a `clip-path` shape in canvas space.
+pub(crate) struct ClipMask {
+  /// The coverage values (0-255) of the pixels in the placement
+  pub mask: Vec<u8>,
+  /// The placement of the mask on the canvas
+  pub placement: Placement,
+}
+
+impl ClipMask {
+  /// Rasterizes the shape against the border box of the node, the edges are anti-aliased.
+  pub fn new(
+    clip_path: &ClipPath,
+    context: &RenderContext,
+    layout: Layout,
+    transform: Affine,
+  ) -> Self {
+    let size = layout.size;
+    let mut paths = Vec::new();
+
+    let fill = match &clip_path.0 {
+      BasicShape::Inset { offsets, radius } => {
+        let top = offsets.0[0].resolve_to_px(context, size.height);
+        let right = offsets.0[1].resolve_to_px(context, size.width);
+        let bottom = offsets.0[2].resolve_to_px(context, size.height);
+        let left = offsets.0[3].resolve_to_px(context, size.width);
+
+        let inset_size = Size {
+          width: (size.width - left - right).max(0.0),
+          height: (size.height - top - bottom).max(0.0),
+        };
+        let reference_size = inset_size.width.min(inset_size.height);
+        let radius = Sides(radius.0.map(|radius| {
+          radius
+            .resolve_to_px(context, reference_size)
+            .min(reference_size / 2.0)
+        }));
+
+        BorderProperties {
+          offset: Point { x: left, y: top },
+          size: inset_size,
+          radius_x: radius,
+          radius_y: radius,
+          ..BorderProperties::zero()
+        }
+        .append_mask_commands(&mut paths);
+
+        Fill::NonZero
+      }
+      BasicShape::Circle { radius, position } => {
+        let cx = resolve_position_component_x(*position, 0, size.width as u32, context) as f32;
+        let cy = resolve_position_component_y(*position, 0, size.height as u32, context) as f32;
+
+        let sides = [cx, size.width - cx, cy, size.height - cy];
+        let radius = match radius {
+          ShapeRadius::Length(length) => length.resolve_to_px(
+            context,
+            (size.width * size.width + size.height * size.height).sqrt() / SQRT_2,
+          ),
+          ShapeRadius::ClosestSide => sides.into_iter().fold(f32::INFINITY, f32::min),
+          ShapeRadius::FarthestSide => sides.into_iter().fold(0.0, f32::max),
+        }
+        .max(0.0);
+
+        // a rounded rect with every corner at the full radius is the circle
+        BorderProperties {
+          offset: Point {
+            x: cx - radius,
+            y: cy - radius,
+          },
+          size: Size {
+            width: radius * 2.0,
+            height: radius * 2.0,
+          },
+          radius_x: Sides([radius; 4]),
+          radius_y: Sides([radius; 4]),
+          ..BorderProperties::zero()
+        }
+        .append_mask_commands(&mut paths);
+
+        Fill::NonZero
+      }
+      BasicShape::Polygon { fill_rule, points } => {
+        let mut points = points.iter().map(|(x, y)| {
+          (
+            x.resolve_to_px(context, size.width),
+            y.resolve_to_px(context, size.height),
+          )
+        });
+
+        if let Some(first) = points.next() {
+          paths.move_to(first);
+
+          for point in points {
+            paths.line_to(point);
+          }
+
+          paths.close();
+        }
+
+        match fill_rule {
+          FillRule::Nonzero => Fill::NonZero,
+          FillRule::Evenodd => Fill::EvenOdd,
+        }
+      }
+    };
+
+    Self::from_paths(paths, fill, layout, transform)
+  }
+
+  /// Rasterizes the padding box of the node, the area `overflow: hidden` clips its descendants
+  /// to.
+  pub fn padding_box(context: &RenderContext, layout: Layout, transform: Affine) -> Self {
+    let mut paths = Vec::new();
+
+    context
+      .border_properties(&layout)
+      .inset_by_border_width()
+      .append_mask_commands(&mut paths);
+
+    Self::from_paths(paths, Fill::NonZero, layout, transform)
+  }
+
+  /// Rasterizes the paths drawn relative to the border box of the node.
+  fn from_paths(mut paths: Vec<Command>, fill: Fill, layout: Layout, transform: Affine) -> Self {
+    transform.apply_on_paths(&mut paths);
+
+    let (mask, mut placement) = render_paths(&paths, fill);
+
+    placement.left += layout.location.x as i32;
+    placement.top += layout.location.y as i32;
+
+    Self { mask, placement }
+  }
+
+  /// Multiplies the alpha of the layer by the coverage, pixels outside the mask are cleared.
+  pub fn apply(&self, layer: &mut RgbaImage) {
+    for (x, y, pixel) in layer.enumerate_pixels_mut() {
+      if pixel.0[3] == 0 {
+        continue;
+      }
+
+      *pixel = apply_mask_alpha_to_pixel(*pixel, self.coverage(x as i32, y as i32));
+    }
+  }
+
+  fn coverage(&self, x: i32, y: i32) -> u8 {
+    let x = x - self.placement.left;
+    let y = y - self.placement.top;
+
+    if x < 0 || y < 0 || x >= self.placement.width as i32 || y >= self.placement.height as i32 {
+      return 0;
+    }
+
+    self.mask[y as usize * self.placement.width as usize + x as usize]
+  }
+}
+
+/// Renders the paths into a mask, empty paths clip everything away.
+fn render_paths(paths: &[Command], fill: Fill) -> (Vec<u8>, Placement) {
+  if paths.is_empty() {
+    return (Vec::new(), Placement::default());
+  }
+
+  Mask::new(paths).style(fill).render()
+}
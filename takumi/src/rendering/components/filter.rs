@@ -0,0 +1,313 @@
+use image::{Rgba, RgbaImage, imageops::fast_blur};
+use smallvec::SmallVec;
+use taffy::{Point, Size};
+use zeno::Mask;
+
+use crate::{
+  layout::style::{Affine, Filter, Filters},
+  rendering::{BorderProperties, RenderContext, SizedShadow, blend_row},
+};
+
+/// A [`Filter`] with its lengths resolved to pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum SizedFilter {
+  /// Gaussian blur with the standard deviation in pixels.
+  Blur(f32),
+  /// Multiplies the color channels.
+  Brightness(f32),
+  /// Scales the distance of the color channels from mid gray.
+  Contrast(f32),
+  /// Scales the saturation.
+  Saturate(f32),
+  /// Paints a tinted and blurred copy of the alpha channel below the image.
+  DropShadow(SizedShadow),
+}
+
+impl SizedFilter {
+  /// Resolves the filters of a node, percentages resolve against the node width.
+  ///
+  /// Lengths are in image pixels, so they follow the scale factor of the viewport.
+  pub(crate) fn from_filters(
+    filters: &Filters,
+    context: &RenderContext,
+    size: Size<f32>,
+  ) -> SmallVec<[SizedFilter; 4]> {
+    filters
+      .0
+      .iter()
+      .map(|filter| match *filter {
+        Filter::Blur(radius) => SizedFilter::Blur(
+          (radius.resolve_to_px(context, size.width) * context.viewport.scale_factor).max(0.0),
+        ),
+        Filter::Brightness(amount) => SizedFilter::Brightness(amount),
+        Filter::Contrast(amount) => SizedFilter::Contrast(amount),
+        Filter::Saturate(amount) => SizedFilter::Saturate(amount),
+        Filter::DropShadow(shadow) => SizedFilter::DropShadow(
+          SizedShadow::from_text_shadow(shadow, context, size)
+            .scaled(context.viewport.scale_factor),
+        ),
+      })
+      .collect()
+  }
+
+  /// Returns how far in pixels the filter reads around a pixel.
+  pub(crate) fn sample_extent(self) -> u32 {
+    match self {
+      // a gaussian is negligible past three standard deviations
+      SizedFilter::Blur(sigma) => (sigma * 3.0).ceil() as u32,
+      SizedFilter::DropShadow(shadow) => {
+        (shadow.offset_x.abs().max(shadow.offset_y.abs()) + shadow.blur_radius).ceil() as u32
+      }
+      _ => 0,
+    }
+  }
+
+  fn apply(self, image: &mut RgbaImage) {
+    match self {
+      SizedFilter::Blur(sigma) => {
+        if sigma > 0.0 {
+          *image = fast_blur(image, sigma);
+        }
+      }
+      SizedFilter::Brightness(amount) => map_channels(image, |value| value * amount),
+      SizedFilter::Contrast(amount) => map_channels(image, |value| (value - 0.5) * amount + 0.5),
+      SizedFilter::Saturate(amount) => {
+        for pixel in image.pixels_mut() {
+          let [r, g, b, _] = pixel.0.map(|value| value as f32 / 255.0);
+
+          // luminance-preserving saturation matrix from the filter effects spec
+          let saturated = [
+            (0.213 + 0.787 * amount) * r
+              + (0.715 - 0.715 * amount) * g
+              + (0.072 - 0.072 * amount) * b,
+            (0.213 - 0.213 * amount) * r
+              + (0.715 + 0.285 * amount) * g
+              + (0.072 - 0.072 * amount) * b,
+            (0.213 - 0.213 * amount) * r
+              + (0.715 - 0.715 * amount) * g
+              + (0.072 + 0.928 * amount) * b,
+          ];
+
+          for (channel, value) in pixel.0.iter_mut().zip(saturated) {
+            *channel = to_channel(value);
+          }
+        }
+      }
+      SizedFilter::DropShadow(shadow) => apply_drop_shadow(image, shadow),
+    }
+  }
+}
+
+/// Paints the image over a copy of its alpha channel, tinted, blurred and offset by the shadow.
+fn apply_drop_shadow(image: &mut RgbaImage, shadow: SizedShadow) {
+  let [r, g, b, a] = shadow.color.0;
+
+  // the transparent pixels share the shadow color, so the blur doesn't darken the edges
+  let mut shadow_image = RgbaImage::from_pixel(image.width(), image.height(), Rgba([r, g, b, 0]));
+
+  let offset_x = shadow.offset_x.round() as i64;
+  let offset_y = shadow.offset_y.round() as i64;
+
+  for (x, y, pixel) in image.enumerate_pixels() {
+    if pixel.0[3] == 0 {
+      continue;
+    }
+
+    let (shadow_x, shadow_y) = (x as i64 + offset_x, y as i64 + offset_y);
+
+    if shadow_x < 0
+      || shadow_y < 0
+      || shadow_x >= image.width() as i64
+      || shadow_y >= image.height() as i64
+    {
+      continue;
+    }
+
+    shadow_image.put_pixel(
+      shadow_x as u32,
+      shadow_y as u32,
+      Rgba([r, g, b, (pixel.0[3] as u16 * a as u16 / 255) as u8]),
+    );
+  }
+
+  // same conversion as the box and text shadows, the blur radius is about three sigmas
+  if shadow.blur_radius > 0.0 {
+    shadow_image = fast_blur(&shadow_image, shadow.blur_radius / 3.0);
+  }
+
+  blend_row(shadow_image.as_mut(), image.as_raw());
+
+  *image = shadow_image;
+}
+
+/// Applies the filters in order to a layer painted on its own.
+pub(crate) fn apply_filters(image: &mut RgbaImage, filters: &[SizedFilter]) {
+  for filter in filters {
+    filter.apply(image);
+  }
+}
+
+fn to_channel(value: f32) -> u8 {
+  (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+fn map_channels(image: &mut RgbaImage, map: impl Fn(f32) -> f32) {
+  for pixel in image.pixels_mut() {
+    for channel in pixel.0.iter_mut().take(3) {
+      *channel = to_channel(map(*channel as f32 / 255.0));
+    }
+  }
+}
+
+/// Replaces the painted pixels under the border box with their filtered version.
+///
+/// The pixels around the box that the filters read from (like the blur extent) are sampled
+/// too, so the result blends into the backdrop instead of fading at the edges.
+pub(crate) fn apply_backdrop_filter(
+  canvas: &mut RgbaImage,
+  filters: &[SizedFilter],
+  offset: Point<i32>,
+  border: BorderProperties,
+  transform: Affine,
+) {
+  let mut paths = Vec::new();
+
+  border.append_mask_commands(&mut paths);
+  transform.apply_on_paths(&mut paths);
+
+  let (mask, mut placement) = Mask::new(&paths).render();
+
+  placement.left += offset.x;
+  placement.top += offset.y;
+
+  let extent = filters
+    .iter()
+    .map(|filter| filter.sample_extent() as i32)
+    .sum::<i32>();
+
+  let left = (placement.left - extent).clamp(0, canvas.width() as i32);
+  let top = (placement.top - extent).clamp(0, canvas.height() as i32);
+  let right = (placement.left + placement.width as i32 + extent).clamp(0, canvas.width() as i32);
+  let bottom = (placement.top + placement.height as i32 + extent).clamp(0, canvas.height() as i32);
+
+  if left >= right || top >= bottom {
+    return;
+  }
+
+  let mut backdrop = image::imageops::crop_imm(
+    canvas,
+    left as u32,
+    top as u32,
+    (right - left) as u32,
+    (bottom - top) as u32,
+  )
+  .to_image();
+
+  apply_filters(&mut backdrop, filters);
+
+  for (index, &alpha) in mask.iter().enumerate() {
+    if alpha == 0 {
+      continue;
+    }
+
+    let x = placement.left + (index as u32 % placement.width) as i32;
+    let y = placement.top + (index as u32 / placement.width) as i32;
+
+    if x < left || y < top || x >= right || y >= bottom {
+      continue;
+    }
+
+    let filtered = backdrop.get_pixel((x - left) as u32, (y - top) as u32);
+    let pixel = canvas.get_pixel_mut(x as u32, y as u32);
+
+    if alpha == u8::MAX {
+      *pixel = *filtered;
+      continue;
+    }
+
+    let alpha = alpha as f32 / 255.0;
+
+    for (channel, filtered) in pixel.0.iter_mut().zip(filtered.0) {
+      *channel = (*channel as f32 * (1.0 - alpha) + filtered as f32 * alpha).round() as u8;
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::layout::style::Color;
+
+  use super::*;
+
+  #[test]
+  fn test_backdrop_blur_stays_inside_box() {
+    let mut canvas = RgbaImage::from_fn(40, 40, |x, _| {
+      if x % 2 == 0 {
+        Rgba([0, 0, 0, 255])
+      } else {
+        Rgba([255, 255, 255, 255])
+      }
+    });
+    let original = canvas.clone();
+
+    let border = BorderProperties {
+      size: Size {
+        width: 20.0,
+        height: 20.0,
+      },
+      ..Default::default()
+    };
+
+    apply_backdrop_filter(
+      &mut canvas,
+      &[SizedFilter::Blur(4.0)],
+      Point { x: 10, y: 10 },
+      border,
+      Affine::identity(),
+    );
+
+    // outside the box nothing changes
+    assert_eq!(canvas.get_pixel(5, 20), original.get_pixel(5, 20));
+    assert_eq!(canvas.get_pixel(20, 35), original.get_pixel(20, 35));
+
+    // inside the stripes are blurred to gray
+    let [r, g, b, a] = canvas.get_pixel(20, 20).0;
+    assert!((100..=155).contains(&r), "r = {r}");
+    assert_eq!((r, a), (g, 255));
+    assert_eq!(g, b);
+  }
+
+  #[test]
+  fn test_brightness_and_saturate() {
+    let mut image = RgbaImage::from_pixel(1, 1, Rgba([100, 50, 200, 255]));
+
+    SizedFilter::Brightness(2.0).apply(&mut image);
+    assert_eq!(image.get_pixel(0, 0).0, [200, 100, 255, 255]);
+
+    SizedFilter::Saturate(0.0).apply(&mut image);
+    let [r, g, b, _] = image.get_pixel(0, 0).0;
+    assert!(r == g && g == b);
+  }
+
+  #[test]
+  fn test_drop_shadow_follows_alpha() {
+    // a single opaque pixel in the corner, like the visible part of a transparent logo
+    let mut image = RgbaImage::new(8, 8);
+    image.put_pixel(1, 1, Rgba([255, 0, 0, 255]));
+
+    SizedFilter::DropShadow(SizedShadow {
+      offset_x: 3.0,
+      offset_y: 2.0,
+      blur_radius: 0.0,
+      spread_radius: 0.0,
+      color: Color([0, 0, 255, 128]),
+    })
+    .apply(&mut image);
+
+    // the content stays on top and the shadow only appears where the content casts it
+    assert_eq!(image.get_pixel(1, 1).0, [255, 0, 0, 255]);
+    assert_eq!(image.get_pixel(4, 3).0, [0, 0, 255, 128]);
+    assert_eq!(image.get_pixel(4, 1).0[3], 0);
+    assert_eq!(image.get_pixel(1, 3).0[3], 0);
+  }
+}
@@ -1,5 +1,11 @@
+mod blend;
 mod border;
+mod clip_path;
+mod filter;
 mod shadow;
 
+pub(crate) use blend::*;
 pub(crate) use border::*;
+pub(crate) use clip_path::*;
+pub(crate) use filter::*;
 pub(crate) use shadow::*;
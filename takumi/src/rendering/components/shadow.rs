@@ -23,7 +23,7 @@ fn apply_fast_blur(image: &mut RgbaImage, radius: f32) {
 }
 
 /// Represents a resolved box shadow with all its properties.
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub(crate) struct SizedShadow {
   /// Horizontal offset of the shadow.
   pub offset_x: f32,
@@ -64,6 +64,17 @@ impl SizedShadow {
     }
   }
 
+  /// Scales the lengths of the shadow, for shadows drawn in image pixels after the transform.
+  pub fn scaled(self, scale: f32) -> Self {
+    Self {
+      offset_x: self.offset_x * scale,
+      offset_y: self.offset_y * scale,
+      blur_radius: self.blur_radius * scale,
+      spread_radius: self.spread_radius * scale,
+      color: self.color,
+    }
+  }
+
   pub fn draw_outset(
     &self,
     canvas: &Canvas,
@@ -1,42 +1,377 @@
-use taffy::{Layout, Point};
+use std::sync::mpsc::channel;
+
+use image::RgbaImage;
+use taffy::{Layout, NodeId, Point, Rect, Size, TaffyTree};
+use zeno::{Fill, Mask};
 
 use crate::{
-  layout::style::{Affine, Color, Sides},
-  rendering::{BorderProperties, Canvas, draw_border},
+  GlobalContext,
+  layout::{
+    Viewport,
+    node::Node,
+    style::{Affine, Color, InheritedStyle, LengthUnit},
+  },
+  rendering::{
+    BorderProperties, Canvas, NodeContext, RenderContext, apply_device_scale, check_node_resources,
+    check_scale_factor, compute_taffy_layout, create_blocking_canvas_loop, draw_text,
+    paint_taffy_tree, resolve_canvas_size, resolve_node_layout, resolve_node_transform,
+  },
 };
 
+/// The font size of the labels drawn by [`render_with_debug_overlay`].
+const LABEL_FONT_SIZE: f32 = 10.0;
+
+/// Options for the debug overlays drawn over every node.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DebugOptions {
+  /// The width of the debug strokes in pixels.
+  pub stroke_width: f32,
+  /// The stroke color around the content box.
+  pub content_box_color: Color,
+  /// The stroke color around the border box.
+  pub border_box_color: Color,
+  /// Fills the content, padding and margin areas with their overlay colors, like browser devtools.
+  pub spacing_overlay: bool,
+  /// The fill color of the content area when `spacing_overlay` is enabled.
+  pub content_overlay_color: Color,
+  /// The fill color of the padding area when `spacing_overlay` is enabled.
+  pub padding_overlay_color: Color,
+  /// The fill color of the margin area when `spacing_overlay` is enabled.
+  pub margin_overlay_color: Color,
+}
+
+impl Default for DebugOptions {
+  fn default() -> Self {
+    Self {
+      stroke_width: 1.0,
+      content_box_color: Color([255, 0, 0, 255]),
+      border_box_color: Color([0, 255, 0, 255]),
+      spacing_overlay: false,
+      content_overlay_color: Color([0, 120, 255, 80]),
+      padding_overlay_color: Color([0, 200, 80, 80]),
+      margin_overlay_color: Color([255, 150, 0, 80]),
+    }
+  }
+}
+
 /// Draws debug borders around the node's layout areas.
 ///
-/// This function draws colored rectangles to visualize the content box
-/// (red) and the full layout box (green) for debugging purposes.
-pub fn draw_debug_border(canvas: &Canvas, layout: Layout, transform: Affine) {
-  let x = layout.content_box_x();
-  let y = layout.content_box_y();
-  let size = layout.content_box_size();
-
-  draw_border(
-    canvas,
-    Point { x, y },
-    BorderProperties {
-      width: Sides([1.0; 4]).into(),
-      offset: Point::ZERO,
-      size,
-      color: Color([255, 0, 0, 255]),
-      radius: Sides([0.0; 4]),
+/// The content box and the border box are stroked, and with `spacing_overlay` the content,
+/// padding and margin areas are filled below the strokes.
+pub fn draw_debug_border(
+  canvas: &Canvas,
+  layout: Layout,
+  transform: Affine,
+  options: &DebugOptions,
+) {
+  let content_offset = Point {
+    x: layout.border.left + layout.padding.left,
+    y: layout.border.top + layout.padding.top,
+  };
+  let content_size = layout.content_box_size();
+  let stroke = Rect {
+    left: options.stroke_width,
+    right: options.stroke_width,
+    top: options.stroke_width,
+    bottom: options.stroke_width,
+  };
+
+  if options.spacing_overlay {
+    let margin = layout.margin;
+
+    draw_area(
+      canvas,
+      layout.location,
+      Point {
+        x: -margin.left,
+        y: -margin.top,
+      },
+      Size {
+        width: layout.size.width + margin.left + margin.right,
+        height: layout.size.height + margin.top + margin.bottom,
+      },
+      Some(margin),
+      options.margin_overlay_color,
       transform,
+    );
+
+    draw_area(
+      canvas,
+      layout.location,
+      Point {
+        x: layout.border.left,
+        y: layout.border.top,
+      },
+      Size {
+        width: layout.size.width - layout.border.left - layout.border.right,
+        height: layout.size.height - layout.border.top - layout.border.bottom,
+      },
+      Some(layout.padding),
+      options.padding_overlay_color,
+      transform,
+    );
+
+    draw_area(
+      canvas,
+      layout.location,
+      content_offset,
+      content_size,
+      None,
+      options.content_overlay_color,
+      transform,
+    );
+  }
+
+  draw_area(
+    canvas,
+    layout.location,
+    content_offset,
+    content_size,
+    Some(stroke),
+    options.content_box_color,
+    transform,
+  );
+
+  draw_area(
+    canvas,
+    layout.location,
+    Point::ZERO,
+    layout.size,
+    Some(stroke),
+    options.border_box_color,
+    transform,
+  );
+}
+
+/// Fills the rectangle at `offset` from the node location, leaving out the part inside `inset`.
+fn draw_area(
+  canvas: &Canvas,
+  location: Point<f32>,
+  offset: Point<f32>,
+  size: Size<f32>,
+  inset: Option<Rect<f32>>,
+  color: Color,
+  transform: Affine,
+) {
+  if size.width <= 0.0 || size.height <= 0.0 || color.0[3] == 0 {
+    return;
+  }
+
+  let mut paths = Vec::new();
+
+  rectangle(offset, size).append_mask_commands(&mut paths);
+
+  if let Some(inset) = inset {
+    if inset.left <= 0.0 && inset.right <= 0.0 && inset.top <= 0.0 && inset.bottom <= 0.0 {
+      return;
+    }
+
+    rectangle(
+      Point {
+        x: offset.x + inset.left,
+        y: offset.y + inset.top,
+      },
+      Size {
+        width: (size.width - inset.left - inset.right).max(0.0),
+        height: (size.height - inset.top - inset.bottom).max(0.0),
+      },
+    )
+    .append_mask_commands(&mut paths);
+  }
+
+  transform.apply_on_paths(&mut paths);
+
+  let (mask, mut placement) = Mask::new(&paths).style(Fill::EvenOdd).render();
+
+  placement.left += location.x as i32;
+  placement.top += location.y as i32;
+
+  canvas.draw_mask(mask, placement, color, None);
+}
+
+fn rectangle(offset: Point<f32>, size: Size<f32>) -> BorderProperties {
+  BorderProperties {
+    offset,
+    size,
+    ..BorderProperties::zero()
+  }
+}
+
+/// Renders the node like [`render`](crate::rendering::render), along with a transparent
+/// overlay of the same size annotating every node.
+///
+/// The overlay strokes the box model of each node with the default [`DebugOptions`] and
+/// labels it with its type and size, so it can be toggled over the image without rendering again.
+pub fn render_with_debug_overlay<Nodes: Node<Nodes>>(
+  viewport: Viewport,
+  global: &GlobalContext,
+  root_node: Nodes,
+) -> Result<(RgbaImage, RgbaImage), crate::Error> {
+  let (mut taffy, root_node_id) =
+    compute_taffy_layout(viewport, global, root_node, viewport.available_space())?;
+
+  check_scale_factor(viewport.scale_factor)?;
+
+  let canvas_size = resolve_canvas_size(&taffy, root_node_id, viewport, global.max_canvas_pixels)?;
+
+  check_node_resources(&taffy, root_node_id, viewport.scale_factor)?;
+
+  let overlay = {
+    let (tx, rx) = channel();
+    let canvas = Canvas::new(tx);
+
+    draw_debug_overlay(
+      &taffy,
+      root_node_id,
+      &canvas,
+      Point::ZERO,
+      Affine::identity(),
+      viewport.scale_factor,
+      &DebugOptions::default(),
+    );
+
+    drop(canvas);
+
+    create_blocking_canvas_loop(RgbaImage::new(canvas_size.width, canvas_size.height), rx)
+  };
+
+  let image = paint_taffy_tree(
+    &mut taffy,
+    root_node_id,
+    RgbaImage::new(canvas_size.width, canvas_size.height),
+    None,
+    viewport.scale_factor,
+  );
+
+  Ok((image, overlay))
+}
+
+fn draw_debug_overlay<Nodes: Node<Nodes>>(
+  taffy: &TaffyTree<NodeContext<Nodes>>,
+  node_id: NodeId,
+  canvas: &Canvas,
+  offset: Point<f32>,
+  transform: Affine,
+  scale: f32,
+  options: &DebugOptions,
+) {
+  let layout = resolve_node_layout(taffy, node_id, offset);
+  let node_context = taffy.get_node_context(node_id).unwrap();
+  let transform = resolve_node_transform(&node_context.context, &layout, transform);
+
+  let (scaled_layout, scaled_transform) = apply_device_scale(layout, transform, scale);
+  draw_debug_border(canvas, scaled_layout, scaled_transform, options);
+
+  let (label_layout, label_transform) = apply_device_scale(
+    Layout {
+      location: Point {
+        x: layout.location.x + options.stroke_width,
+        y: layout.location.y + options.stroke_width,
+      },
+      size: Size {
+        width: (node_context.context.viewport.width as f32 - layout.location.x).max(0.0),
+        height: LABEL_FONT_SIZE * 2.0,
+      },
+      ..Layout::new()
     },
+    transform,
+    scale,
   );
 
-  draw_border(
+  let label_style = InheritedStyle {
+    font_size: LengthUnit::Px(LABEL_FONT_SIZE),
+    color: options.border_box_color,
+    line_clamp: Some(1),
+    ..Default::default()
+  };
+
+  let label_context = RenderContext {
+    style: label_style,
+    transform: label_transform,
+    border: None,
+    ..node_context.context
+  };
+
+  draw_text(
+    &format!(
+      "{} {}x{}",
+      node_context.node.node_type(),
+      layout.size.width,
+      layout.size.height
+    ),
+    &label_context,
     canvas,
-    Point { x, y },
-    BorderProperties {
-      width: Sides([1.0; 4]).into(),
-      offset: Point::ZERO,
-      size: layout.size,
-      color: Color([0, 255, 0, 255]),
-      radius: Sides([0.0; 4]),
+    label_layout,
+  );
+
+  for child_id in taffy.children(node_id).unwrap() {
+    draw_debug_overlay(
+      taffy,
+      child_id,
+      canvas,
+      layout.location,
       transform,
+      scale,
+      options,
+    );
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    layout::{
+      node::{ContainerNode, NodeKind},
+      style::{Color, LengthUnit::Px, StyleBuilder},
     },
-  );
+    rendering::render,
+  };
+
+  use super::*;
+
+  const NOTO_SANS_REGULAR_BUFFER: &[u8] =
+    include_bytes!("../../../assets/fonts/noto-sans/NotoSans-Regular.ttf");
+
+  #[test]
+  fn test_render_with_debug_overlay() {
+    let context = GlobalContext::default();
+
+    context
+      .font_context
+      .load_and_store(NOTO_SANS_REGULAR_BUFFER, None, None)
+      .unwrap();
+
+    let node = || -> NodeKind {
+      ContainerNode {
+        style: StyleBuilder::default()
+          .width(Px(200.0))
+          .height(Px(100.0))
+          .background_color(Color([255, 255, 255, 255]))
+          .build()
+          .unwrap(),
+        children: None,
+      }
+      .into()
+    };
+
+    let (image, overlay) =
+      render_with_debug_overlay(Viewport::new(200, 100), &context, node()).unwrap();
+
+    assert_eq!(
+      image,
+      render(Viewport::new(200, 100), &context, node()).unwrap()
+    );
+
+    // the border box is stroked and the rest of the overlay stays transparent
+    assert_eq!(overlay.get_pixel(0, 50).0, [0, 255, 0, 255]);
+    assert_eq!(overlay.get_pixel(100, 80).0[3], 0);
+
+    // the label is drawn in the top left corner
+    let label_pixels = (2..60)
+      .flat_map(|x| (2..14).map(move |y| (x, y)))
+      .filter(|&(x, y)| overlay.get_pixel(x, y).0[3] > 0)
+      .count();
+
+    assert!(label_pixels > 0);
+  }
 }
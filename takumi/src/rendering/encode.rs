@@ -0,0 +1,351 @@
+use std::{
+  borrow::Cow,
+  io::{Seek, Write},
+  sync::OnceLock,
+};
+
+use image::{
+  ExtendedColorType, ImageEncoder, ImageError, ImageFormat, RgbaImage,
+  codecs::{
+    gif::GifEncoder,
+    jpeg::{JpegEncoder, PixelDensity},
+    webp::WebPEncoder,
+  },
+  error::{
+    EncodingError, ImageFormatHint, LimitError, LimitErrorKind, UnsupportedError,
+    UnsupportedErrorKind,
+  },
+};
+use serde::{Deserialize, Serialize};
+
+/// Output format for the rendered image.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageOutputFormat {
+  /// WebP format, suitable for web images with good compression.
+  WebP,
+  /// AVIF format, even better compression than WebP, but requires more CPU time to encode.
+  #[cfg(feature = "avif")]
+  Avif,
+  /// PNG format, lossless and supports transparency.
+  Png,
+  /// JPEG format, lossy compression suitable for photographs.
+  Jpeg,
+  /// GIF format, colors are quantized to a 256 color palette.
+  Gif,
+  /// TIFF format, lossless and supports transparency.
+  Tiff,
+  /// BMP format, lossless.
+  ///
+  /// The alpha channel is written as a 32-bit bitmap, but many viewers ignore it.
+  Bmp,
+}
+
+impl ImageOutputFormat {
+  /// Returns the MIME type for the image output format.
+  pub fn content_type(&self) -> &'static str {
+    match self {
+      ImageOutputFormat::WebP => "image/webp",
+      #[cfg(feature = "avif")]
+      ImageOutputFormat::Avif => "image/avif",
+      ImageOutputFormat::Png => "image/png",
+      ImageOutputFormat::Jpeg => "image/jpeg",
+      ImageOutputFormat::Gif => "image/gif",
+      ImageOutputFormat::Tiff => "image/tiff",
+      ImageOutputFormat::Bmp => "image/bmp",
+    }
+  }
+
+  /// Returns the output format for a MIME type like `image/png`, the inverse of [`Self::content_type`].
+  pub fn from_content_type(content_type: &str) -> Option<Self> {
+    let content_type = content_type.trim();
+
+    [
+      ImageOutputFormat::WebP,
+      #[cfg(feature = "avif")]
+      ImageOutputFormat::Avif,
+      ImageOutputFormat::Png,
+      ImageOutputFormat::Jpeg,
+      ImageOutputFormat::Gif,
+      ImageOutputFormat::Tiff,
+      ImageOutputFormat::Bmp,
+    ]
+    .into_iter()
+    .find(|format| format.content_type().eq_ignore_ascii_case(content_type))
+  }
+}
+
+impl From<ImageOutputFormat> for ImageFormat {
+  fn from(format: ImageOutputFormat) -> Self {
+    match format {
+      ImageOutputFormat::WebP => Self::WebP,
+      #[cfg(feature = "avif")]
+      ImageOutputFormat::Avif => Self::Avif,
+      ImageOutputFormat::Png => Self::Png,
+      ImageOutputFormat::Jpeg => Self::Jpeg,
+      ImageOutputFormat::Gif => Self::Gif,
+      ImageOutputFormat::Tiff => Self::Tiff,
+      ImageOutputFormat::Bmp => Self::Bmp,
+    }
+  }
+}
+
+/// Options for encoding the rendered image, see [`write_image`].
+///
+/// A plain quality value converts into the options, so `write_image(.., 90)` keeps working.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub struct EncodeOptions {
+  /// The JPEG quality from 1 to 100, defaults to 75.
+  pub quality: Option<u8>,
+  /// Writes a progressive JPEG instead of a baseline one.
+  ///
+  /// Progressive JPEGs show a low resolution preview while they are still loading.
+  #[serde(default)]
+  pub progressive: bool,
+  /// Writes a lossless WebP, defaults to `true`.
+  ///
+  /// Only lossless WebP encoding is supported, writing a WebP with `false` fails with
+  /// [`Error::ImageEncodeError`](crate::Error::ImageEncodeError).
+  #[serde(default = "default_lossless")]
+  pub lossless: bool,
+  /// The resolution written into PNG (`pHYs`) and JPEG (JFIF density) metadata.
+  ///
+  /// Only the metadata changes, the pixel dimensions stay the same.
+  /// Without it no density is written, which most software treats as 72 DPI.
+  pub dpi: Option<u32>,
+  /// Embeds an sRGB ICC profile into PNG, JPEG and WebP output.
+  ///
+  /// The rendered pixels are always sRGB, the profile stops clients from guessing.
+  #[serde(default)]
+  pub embed_srgb_profile: bool,
+  /// A custom ICC profile to embed instead of the sRGB one.
+  pub icc_profile: Option<Vec<u8>>,
+}
+
+impl Default for EncodeOptions {
+  fn default() -> Self {
+    Self {
+      quality: None,
+      progressive: false,
+      lossless: default_lossless(),
+      dpi: None,
+      embed_srgb_profile: false,
+      icc_profile: None,
+    }
+  }
+}
+
+fn default_lossless() -> bool {
+  true
+}
+
+impl EncodeOptions {
+  /// Returns the ICC profile to embed, if any.
+  pub fn resolve_icc_profile(&self) -> Option<&[u8]> {
+    if let Some(icc_profile) = self.icc_profile.as_deref() {
+      return Some(icc_profile);
+    }
+
+    self.embed_srgb_profile.then(srgb_icc_profile)
+  }
+}
+
+impl From<u8> for EncodeOptions {
+  fn from(quality: u8) -> Self {
+    Some(quality).into()
+  }
+}
+
+impl From<Option<u8>> for EncodeOptions {
+  fn from(quality: Option<u8>) -> Self {
+    Self {
+      quality,
+      ..Default::default()
+    }
+  }
+}
+
+/// Returns an encoded sRGB ICC profile.
+pub fn srgb_icc_profile() -> &'static [u8] {
+  static PROFILE: OnceLock<Vec<u8>> = OnceLock::new();
+
+  PROFILE.get_or_init(|| {
+    moxcms::ColorProfile::new_srgb()
+      .encode()
+      .expect("sRGB profile should be encodable")
+  })
+}
+
+/// Writes the rendered image to the specified destination.
+///
+/// WebP output is lossless, see [`EncodeOptions::lossless`], PNG, TIFF and BMP are lossless too.
+pub fn write_image<T: Write + Seek>(
+  image: &RgbaImage,
+  destination: &mut T,
+  format: ImageOutputFormat,
+  options: impl Into<EncodeOptions>,
+) -> Result<(), crate::Error> {
+  encode_image(image, destination, format, options.into()).map_err(crate::Error::ImageEncodeError)
+}
+
+fn encode_image<T: Write + Seek>(
+  image: &RgbaImage,
+  destination: &mut T,
+  format: ImageOutputFormat,
+  options: EncodeOptions,
+) -> Result<(), ImageError> {
+  let icc_profile = options.resolve_icc_profile();
+
+  if format == ImageOutputFormat::WebP && !options.lossless {
+    return Err(ImageError::Unsupported(
+      UnsupportedError::from_format_and_kind(
+        ImageFormatHint::Exact(ImageFormat::WebP),
+        UnsupportedErrorKind::GenericFeature("lossy encoding".to_string()),
+      ),
+    ));
+  }
+
+  match format {
+    ImageOutputFormat::Jpeg => {
+      // Strip alpha channel into a tightly packed RGB buffer
+      let raw = image.as_raw();
+      let mut rgb = Vec::with_capacity(raw.len() / 4 * 3);
+      for px in raw.chunks_exact(4) {
+        rgb.extend_from_slice(&px[..3]);
+      }
+
+      if options.progressive {
+        write_progressive_jpeg(&rgb, image.width(), image.height(), destination, &options)?;
+      } else {
+        let mut encoder = JpegEncoder::new_with_quality(destination, options.quality.unwrap_or(75));
+
+        if let Some(dpi) = options.dpi {
+          encoder.set_pixel_density(PixelDensity::dpi(clamp_jpeg_dpi(dpi)));
+        }
+
+        if let Some(icc_profile) = icc_profile {
+          encoder
+            .set_icc_profile(icc_profile.to_vec())
+            .map_err(ImageError::Unsupported)?;
+        }
+
+        encoder.encode(&rgb, image.width(), image.height(), ExtendedColorType::Rgb8)?;
+      }
+    }
+    ImageOutputFormat::Png if options.dpi.is_some() || icc_profile.is_some() => {
+      write_png_with_metadata(image, destination, options.dpi, icc_profile)?;
+    }
+    ImageOutputFormat::WebP if icc_profile.is_some() => {
+      let mut encoder = WebPEncoder::new_lossless(destination);
+
+      if let Some(icc_profile) = icc_profile {
+        encoder
+          .set_icc_profile(icc_profile.to_vec())
+          .map_err(ImageError::Unsupported)?;
+      }
+
+      encoder.write_image(
+        image.as_raw(),
+        image.width(),
+        image.height(),
+        ExtendedColorType::Rgba8,
+      )?;
+    }
+    ImageOutputFormat::Gif => {
+      // speed 10 is the recommended trade-off between palette quality and encoding time
+      let mut encoder = GifEncoder::new_with_speed(destination, 10);
+      encoder.encode(
+        image.as_raw(),
+        image.width(),
+        image.height(),
+        ExtendedColorType::Rgba8,
+      )?;
+    }
+    _ => {
+      image.write_to(destination, format.into())?;
+    }
+  }
+
+  Ok(())
+}
+
+fn clamp_jpeg_dpi(dpi: u32) -> u16 {
+  dpi.min(u16::MAX as u32) as u16
+}
+
+fn write_png_with_metadata<T: Write>(
+  image: &RgbaImage,
+  destination: &mut T,
+  dpi: Option<u32>,
+  icc_profile: Option<&[u8]>,
+) -> Result<(), ImageError> {
+  let encoding_error = |err: png::EncodingError| {
+    ImageError::Encoding(EncodingError::new(
+      ImageFormatHint::Exact(ImageFormat::Png),
+      err,
+    ))
+  };
+
+  let mut info = png::Info::with_size(image.width(), image.height());
+  info.color_type = png::ColorType::Rgba;
+  info.bit_depth = png::BitDepth::Eight;
+  info.icc_profile = icc_profile.map(Cow::Borrowed);
+
+  // pHYs stores pixels per meter
+  info.pixel_dims = dpi.map(|dpi| {
+    let pixels_per_meter = (dpi as f64 / 0.0254).round() as u32;
+
+    png::PixelDimensions {
+      xppu: pixels_per_meter,
+      yppu: pixels_per_meter,
+      unit: png::Unit::Meter,
+    }
+  });
+
+  let encoder = png::Encoder::with_info(destination, info).map_err(encoding_error)?;
+
+  let mut writer = encoder.write_header().map_err(encoding_error)?;
+  writer
+    .write_image_data(image.as_raw())
+    .map_err(encoding_error)?;
+
+  writer.finish().map_err(encoding_error)
+}
+
+fn write_progressive_jpeg<T: Write>(
+  rgb: &[u8],
+  width: u32,
+  height: u32,
+  destination: &mut T,
+  options: &EncodeOptions,
+) -> Result<(), ImageError> {
+  let encoding_error = |err| {
+    ImageError::Encoding(EncodingError::new(
+      ImageFormatHint::Exact(ImageFormat::Jpeg),
+      err,
+    ))
+  };
+
+  let (Ok(width), Ok(height)) = (u16::try_from(width), u16::try_from(height)) else {
+    return Err(ImageError::Limits(LimitError::from_kind(
+      LimitErrorKind::DimensionError,
+    )));
+  };
+
+  let mut encoder = jpeg_encoder::Encoder::new(destination, options.quality.unwrap_or(75));
+  encoder.set_progressive(true);
+
+  if let Some(dpi) = options.dpi {
+    encoder.set_density(jpeg_encoder::PixelDensity::dpi(clamp_jpeg_dpi(dpi)));
+  }
+
+  if let Some(icc_profile) = options.resolve_icc_profile() {
+    encoder
+      .add_icc_profile(icc_profile)
+      .map_err(encoding_error)?;
+  }
+
+  encoder
+    .encode(rgb, width, height, jpeg_encoder::ColorType::Rgb)
+    .map_err(encoding_error)
+}
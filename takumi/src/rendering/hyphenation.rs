@@ -0,0 +1,271 @@
+use std::borrow::Cow;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+  GlobalContext,
+  layout::style::{Hyphens, SizedFontStyle},
+  rendering::{MaxHeight, create_text_layout},
+};
+
+/// An invisible break opportunity, shown as a hyphen when a line is broken there.
+const SOFT_HYPHEN: char = '\u{AD}';
+
+/// The most layouts done to find breaks where the hyphen fits on the line.
+const MAX_HYPHENATION_PASSES: usize = 4;
+
+/// Finds where words can be hyphenated with `hyphens: auto`.
+///
+/// No dictionaries are bundled, a dictionary crate like `hyphenation` can be wrapped instead:
+///
+/// ```rust,ignore
+/// struct English(hyphenation::Standard);
+///
+/// impl takumi::rendering::Hyphenator for English {
+///   fn hyphenate(&self, word: &str) -> Vec<usize> {
+///     hyphenation::Hyphenator::hyphenate(&self.0, word).breaks
+///   }
+/// }
+/// ```
+pub trait Hyphenator: Send + Sync {
+  /// Returns the byte offsets in the word where it can be broken with a hyphen.
+  fn hyphenate(&self, word: &str) -> Vec<usize>;
+}
+
+/// Resolves the soft hyphens of the text laid out in the width, the ones a line is broken at
+/// become visible hyphens and the others are removed.
+///
+/// The text is laid out with the soft hyphens as break opportunities first. Breaks where the
+/// hyphen doesn't fit on the line are dropped and the text is laid out again, so the resolved
+/// text breaks into the same lines with the hyphens measured in.
+pub(crate) fn resolve_hyphens<'a>(
+  text: &'a str,
+  font_style: &SizedFontStyle,
+  global: &GlobalContext,
+  max_width: f32,
+  max_height: Option<MaxHeight>,
+) -> Cow<'a, str> {
+  let text = match (font_style.parent.hyphens, global.hyphenator.as_deref()) {
+    (Hyphens::None, _) => return remove_soft_hyphens(text),
+    (Hyphens::Auto, Some(hyphenator)) => insert_soft_hyphens(text, hyphenator),
+    _ => Cow::Borrowed(text),
+  };
+
+  if !text.contains(SOFT_HYPHEN) {
+    return text;
+  }
+
+  let hyphen_width = measure_hyphen(font_style, global);
+
+  let mut text = text.into_owned();
+  let mut breaks = Vec::new();
+
+  for _ in 0..MAX_HYPHENATION_PASSES {
+    let layout = create_text_layout(&text, font_style, global, max_width, max_height);
+    let mut overflowing = Vec::new();
+
+    breaks.clear();
+
+    for line in layout.lines() {
+      let Some(index) = text[..line.text_range().end]
+        .strip_suffix(SOFT_HYPHEN)
+        .map(str::len)
+      else {
+        continue;
+      };
+
+      if line.metrics().advance + hyphen_width > max_width {
+        overflowing.push(index);
+      } else {
+        breaks.push(index);
+      }
+    }
+
+    if overflowing.is_empty() {
+      break;
+    }
+
+    // without the soft hyphen the line breaks at an earlier opportunity
+    for index in overflowing.into_iter().rev() {
+      text.remove(index);
+    }
+
+    // the indices moved, the breaks are found again in the next pass
+    breaks.clear();
+  }
+
+  let mut resolved = String::with_capacity(text.len());
+
+  for (index, ch) in text.char_indices() {
+    if ch != SOFT_HYPHEN {
+      resolved.push(ch);
+    } else if breaks.contains(&index) {
+      resolved.push('-');
+    }
+  }
+
+  Cow::Owned(resolved)
+}
+
+fn remove_soft_hyphens(text: &str) -> Cow<'_, str> {
+  if text.contains(SOFT_HYPHEN) {
+    Cow::Owned(text.replace(SOFT_HYPHEN, ""))
+  } else {
+    Cow::Borrowed(text)
+  }
+}
+
+/// Inserts soft hyphens where the hyphenator allows, words already containing one are kept.
+fn insert_soft_hyphens<'a>(text: &'a str, hyphenator: &dyn Hyphenator) -> Cow<'a, str> {
+  let mut result = String::with_capacity(text.len());
+
+  for word in text.split_word_bounds() {
+    if word.contains(SOFT_HYPHEN) || !word.chars().next().is_some_and(char::is_alphabetic) {
+      result.push_str(word);
+      continue;
+    }
+
+    let mut breaks = hyphenator.hyphenate(word);
+    breaks.sort_unstable();
+    breaks.dedup();
+
+    let mut start = 0;
+
+    for index in breaks {
+      if index == 0 || index >= word.len() || !word.is_char_boundary(index) {
+        continue;
+      }
+
+      result.push_str(&word[start..index]);
+      result.push(SOFT_HYPHEN);
+      start = index;
+    }
+
+    result.push_str(&word[start..]);
+  }
+
+  if result.len() == text.len() {
+    Cow::Borrowed(text)
+  } else {
+    Cow::Owned(result)
+  }
+}
+
+fn measure_hyphen(font_style: &SizedFontStyle, global: &GlobalContext) -> f32 {
+  let font_style = SizedFontStyle {
+    text_indent: None,
+    line_reservation: None,
+    ..font_style.clone()
+  };
+
+  create_text_layout("-", &font_style, global, f32::MAX, None)
+    .lines()
+    .next()
+    .map_or(0.0, |line| line.metrics().advance)
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    layout::{
+      DEFAULT_FONT_SIZE, Viewport,
+      style::{Affine, InheritedStyle, LengthUnit::Px},
+    },
+    rendering::RenderContext,
+  };
+
+  use super::*;
+
+  /// Breaks the word every three characters.
+  struct EveryThree;
+
+  impl Hyphenator for EveryThree {
+    fn hyphenate(&self, word: &str) -> Vec<usize> {
+      word
+        .char_indices()
+        .map(|(index, _)| index)
+        .skip(3)
+        .step_by(3)
+        .collect()
+    }
+  }
+
+  fn resolve(global: &GlobalContext, text: &str, hyphens: Hyphens, max_width: f32) -> String {
+    let context = RenderContext {
+      global,
+      viewport: Viewport::new(800, 600),
+      parent_font_size: DEFAULT_FONT_SIZE,
+      transform: Affine::identity(),
+      style: InheritedStyle {
+        hyphens,
+        font_size: Px(16.0),
+        ..Default::default()
+      },
+      border: None,
+    };
+
+    let font_style = context.style.to_sized_font_style(&context);
+
+    resolve_hyphens(text, &font_style, global, max_width, None).into_owned()
+  }
+
+  #[test]
+  fn test_insert_soft_hyphens() {
+    assert_eq!(
+      insert_soft_hyphens("typography, 123 über", &EveryThree),
+      "typ\u{AD}ogr\u{AD}aph\u{AD}y, 123 übe\u{AD}r"
+    );
+
+    // words hyphenated by hand are kept as they are
+    assert_eq!(
+      insert_soft_hyphens("hyphen\u{AD}ation", &EveryThree),
+      "hyphen\u{AD}ation"
+    );
+  }
+
+  #[test]
+  fn test_resolve_hyphens() {
+    let mut global = GlobalContext::default();
+
+    global
+      .font_context
+      .load_and_store(
+        include_bytes!("../../../assets/fonts/noto-sans/NotoSans-Regular.ttf"),
+        None,
+        None,
+      )
+      .unwrap();
+
+    let text = "extra\u{AD}ordinary";
+
+    // the soft hyphen is shown where the line breaks and removed where it doesn't
+    assert_eq!(
+      resolve(&global, text, Hyphens::Manual, 60.0),
+      "extra-ordinary"
+    );
+    assert_eq!(
+      resolve(&global, text, Hyphens::Manual, 500.0),
+      "extraordinary"
+    );
+    assert_eq!(resolve(&global, text, Hyphens::None, 60.0), "extraordinary");
+
+    // the hyphen doesn't fit after the first part, so the word isn't broken
+    assert_eq!(
+      resolve(&global, text, Hyphens::Manual, 1.0),
+      "extraordinary"
+    );
+
+    assert_eq!(
+      resolve(&global, "typography", Hyphens::Auto, 500.0),
+      "typography"
+    );
+
+    global.hyphenator = Some(Box::new(EveryThree));
+
+    let resolved = resolve(&global, "typography", Hyphens::Auto, 45.0);
+
+    assert!(resolved.contains('-'));
+    assert!(!resolved.contains(SOFT_HYPHEN));
+    assert_eq!(resolved.replace('-', ""), "typography");
+  }
+}
@@ -6,8 +6,8 @@ use image::imageops::crop_imm;
 use taffy::{Layout, Point, Size};
 
 use crate::{
-  layout::style::{Affine, ObjectFit},
-  rendering::{BorderProperties, Canvas, RenderContext},
+  layout::style::{Affine, BackgroundRepeat, ImageScalingAlgorithm, ObjectFit},
+  rendering::{Canvas, RenderContext, overlay_image, resolve_tile_positions},
   resources::image::ImageSource,
 };
 
@@ -146,6 +146,38 @@ pub fn process_image_for_object_fit<'i>(
         },
       )
     }
+    ObjectFit::None if context.style.object_repeat != BackgroundRepeat::no_repeat() => {
+      // tile the image at its intrinsic size, with `object-position` as the tile origin
+      let tile = image
+        .render_to_rgba_image(image_width as u32, image_height as u32, filter_type)
+        .into_owned();
+
+      let (tile, xs, ys) = resolve_tile_positions(
+        tile,
+        context.style.object_position,
+        context.style.object_repeat,
+        content_box.width as u32,
+        content_box.height as u32,
+        context,
+      );
+
+      let mut composed = RgbaImage::new(content_box.width as u32, content_box.height as u32);
+
+      for y in &ys {
+        for x in &xs {
+          overlay_image(
+            &mut composed,
+            &tile,
+            Point { x: *x, y: *y },
+            Default::default(),
+            Affine::identity(),
+            ImageScalingAlgorithm::Auto,
+          );
+        }
+      }
+
+      (Cow::Owned(composed), Point::zero())
+    }
     ObjectFit::None => {
       // If the image is smaller than the content box, we don't need to crop
       if image_width <= content_box.width && image_height <= content_box.height {
@@ -235,7 +267,7 @@ pub fn draw_image(image: &ImageSource, context: &RenderContext, canvas: &Canvas,
       x: (offset.x + layout.location.x) as i32,
       y: (offset.y + layout.location.y) as i32,
     },
-    BorderProperties::from_context(context, &layout).inset_by_border_width(),
+    context.border_properties(&layout).inset_by_border_width(),
     transform_with_content_offset,
     context.style.image_rendering,
   );
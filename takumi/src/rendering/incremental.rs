@@ -0,0 +1,496 @@
+use std::collections::HashMap;
+
+use image::RgbaImage;
+use taffy::{NodeId, Point, Rect, TaffyTree};
+
+use crate::{
+  GlobalContext,
+  layout::{Viewport, node::Node, style::Affine},
+  rendering::{
+    NodeContext, NodePath, SizedFilter, check_node_resources, check_scale_factor,
+    compute_taffy_layout, paint_taffy_tree, rects_intersect, resolve_canvas_size,
+    resolve_node_layout, resolve_node_transform, resolve_paint_bounds,
+  },
+};
+
+/// Renders animation frames, repainting only the regions of nodes that changed since the
+/// previous frame.
+///
+/// Nodes are identified across frames by their [`NodePath`], so the tree should keep the
+/// same structure between frames. Nodes with `display: none` are left out of the tree, so
+/// toggling one shifts the paths of its later siblings. The repainted region covers the
+/// changed nodes both where they are now and where they were painted in the previous frame.
+///
+/// The region grows to cover nodes with a `backdrop-filter` reading from it, so they filter
+/// the repainted backdrop. A node painting outside its border box (other than box shadows),
+/// like overflowing text, is only repainted when its border box intersects the region.
+pub struct FrameRenderer {
+  viewport: Viewport,
+  previous: Option<Frame>,
+}
+
+struct Frame {
+  image: RgbaImage,
+  paint_bounds: HashMap<NodePath, Rect<f32>>,
+}
+
+impl FrameRenderer {
+  /// Creates a renderer for frames of the viewport.
+  pub fn new(viewport: Viewport) -> Self {
+    Self {
+      viewport,
+      previous: None,
+    }
+  }
+
+  /// Returns the last rendered frame.
+  pub fn previous_frame(&self) -> Option<&RgbaImage> {
+    self.previous.as_ref().map(|frame| &frame.image)
+  }
+
+  /// Renders the whole frame, like [`render`](crate::rendering::render).
+  pub fn render<Nodes: Node<Nodes>>(
+    &mut self,
+    global: &GlobalContext,
+    root_node: Nodes,
+  ) -> Result<&RgbaImage, crate::Error> {
+    self.render_frame(global, root_node, None)
+  }
+
+  /// Renders a frame where only the nodes at `dirty_paths` changed since the previous frame.
+  ///
+  /// The layout is recomputed for the whole tree, but only the region covered by the dirty
+  /// nodes is repainted onto a copy of the previous frame. Without a previous frame of the
+  /// same size the whole frame is rendered.
+  pub fn render_dirty<Nodes: Node<Nodes>>(
+    &mut self,
+    global: &GlobalContext,
+    root_node: Nodes,
+    dirty_paths: &[NodePath],
+  ) -> Result<&RgbaImage, crate::Error> {
+    self.render_frame(global, root_node, Some(dirty_paths))
+  }
+
+  fn render_frame<Nodes: Node<Nodes>>(
+    &mut self,
+    global: &GlobalContext,
+    root_node: Nodes,
+    dirty_paths: Option<&[NodePath]>,
+  ) -> Result<&RgbaImage, crate::Error> {
+    let scale = self.viewport.scale_factor;
+    check_scale_factor(scale)?;
+
+    let (mut taffy, root_node_id) = compute_taffy_layout(
+      self.viewport,
+      global,
+      root_node,
+      self.viewport.available_space(),
+    )?;
+
+    let canvas_size = resolve_canvas_size(
+      &taffy,
+      root_node_id,
+      self.viewport,
+      global.max_canvas_pixels,
+    )?;
+
+    check_node_resources(&taffy, root_node_id, self.viewport.scale_factor)?;
+
+    let mut paint_bounds = HashMap::new();
+    let mut backdrop_bounds = Vec::new();
+    collect_paint_bounds(
+      &taffy,
+      root_node_id,
+      Vec::new(),
+      Point::ZERO,
+      Affine::identity(),
+      &mut paint_bounds,
+      &mut backdrop_bounds,
+    );
+
+    let previous = self
+      .previous
+      .take()
+      .filter(|previous| previous.image.dimensions() == (canvas_size.width, canvas_size.height));
+
+    let image = match (previous, dirty_paths) {
+      (Some(previous), Some(dirty_paths)) => {
+        let region = dirty_paths
+          .iter()
+          .flat_map(|path| [paint_bounds.get(path), previous.paint_bounds.get(path)])
+          .flatten()
+          .copied()
+          .reduce(union_rects)
+          .map(|region| expand_to_backdrops(region, &backdrop_bounds))
+          .and_then(|region| clamp_to_pixels(region, scale, previous.image.dimensions()));
+
+        match region {
+          Some(region) => repaint_region(&mut taffy, root_node_id, previous.image, region, scale),
+          None => previous.image,
+        }
+      }
+      _ => paint_taffy_tree(
+        &mut taffy,
+        root_node_id,
+        RgbaImage::new(canvas_size.width, canvas_size.height),
+        None,
+        scale,
+      ),
+    };
+
+    Ok(
+      &self
+        .previous
+        .insert(Frame {
+          image,
+          paint_bounds,
+        })
+        .image,
+    )
+  }
+}
+
+/// Paints the nodes intersecting the region and copies the region onto the previous frame.
+///
+/// The region is in image pixels, the nodes are matched against it in CSS pixels.
+fn repaint_region<Nodes: Node<Nodes>>(
+  taffy: &mut TaffyTree<NodeContext<Nodes>>,
+  root_node_id: NodeId,
+  mut image: RgbaImage,
+  region: Rect<u32>,
+  scale: f32,
+) -> RgbaImage {
+  let painted = paint_taffy_tree(
+    taffy,
+    root_node_id,
+    RgbaImage::new(image.width(), image.height()),
+    Some(Rect {
+      left: region.left as f32 / scale,
+      right: region.right as f32 / scale,
+      top: region.top as f32 / scale,
+      bottom: region.bottom as f32 / scale,
+    }),
+    scale,
+  );
+
+  let stride = image.width() as usize * 4;
+  let row = region.left as usize * 4..region.right as usize * 4;
+
+  for y in region.top as usize..region.bottom as usize {
+    let start = y * stride;
+
+    image.as_mut()[start + row.start..start + row.end]
+      .copy_from_slice(&painted.as_raw()[start + row.start..start + row.end]);
+  }
+
+  image
+}
+
+fn collect_paint_bounds<Nodes: Node<Nodes>>(
+  taffy: &TaffyTree<NodeContext<Nodes>>,
+  node_id: NodeId,
+  path: NodePath,
+  offset: Point<f32>,
+  transform: Affine,
+  bounds: &mut HashMap<NodePath, Rect<f32>>,
+  backdrop_bounds: &mut Vec<Rect<f32>>,
+) {
+  let layout = resolve_node_layout(taffy, node_id, offset);
+
+  let context = &taffy.get_node_context(node_id).unwrap().context;
+  let transform = resolve_node_transform(context, &layout, transform);
+
+  for (index, child_id) in taffy.children(node_id).unwrap().into_iter().enumerate() {
+    let mut child_path = path.clone();
+    child_path.push(index);
+
+    collect_paint_bounds(
+      taffy,
+      child_id,
+      child_path,
+      layout.location,
+      transform,
+      bounds,
+      backdrop_bounds,
+    );
+  }
+
+  let paint_bounds = resolve_paint_bounds(context, &layout, transform);
+
+  // the filtered pixels depend on the backdrop around the box, as far as the filters read
+  if let Some(backdrop_filter) = context.style.backdrop_filter.as_ref() {
+    let extent = SizedFilter::from_filters(backdrop_filter, context, layout.size)
+      .into_iter()
+      .map(|filter| filter.sample_extent() as f32)
+      .sum::<f32>();
+
+    backdrop_bounds.push(Rect {
+      left: paint_bounds.left - extent,
+      right: paint_bounds.right + extent,
+      top: paint_bounds.top - extent,
+      bottom: paint_bounds.bottom + extent,
+    });
+  }
+
+  // the filtered layer depends on the whole subtree, as far as the filters read around it
+  if let Some(filter) = context.style.filter.as_ref() {
+    let extent = SizedFilter::from_filters(filter, context, layout.size)
+      .into_iter()
+      .map(|filter| filter.sample_extent() as f32)
+      .sum::<f32>();
+
+    let subtree_bounds = bounds
+      .iter()
+      .filter(|(child_path, _)| child_path.starts_with(&path))
+      .fold(paint_bounds, |subtree_bounds, (_, child_bounds)| {
+        union_rects(subtree_bounds, *child_bounds)
+      });
+
+    backdrop_bounds.push(Rect {
+      left: subtree_bounds.left - extent,
+      right: subtree_bounds.right + extent,
+      top: subtree_bounds.top - extent,
+      bottom: subtree_bounds.bottom + extent,
+    });
+  }
+
+  bounds.insert(path, paint_bounds);
+}
+
+fn union_rects(a: Rect<f32>, b: Rect<f32>) -> Rect<f32> {
+  Rect {
+    left: a.left.min(b.left),
+    right: a.right.max(b.right),
+    top: a.top.min(b.top),
+    bottom: a.bottom.max(b.bottom),
+  }
+}
+
+/// Grows the region until it covers every backdrop read area it intersects.
+fn expand_to_backdrops(mut region: Rect<f32>, backdrop_bounds: &[Rect<f32>]) -> Rect<f32> {
+  loop {
+    let expanded = backdrop_bounds
+      .iter()
+      .filter(|bounds| rects_intersect(**bounds, region))
+      .fold(region, |region, bounds| union_rects(region, *bounds));
+
+    if expanded == region {
+      return region;
+    }
+
+    region = expanded;
+  }
+}
+
+/// Rounds the region out to whole pixels inside the canvas, `None` if nothing is left.
+fn clamp_to_pixels(
+  region: Rect<f32>,
+  scale: f32,
+  (width, height): (u32, u32),
+) -> Option<Rect<u32>> {
+  let region = Rect {
+    left: (region.left * scale).floor().clamp(0.0, width as f32) as u32,
+    right: (region.right * scale).ceil().clamp(0.0, width as f32) as u32,
+    top: (region.top * scale).floor().clamp(0.0, height as f32) as u32,
+    bottom: (region.bottom * scale).ceil().clamp(0.0, height as f32) as u32,
+  };
+
+  (region.left < region.right && region.top < region.bottom).then_some(region)
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    GlobalContext,
+    layout::{
+      Viewport,
+      node::{ContainerNode, NodeKind},
+      style::{
+        BoxShadow, BoxShadows, Color, Filter, Filters, LengthUnit::Px, Position, Sides,
+        StyleBuilder, TextShadow,
+      },
+    },
+    rendering::render,
+  };
+
+  use super::*;
+
+  fn create_frame(progress: f32) -> NodeKind {
+    ContainerNode {
+      style: StyleBuilder::default()
+        .width(Px(200.0))
+        .height(Px(40.0))
+        .padding(Sides([Px(10.0); 4]))
+        .background_color(Color([255, 255, 255, 255]))
+        .build()
+        .unwrap(),
+      children: Some(vec![
+        ContainerNode {
+          style: StyleBuilder::default()
+            .width(Px(progress))
+            .height(Px(20.0))
+            .background_color(Color([255, 0, 0, 255]))
+            .box_shadow(Some(BoxShadows(
+              [BoxShadow {
+                inset: false,
+                offset_x: Px(2.0),
+                offset_y: Px(2.0),
+                blur_radius: Px(4.0),
+                spread_radius: Px(0.0),
+                color: Color([0, 0, 0, 128]),
+              }]
+              .into_iter()
+              .collect(),
+            )))
+            .build()
+            .unwrap(),
+          children: None,
+        }
+        .into(),
+      ]),
+    }
+    .into()
+  }
+
+  #[test]
+  fn test_render_dirty_refilters_backdrop() {
+    let context = GlobalContext::default();
+    let viewport = Viewport::new(200, 40);
+
+    let create_frame = |progress: f32| -> NodeKind {
+      let mut frame = create_frame(progress);
+
+      if let NodeKind::Container(container) = &mut frame {
+        container.children.as_mut().unwrap().push(
+          ContainerNode {
+            style: StyleBuilder::default()
+              .position(Position::Absolute)
+              .inset(Sides([Px(0.0), Px(0.0), Px(0.0), Px(150.0)]))
+              .backdrop_filter(Some(Filters([Filter::Blur(Px(3.0))].into_iter().collect())))
+              .build()
+              .unwrap(),
+            children: None,
+          }
+          .into(),
+        );
+      }
+
+      frame
+    };
+
+    let mut renderer = FrameRenderer::new(viewport);
+    renderer.render(&context, create_frame(120.0)).unwrap();
+
+    // the bar ends under the blurred panel, which spreads the change further out
+    let frame = renderer
+      .render_dirty(&context, create_frame(160.0), &[vec![0]])
+      .unwrap()
+      .clone();
+
+    let expected = render(viewport, &context, create_frame(160.0)).unwrap();
+
+    assert_eq!(frame, expected);
+  }
+
+  #[test]
+  fn test_render_dirty_repaints_drop_shadow() {
+    let context = GlobalContext::default();
+    let viewport = Viewport::new(200, 40);
+
+    // the bar is wrapped in a filtered node, its shadow falls below both of their boxes
+    let create_frame = |progress: f32| -> NodeKind {
+      ContainerNode {
+        style: StyleBuilder::default()
+          .width(Px(200.0))
+          .height(Px(40.0))
+          .background_color(Color([255, 255, 255, 255]))
+          .build()
+          .unwrap(),
+        children: Some(vec![
+          ContainerNode {
+            style: StyleBuilder::default()
+              .height(Px(10.0))
+              .filter(Some(Filters(
+                [Filter::DropShadow(TextShadow {
+                  offset_x: Px(0.0),
+                  offset_y: Px(16.0),
+                  blur_radius: Px(0.0),
+                  color: Color([0, 0, 255, 255]),
+                })]
+                .into_iter()
+                .collect(),
+              )))
+              .build()
+              .unwrap(),
+            children: Some(vec![
+              ContainerNode {
+                style: StyleBuilder::default()
+                  .width(Px(progress))
+                  .height(Px(10.0))
+                  .background_color(Color([255, 0, 0, 255]))
+                  .build()
+                  .unwrap(),
+                children: None,
+              }
+              .into(),
+            ]),
+          }
+          .into(),
+        ]),
+      }
+      .into()
+    };
+
+    let mut renderer = FrameRenderer::new(viewport);
+    renderer.render(&context, create_frame(120.0)).unwrap();
+
+    let frame = renderer
+      .render_dirty(&context, create_frame(160.0), &[vec![0, 0]])
+      .unwrap()
+      .clone();
+
+    let expected = render(viewport, &context, create_frame(160.0)).unwrap();
+
+    assert_eq!(frame, expected);
+  }
+
+  #[test]
+  fn test_render_dirty_matches_full_render() {
+    let context = GlobalContext::default();
+    let viewport = Viewport::new(200, 40);
+
+    let mut renderer = FrameRenderer::new(viewport);
+    renderer.render(&context, create_frame(120.0)).unwrap();
+
+    // growing and shrinking both repaint the old and the new bounds of the bar
+    for progress in [160.0, 40.0] {
+      let frame = renderer
+        .render_dirty(&context, create_frame(progress), &[vec![0]])
+        .unwrap()
+        .clone();
+
+      let expected = render(viewport, &context, create_frame(progress)).unwrap();
+
+      assert_eq!(frame, expected);
+    }
+  }
+
+  #[test]
+  fn test_render_dirty_with_scale_factor() {
+    let context = GlobalContext::default();
+    let viewport = Viewport::new(200, 40).with_scale_factor(2.0);
+
+    let mut renderer = FrameRenderer::new(viewport);
+    renderer.render(&context, create_frame(120.0)).unwrap();
+
+    let frame = renderer
+      .render_dirty(&context, create_frame(60.0), &[vec![0]])
+      .unwrap()
+      .clone();
+
+    let expected = render(viewport, &context, create_frame(60.0)).unwrap();
+
+    assert_eq!(frame.dimensions(), (400, 80));
+    assert_eq!(frame, expected);
+  }
+}
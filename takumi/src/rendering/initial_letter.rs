@@ -0,0 +1,171 @@
+use parley::{Alignment, PositionedLayoutItem};
+use taffy::{Point, Size};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+  GlobalContext, layout::style::SizedFontStyle, rendering::create_text_layout,
+  resources::font::FontMetrics,
+};
+
+/// The space taken from the start of the first lines of a text, like the ones a drop cap sinks
+/// into.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct LineReservation {
+  /// The width taken from each line
+  pub width: f32,
+  /// The number of lines shortened
+  pub lines: u32,
+}
+
+/// The first letter of a text with `initial-letter`, laid out on its own in a larger font.
+pub(crate) struct InitialLetterLayout<'t, 's> {
+  /// The letter, with the punctuation in front of it
+  pub text: &'t str,
+  /// The font style of the letter, the font size is scaled up to span the lines
+  pub font_style: SizedFontStyle<'s>,
+  /// The laid out letter
+  pub layout: parley::Layout<()>,
+  /// Where the letter layout is drawn, relative to the content box
+  pub offset: Point<f32>,
+  /// The size of the lines the letter sinks into, from the top of the content box
+  pub size: Size<f32>,
+}
+
+/// Splits the first letter off the text if the style has an `initial-letter`.
+///
+/// Returns the laid out letter and the font style for the rest of the text, which makes room
+/// for the letter at the start of the lines it sinks into.
+pub(crate) fn create_initial_letter<'t, 's>(
+  text: &'t str,
+  font_style: &SizedFontStyle<'s>,
+  global: &GlobalContext,
+) -> Option<(InitialLetterLayout<'t, 's>, &'t str, SizedFontStyle<'s>)> {
+  let initial_letter = font_style.parent.initial_letter?;
+
+  if font_style.parent.writing_mode.is_vertical() || font_style.font_size == 0.0 {
+    return None;
+  }
+
+  let (letter, rest) = split_initial_letter(text)?;
+
+  let line_style = SizedFontStyle {
+    text_indent: None,
+    line_reservation: None,
+    ..font_style.clone()
+  };
+
+  // the lines of the rest of the text, measured on the letter in the same style
+  let probe = create_text_layout(letter, &line_style, global, f32::MAX, None);
+  let line = probe.lines().next()?;
+  let line_metrics = *line.metrics();
+
+  let cap_height = line
+    .items()
+    .find_map(|item| match item {
+      PositionedLayoutItem::GlyphRun(glyph_run) => {
+        Some(FontMetrics::from_run(glyph_run.run()).cap_height)
+      }
+      PositionedLayoutItem::InlineBox(_) => None,
+    })
+    .filter(|cap_height| *cap_height > 0.0)
+    .unwrap_or(line_metrics.ascent);
+
+  if cap_height <= 0.0 {
+    return None;
+  }
+
+  // the capital of the letter reaches from the capitals of the first line down to the baseline
+  // `size` lines below
+  let letter_cap_height = (initial_letter.size - 1.0) * line_metrics.line_height + cap_height;
+
+  let letter_style = SizedFontStyle {
+    font_size: font_style.font_size * letter_cap_height / cap_height,
+    line_height: parley::LineHeight::FontSizeRelative(1.0),
+    letter_spacing: None,
+    ..line_style
+  };
+
+  let mut layout = create_text_layout(letter, &letter_style, global, f32::MAX, None);
+  layout.align(None, Alignment::Start, Default::default());
+
+  let letter_metrics = *layout.lines().next()?.metrics();
+
+  let sink = initial_letter.sink as f32;
+  let baseline = line_metrics.baseline + (sink - 1.0) * line_metrics.line_height;
+
+  let reservation = LineReservation {
+    width: letter_metrics.advance,
+    lines: initial_letter.sink,
+  };
+
+  let letter = InitialLetterLayout {
+    text: letter,
+    font_style: letter_style,
+    layout,
+    offset: Point {
+      x: 0.0,
+      y: baseline - letter_metrics.baseline,
+    },
+    size: Size {
+      width: reservation.width,
+      height: sink * line_metrics.line_height,
+    },
+  };
+
+  let rest_style = SizedFontStyle {
+    line_reservation: Some(reservation),
+    ..font_style.clone()
+  };
+
+  Some((letter, rest, rest_style))
+}
+
+/// Splits the text after its first letter, punctuation in front of it like an opening quote is
+/// kept with the letter.
+///
+/// Returns `None` if the text starts with whitespace or has nothing to draw after the letter.
+fn split_initial_letter(text: &str) -> Option<(&str, &str)> {
+  let mut end = 0;
+
+  for grapheme in text.graphemes(true) {
+    if grapheme.chars().any(char::is_whitespace) {
+      return None;
+    }
+
+    end += grapheme.len();
+
+    if grapheme.chars().any(char::is_alphanumeric) {
+      break;
+    }
+  }
+
+  let (letter, rest) = text.split_at(end);
+
+  (!rest.trim().is_empty()).then_some((letter, rest))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_split_initial_letter() {
+    assert_eq!(
+      split_initial_letter("Once upon a time"),
+      Some(("O", "nce upon a time"))
+    );
+    assert_eq!(
+      split_initial_letter("“Hello,” she said"),
+      Some(("“H", "ello,” she said"))
+    );
+    assert_eq!(
+      split_initial_letter("e\u{301}cole"),
+      Some(("e\u{301}", "cole"))
+    );
+    assert_eq!(split_initial_letter("東京の夜"), Some(("東", "京の夜")));
+
+    assert_eq!(split_initial_letter(" Once"), None);
+    assert_eq!(split_initial_letter("A"), None);
+    assert_eq!(split_initial_letter(""), None);
+  }
+}
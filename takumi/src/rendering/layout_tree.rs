@@ -0,0 +1,781 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use taffy::{NodeId, Point, Rect, Size, TaffyTree};
+
+use crate::{
+  GlobalContext,
+  layout::{
+    Viewport,
+    node::Node,
+    style::{Affine, Display, FlexDirection, Overflow, Position, Visibility},
+  },
+  rendering::{
+    NodeContext, collect_sorted_stacking_layers, compute_taffy_layout, resolve_node_layout,
+    resolve_node_transform,
+  },
+};
+
+/// Child indices leading from the root node to a node, the root node has an empty path.
+pub type NodePath = Vec<usize>;
+
+/// The computed box of a single node, in canvas coordinates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutTreeNode {
+  /// The path of the node from the root node.
+  pub path: NodePath,
+  /// The absolute position of the node's border box before transforms are applied.
+  pub location: Point<f32>,
+  /// The size of the node's border box.
+  pub size: Size<f32>,
+  /// The accumulated transform applied when painting the node.
+  pub transform: Affine,
+  /// Whether the node is painted, nodes with `visibility: hidden` are skipped by hit tests.
+  pub visible: bool,
+  /// The padding boxes of the `overflow: hidden` ancestors the node is clipped to.
+  pub clips: Vec<LayoutTreeClip>,
+}
+
+impl LayoutTreeNode {
+  /// Checks if the point (in canvas coordinates) is inside the node's transformed border box.
+  ///
+  /// The clips of the node are not taken into account, see [`LayoutTreeNode::is_hit`].
+  pub fn contains(&self, x: f32, y: f32) -> bool {
+    let Some(local) = to_local_point(self.location, self.transform, x, y) else {
+      return false;
+    };
+
+    local.x >= 0.0 && local.y >= 0.0 && local.x < self.size.width && local.y < self.size.height
+  }
+
+  /// Checks if the point (in canvas coordinates) hits the painted part of the node, inside its
+  /// box and all of its clips.
+  pub fn is_hit(&self, x: f32, y: f32) -> bool {
+    self.visible && self.contains(x, y) && self.clips.iter().all(|clip| clip.contains(x, y))
+  }
+}
+
+/// The padding box an `overflow: hidden` node clips its descendants to.
+///
+/// The rounded corners of the box are not taken into account.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LayoutTreeClip {
+  /// The absolute position of the clipping node's border box before transforms are applied.
+  pub location: Point<f32>,
+  /// The padding box, relative to the border box of the clipping node.
+  pub padding_box: Rect<f32>,
+  /// The accumulated transform applied when painting the clipping node.
+  pub transform: Affine,
+}
+
+impl LayoutTreeClip {
+  /// Checks if the point (in canvas coordinates) is inside the transformed padding box.
+  pub fn contains(&self, x: f32, y: f32) -> bool {
+    let Some(local) = to_local_point(self.location, self.transform, x, y) else {
+      return false;
+    };
+
+    local.x >= self.padding_box.left
+      && local.y >= self.padding_box.top
+      && local.x < self.padding_box.right
+      && local.y < self.padding_box.bottom
+  }
+}
+
+/// Maps a canvas point into the coordinates of a box, `None` if the transform can't be inverted.
+fn to_local_point(location: Point<f32>, transform: Affine, x: f32, y: f32) -> Option<Point<f32>> {
+  let inverse = transform.invert()?;
+
+  Some(
+    Point {
+      x: x - location.x,
+      y: y - location.y,
+    } * inverse,
+  )
+}
+
+/// The computed layout of a node tree, used to map canvas coordinates back to nodes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutTree {
+  /// Nodes in painting order, later nodes are painted on top of earlier ones.
+  nodes: Vec<LayoutTreeNode>,
+}
+
+impl LayoutTree {
+  /// Returns all nodes in painting order.
+  pub fn nodes(&self) -> &[LayoutTreeNode] {
+    &self.nodes
+  }
+
+  /// Returns the node at the given path.
+  pub fn get(&self, path: &[usize]) -> Option<&LayoutTreeNode> {
+    self.nodes.iter().find(|node| node.path == path)
+  }
+
+  /// Returns the path of the topmost node painted at the point.
+  ///
+  /// Nodes are ordered like they are painted, by `z-index` within their stacking context and
+  /// then in tree order. Hidden nodes and the parts of nodes clipped by an `overflow: hidden`
+  /// ancestor are never hit.
+  pub fn hit_test(&self, x: f32, y: f32) -> Option<&NodePath> {
+    self
+      .nodes
+      .iter()
+      .rev()
+      .find(|node| node.is_hit(x, y))
+      .map(|node| &node.path)
+  }
+}
+
+/// Computes the layout of a node tree without painting it.
+pub fn compute_layout_tree<Nodes: Node<Nodes>>(
+  viewport: Viewport,
+  global: &GlobalContext,
+  root_node: Nodes,
+) -> LayoutTree {
+  let (taffy, root_node_id) =
+    compute_taffy_layout(viewport, global, root_node, viewport.available_space())
+      .expect("the layout only fails for nodes missing from the tree");
+
+  let mut paths = HashMap::new();
+  collect_node_paths(&taffy, root_node_id, Vec::new(), &mut paths);
+
+  let mut nodes = Vec::new();
+
+  collect_stacking_context_nodes(
+    &taffy,
+    root_node_id,
+    &paths,
+    Point::ZERO,
+    Affine::identity(),
+    &[],
+    &mut nodes,
+  );
+
+  LayoutTree { nodes }
+}
+
+fn collect_node_paths<Nodes: Node<Nodes>>(
+  taffy: &TaffyTree<NodeContext<Nodes>>,
+  node_id: NodeId,
+  path: NodePath,
+  paths: &mut HashMap<NodeId, NodePath>,
+) {
+  for (index, child_id) in taffy.children(node_id).unwrap().into_iter().enumerate() {
+    let mut child_path = path.clone();
+    child_path.push(index);
+
+    collect_node_paths(taffy, child_id, child_path, paths);
+  }
+
+  paths.insert(node_id, path);
+}
+
+/// Collects the node and the descendants stacked in its context in the order `render` paints
+/// them.
+fn collect_stacking_context_nodes<Nodes: Node<Nodes>>(
+  taffy: &TaffyTree<NodeContext<Nodes>>,
+  node_id: NodeId,
+  paths: &HashMap<NodeId, NodePath>,
+  offset: Point<f32>,
+  transform: Affine,
+  clips: &[LayoutTreeClip],
+  nodes: &mut Vec<LayoutTreeNode>,
+) {
+  let node = collect_node(taffy, node_id, paths, offset, transform, clips, nodes);
+
+  let mut clips = clips.to_vec();
+
+  let context = &taffy.get_node_context(node_id).unwrap().context;
+
+  if context.style.overflow == Overflow::Hidden {
+    let border = taffy.layout(node_id).unwrap().border;
+
+    clips.push(LayoutTreeClip {
+      location: node.location,
+      padding_box: Rect {
+        left: border.left,
+        right: node.size.width - border.right,
+        top: border.top,
+        bottom: node.size.height - border.bottom,
+      },
+      transform: node.transform,
+    });
+  }
+
+  for layer in collect_sorted_stacking_layers(taffy, node_id, node.location, node.transform) {
+    if layer.is_stacking_context {
+      collect_stacking_context_nodes(
+        taffy,
+        layer.node_id,
+        paths,
+        layer.offset,
+        layer.transform,
+        &clips,
+        nodes,
+      );
+    } else {
+      collect_node(
+        taffy,
+        layer.node_id,
+        paths,
+        layer.offset,
+        layer.transform,
+        &clips,
+        nodes,
+      );
+    }
+  }
+}
+
+/// Adds the node on its own and returns it.
+fn collect_node<'a, Nodes: Node<Nodes>>(
+  taffy: &TaffyTree<NodeContext<Nodes>>,
+  node_id: NodeId,
+  paths: &HashMap<NodeId, NodePath>,
+  offset: Point<f32>,
+  transform: Affine,
+  clips: &[LayoutTreeClip],
+  nodes: &'a mut Vec<LayoutTreeNode>,
+) -> &'a LayoutTreeNode {
+  let layout = resolve_node_layout(taffy, node_id, offset);
+
+  let context = &taffy.get_node_context(node_id).unwrap().context;
+  let transform = resolve_node_transform(context, &layout, transform);
+
+  nodes.push(LayoutTreeNode {
+    path: paths[&node_id].clone(),
+    location: layout.location,
+    size: layout.size,
+    transform,
+    visible: context.style.visibility != Visibility::Hidden,
+    clips: clips.to_vec(),
+  });
+
+  nodes.last().unwrap()
+}
+
+/// A serializable snapshot of the computed layout of a node and its children.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LayoutReport {
+  /// The type of the node, e.g. `container`, `text` or `image`.
+  pub node_type: &'static str,
+  /// The absolute x position of the node's border box.
+  pub x: f32,
+  /// The absolute y position of the node's border box.
+  pub y: f32,
+  /// The width of the node's border box.
+  pub width: f32,
+  /// The height of the node's border box.
+  pub height: f32,
+  /// The box enclosing the border boxes of all descendants, in absolute coordinates.
+  ///
+  /// Content overflowing the node makes it larger than the node's own box, like the scrollable
+  /// area of a container, but the descendants of an `overflow: hidden` child only reach as far
+  /// as the child. Transforms are not applied and it is `None` for nodes without children.
+  pub content_bounds: Option<LayoutReportBounds>,
+  /// Resolved style values that affect layout the most.
+  pub style: LayoutReportStyle,
+  /// Reports of the child nodes.
+  pub children: Vec<LayoutReport>,
+}
+
+/// A rectangle in a [`LayoutReport`], in absolute coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LayoutReportBounds {
+  /// The absolute x position of the left edge.
+  pub x: f32,
+  /// The absolute y position of the top edge.
+  pub y: f32,
+  /// The width of the rectangle.
+  pub width: f32,
+  /// The height of the rectangle.
+  pub height: f32,
+}
+
+impl LayoutReportBounds {
+  /// Returns the smallest rectangle containing both rectangles.
+  pub fn union(self, other: Self) -> Self {
+    let x = self.x.min(other.x);
+    let y = self.y.min(other.y);
+
+    Self {
+      x,
+      y,
+      width: (self.x + self.width).max(other.x + other.width) - x,
+      height: (self.y + self.height).max(other.y + other.height) - y,
+    }
+  }
+}
+
+impl LayoutReport {
+  /// Returns the border box of the node.
+  pub fn bounds(&self) -> LayoutReportBounds {
+    LayoutReportBounds {
+      x: self.x,
+      y: self.y,
+      width: self.width,
+      height: self.height,
+    }
+  }
+}
+
+/// Resolved style values included in a [`LayoutReport`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LayoutReportStyle {
+  /// The display mode of the node.
+  pub display: Display,
+  /// The positioning method of the node.
+  pub position: Position,
+  /// The flex direction of the node.
+  pub flex_direction: FlexDirection,
+  /// Whether the node clips its descendants.
+  pub overflow: Overflow,
+  /// The font size in pixels after inheritance.
+  pub font_size: f32,
+}
+
+/// Computes the layout of a node tree and returns a nested report, without painting it.
+pub fn compute_layout_report<Nodes: Node<Nodes>>(
+  viewport: Viewport,
+  global: &GlobalContext,
+  root_node: Nodes,
+) -> LayoutReport {
+  let (taffy, root_node_id) =
+    compute_taffy_layout(viewport, global, root_node, viewport.available_space())
+      .expect("the layout only fails for nodes missing from the tree");
+
+  create_layout_report(&taffy, root_node_id, Point::ZERO)
+}
+
+fn create_layout_report<Nodes: Node<Nodes>>(
+  taffy: &TaffyTree<NodeContext<Nodes>>,
+  node_id: NodeId,
+  offset: Point<f32>,
+) -> LayoutReport {
+  let layout = resolve_node_layout(taffy, node_id, offset);
+  let node_context = taffy.get_node_context(node_id).unwrap();
+  let context = &node_context.context;
+
+  let location = layout.location;
+
+  let children: Vec<_> = taffy
+    .children(node_id)
+    .unwrap()
+    .into_iter()
+    .map(|child_id| create_layout_report(taffy, child_id, location))
+    .collect();
+
+  // the descendants overflowing a child reach past its own box, unless the child clips them
+  let content_bounds = children
+    .iter()
+    .map(|child| match child.content_bounds {
+      Some(bounds) if child.style.overflow == Overflow::Visible => bounds.union(child.bounds()),
+      _ => child.bounds(),
+    })
+    .reduce(LayoutReportBounds::union);
+
+  LayoutReport {
+    node_type: node_context.node.node_type(),
+    x: location.x,
+    y: location.y,
+    width: layout.size.width,
+    height: layout.size.height,
+    content_bounds,
+    style: LayoutReportStyle {
+      display: context.style.display,
+      position: context.style.position,
+      flex_direction: context.style.flex_direction,
+      overflow: context.style.overflow,
+      font_size: context
+        .style
+        .font_size
+        .resolve_to_px(context, context.parent_font_size),
+    },
+    children,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    GlobalContext,
+    layout::{
+      Viewport,
+      node::{ContainerNode, NodeKind, TextNode},
+      style::{LengthUnit::Px, Overflow, Position, Sides, StyleBuilder, Visibility},
+    },
+  };
+
+  use super::*;
+
+  fn create_tree() -> NodeKind {
+    ContainerNode {
+      style: StyleBuilder::default()
+        .width(Px(200.0))
+        .height(Px(100.0))
+        .build()
+        .unwrap(),
+      children: Some(vec![
+        ContainerNode {
+          style: StyleBuilder::default()
+            .width(Px(100.0))
+            .height(Px(100.0))
+            .build()
+            .unwrap(),
+          children: None,
+        }
+        .into(),
+        ContainerNode {
+          style: StyleBuilder::default()
+            .width(Px(100.0))
+            .height(Px(100.0))
+            .build()
+            .unwrap(),
+          children: None,
+        }
+        .into(),
+        // overlaps the first child, painted last
+        ContainerNode {
+          style: StyleBuilder::default()
+            .position(Position::Absolute)
+            .inset(Sides([Px(0.0), Px(0.0), Px(0.0), Px(0.0)]))
+            .width(Px(50.0))
+            .height(Px(50.0))
+            .build()
+            .unwrap(),
+          children: None,
+        }
+        .into(),
+      ]),
+    }
+    .into()
+  }
+
+  #[test]
+  fn test_layout_tree_absolute_rects() {
+    let context = GlobalContext::default();
+    let tree = compute_layout_tree(Viewport::new(400, 400), &context, create_tree());
+
+    assert_eq!(tree.nodes().len(), 4);
+    assert_eq!(tree.get(&[1]).unwrap().location, Point { x: 100.0, y: 0.0 });
+    assert_eq!(
+      tree.get(&[1]).unwrap().size,
+      Size {
+        width: 100.0,
+        height: 100.0
+      }
+    );
+  }
+
+  #[test]
+  fn test_hit_test_topmost_node() {
+    let context = GlobalContext::default();
+    let tree = compute_layout_tree(Viewport::new(400, 400), &context, create_tree());
+
+    assert_eq!(tree.hit_test(10.0, 10.0), Some(&vec![2]));
+    assert_eq!(tree.hit_test(60.0, 60.0), Some(&vec![0]));
+    assert_eq!(tree.hit_test(150.0, 50.0), Some(&vec![1]));
+    assert_eq!(tree.hit_test(300.0, 300.0), None);
+  }
+
+  #[test]
+  fn test_hit_test_skips_hidden_nodes() {
+    let context = GlobalContext::default();
+
+    let mut node = create_tree();
+
+    // the overlay on top of the first child
+    if let NodeKind::Container(container) = &mut node
+      && let Some(NodeKind::Container(overlay)) = container
+        .children
+        .as_mut()
+        .and_then(|children| children.get_mut(2))
+    {
+      overlay.style.visibility = Visibility::Hidden.into();
+    }
+
+    let tree = compute_layout_tree(Viewport::new(400, 400), &context, node);
+
+    assert!(!tree.get(&[2]).unwrap().visible);
+    assert_eq!(tree.hit_test(10.0, 10.0), Some(&vec![0]));
+  }
+
+  #[test]
+  fn test_hit_test_respects_overflow_clip() {
+    let context = GlobalContext::default();
+
+    let node: NodeKind = ContainerNode {
+      style: StyleBuilder::default()
+        .width(Px(300.0))
+        .height(Px(100.0))
+        .build()
+        .unwrap(),
+      children: Some(vec![
+        ContainerNode {
+          style: StyleBuilder::default()
+            .width(Px(100.0))
+            .height(Px(100.0))
+            .border_width(Sides([Px(10.0); 4]))
+            .overflow(Overflow::Hidden)
+            .flex_shrink(Some(0.0))
+            .build()
+            .unwrap(),
+          children: Some(vec![
+            // overflows its parent to the right
+            ContainerNode {
+              style: StyleBuilder::default()
+                .width(Px(200.0))
+                .height(Px(50.0))
+                .flex_shrink(Some(0.0))
+                .build()
+                .unwrap(),
+              children: None,
+            }
+            .into(),
+          ]),
+        }
+        .into(),
+      ]),
+    }
+    .into();
+
+    let tree = compute_layout_tree(Viewport::new(400, 400), &context, node);
+
+    // the child box reaches past the clip, only the part in the padding box is hit
+    assert!(tree.get(&[0, 0]).unwrap().contains(150.0, 20.0));
+    assert_eq!(tree.hit_test(50.0, 20.0), Some(&vec![0, 0]));
+    assert_eq!(tree.hit_test(150.0, 20.0), Some(&vec![]));
+
+    // the border of the clipping node is outside of the padding box
+    assert_eq!(tree.hit_test(95.0, 20.0), Some(&vec![0]));
+  }
+
+  #[test]
+  fn test_hit_test_follows_z_index() {
+    let context = GlobalContext::default();
+
+    let square = |z_index: Option<i32>| -> NodeKind {
+      ContainerNode {
+        style: StyleBuilder::default()
+          .width(Px(150.0))
+          .height(Px(100.0))
+          .flex_shrink(Some(0.0))
+          .z_index(z_index)
+          .build()
+          .unwrap(),
+        children: None,
+      }
+      .into()
+    };
+
+    // the first child overlaps the second one, which comes later in the tree
+    let node: NodeKind = ContainerNode {
+      style: StyleBuilder::default()
+        .width(Px(300.0))
+        .height(Px(100.0))
+        .build()
+        .unwrap(),
+      children: Some(vec![square(Some(1)), square(None)]),
+    }
+    .into();
+
+    let tree = compute_layout_tree(Viewport::new(400, 400), &context, node);
+
+    assert_eq!(tree.hit_test(125.0, 50.0), Some(&vec![0]));
+    assert_eq!(tree.hit_test(200.0, 50.0), Some(&vec![1]));
+  }
+
+  #[test]
+  fn test_layout_report_json() {
+    let context = GlobalContext::default();
+    let node: NodeKind = ContainerNode {
+      style: StyleBuilder::default()
+        .width(Px(200.0))
+        .height(Px(100.0))
+        .padding(Sides([Px(10.0); 4]))
+        .build()
+        .unwrap(),
+      children: Some(vec![
+        TextNode {
+          style: StyleBuilder::default()
+            .width(Px(50.0))
+            .height(Px(20.0))
+            .font_size(Px(24.0))
+            .build()
+            .unwrap(),
+          text: String::new(),
+        }
+        .into(),
+      ]),
+    }
+    .into();
+
+    let report = compute_layout_report(Viewport::new(400, 400), &context, node);
+
+    assert_eq!(
+      serde_json::to_value(&report).unwrap(),
+      serde_json::json!({
+        "nodeType": "container",
+        "x": 0.0,
+        "y": 0.0,
+        "width": 200.0,
+        "height": 100.0,
+        "contentBounds": {
+          "x": 10.0,
+          "y": 10.0,
+          "width": 50.0,
+          "height": 20.0,
+        },
+        "style": {
+          "display": "flex",
+          "position": "relative",
+          "flexDirection": "row",
+          "overflow": "visible",
+          "fontSize": 16.0,
+        },
+        "children": [{
+          "nodeType": "text",
+          "x": 10.0,
+          "y": 10.0,
+          "width": 50.0,
+          "height": 20.0,
+          "contentBounds": null,
+          "style": {
+            "display": "flex",
+            "position": "relative",
+            "flexDirection": "row",
+            "overflow": "visible",
+            "fontSize": 24.0,
+          },
+          "children": [],
+        }],
+      })
+    );
+  }
+
+  #[test]
+  fn test_layout_report_content_bounds() {
+    let context = GlobalContext::default();
+    let node: NodeKind = ContainerNode {
+      style: StyleBuilder::default()
+        .width(Px(100.0))
+        .height(Px(100.0))
+        .flex_direction(FlexDirection::Column)
+        .flex_shrink(Some(0.0))
+        .build()
+        .unwrap(),
+      children: Some(vec![
+        ContainerNode {
+          style: StyleBuilder::default()
+            .width(Px(80.0))
+            .height(Px(60.0))
+            .flex_shrink(Some(0.0))
+            .build()
+            .unwrap(),
+          children: Some(vec![
+            // overflows the child and the root to the right
+            ContainerNode {
+              style: StyleBuilder::default()
+                .width(Px(150.0))
+                .height(Px(20.0))
+                .flex_shrink(Some(0.0))
+                .build()
+                .unwrap(),
+              children: None,
+            }
+            .into(),
+          ]),
+        }
+        .into(),
+        ContainerNode {
+          style: StyleBuilder::default()
+            .width(Px(50.0))
+            .height(Px(90.0))
+            .flex_shrink(Some(0.0))
+            .build()
+            .unwrap(),
+          children: None,
+        }
+        .into(),
+      ]),
+    }
+    .into();
+
+    let report = compute_layout_report(Viewport::new(400, 400), &context, node);
+
+    assert_eq!((report.width, report.height), (100.0, 100.0));
+    assert_eq!(
+      report.content_bounds,
+      Some(LayoutReportBounds {
+        x: 0.0,
+        y: 0.0,
+        width: 150.0,
+        height: 150.0,
+      })
+    );
+    assert_eq!(
+      report.children[0].content_bounds,
+      Some(LayoutReportBounds {
+        x: 0.0,
+        y: 0.0,
+        width: 150.0,
+        height: 20.0,
+      })
+    );
+    assert_eq!(report.children[1].content_bounds, None);
+  }
+
+  #[test]
+  fn test_layout_report_content_bounds_stop_at_overflow_clip() {
+    let context = GlobalContext::default();
+    let node: NodeKind = ContainerNode {
+      style: StyleBuilder::default()
+        .width(Px(100.0))
+        .height(Px(100.0))
+        .build()
+        .unwrap(),
+      children: Some(vec![
+        ContainerNode {
+          style: StyleBuilder::default()
+            .width(Px(80.0))
+            .height(Px(60.0))
+            .overflow(Overflow::Hidden)
+            .build()
+            .unwrap(),
+          children: Some(vec![
+            ContainerNode {
+              style: StyleBuilder::default()
+                .width(Px(150.0))
+                .height(Px(20.0))
+                .flex_shrink(Some(0.0))
+                .build()
+                .unwrap(),
+              children: None,
+            }
+            .into(),
+          ]),
+        }
+        .into(),
+      ]),
+    }
+    .into();
+
+    let report = compute_layout_report(Viewport::new(400, 400), &context, node);
+
+    // the clipped child still reports its own overflow
+    assert_eq!(report.children[0].content_bounds.unwrap().width, 150.0);
+    assert_eq!(
+      report.content_bounds,
+      Some(LayoutReportBounds {
+        x: 0.0,
+        y: 0.0,
+        width: 80.0,
+        height: 60.0,
+      })
+    );
+  }
+}
@@ -1,24 +1,52 @@
 /// Background and color drawing functions
 mod background_drawing;
+/// Rendering many nodes or scales at once
+mod batch;
 /// Canvas operations and image blending
 mod canvas;
 mod components;
 /// Debug drawing utilities
 mod debug_drawing;
+/// Encoding rendered images
+mod encode;
+/// Hyphenating words at soft hyphens
+mod hyphenation;
 /// Image drawing functions
 mod image_drawing;
+/// Repainting only the changed regions of animation frames
+mod incremental;
+/// Drop caps for `initial-letter`
+mod initial_letter;
+/// Computed layout tree, hit-testing and layout reports
+mod layout_tree;
 /// Main image renderer and viewport management
 mod render;
+/// Painting nodes in `z-index` order within their stacking contexts
+mod stacking;
 /// Text drawing functions
 mod text_drawing;
 
 pub(crate) use background_drawing::*;
+pub use batch::*;
 pub(crate) use canvas::*;
+#[cfg(feature = "test-utils")]
+pub use canvas::{blend_pixel, blend_row};
 pub(crate) use components::*;
 pub(crate) use debug_drawing::*;
+pub use debug_drawing::{DebugOptions, render_with_debug_overlay};
+pub use encode::*;
+pub use hyphenation::Hyphenator;
+pub(crate) use hyphenation::*;
 pub(crate) use image_drawing::*;
+pub use incremental::*;
+pub(crate) use initial_letter::*;
+pub use layout_tree::*;
 pub use render::*;
+pub(crate) use stacking::*;
 pub(crate) use text_drawing::*;
+pub use text_drawing::{MissingGlyph, font_metrics, preload_glyphs};
+
+use taffy::Layout;
 
 use crate::{
   GlobalContext,
@@ -41,4 +69,17 @@ pub struct RenderContext<'g> {
   pub(crate) transform: Affine,
   /// The style after inheritance.
   pub(crate) style: InheritedStyle,
+  /// The border of the node, resolved before painting and shared by the draw steps.
+  pub(crate) border: Option<BorderProperties>,
+}
+
+impl RenderContext<'_> {
+  /// Returns the border properties of the node with the layout.
+  ///
+  /// Nodes being painted reuse the properties resolved before drawing, other contexts resolve them.
+  pub(crate) fn border_properties(&self, layout: &Layout) -> BorderProperties {
+    self
+      .border
+      .unwrap_or_else(|| BorderProperties::from_context(self, layout))
+  }
 }
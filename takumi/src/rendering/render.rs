@@ -1,170 +1,313 @@
 use std::{
-  io::{Seek, Write},
   sync::mpsc::channel,
+  time::{Duration, Instant},
 };
 
-use image::{ExtendedColorType, ImageFormat, RgbaImage, codecs::jpeg::JpegEncoder};
-use serde::{Deserialize, Serialize};
-use taffy::{AvailableSpace, NodeId, Point, TaffyTree, geometry::Size};
+use image::RgbaImage;
+use serde::Serialize;
+use taffy::{AvailableSpace, Layout, NodeId, Point, Rect, TaffyTree, geometry::Size};
 
 use crate::{
   GlobalContext,
   layout::{
     Viewport,
     node::Node,
-    style::{Affine, InheritedStyle},
+    style::{
+      Affine, CssValue, Display, InheritedStyle, LengthUnit, OutlineStyle, Position, VerticalAlign,
+    },
+  },
+  rendering::{
+    Canvas, SCRIPT_FONT_SCALE, SizedShadow, create_blocking_canvas_loop, render_node,
+    resolve_vertical_align_offset,
   },
-  rendering::{Canvas, create_blocking_canvas_loop, draw_debug_border},
 };
 
 use crate::rendering::RenderContext;
 
 /// Stores the context and node for rendering.
-struct NodeContext<'ctx, N: Node<N>> {
-  context: RenderContext<'ctx>,
-  node: N,
+pub(crate) struct NodeContext<'ctx, N: Node<N>> {
+  pub(crate) context: RenderContext<'ctx>,
+  pub(crate) node: N,
+  /// How far the node is moved down to align its first baseline with its siblings, see
+  /// [`align_flex_baselines`].
+  pub(crate) baseline_shift: f32,
+  /// The room measured above the node in a flex row with super- or subscripts, like the space a
+  /// line box makes for the text raised and lowered on it, see [`reserve_script_room`].
+  ///
+  /// The node is painted below the room, for a script it includes the shift of its baseline.
+  pub(crate) line_room: Option<f32>,
 }
 
-/// Output format for the rendered image.
-#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
-#[serde(rename_all = "lowercase")]
-pub enum ImageOutputFormat {
-  /// WebP format, suitable for web images with good compression.
-  WebP,
-  /// AVIF format, even better compression than WebP, but requires more CPU time to encode.
-  #[cfg(feature = "avif")]
-  Avif,
-  /// PNG format, lossless and supports transparency.
-  Png,
-  /// JPEG format, lossy compression suitable for photographs.
-  Jpeg,
+/// Renders a node to an image.
+///
+/// If the viewport has no height, the image is as tall as the root node's computed height.
+/// The image size is the viewport size multiplied by its [scale factor](Viewport::scale_factor).
+pub fn render<Nodes: Node<Nodes>>(
+  viewport: Viewport,
+  global: &GlobalContext,
+  root_node: Nodes,
+) -> Result<RgbaImage, crate::Error> {
+  render_with_taffy(
+    &mut TaffyTree::new(),
+    viewport,
+    global,
+    root_node,
+    create_image,
+    None,
+  )
 }
 
-impl ImageOutputFormat {
-  /// Returns the MIME type for the image output format.
-  pub fn content_type(&self) -> &'static str {
-    match self {
-      ImageOutputFormat::WebP => "image/webp",
-      #[cfg(feature = "avif")]
-      ImageOutputFormat::Avif => "image/avif",
-      ImageOutputFormat::Png => "image/png",
-      ImageOutputFormat::Jpeg => "image/jpeg",
-    }
-  }
+/// Timings and counters of a single render, see [`render_with_stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderStats {
+  /// Time spent building the layout tree from the nodes.
+  pub tree_build: Duration,
+  /// Time spent resolving the resources of the nodes, like fetching images.
+  pub resource_resolve: Duration,
+  /// Time spent computing the layout.
+  pub layout: Duration,
+  /// Time spent painting the nodes onto the canvas.
+  pub draw: Duration,
+  /// Time spent encoding the image, only set by [`RenderStats::measure_encode`].
+  pub encode: Duration,
+  /// The number of nodes in the layout tree.
+  pub node_count: usize,
+  /// Glyph lookups served from the glyph cache during the render.
+  pub glyph_cache_hits: u64,
+  /// Glyph lookups that had to rasterize the glyph during the render.
+  pub glyph_cache_misses: u64,
 }
 
-impl From<ImageOutputFormat> for ImageFormat {
-  fn from(format: ImageOutputFormat) -> Self {
-    match format {
-      ImageOutputFormat::WebP => Self::WebP,
-      #[cfg(feature = "avif")]
-      ImageOutputFormat::Avif => Self::Avif,
-      ImageOutputFormat::Png => Self::Png,
-      ImageOutputFormat::Jpeg => Self::Jpeg,
-    }
+impl RenderStats {
+  /// Returns the sum of all the phase timings.
+  pub fn total(&self) -> Duration {
+    self.tree_build + self.resource_resolve + self.layout + self.draw + self.encode
+  }
+
+  /// Runs the encoding step, like a [`write_image`](crate::rendering::write_image) call, and
+  /// records its duration.
+  pub fn measure_encode<T>(&mut self, encode: impl FnOnce() -> T) -> T {
+    measure(Some(&mut self.encode), encode)
   }
 }
 
-/// Writes the rendered image to the specified destination.
-pub fn write_image<T: Write + Seek>(
-  image: &RgbaImage,
-  destination: &mut T,
-  format: ImageOutputFormat,
-  jpeg_quality: Option<u8>,
-) -> Result<(), image::ImageError> {
-  match format {
-    ImageOutputFormat::Jpeg => {
-      // Strip alpha channel into a tightly packed RGB buffer
-      let raw = image.as_raw();
-      let mut rgb = Vec::with_capacity(raw.len() / 4 * 3);
-      for px in raw.chunks_exact(4) {
-        rgb.extend_from_slice(&px[..3]);
-      }
+/// Runs `f`, storing how long it took if a duration is given.
+fn measure<T>(duration: Option<&mut Duration>, f: impl FnOnce() -> T) -> T {
+  let Some(duration) = duration else {
+    return f();
+  };
 
-      let mut encoder = JpegEncoder::new_with_quality(destination, jpeg_quality.unwrap_or(75));
-      encoder.encode(&rgb, image.width(), image.height(), ExtendedColorType::Rgb8)?;
-    }
-    _ => {
-      image.write_to(destination, format.into())?;
-    }
-  }
+  let start = Instant::now();
+  let result = f();
+  *duration = start.elapsed();
 
-  Ok(())
+  result
 }
 
-/// Renders a node to an image.
-pub fn render<Nodes: Node<Nodes>>(
+/// Renders a node like [`render`], also returning how long each phase took.
+///
+/// The glyph cache counters are the difference before and after the render, so renders
+/// running at the same time with the same context are counted too.
+/// Timings use [`Instant`], which isn't available on `wasm32-unknown-unknown`.
+pub fn render_with_stats<Nodes: Node<Nodes>>(
   viewport: Viewport,
   global: &GlobalContext,
   root_node: Nodes,
-) -> Result<RgbaImage, crate::Error> {
-  let mut taffy = TaffyTree::new();
+) -> Result<(RgbaImage, RenderStats), crate::Error> {
+  let mut stats = RenderStats::default();
+  let (hits_before, misses_before) = global.font_context.glyph_cache_hit_stats();
 
-  let (tx, rx) = channel();
-  let canvas = Canvas::new(tx);
+  let image = render_with_taffy(
+    &mut TaffyTree::new(),
+    viewport,
+    global,
+    root_node,
+    create_image,
+    Some(&mut stats),
+  )?;
 
-  let render_context = RenderContext {
+  let (hits, misses) = global.font_context.glyph_cache_hit_stats();
+  stats.glyph_cache_hits = hits.saturating_sub(hits_before);
+  stats.glyph_cache_misses = misses.saturating_sub(misses_before);
+
+  Ok((image, stats))
+}
+
+/// Renders a node into an existing image instead of allocating a new one.
+///
+/// The image must be as large as the canvas [`render`] would create, its pixels are cleared to
+/// transparent before drawing and left as they were if the render fails. Reusing one image for
+/// same-size renders avoids allocating the pixel buffer each time.
+pub fn render_into<Nodes: Node<Nodes>>(
+  image: &mut RgbaImage,
+  viewport: Viewport,
+  global: &GlobalContext,
+  root_node: Nodes,
+) -> Result<(), crate::Error> {
+  *image = render_with_taffy(
+    &mut TaffyTree::new(),
+    viewport,
     global,
+    root_node,
+    |canvas_size| {
+      if image.dimensions() != (canvas_size.width, canvas_size.height) {
+        return Err(crate::Error::CanvasSizeMismatch {
+          expected: (canvas_size.width, canvas_size.height),
+          actual: image.dimensions(),
+        });
+      }
+
+      image.fill(0);
+
+      // taken instead of borrowed since it is drawn on another thread
+      Ok(std::mem::take(image))
+    },
+    None,
+  )?;
+
+  Ok(())
+}
+
+/// Allocates a transparent image for the canvas.
+pub(crate) fn create_image(canvas_size: Size<u32>) -> Result<RgbaImage, crate::Error> {
+  Ok(RgbaImage::new(canvas_size.width, canvas_size.height))
+}
+
+pub(crate) fn render_with_taffy<'g, Nodes: Node<Nodes>>(
+  taffy: &mut TaffyTree<NodeContext<'g, Nodes>>,
+  viewport: Viewport,
+  global: &'g GlobalContext,
+  root_node: Nodes,
+  create_image: impl FnOnce(Size<u32>) -> Result<RgbaImage, crate::Error>,
+  mut stats: Option<&mut RenderStats>,
+) -> Result<RgbaImage, crate::Error> {
+  check_scale_factor(viewport.scale_factor)?;
+
+  let root_node_id = compute_taffy_layout_with(
+    taffy,
     viewport,
-    parent_font_size: viewport.font_size,
-    transform: Affine::identity(),
-    style: InheritedStyle::default(),
-  };
+    global,
+    root_node,
+    viewport.available_space(),
+    stats.as_deref_mut(),
+  )?;
 
-  let root_node_id = insert_taffy_node(&mut taffy, root_node, &render_context);
+  let canvas_size = resolve_canvas_size(taffy, root_node_id, viewport, global.max_canvas_pixels)?;
 
-  let available_space = Size {
-    width: AvailableSpace::Definite(viewport.width as f32),
-    height: AvailableSpace::Definite(viewport.height as f32),
-  };
+  measure(
+    stats
+      .as_deref_mut()
+      .map(|stats| &mut stats.resource_resolve),
+    || check_node_resources(taffy, root_node_id, viewport.scale_factor),
+  )?;
 
-  taffy
-    .compute_layout_with_measure(
-      root_node_id,
-      available_space,
-      |known_dimensions, available_space, _node_id, node_context, _style| {
-        let node = node_context.unwrap();
-
-        if let Size {
-          width: Some(width),
-          height: Some(height),
-        } = known_dimensions
-        {
-          return Size { width, height };
-        }
-
-        node
-          .node
-          .measure(&node.context, available_space, known_dimensions)
-      },
-    )
-    .unwrap();
+  let image = create_image(canvas_size)?;
+
+  if let Some(stats) = stats.as_deref_mut() {
+    stats.node_count = taffy.total_node_count();
+  }
+
+  Ok(measure(stats.map(|stats| &mut stats.draw), || {
+    paint_taffy_tree(taffy, root_node_id, image, None, viewport.scale_factor)
+  }))
+}
+
+/// Returns the size of the canvas in image pixels, the height follows the root node if the
+/// viewport has none.
+///
+/// Fails for a canvas without pixels or with more than `max_pixels`, before it is allocated.
+pub(crate) fn resolve_canvas_size<Nodes: Node<Nodes>>(
+  taffy: &TaffyTree<NodeContext<Nodes>>,
+  root_node_id: NodeId,
+  viewport: Viewport,
+  max_pixels: u64,
+) -> Result<Size<u32>, crate::Error> {
+  let height = viewport
+    .height
+    .unwrap_or_else(|| taffy.layout(root_node_id).unwrap().size.height.ceil() as u32);
+
+  let width = (viewport.width as f32 * viewport.scale_factor).round() as u32;
+  let height = (height as f32 * viewport.scale_factor).round() as u32;
+
+  if width == 0 || height == 0 {
+    return Err(crate::Error::InvalidViewport { width, height });
+  }
+
+  if width as u64 * height as u64 > max_pixels {
+    return Err(crate::Error::CanvasTooLarge {
+      width,
+      height,
+      max_pixels,
+    });
+  }
+
+  Ok(Size { width, height })
+}
+
+/// Checks that the scale factor is a positive finite number.
+pub(crate) fn check_scale_factor(scale_factor: f32) -> Result<(), crate::Error> {
+  if !scale_factor.is_finite() || scale_factor <= 0.0 {
+    return Err(crate::Error::InvalidScale(scale_factor));
+  }
+
+  Ok(())
+}
+
+/// Paints the laid out tree onto the image.
+///
+/// Only nodes whose [paint bounds](resolve_paint_bounds) intersect the region are drawn, the
+/// whole image without one, so nodes outside the canvas are skipped but still take part in
+/// the layout. The tree is rasterized at `scale` device pixels per layout pixel, see
+/// [`apply_device_scale`].
+pub(crate) fn paint_taffy_tree<Nodes: Node<Nodes>>(
+  taffy: &mut TaffyTree<NodeContext<Nodes>>,
+  root_node_id: NodeId,
+  image: RgbaImage,
+  region: Option<Rect<f32>>,
+  scale: f32,
+) -> RgbaImage {
+  set_scale_factor(taffy, root_node_id, scale);
+
+  let region = Some(region.unwrap_or(Rect {
+    left: 0.0,
+    right: image.width() as f32 / scale,
+    top: 0.0,
+    bottom: image.height() as f32 / scale,
+  }));
+
+  let (tx, rx) = channel();
+  let canvas = Canvas::new(tx);
 
   #[cfg(target_arch = "wasm32")]
   let canvas = {
     render_node(
-      &mut taffy,
+      taffy,
       root_node_id,
       &canvas,
       Point::ZERO,
       Affine::identity(),
+      region,
+      scale,
     );
 
     drop(canvas);
 
-    create_blocking_canvas_loop(viewport, rx)
+    create_blocking_canvas_loop(image, rx)
   };
 
   #[cfg(not(target_arch = "wasm32"))]
   let canvas = {
-    let handler = std::thread::spawn(move || create_blocking_canvas_loop(viewport, rx));
+    let handler = std::thread::spawn(move || create_blocking_canvas_loop(image, rx));
 
     render_node(
-      &mut taffy,
+      taffy,
       root_node_id,
       &canvas,
       Point::ZERO,
       Affine::identity(),
+      region,
+      scale,
     );
 
     drop(canvas);
@@ -172,50 +315,544 @@ pub fn render<Nodes: Node<Nodes>>(
     handler.join().unwrap()
   };
 
-  Ok(canvas)
+  canvas
 }
 
-fn render_node<Nodes: Node<Nodes>>(
+/// Sets the scale factor the lengths drawn in image pixels are resolved with, like shadows.
+fn set_scale_factor<Nodes: Node<Nodes>>(
   taffy: &mut TaffyTree<NodeContext<Nodes>>,
   node_id: NodeId,
-  canvas: &Canvas,
-  offset: Point<f32>,
-  mut transform: Affine,
+  scale: f32,
 ) {
+  taffy
+    .get_node_context_mut(node_id)
+    .unwrap()
+    .context
+    .viewport
+    .scale_factor = scale;
+
+  for child_id in taffy.children(node_id).unwrap() {
+    set_scale_factor(taffy, child_id, scale);
+  }
+}
+
+/// Computes the size of the root node without painting anything.
+///
+/// The width is constrained to the viewport width while the height is sized by its content,
+/// which is useful to decide the canvas size before calling [`render`].
+///
+/// Fails with [`Error::LayoutFailed`](crate::Error::LayoutFailed) like [`render`] when the layout
+/// can't be computed.
+pub fn measure_root<Nodes: Node<Nodes>>(
+  viewport: Viewport,
+  global: &GlobalContext,
+  root_node: Nodes,
+) -> Result<Size<f32>, crate::Error> {
+  let (taffy, root_node_id) = compute_taffy_layout(
+    viewport,
+    global,
+    root_node,
+    Size {
+      width: AvailableSpace::Definite(viewport.width as f32),
+      height: AvailableSpace::MaxContent,
+    },
+  )?;
+
+  taffy
+    .layout(root_node_id)
+    .map(|layout| layout.size)
+    .map_err(crate::Error::LayoutFailed)
+}
+
+/// Builds the taffy tree for the node and computes its layout within the available space.
+pub(crate) fn compute_taffy_layout<'g, Nodes: Node<Nodes>>(
+  viewport: Viewport,
+  global: &'g GlobalContext,
+  root_node: Nodes,
+  available_space: Size<AvailableSpace>,
+) -> Result<(TaffyTree<NodeContext<'g, Nodes>>, NodeId), crate::Error> {
+  let mut taffy = TaffyTree::new();
+
+  let root_node_id = compute_taffy_layout_with(
+    &mut taffy,
+    viewport,
+    global,
+    root_node,
+    available_space,
+    None,
+  )?;
+
+  Ok((taffy, root_node_id))
+}
+
+/// Like [`compute_taffy_layout`], but clears and reuses an existing taffy tree.
+pub(crate) fn compute_taffy_layout_with<'g, Nodes: Node<Nodes>>(
+  taffy: &mut TaffyTree<NodeContext<'g, Nodes>>,
+  viewport: Viewport,
+  global: &'g GlobalContext,
+  root_node: Nodes,
+  available_space: Size<AvailableSpace>,
+  mut stats: Option<&mut RenderStats>,
+) -> Result<NodeId, crate::Error> {
+  taffy.clear();
+
+  let render_context = RenderContext {
+    global,
+    viewport,
+    parent_font_size: viewport.font_size,
+    transform: Affine::identity(),
+    style: InheritedStyle::default(),
+    border: None,
+  };
+
+  let root_node_id = measure(
+    stats.as_deref_mut().map(|stats| &mut stats.tree_build),
+    || insert_taffy_node(taffy, root_node, &render_context),
+  );
+
+  measure(stats.map(|stats| &mut stats.layout), || {
+    taffy
+      .compute_layout_with_measure(
+        root_node_id,
+        available_space,
+        |known_dimensions, available_space, _node_id, node_context, _style| {
+          let node = node_context.unwrap();
+
+          if let Size {
+            width: Some(width),
+            height: Some(height),
+          } = known_dimensions
+          {
+            return Size { width, height };
+          }
+
+          let room = node.line_room.unwrap_or_default();
+
+          let mut size = node.node.measure(
+            &node.context,
+            available_space,
+            Size {
+              width: known_dimensions.width,
+              height: known_dimensions
+                .height
+                .map(|height| (height - room).max(0.0)),
+            },
+          );
+
+          size.height += room;
+          size
+        },
+      )
+      .map_err(crate::Error::LayoutFailed)?;
+
+    align_flex_baselines(taffy, root_node_id);
+
+    Ok(root_node_id)
+  })
+}
+
+/// Aligns the items of flex rows with `align-items: baseline` on their first baselines.
+///
+/// Taffy only gets the sizes of the measured nodes, so it aligns them on the bottom of their
+/// boxes. The shift to their baseline position is stored for [`resolve_node_layout`], nested rows
+/// are aligned first so the baselines of their items are in place.
+fn align_flex_baselines<Nodes: Node<Nodes>>(
+  taffy: &mut TaffyTree<NodeContext<'_, Nodes>>,
+  node_id: NodeId,
+) {
+  let children_ids = taffy.children(node_id).unwrap();
+
+  for &child_id in &children_ids {
+    align_flex_baselines(taffy, child_id);
+  }
+
+  let style = taffy.style(node_id).unwrap();
+
+  let reverse = match style.flex_direction {
+    taffy::FlexDirection::Row => false,
+    taffy::FlexDirection::RowReverse => true,
+    _ => return,
+  };
+
+  if style.display != taffy::Display::Flex {
+    return;
+  }
+
+  let align_items = style.align_items;
+
+  let items: Vec<_> = children_ids
+    .into_iter()
+    .filter(|&child_id| {
+      let child_style = taffy.style(child_id).unwrap();
+
+      child_style.position != taffy::Position::Absolute
+        && child_style.align_self.or(align_items) == Some(taffy::AlignItems::Baseline)
+    })
+    .collect();
+
+  let mut lines: Vec<Vec<NodeId>> = Vec::new();
+  let mut last_x = None;
+
+  // a wrapped line starts back at the start of the row
+  for child_id in items {
+    let x = taffy.layout(child_id).unwrap().location.x;
+
+    let wrapped = last_x.is_some_and(|last_x| if reverse { x >= last_x } else { x <= last_x });
+
+    match lines.last_mut() {
+      Some(line) if !wrapped => line.push(child_id),
+      _ => lines.push(vec![child_id]),
+    }
+
+    last_x = Some(x);
+  }
+
+  for line in lines.into_iter().filter(|line| line.len() > 1) {
+    // (height, first baseline, top margin) of each item
+    let metrics: Vec<_> = line
+      .iter()
+      .map(|&child_id| {
+        let layout = taffy.layout(child_id).unwrap();
+
+        (
+          layout.size.height,
+          resolve_first_baseline(taffy, child_id).unwrap_or(layout.size.height),
+          layout.margin.top,
+        )
+      })
+      .collect();
+
+    let laid_out_baseline = metrics
+      .iter()
+      .map(|(height, _, margin)| height + margin)
+      .fold(0.0, f32::max);
+    let baseline = metrics
+      .iter()
+      .map(|(_, baseline, margin)| baseline + margin)
+      .fold(0.0, f32::max);
+
+    for (child_id, (height, first_baseline, _)) in line.into_iter().zip(metrics) {
+      taffy.get_node_context_mut(child_id).unwrap().baseline_shift =
+        baseline - laid_out_baseline + height - first_baseline;
+    }
+  }
+}
+
+/// Returns the first baseline of the node from the top of its border box, the one of its first
+/// in-flow child if it has none of its own.
+fn resolve_first_baseline<Nodes: Node<Nodes>>(
+  taffy: &TaffyTree<NodeContext<'_, Nodes>>,
+  node_id: NodeId,
+) -> Option<f32> {
+  let node_context = taffy.get_node_context(node_id).unwrap();
+  let layout = *taffy.layout(node_id).unwrap();
+
+  if let Some(baseline) = node_context
+    .node
+    .first_baseline(&node_context.context, layout)
+  {
+    return Some(baseline + node_context.line_room.unwrap_or_default());
+  }
+
+  let child_id = taffy
+    .children(node_id)
+    .unwrap()
+    .into_iter()
+    .find(|&child_id| taffy.style(child_id).unwrap().position != taffy::Position::Absolute)?;
+
+  let child_layout = taffy.layout(child_id).unwrap();
+  let child_shift = taffy.get_node_context(child_id).unwrap().baseline_shift;
+
+  Some(
+    child_layout.location.y
+      + child_shift
+      + resolve_first_baseline(taffy, child_id).unwrap_or(child_layout.size.height),
+  )
+}
+
+/// Checks the resources of every node before painting, see [`Node::check_resources`].
+///
+/// Nodes painting an image the size of their box, see [`Node::paints_box_sized_image`], fail
+/// with [`Error::CanvasTooLarge`](crate::Error::CanvasTooLarge) when that image at `scale` would
+/// have more than [`GlobalContext::max_canvas_pixels`], even if the canvas is smaller.
+pub(crate) fn check_node_resources<Nodes: Node<Nodes>>(
+  taffy: &TaffyTree<NodeContext<Nodes>>,
+  node_id: NodeId,
+  scale: f32,
+) -> Result<(), crate::Error> {
+  let node_context = taffy.get_node_context(node_id).unwrap();
+  let context = &node_context.context;
+
+  node_context.node.check_resources(context)?;
+
+  if node_context.node.paints_box_sized_image(context) {
+    let size = taffy.layout(node_id).unwrap().size;
+    let width = (size.width * scale).ceil() as u32;
+    let height = (size.height * scale).ceil() as u32;
+    let max_pixels = context.global.max_canvas_pixels;
+
+    if width as u64 * height as u64 > max_pixels {
+      return Err(crate::Error::CanvasTooLarge {
+        width,
+        height,
+        max_pixels,
+      });
+    }
+  }
+
+  for child_id in taffy.children(node_id).unwrap() {
+    check_node_resources(taffy, child_id, scale)?;
+  }
+
+  Ok(())
+}
+
+/// Returns the layout of the node in canvas coordinates, `offset` being the parent location.
+///
+/// `vertical-align` and the baseline alignment of flex rows are applied here, so their shifts carry
+/// over to the children.
+pub(crate) fn resolve_node_layout<Nodes: Node<Nodes>>(
+  taffy: &TaffyTree<NodeContext<Nodes>>,
+  node_id: NodeId,
+  offset: Point<f32>,
+) -> Layout {
   let mut layout = *taffy.layout(node_id).unwrap();
 
   layout.location.x += offset.x;
   layout.location.y += offset.y;
 
-  let node_context = taffy.get_node_context_mut(node_id).unwrap();
+  let node_context = taffy.get_node_context(node_id).unwrap();
+  let context = &node_context.context;
 
-  if let Some(node_transform) = &node_context.context.style.transform {
-    let node_transform = node_transform.to_affine(
-      &node_context.context,
-      &layout,
-      node_context
-        .context
-        .style
-        .transform_origin
-        .unwrap_or_default(),
-    );
+  layout.location.y += node_context.baseline_shift;
 
-    transform = transform * node_transform;
+  if let Some(room) = node_context.line_room {
+    layout.location.y += room;
+    layout.size.height = (layout.size.height - room).max(0.0);
   }
 
-  node_context.context.transform = transform;
+  if context.style.position == Position::Fixed {
+    layout.location = resolve_fixed_location(taffy, node_id, context, &layout);
+  }
 
-  node_context
-    .node
-    .draw_on_canvas(&node_context.context, canvas, layout);
+  // the shift of a script is part of the room made for it
+  if let Some(vertical_align) = context.style.vertical_align
+    && !(vertical_align.is_script() && node_context.line_room.is_some())
+  {
+    layout.location.y += resolve_vertical_align_offset(context, vertical_align, layout.size.height);
+  }
 
-  if node_context.context.global.draw_debug_border {
-    draw_debug_border(canvas, layout, node_context.context.transform);
+  layout
+}
+
+/// Anchors a `position: fixed` node to the viewport on the axes it has an inset on, it keeps the
+/// position it was laid out at on the others.
+///
+/// Without a viewport height, the height of the root node is the height of the viewport.
+fn resolve_fixed_location<Nodes: Node<Nodes>>(
+  taffy: &TaffyTree<NodeContext<Nodes>>,
+  node_id: NodeId,
+  context: &RenderContext,
+  layout: &Layout,
+) -> Point<f32> {
+  let mut root_id = node_id;
+
+  while let Some(parent_id) = taffy.parent(root_id) {
+    root_id = parent_id;
   }
 
-  for child_id in taffy.children(node_id).unwrap() {
-    render_node(taffy, child_id, canvas, layout.location, transform);
+  let viewport = Size {
+    width: context.viewport.width as f32,
+    height: context.viewport.height.map_or_else(
+      || taffy.layout(root_id).unwrap().size.height,
+      |height| height as f32,
+    ),
+  };
+
+  let inset = context.style.resolve_fixed_inset(context, viewport);
+
+  let resolve = |start: Option<f32>,
+                 end: Option<f32>,
+                 margin: (f32, f32),
+                 size: f32,
+                 full: f32,
+                 laid_out: f32| match (start, end) {
+    (Some(start), _) => start + margin.0,
+    (None, Some(end)) => full - end - margin.1 - size,
+    (None, None) => laid_out,
+  };
+
+  Point {
+    x: resolve(
+      inset.left,
+      inset.right,
+      (layout.margin.left, layout.margin.right),
+      layout.size.width,
+      viewport.width,
+      layout.location.x,
+    ),
+    y: resolve(
+      inset.top,
+      inset.bottom,
+      (layout.margin.top, layout.margin.bottom),
+      layout.size.height,
+      viewport.height,
+      layout.location.y,
+    ),
+  }
+}
+
+/// Combines the parent transform with the node's own `transform` property.
+pub(crate) fn resolve_node_transform(
+  context: &RenderContext,
+  layout: &Layout,
+  parent_transform: Affine,
+) -> Affine {
+  let Some(node_transform) = &context.style.transform else {
+    return parent_transform;
+  };
+
+  parent_transform
+    * node_transform.to_affine(
+      context,
+      layout,
+      context.style.transform_origin.unwrap_or_default(),
+    )
+}
+
+/// Moves the location of the node into its transform, then scales both by the device scale.
+///
+/// Nodes build their paths around their own origin and only add the location afterwards, so the
+/// location has to go through the scale too. Paths are rasterized after the transform, which keeps
+/// the text and the edges sharp at any scale.
+pub(crate) fn apply_device_scale(
+  layout: Layout,
+  transform: Affine,
+  scale: f32,
+) -> (Layout, Affine) {
+  if scale == 1.0 {
+    return (layout, transform);
+  }
+
+  let mut scaled_layout = layout;
+  scaled_layout.location = Point::ZERO;
+
+  let transform = transform
+    * Affine::translation(Size {
+      width: layout.location.x,
+      height: layout.location.y,
+    })
+    * Affine::scale(
+      Size {
+        width: scale,
+        height: scale,
+      },
+      Point::ZERO,
+    );
+
+  (scaled_layout, transform)
+}
+
+/// Returns the canvas area a node paints into, its transformed border box grown by its
+/// outset box shadows, text shadows and its outline.
+///
+/// Content overflowing the border box, like text that doesn't fit, is not included.
+pub(crate) fn resolve_paint_bounds(
+  context: &RenderContext,
+  layout: &Layout,
+  transform: Affine,
+) -> Rect<f32> {
+  let mut extent = 0.0f32;
+
+  let outline = context.style.resolved_outline();
+
+  if outline.style != OutlineStyle::None {
+    let width = outline.width.resolve_to_px(context, layout.size.width);
+    let offset = context
+      .style
+      .outline_offset
+      .resolve_to_px(context, layout.size.width);
+
+    extent = (width.max(0.0) + offset).max(0.0);
+  }
+
+  if let Some(box_shadow) = context.style.box_shadow.as_ref() {
+    for shadow in box_shadow.0.iter().filter(|shadow| !shadow.inset) {
+      let shadow = SizedShadow::from_box_shadow(*shadow, context, layout.size);
+
+      extent = extent.max(
+        shadow.offset_x.abs().max(shadow.offset_y.abs())
+          + shadow.blur_radius
+          + shadow.spread_radius,
+      );
+    }
+  }
+
+  // text shadows are inherited, they only reach past nodes drawing text
+  if let Some(text_shadow) = context.style.text_shadow.as_ref() {
+    let font_size = context
+      .style
+      .font_size
+      .resolve_to_px(context, context.parent_font_size);
+
+    for shadow in text_shadow.0.iter() {
+      let shadow = SizedShadow::from_text_shadow(
+        *shadow,
+        context,
+        Size {
+          width: font_size,
+          height: font_size,
+        },
+      );
+
+      extent = extent.max(shadow.offset_x.abs().max(shadow.offset_y.abs()) + shadow.blur_radius);
+    }
   }
+
+  // one extra pixel for anti-aliased edges
+  extent += 1.0;
+
+  let corners = [
+    Point {
+      x: -extent,
+      y: -extent,
+    },
+    Point {
+      x: layout.size.width + extent,
+      y: -extent,
+    },
+    Point {
+      x: -extent,
+      y: layout.size.height + extent,
+    },
+    Point {
+      x: layout.size.width + extent,
+      y: layout.size.height + extent,
+    },
+  ];
+
+  corners.into_iter().fold(
+    Rect {
+      left: f32::INFINITY,
+      right: f32::NEG_INFINITY,
+      top: f32::INFINITY,
+      bottom: f32::NEG_INFINITY,
+    },
+    |bounds, corner| {
+      let point = corner * transform;
+
+      Rect {
+        left: bounds.left.min(point.x + layout.location.x),
+        right: bounds.right.max(point.x + layout.location.x),
+        top: bounds.top.min(point.y + layout.location.y),
+        bottom: bounds.bottom.max(point.y + layout.location.y),
+      }
+    },
+  )
+}
+
+pub(crate) fn rects_intersect(a: Rect<f32>, b: Rect<f32>) -> bool {
+  a.left < b.right && b.left < a.right && a.top < b.bottom && b.top < a.bottom
 }
 
 fn insert_taffy_node<'ctx, Nodes: Node<Nodes>>(
@@ -224,12 +861,27 @@ fn insert_taffy_node<'ctx, Nodes: Node<Nodes>>(
   render_context: &RenderContext<'ctx>,
 ) -> NodeId {
   let children = node.take_children();
-  let node_style = node.get_style().inherit(&render_context.style);
+  let mut node_style = node.get_style().inherit(&render_context.style);
 
-  let parent_font_size = node_style
+  let mut parent_font_size = node_style
     .font_size
     .resolve_to_px(render_context, render_context.parent_font_size);
 
+  // super- and subscripts are set smaller unless they have a font size of their own, resolved so
+  // their children don't shrink again
+  if node_style
+    .vertical_align
+    .is_some_and(VerticalAlign::is_script)
+    && matches!(node.get_style().font_size, CssValue::Inherit)
+  {
+    parent_font_size *= SCRIPT_FONT_SCALE;
+    node_style.font_size = LengthUnit::Px(parent_font_size);
+  }
+
+  node_style.line_height = node_style
+    .line_height
+    .to_computed(render_context, parent_font_size);
+
   let node_id = taffy
     .new_leaf_with_context(
       node_style.to_taffy_style(render_context),
@@ -239,6 +891,8 @@ fn insert_taffy_node<'ctx, Nodes: Node<Nodes>>(
           ..*render_context
         },
         node,
+        baseline_shift: 0.0,
+        line_room: None,
       },
     )
     .unwrap();
@@ -250,13 +904,246 @@ fn insert_taffy_node<'ctx, Nodes: Node<Nodes>>(
       ..*render_context
     };
 
+    // `display: none` children are left out of the tree, so they take no space at all
     let children_ids = children
       .into_iter()
+      .filter(|child| {
+        child
+          .get_style()
+          .display
+          .inherit(&render_context.style.display, Display::Flex)
+          != Display::None
+      })
       .map(|child| insert_taffy_node(taffy, child, &render_context))
       .collect::<Vec<_>>();
 
     taffy.set_children(node_id, &children_ids).unwrap();
+
+    reserve_script_room(taffy, node_id, &children_ids);
   }
 
   node_id
 }
+
+/// Makes room in a row for its super- and subscripts like a line box, so they aren't clipped.
+///
+/// The room is reserved in the measured boxes of the items, see [`NodeContext::line_room`], the
+/// styles of the row and its items are left alone. A subscript's box reaches down to its lowered
+/// glyphs, the highest superscript moves the other items of the row down.
+///
+/// Only items measured to their content have room made, the ones with a height of their own stay
+/// where they are.
+fn reserve_script_room<Nodes: Node<Nodes>>(
+  taffy: &mut TaffyTree<NodeContext<'_, Nodes>>,
+  node_id: NodeId,
+  children_ids: &[NodeId],
+) {
+  let style = taffy.style(node_id).unwrap();
+
+  if style.display != taffy::Display::Flex
+    || !matches!(
+      style.flex_direction,
+      taffy::FlexDirection::Row | taffy::FlexDirection::RowReverse
+    )
+  {
+    return;
+  }
+
+  // how far each script moves down from the top of the row
+  let offsets: Vec<_> = children_ids
+    .iter()
+    .map(|&child_id| {
+      let context = &taffy.get_node_context(child_id).unwrap().context;
+
+      context
+        .style
+        .vertical_align
+        .filter(|align| align.is_script())
+        .map(|vertical_align| resolve_vertical_align_offset(context, vertical_align, 0.0))
+    })
+    .collect();
+
+  if offsets.iter().all(Option::is_none) {
+    return;
+  }
+
+  let raise = offsets
+    .iter()
+    .flatten()
+    .fold(0.0_f32, |raise, offset| raise.max(-offset));
+
+  for (&child_id, offset) in children_ids.iter().zip(offsets) {
+    let style = taffy.style(child_id).unwrap();
+
+    let measured = taffy.children(child_id).unwrap().is_empty()
+      && style.position != taffy::Position::Absolute
+      && style.size.height.is_auto()
+      && style.aspect_ratio.is_none();
+
+    if !measured || (offset.is_none() && raise == 0.0) {
+      continue;
+    }
+
+    taffy.get_node_context_mut(child_id).unwrap().line_room =
+      Some(raise + offset.unwrap_or_default());
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::layout::{
+    node::{ContainerNode, NodeKind, TextNode},
+    style::{AlignItems, LengthUnit, LengthUnit::Px, LineHeight, Sides, StyleBuilder},
+  };
+
+  use super::*;
+
+  #[test]
+  fn test_line_height_number_inherits_as_multiplier() {
+    let context = GlobalContext::default();
+
+    let child = || -> NodeKind {
+      ContainerNode {
+        style: StyleBuilder::default().font_size(Px(40.0)).build().unwrap(),
+        children: None,
+      }
+      .into()
+    };
+
+    let parent = |line_height: LineHeight| -> NodeKind {
+      ContainerNode {
+        style: StyleBuilder::default()
+          .font_size(Px(10.0))
+          .line_height(line_height)
+          .build()
+          .unwrap(),
+        children: Some(vec![child()]),
+      }
+      .into()
+    };
+
+    let child_line_height = |line_height: LineHeight| {
+      let (taffy, root_node_id) = compute_taffy_layout(
+        Viewport::new(100, 100),
+        &context,
+        parent(line_height),
+        Viewport::new(100, 100).available_space(),
+      )
+      .unwrap();
+      let child_id = taffy.children(root_node_id).unwrap()[0];
+
+      taffy
+        .get_node_context(child_id)
+        .unwrap()
+        .context
+        .style
+        .line_height
+    };
+
+    // a number stays relative to the font size of the child
+    assert_eq!(
+      child_line_height(LineHeight::Number(1.5)),
+      LineHeight::Number(1.5)
+    );
+
+    // an em length is computed against the parent font size before inheriting
+    assert_eq!(
+      child_line_height(LineHeight::Length(LengthUnit::Em(1.5))),
+      LineHeight::Length(Px(15.0))
+    );
+  }
+
+  #[test]
+  fn test_align_items_baseline_aligns_text_baselines() {
+    let context = GlobalContext::default();
+
+    context
+      .font_context
+      .load_and_store(
+        include_bytes!("../../../assets/fonts/noto-sans/NotoSans-Regular.ttf"),
+        None,
+        None,
+      )
+      .unwrap();
+
+    let text = |font_size: f32| -> NodeKind {
+      TextNode {
+        style: StyleBuilder::default()
+          .font_size(Px(font_size))
+          .build()
+          .unwrap(),
+        text: "Baseline".to_string(),
+      }
+      .into()
+    };
+
+    let row = |align_items: AlignItems| -> NodeKind {
+      ContainerNode {
+        style: StyleBuilder::default()
+          .align_items(Some(align_items))
+          .build()
+          .unwrap(),
+        children: Some(vec![
+          text(16.0),
+          text(32.0),
+          // a nested row takes the baseline of its first item
+          ContainerNode {
+            style: StyleBuilder::default()
+              .padding(Sides([Px(4.0), Px(0.0), Px(0.0), Px(0.0)]))
+              .build()
+              .unwrap(),
+            children: Some(vec![text(24.0)]),
+          }
+          .into(),
+        ]),
+      }
+      .into()
+    };
+
+    let viewport = Viewport::new(600, 200);
+
+    let baselines = |align_items: AlignItems| -> Vec<f32> {
+      let (taffy, root_node_id) = compute_taffy_layout(
+        viewport,
+        &context,
+        row(align_items),
+        viewport.available_space(),
+      )
+      .unwrap();
+
+      taffy
+        .children(root_node_id)
+        .unwrap()
+        .into_iter()
+        .map(|child_id| {
+          let layout = resolve_node_layout(&taffy, child_id, Point::ZERO);
+
+          layout.location.y + resolve_first_baseline(&taffy, child_id).unwrap()
+        })
+        .collect()
+    };
+
+    let aligned = baselines(AlignItems::Baseline);
+
+    assert!((aligned[0] - aligned[1]).abs() < 0.5, "{aligned:?}");
+    assert!((aligned[0] - aligned[2]).abs() < 0.5, "{aligned:?}");
+
+    // the tallest text starts at the top of the row
+    let (taffy, root_node_id) = compute_taffy_layout(
+      viewport,
+      &context,
+      row(AlignItems::Baseline),
+      viewport.available_space(),
+    )
+    .unwrap();
+    let big = taffy.children(root_node_id).unwrap()[1];
+    assert_eq!(
+      resolve_node_layout(&taffy, big, Point::ZERO).location.y,
+      0.0
+    );
+
+    let top_aligned = baselines(AlignItems::FlexStart);
+
+    assert!(top_aligned[1] - top_aligned[0] > 5.0, "{top_aligned:?}");
+  }
+}
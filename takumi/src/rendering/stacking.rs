@@ -0,0 +1,345 @@
+use std::sync::{Arc, mpsc::channel};
+
+use taffy::{Layout, NodeId, Point, Rect, TaffyTree};
+
+use crate::{
+  layout::{
+    node::Node,
+    style::{Affine, Display, ImageScalingAlgorithm, MixBlendMode, Overflow, Visibility},
+  },
+  rendering::{
+    BorderProperties, Canvas, ClipMask, DrawCommand, NodeContext, RenderContext, SizedFilter,
+    apply_device_scale, compose_layers_tiles, draw_debug_border, rects_intersect,
+    resolve_layers_tiles, resolve_node_layout, resolve_node_transform, resolve_paint_bounds,
+  },
+};
+
+/// Paints a node that forms a stacking context, along with the descendants stacked in it.
+pub(crate) fn render_node<Nodes: Node<Nodes>>(
+  taffy: &mut TaffyTree<NodeContext<Nodes>>,
+  node_id: NodeId,
+  canvas: &Canvas,
+  offset: Point<f32>,
+  transform: Affine,
+  region: Option<Rect<f32>>,
+  scale: f32,
+) {
+  // only the root node can be `display: none` here, its children are never inserted
+  if taffy
+    .get_node_context(node_id)
+    .unwrap()
+    .context
+    .style
+    .display
+    == Display::None
+  {
+    return;
+  }
+
+  let node_context = taffy.get_node_context(node_id).unwrap();
+
+  let filters = node_context
+    .context
+    .style
+    .filter
+    .as_ref()
+    .filter(|filters| !filters.0.is_empty())
+    .map(|filters| {
+      SizedFilter::from_filters(
+        filters,
+        &node_context.context,
+        taffy.layout(node_id).unwrap().size,
+      )
+    });
+
+  // the shape moves with the node, so it's resolved against the same layout and transform
+  let clip = node_context
+    .context
+    .style
+    .clip_path
+    .as_ref()
+    .map(|clip_path| {
+      let layout = resolve_node_layout(taffy, node_id, offset);
+      let (layout, transform) = apply_device_scale(
+        layout,
+        resolve_node_transform(&node_context.context, &layout, transform),
+        scale,
+      );
+
+      ClipMask::new(clip_path, &node_context.context, layout, transform)
+    });
+
+  // the mask layers are composed like backgrounds over the border box, only their alpha is used
+  let mask = node_context
+    .context
+    .style
+    .mask_image
+    .as_ref()
+    .map(|mask_image| {
+      let context = &node_context.context;
+      let layout = resolve_node_layout(taffy, node_id, offset);
+      let (scaled_layout, transform) = apply_device_scale(
+        layout,
+        resolve_node_transform(context, &layout, transform),
+        scale,
+      );
+
+      let tiles = resolve_layers_tiles(
+        mask_image,
+        context.style.mask_position.as_ref(),
+        context.style.mask_size.as_ref(),
+        context.style.mask_repeat.as_ref(),
+        context,
+        layout,
+      );
+
+      Box::new(DrawCommand::OverlayImage {
+        image: Arc::new(compose_layers_tiles(
+          tiles,
+          layout.size.width as u32,
+          layout.size.height as u32,
+        )),
+        offset: Point {
+          x: scaled_layout.location.x as i32,
+          y: scaled_layout.location.y as i32,
+        },
+        border: BorderProperties::zero(),
+        transform,
+        algorithm: ImageScalingAlgorithm::Auto,
+      })
+    });
+
+  let blend_mode = node_context.context.style.mix_blend_mode;
+
+  // filters, clip paths, masks and blend modes apply to the node and its descendants at once, so
+  // they're painted on a layer first
+  if filters.is_some() || clip.is_some() || mask.is_some() || blend_mode != MixBlendMode::Normal {
+    let (tx, rx) = channel();
+
+    // the filters read around each pixel, so nodes just outside the region can bleed into it
+    let extent = filters
+      .iter()
+      .flatten()
+      .map(|filter| filter.sample_extent() as f32)
+      .sum::<f32>()
+      / scale;
+
+    let region = region.map(|region| Rect {
+      left: region.left - extent,
+      right: region.right + extent,
+      top: region.top - extent,
+      bottom: region.bottom + extent,
+    });
+
+    paint_stacking_context(
+      taffy,
+      node_id,
+      &Canvas::new(tx),
+      offset,
+      transform,
+      region,
+      scale,
+    );
+
+    canvas.draw_layer(
+      rx.try_iter().collect(),
+      filters.unwrap_or_default(),
+      clip,
+      mask,
+      blend_mode,
+    );
+
+    return;
+  }
+
+  paint_stacking_context(taffy, node_id, canvas, offset, transform, region, scale);
+}
+
+/// Paints the node and the descendants stacked in its context, ordered by `z-index`.
+fn paint_stacking_context<Nodes: Node<Nodes>>(
+  taffy: &mut TaffyTree<NodeContext<Nodes>>,
+  node_id: NodeId,
+  canvas: &Canvas,
+  offset: Point<f32>,
+  transform: Affine,
+  region: Option<Rect<f32>>,
+  scale: f32,
+) {
+  let (layout, transform) = paint_node(taffy, node_id, canvas, offset, transform, region, scale);
+
+  let layers = collect_sorted_stacking_layers(taffy, node_id, layout.location, transform);
+
+  let node_context = taffy.get_node_context(node_id).unwrap();
+
+  // the node itself is painted unclipped, only the layers stacked in it are cut to the padding box
+  if node_context.context.style.overflow == Overflow::Hidden {
+    let (scaled_layout, scaled_transform) = apply_device_scale(layout, transform, scale);
+    let clip = ClipMask::padding_box(&node_context.context, scaled_layout, scaled_transform);
+
+    let (tx, rx) = channel();
+
+    paint_stacking_layers(taffy, layers, &Canvas::new(tx), region, scale);
+
+    canvas.draw_layer(
+      rx.try_iter().collect(),
+      Default::default(),
+      Some(clip),
+      None,
+      MixBlendMode::Normal,
+    );
+
+    return;
+  }
+
+  paint_stacking_layers(taffy, layers, canvas, region, scale);
+}
+
+fn paint_stacking_layers<Nodes: Node<Nodes>>(
+  taffy: &mut TaffyTree<NodeContext<Nodes>>,
+  layers: Vec<StackingLayer>,
+  canvas: &Canvas,
+  region: Option<Rect<f32>>,
+  scale: f32,
+) {
+  for layer in layers {
+    if layer.is_stacking_context {
+      render_node(
+        taffy,
+        layer.node_id,
+        canvas,
+        layer.offset,
+        layer.transform,
+        region,
+        scale,
+      );
+    } else {
+      paint_node(
+        taffy,
+        layer.node_id,
+        canvas,
+        layer.offset,
+        layer.transform,
+        region,
+        scale,
+      );
+    }
+  }
+}
+
+/// Collects the descendants stacked in the context of `node_id` in the order they're painted,
+/// by `z-index` and then in tree order.
+pub(crate) fn collect_sorted_stacking_layers<Nodes: Node<Nodes>>(
+  taffy: &TaffyTree<NodeContext<Nodes>>,
+  node_id: NodeId,
+  offset: Point<f32>,
+  transform: Affine,
+) -> Vec<StackingLayer> {
+  let mut layers = Vec::new();
+  collect_stacking_layers(taffy, node_id, offset, transform, &mut layers);
+
+  // stable, so layers with the same z-index keep the tree order
+  layers.sort_by_key(|layer| layer.z_index);
+
+  layers
+}
+
+/// A descendant painted within the stacking context of an ancestor.
+pub(crate) struct StackingLayer {
+  pub(crate) node_id: NodeId,
+  z_index: i32,
+  /// Whether the descendants of the node are painted with it, instead of in the ancestor context.
+  pub(crate) is_stacking_context: bool,
+  pub(crate) offset: Point<f32>,
+  pub(crate) transform: Affine,
+}
+
+/// Returns whether the node stacks its descendants, like a node with `z-index`, `transform`,
+/// `filter` or `clip-path` in CSS.
+///
+/// Unlike CSS, `overflow: hidden` stacks its descendants too, so they're all clipped at once.
+fn forms_stacking_context(context: &RenderContext) -> bool {
+  context.style.z_index.is_some()
+    || context.style.overflow == Overflow::Hidden
+    || context.style.transform.is_some()
+    || context.style.filter.is_some()
+    || context.style.clip_path.is_some()
+    || context.style.mask_image.is_some()
+    || context.style.mix_blend_mode != MixBlendMode::Normal
+}
+
+/// Collects the descendants that are stacked in the context of `node_id` in tree order.
+///
+/// Descendants of nodes that don't form their own stacking context are lifted into this one,
+/// so their `z-index` is compared against all the other layers here.
+fn collect_stacking_layers<Nodes: Node<Nodes>>(
+  taffy: &TaffyTree<NodeContext<Nodes>>,
+  node_id: NodeId,
+  offset: Point<f32>,
+  transform: Affine,
+  layers: &mut Vec<StackingLayer>,
+) {
+  for child_id in taffy.children(node_id).unwrap() {
+    let context = &taffy.get_node_context(child_id).unwrap().context;
+    let is_stacking_context = forms_stacking_context(context);
+
+    layers.push(StackingLayer {
+      node_id: child_id,
+      z_index: context.style.z_index.unwrap_or(0),
+      is_stacking_context,
+      offset,
+      transform,
+    });
+
+    if !is_stacking_context {
+      let layout = resolve_node_layout(taffy, child_id, offset);
+      let child_transform = resolve_node_transform(context, &layout, transform);
+
+      collect_stacking_layers(taffy, child_id, layout.location, child_transform, layers);
+    }
+  }
+}
+
+/// Paints the node itself without its children, returning its layout and transform.
+fn paint_node<Nodes: Node<Nodes>>(
+  taffy: &mut TaffyTree<NodeContext<Nodes>>,
+  node_id: NodeId,
+  canvas: &Canvas,
+  offset: Point<f32>,
+  transform: Affine,
+  region: Option<Rect<f32>>,
+  scale: f32,
+) -> (Layout, Affine) {
+  let layout = resolve_node_layout(taffy, node_id, offset);
+
+  let node_context = taffy.get_node_context_mut(node_id).unwrap();
+
+  let transform = resolve_node_transform(&node_context.context, &layout, transform);
+  let (scaled_layout, scaled_transform) = apply_device_scale(layout, transform, scale);
+
+  node_context.context.transform = scaled_transform;
+  node_context.context.border = Some(BorderProperties::from_context(
+    &node_context.context,
+    &scaled_layout,
+  ));
+
+  let is_in_region = region.is_none_or(|region| {
+    rects_intersect(
+      resolve_paint_bounds(&node_context.context, &layout, transform),
+      region,
+    )
+  });
+
+  if is_in_region {
+    if node_context.context.style.visibility != Visibility::Hidden {
+      node_context
+        .node
+        .draw_on_canvas(&node_context.context, canvas, scaled_layout);
+    }
+
+    if let Some(debug) = node_context.context.global.debug.as_ref() {
+      draw_debug_border(canvas, scaled_layout, node_context.context.transform, debug);
+    }
+  }
+
+  (layout, transform)
+}
@@ -2,25 +2,230 @@ use std::borrow::Cow;
 use std::sync::Arc;
 
 use image::RgbaImage;
-use parley::{Glyph, PositionedLayoutItem, StyleProperty};
-use swash::{Setting, tag_from_bytes};
+use parley::{Glyph, InlineBox, LineMetrics, PositionedLayoutItem, Run, StyleProperty};
+use swash::{FontRef, Setting, tag_from_bytes};
 use taffy::{Layout, Point, Size};
+use unicode_segmentation::UnicodeSegmentation;
 use zeno::{Command, Join, Mask, PathData, Placement, Stroke};
 
 use crate::{
   GlobalContext,
-  layout::style::{Affine, ImageScalingAlgorithm, SizedFontStyle, TextOverflow, TextTransform},
+  layout::{
+    Viewport,
+    style::{
+      Affine, Angle, ImageScalingAlgorithm, InheritedStyle, SizedFontStyle, Style, TextOverflow,
+      TextTransform, VerticalAlign, WritingMode,
+    },
+  },
   rendering::{
-    BorderProperties, Canvas, RenderContext, apply_mask_alpha_to_pixel, overlay_image,
-    resolve_layers_tiles,
+    BorderProperties, Canvas, InitialLetterLayout, LineReservation, RenderContext,
+    apply_mask_alpha_to_pixel, compose_layers_tiles, create_initial_letter, crop_to_placement,
+    resolve_hyphens, resolve_layers_tiles,
   },
-  resources::font::{CachedGlyph, ResolvedGlyph},
+  resources::font::{CachedGlyph, FontMetrics, ResolvedGlyph},
 };
 
 const ELLIPSIS_CHAR: &str = "…";
 
+/// The glyph id fonts use for characters they don't have.
+const NOTDEF_GLYPH_ID: u16 = 0;
+
+/// How characters without a glyph in any loaded font are drawn, see [`GlobalContext::missing_glyph`].
+///
+/// Whatever is drawn, the characters are recorded, see
+/// [`FontContext::take_missing_codepoints`](crate::resources::font::FontContext::take_missing_codepoints).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MissingGlyph {
+  /// Draws the `.notdef` glyph of the font, a box in most fonts but nothing in some.
+  #[default]
+  Notdef,
+  /// Draws nothing.
+  Hidden,
+  /// Strokes a box over the advance of the glyph in the text color.
+  Box,
+  /// Draws this character instead, falling back to `.notdef` if the font doesn't have it either.
+  Replacement(char),
+}
+
+/// The width of the `text-overflow: fade` ramp relative to the font size.
+const FADE_WIDTH_EM: f32 = 3.0;
+
+/// The font size of super- and subscripts relative to the parent, one `font-size: smaller` step.
+pub(crate) const SCRIPT_FONT_SCALE: f32 = 1.0 / 1.2;
+
+/// How far `vertical-align: super` raises the baseline, relative to the parent font size.
+const SUPER_SHIFT_EM: f32 = 1.0 / 3.0;
+
+/// How far `vertical-align: sub` lowers the baseline, relative to the parent font size.
+const SUB_SHIFT_EM: f32 = 0.2;
+
+/// Returns the metrics of the font the renderer would pick for the style, including fallback.
+///
+/// The font is resolved by shaping `text`, pass a sample of the text the metrics are used with,
+/// e.g. a single letter of the script. Returns `None` if no font could be resolved.
+pub fn font_metrics(
+  viewport: Viewport,
+  global: &GlobalContext,
+  text: &str,
+  style: &Style,
+) -> Option<FontMetrics> {
+  let layout = create_root_text_layout(viewport, global, text, style);
+
+  layout.lines().find_map(|line| {
+    line.items().find_map(|item| match item {
+      PositionedLayoutItem::GlyphRun(glyph_run) => Some(FontMetrics::from_run(glyph_run.run())),
+      PositionedLayoutItem::InlineBox(_) => None,
+    })
+  })
+}
+
+/// Shapes the text with the style and resolves its glyphs into the glyph cache, without drawing.
+///
+/// The style is resolved as if the text was the root node of the viewport.
+/// Returns the number of glyphs warmed, glyphs shared by several font runs are counted per run.
+pub fn preload_glyphs(
+  viewport: Viewport,
+  global: &GlobalContext,
+  text: &str,
+  style: &Style,
+) -> usize {
+  let layout = create_root_text_layout(viewport, global, text, style);
+
+  global.font_context.preload_layout_glyphs(&layout)
+}
+
+/// Returns how far down a node with `vertical-align` moves from the top of the line.
+///
+/// Super- and subscripts don't depend on `height`, they move the baseline of their own first line
+/// of text.
+pub(crate) fn resolve_vertical_align_offset(
+  context: &RenderContext,
+  vertical_align: VerticalAlign,
+  height: f32,
+) -> f32 {
+  let font_style = context.style.to_sized_font_style(context);
+  let layout = create_text_layout("x", &font_style, context.global, f32::MAX, None);
+
+  let Some(line) = layout.lines().next() else {
+    return 0.0;
+  };
+
+  let metrics = line.metrics();
+  let baseline = metrics.baseline;
+
+  match vertical_align {
+    VerticalAlign::Top => 0.0,
+    VerticalAlign::Bottom => metrics.line_height - height,
+    VerticalAlign::Baseline => baseline - height,
+    VerticalAlign::Middle => {
+      let x_height = line
+        .items()
+        .find_map(|item| match item {
+          PositionedLayoutItem::GlyphRun(glyph_run) => {
+            Some(FontMetrics::from_run(glyph_run.run()).x_height)
+          }
+          PositionedLayoutItem::InlineBox(_) => None,
+        })
+        .unwrap_or_default();
+
+      baseline - x_height / 2.0 - height / 2.0
+    }
+    // percentages refer to the line height
+    VerticalAlign::Length(length) => {
+      baseline - height - length.resolve_to_px(context, metrics.line_height)
+    }
+    // the node has a smaller font than the line it's aligned with, which is measured at the
+    // parent font size
+    VerticalAlign::Super | VerticalAlign::Sub => {
+      let line_style = SizedFontStyle {
+        font_size: context.parent_font_size,
+        ..font_style.clone()
+      };
+
+      let line_baseline = create_text_layout("x", &line_style, context.global, f32::MAX, None)
+        .lines()
+        .next()
+        .map_or(baseline, |line| line.metrics().baseline);
+
+      let shift = match vertical_align {
+        VerticalAlign::Super => -SUPER_SHIFT_EM,
+        _ => SUB_SHIFT_EM,
+      } * context.parent_font_size;
+
+      line_baseline + shift - baseline
+    }
+  }
+}
+
+/// Shapes the text as if it was the root node of the viewport.
+fn create_root_text_layout(
+  viewport: Viewport,
+  global: &GlobalContext,
+  text: &str,
+  style: &Style,
+) -> parley::Layout<()> {
+  let context = RenderContext {
+    global,
+    viewport,
+    parent_font_size: viewport.font_size,
+    transform: Affine::identity(),
+    style: style.inherit(&InheritedStyle::default()),
+    border: None,
+  };
+
+  let font_style = context.style.to_sized_font_style(&context);
+  let render_text = apply_text_transform(text, font_style.parent.text_transform);
+  let render_text = resolve_hyphens(
+    &render_text,
+    &font_style,
+    global,
+    viewport.width as f32,
+    None,
+  );
+
+  create_text_layout(
+    &render_text,
+    &font_style,
+    global,
+    viewport.width as f32,
+    None,
+  )
+}
+
 /// Draws text on the canvas with the specified font style and layout.
 pub fn draw_text(text: &str, context: &RenderContext, canvas: &Canvas, layout: Layout) {
+  let vertical = context.style.writing_mode.is_vertical();
+
+  // vertical text is laid out as horizontal lines in the content box turned on its side, then
+  // rotated clockwise into place so the first line sits against the right edge
+  let vertical_context;
+  let (context, layout) = if vertical {
+    let content_box = layout.content_box_size();
+
+    vertical_context = RenderContext {
+      transform: Affine::rotation(Angle::new(90.0), Point::ZERO)
+        * Affine::translation(Size {
+          width: layout.border.left + layout.padding.left + content_box.width,
+          height: layout.border.top + layout.padding.top,
+        })
+        * context.transform,
+      ..context.clone()
+    };
+
+    let vertical_layout = Layout {
+      location: layout.location,
+      size: Size {
+        width: content_box.height,
+        height: content_box.width,
+      },
+      ..Layout::new()
+    };
+
+    (&vertical_context, vertical_layout)
+  } else {
+    (context, layout)
+  };
+
   let font_style = context.style.to_sized_font_style(context);
   if font_style.font_size == 0.0 {
     return;
@@ -30,11 +235,26 @@ pub fn draw_text(text: &str, context: &RenderContext, canvas: &Canvas, layout: L
 
   let render_text = apply_text_transform(text, font_style.parent.text_transform);
 
+  // the drop cap is drawn on its own, the rest of the text makes room for it
+  let (initial_letter, render_text, font_style) =
+    match create_initial_letter(&render_text, &font_style, context.global) {
+      Some((letter, rest, rest_style)) => (Some(letter), rest, rest_style),
+      None => (None, &*render_text, font_style),
+    };
+
   let max_height = match font_style.parent.line_clamp {
     Some(max_lines) => Some(MaxHeight::Both(content_box.height, max_lines)),
     None => Some(MaxHeight::Absolute(content_box.height)),
   };
 
+  let render_text = resolve_hyphens(
+    render_text,
+    &font_style,
+    context.global,
+    content_box.width,
+    max_height,
+  );
+
   let mut buffer = create_text_layout(
     &render_text,
     &font_style,
@@ -49,6 +269,12 @@ pub fn draw_text(text: &str, context: &RenderContext, canvas: &Canvas, layout: L
 
   let last_line_range = last_line.text_range();
 
+  // the ramp is measured on the canvas axes, rotated lines are clipped instead
+  let fade = (font_style.parent.text_overflow == TextOverflow::Fade
+    && !vertical
+    && last_line_range.end < render_text.len())
+  .then(|| TextFade::new(last_line.metrics(), font_style.font_size, layout));
+
   let should_append_ellipsis = font_style.parent.text_overflow == TextOverflow::Ellipsis
     && last_line_range.end < render_text.len();
 
@@ -57,6 +283,7 @@ pub fn draw_text(text: &str, context: &RenderContext, canvas: &Canvas, layout: L
       &render_text,
       last_line_range.start,
       last_line_range.end,
+      buffer.lines().count() - 1,
       &font_style,
       context.global,
       content_box.width,
@@ -72,7 +299,8 @@ pub fn draw_text(text: &str, context: &RenderContext, canvas: &Canvas, layout: L
   }
 
   // If we have a mask image on the style, render it using the background tiling logic into a
-  // temporary image and use that as the glyph fill.
+  // temporary image and use that as the glyph fill. The node is masked by its alpha already, so
+  // the fill is opaque to not apply the alpha twice.
   if let Some(images) = &font_style.parent.mask_image {
     let resolved_tiles = resolve_layers_tiles(
       images,
@@ -87,40 +315,185 @@ pub fn draw_text(text: &str, context: &RenderContext, canvas: &Canvas, layout: L
       return;
     }
 
-    let mut composed = RgbaImage::new(content_box.width as u32, content_box.height as u32);
+    let mut composed = compose_layers_tiles(
+      resolved_tiles,
+      content_box.width as u32,
+      content_box.height as u32,
+    );
 
-    for (tile_image, xs, ys) in resolved_tiles {
-      for y in &ys {
-        for x in &xs {
-          overlay_image(
-            &mut composed,
-            &tile_image,
-            Point { x: *x, y: *y },
-            Default::default(),
-            Affine::identity(),
-            ImageScalingAlgorithm::Auto,
-          )
-        }
-      }
+    for pixel in composed.pixels_mut() {
+      pixel.0[3] = u8::MAX;
     }
 
-    draw_buffer(context, &buffer, canvas, font_style, layout, Some(composed));
+    let fill = GlyphFill {
+      image: Some(&composed),
+      fade,
+    };
+
+    if let Some(initial_letter) = &initial_letter {
+      draw_initial_letter(context, initial_letter, canvas, layout, fill);
+    }
+
+    draw_buffer(
+      context,
+      &buffer,
+      &render_text,
+      canvas,
+      font_style,
+      layout,
+      fill,
+    );
 
     return;
   }
 
-  draw_buffer(context, &buffer, canvas, font_style, layout, None);
+  if let Some(initial_letter) = &initial_letter {
+    draw_initial_letter(
+      context,
+      initial_letter,
+      canvas,
+      layout,
+      GlyphFill {
+        image: None,
+        fade: None,
+      },
+    );
+  }
+
+  draw_buffer(
+    context,
+    &buffer,
+    &render_text,
+    canvas,
+    font_style,
+    layout,
+    GlyphFill { image: None, fade },
+  );
+}
+
+/// Draws the drop cap of `initial-letter` at its place in the content box.
+fn draw_initial_letter(
+  context: &RenderContext,
+  initial_letter: &InitialLetterLayout,
+  canvas: &Canvas,
+  layout: Layout,
+  fill: GlyphFill,
+) {
+  let letter_context = RenderContext {
+    transform: Affine::translation(Size {
+      width: initial_letter.offset.x,
+      height: initial_letter.offset.y,
+    }) * context.transform,
+    ..context.clone()
+  };
+
+  draw_buffer(
+    &letter_context,
+    &initial_letter.layout,
+    initial_letter.text,
+    canvas,
+    initial_letter.font_style.clone(),
+    layout,
+    GlyphFill { fade: None, ..fill },
+  );
+}
+
+/// How the glyphs are filled, on top of the coverage of their outlines.
+#[derive(Clone, Copy)]
+struct GlyphFill<'i> {
+  /// The image to sample colors from instead of the text color
+  image: Option<&'i RgbaImage>,
+  /// The ramp fading out the end of the last line
+  fade: Option<TextFade>,
+}
+
+/// A horizontal alpha ramp over the end of a line, in coordinates relative to the border box.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct TextFade {
+  start: f32,
+  end: f32,
+  top: f32,
+  bottom: f32,
+}
+
+impl TextFade {
+  fn new(metrics: &LineMetrics, font_size: f32, layout: Layout) -> Self {
+    let left = layout.border.left + layout.padding.left;
+    let top = layout.border.top + layout.padding.top;
+
+    let advance = (metrics.advance - metrics.trailing_whitespace).max(0.0);
+    let end = left + metrics.offset + advance;
+
+    Self {
+      start: end - (font_size * FADE_WIDTH_EM).min(advance),
+      end,
+      top: top + metrics.min_coord,
+      bottom: top + metrics.max_coord,
+    }
+  }
+
+  /// Returns the opacity at a point, fully opaque outside of the line.
+  fn opacity(&self, x: f32, y: f32) -> f32 {
+    if y < self.top || y >= self.bottom || x <= self.start {
+      return 1.0;
+    }
+
+    if x >= self.end {
+      return 0.0;
+    }
+
+    1.0 - (x - self.start) / (self.end - self.start)
+  }
+
+  /// Scales the alpha values of a row-major area of `width` pixels placed at `left` and `top`.
+  fn apply<'a>(&self, alphas: impl Iterator<Item = &'a mut u8>, width: u32, left: f32, top: f32) {
+    if width == 0 {
+      return;
+    }
+
+    for (index, alpha) in alphas.enumerate() {
+      let x = left + (index as u32 % width) as f32 + 0.5;
+      let y = top + (index as u32 / width) as f32 + 0.5;
+
+      let opacity = self.opacity(x, y);
+
+      if opacity < 1.0 {
+        *alpha = (*alpha as f32 * opacity).round() as u8;
+      }
+    }
+  }
+
+  fn apply_to_mask(&self, mask: &mut [u8], placement: Placement) {
+    self.apply(
+      mask.iter_mut(),
+      placement.width,
+      placement.left as f32,
+      placement.top as f32,
+    );
+  }
 }
 
 fn draw_buffer(
   context: &RenderContext,
   buffer: &parley::Layout<()>,
+  text: &str,
   canvas: &Canvas,
   style: SizedFontStyle,
   layout: Layout,
-  image_fill: Option<RgbaImage>,
+  fill: GlyphFill,
 ) {
+  let writing_mode = style.parent.writing_mode;
+
   for line in buffer.lines() {
+    let metrics = line.metrics();
+
+    // vertical-lr stacks the lines the other way, so each line is mirrored across the box
+    let line_offset = if writing_mode == WritingMode::VerticalLr {
+      layout.content_box_size().height - metrics.min_coord - metrics.max_coord
+    } else {
+      0.0
+    };
+
     for item in line.items() {
       let PositionedLayoutItem::GlyphRun(glyph_run) = item else {
         continue;
@@ -128,8 +501,43 @@ fn draw_buffer(
 
       let run = glyph_run.run();
 
+      let upright = writing_mode.is_vertical()
+        && text[run.text_range()]
+          .chars()
+          .find(|ch| !ch.is_whitespace())
+          .is_some_and(is_upright_in_vertical_text);
+
+      let run_metrics = run.metrics();
+
+      let has_missing_glyphs = glyph_run
+        .positioned_glyphs()
+        .any(|glyph| glyph.id == NOTDEF_GLYPH_ID);
+
+      if has_missing_glyphs {
+        context
+          .global
+          .font_context
+          .record_missing_codepoints(missing_codepoints(run, text));
+      }
+
+      let missing_glyph = context.global.missing_glyph;
+
+      let replacement_glyph_id = match missing_glyph {
+        MissingGlyph::Replacement(ch) if has_missing_glyphs => {
+          let font = run.font();
+
+          FontRef::from_index(font.data.as_ref(), font.index as usize)
+            .map(|font_ref| font_ref.charmap().map(ch))
+            .filter(|glyph_id| *glyph_id != NOTDEF_GLYPH_ID)
+        }
+        _ => None,
+      };
+
       // Collect all glyph IDs for batch processing
-      let glyph_ids = glyph_run.positioned_glyphs().map(|glyph| glyph.id);
+      let glyph_ids = glyph_run
+        .positioned_glyphs()
+        .map(|glyph| glyph.id)
+        .chain(replacement_glyph_id);
 
       // Batch resolve all glyphs in one mutex acquisition
       let resolved_glyphs = context
@@ -139,29 +547,138 @@ fn draw_buffer(
 
       // Draw each glyph using the batch-resolved cache
       glyph_run.positioned_glyphs().for_each(|glyph| {
-        if let Some(cached_glyph) = resolved_glyphs.get(&glyph.id) {
-          draw_glyph(
-            glyph,
-            cached_glyph,
-            canvas,
-            &style,
-            layout,
-            image_fill.as_ref(),
-            context.transform,
-          );
+        let glyph = Glyph {
+          y: glyph.y + line_offset,
+          ..glyph
+        };
+
+        // upright glyphs turn back around the center of their em box
+        let transform = if upright {
+          let center = Point {
+            x: layout.border.left + layout.padding.left + glyph.x + glyph.advance / 2.0,
+            y: layout.border.top + layout.padding.top + glyph.y
+              - (run_metrics.ascent - run_metrics.descent) / 2.0,
+          };
+
+          Affine::rotation(Angle::new(-90.0), center) * context.transform
+        } else {
+          context.transform
+        };
+
+        let glyph_id = if glyph.id == NOTDEF_GLYPH_ID {
+          match missing_glyph {
+            MissingGlyph::Notdef => glyph.id,
+            MissingGlyph::Hidden => return,
+            MissingGlyph::Box => {
+              return draw_missing_glyph_box(glyph, run, canvas, &style, layout, transform);
+            }
+            MissingGlyph::Replacement(_) => replacement_glyph_id.unwrap_or(glyph.id),
+          }
+        } else {
+          glyph.id
+        };
+
+        if let Some(cached_glyph) = resolved_glyphs.get(&glyph_id) {
+          draw_glyph(glyph, cached_glyph, canvas, &style, layout, fill, transform);
         }
       });
     }
   }
 }
 
+/// Returns the visible characters of the run's clusters that were shaped to `.notdef`.
+fn missing_codepoints<'a>(run: &'a Run<'a, ()>, text: &'a str) -> impl Iterator<Item = char> + 'a {
+  run
+    .clusters()
+    .filter(|cluster| cluster.glyphs().any(|glyph| glyph.id == NOTDEF_GLYPH_ID))
+    .flat_map(move |cluster| text[cluster.text_range()].chars())
+    .filter(|ch| !ch.is_whitespace() && !ch.is_control())
+}
+
+/// Strokes a box in place of a missing glyph, from the baseline up to the cap height.
+fn draw_missing_glyph_box(
+  glyph: Glyph,
+  run: &Run<'_, ()>,
+  canvas: &Canvas,
+  style: &SizedFontStyle,
+  layout: Layout,
+  transform: Affine,
+) {
+  if glyph.advance <= 0.0 {
+    return;
+  }
+
+  let font_size = run.font_size();
+  let metrics = FontMetrics::from_run(run);
+
+  let height = if metrics.cap_height > 0.0 {
+    metrics.cap_height
+  } else {
+    font_size * 0.7
+  };
+
+  let transform = Affine::translation(Size {
+    width: layout.border.left + layout.padding.left + glyph.x,
+    height: layout.border.top + layout.padding.top + glyph.y,
+  }) * transform;
+
+  let inset = glyph.advance * 0.1;
+  let (left, right, top) = (inset, glyph.advance - inset, -height);
+
+  let mut paths = vec![
+    Command::MoveTo((left, 0.0).into()),
+    Command::LineTo((right, 0.0).into()),
+    Command::LineTo((right, top).into()),
+    Command::LineTo((left, top).into()),
+    Command::Close,
+  ];
+
+  transform.apply_on_paths(&mut paths);
+
+  let mut stroke = Stroke::new((font_size / 16.0).max(1.0));
+  stroke.scale = false;
+
+  let (mask, mut placement) = Mask::new(&paths).style(stroke).render();
+
+  placement.left += layout.location.x as i32;
+  placement.top += layout.location.y as i32;
+
+  canvas.draw_mask(mask, placement, style.parent.color, None);
+}
+
+/// Returns whether the character stays upright in vertical text instead of turning sideways.
+///
+/// Covers the CJK scripts, their punctuation and fullwidth forms, following the common cases of
+/// `text-orientation: mixed`.
+fn is_upright_in_vertical_text(ch: char) -> bool {
+  matches!(
+    ch,
+    '\u{1100}'..='\u{11FF}'
+      | '\u{2E80}'..='\u{A4CF}'
+      | '\u{A960}'..='\u{A97F}'
+      | '\u{AC00}'..='\u{D7FF}'
+      | '\u{F900}'..='\u{FAFF}'
+      | '\u{FE10}'..='\u{FE1F}'
+      | '\u{FE30}'..='\u{FE4F}'
+      | '\u{FF00}'..='\u{FF60}'
+      | '\u{FFE0}'..='\u{FFE6}'
+      | '\u{1F000}'..='\u{1FAFF}'
+      | '\u{20000}'..='\u{3FFFF}'
+  )
+}
+
+/// Draws a glyph at its position in the layout.
+///
+/// Outlines are rasterized after the translation to the fractional glyph origin, so the coverage
+/// follows the exact advances instead of snapping to whole pixels, and the outline cache stays
+/// independent of the position.
 fn draw_glyph(
   glyph: Glyph,
   cached_glyph: &CachedGlyph,
   canvas: &Canvas,
   style: &SizedFontStyle,
   layout: Layout,
-  image_fill: Option<&RgbaImage>,
+  fill: GlyphFill,
   transform: Affine,
 ) {
   let transform = Affine::translation(Size {
@@ -183,7 +700,20 @@ fn draw_glyph(
       y: layout.location.y as i32 - bitmap.placement.top,
     };
 
-    if let Some(image_fill) = image_fill {
+    let fade_image = |image: &mut RgbaImage| {
+      if let Some(fade) = fill.fade {
+        let width = image.width();
+
+        fade.apply(
+          image.pixels_mut().map(|pixel| &mut pixel.0[3]),
+          width,
+          layout.border.left + layout.padding.left + glyph.x + bitmap.placement.left as f32,
+          layout.border.top + layout.padding.top + glyph.y - bitmap.placement.top as f32,
+        );
+      }
+    };
+
+    if let Some(image_fill) = fill.image {
       let mask = bitmap
         .data
         .iter()
@@ -225,6 +755,8 @@ fn draw_glyph(
         }
       }
 
+      fade_image(&mut bottom);
+
       return canvas.overlay_image(
         Arc::new(bottom),
         offset,
@@ -234,13 +766,15 @@ fn draw_glyph(
       );
     }
 
-    let image = RgbaImage::from_raw(
+    let mut image = RgbaImage::from_raw(
       bitmap.placement.width,
       bitmap.placement.height,
       bitmap.data.clone(),
     )
     .unwrap();
 
+    fade_image(&mut image);
+
     return canvas.overlay_image(
       Arc::new(image),
       offset,
@@ -272,7 +806,11 @@ fn draw_glyph(
 
     transform.apply_on_paths(&mut paths);
 
-    let (mask, mut placement) = Mask::new(&paths).render();
+    let (mut mask, mut placement) = Mask::new(&paths).render();
+
+    if let Some(fade) = fill.fade {
+      fade.apply_to_mask(&mut mask, placement);
+    }
 
     if let Some(ref shadows) = style.text_shadow {
       for shadow in shadows.iter() {
@@ -280,29 +818,7 @@ fn draw_glyph(
       }
     }
 
-    let cropped_fill_image = image_fill.map(|image| {
-      let mut bottom = RgbaImage::new(placement.width, placement.height);
-
-      for y in 0..placement.height {
-        let dest_y = y + placement.top as u32;
-
-        if dest_y >= image.height() {
-          continue;
-        }
-
-        for x in 0..placement.width {
-          let dest_x = x + placement.left as u32;
-
-          if dest_x >= image.width() {
-            continue;
-          }
-
-          bottom.put_pixel(x, y, *image.get_pixel(dest_x, dest_y));
-        }
-      }
-
-      bottom
-    });
+    let cropped_fill_image = fill.image.map(|image| crop_to_placement(image, placement));
 
     placement.left += layout.location.x as i32;
     placement.top += layout.location.y as i32;
@@ -314,7 +830,11 @@ fn draw_glyph(
       stroke.scale = false;
       stroke.join = Join::Bevel;
 
-      let (stroke_mask, mut stroke_placement) = Mask::new(&paths).style(stroke).render();
+      let (mut stroke_mask, mut stroke_placement) = Mask::new(&paths).style(stroke).render();
+
+      if let Some(fade) = fill.fade {
+        fade.apply_to_mask(&mut stroke_mask, stroke_placement);
+      }
 
       stroke_placement.left += layout.location.x as i32;
       stroke_placement.top += layout.location.y as i32;
@@ -339,6 +859,12 @@ pub(crate) enum MaxHeight {
 
 const VARIABLE_FONT_WEIGHT_TAG: u32 = tag_from_bytes(b"wght");
 
+/// The ids of the inline boxes in the text, the tabs are numbered up from `TAB_BOX_ID`.
+const INDENT_BOX_ID: u64 = 0;
+const RESERVATION_BOX_ID: u64 = 1;
+const TAB_BOX_ID: u64 = 2;
+const VERTICAL_ALTERNATES_TAG: u32 = tag_from_bytes(b"vert");
+
 pub(crate) fn create_text_layout(
   text: &str,
   font_style: &SizedFontStyle,
@@ -346,7 +872,171 @@ pub(crate) fn create_text_layout(
   max_width: f32,
   max_height: Option<MaxHeight>,
 ) -> parley::Layout<()> {
-  let mut layout = global.font_context.create_layout(text, |builder| {
+  let mut layout = if text.contains('\t') {
+    create_tabbed_text_layout(text, font_style, global, max_width, max_height)
+  } else {
+    build_text_layout(text, font_style, global, max_width, max_height, &[])
+  };
+
+  layout.align(
+    Some(max_width),
+    font_style.parent.text_align.into(),
+    Default::default(),
+  );
+
+  layout
+}
+
+/// Lays out text with tabs, each tab is replaced with a space widened by an inline box in
+/// front of it so the space ends at the next tab stop.
+///
+/// The stops depend on where the tabs land, so the text is laid out once with empty boxes to
+/// find them and again with the boxes sized.
+fn create_tabbed_text_layout(
+  text: &str,
+  font_style: &SizedFontStyle,
+  global: &GlobalContext,
+  max_width: f32,
+  max_height: Option<MaxHeight>,
+) -> parley::Layout<()> {
+  let mut tabs = text
+    .match_indices('\t')
+    .map(|(index, _)| (index, 0.0))
+    .collect::<Vec<_>>();
+
+  // the space keeps the byte length, so ranges of the layout still index into `text`
+  let text = text.replace('\t', " ");
+
+  let layout = build_text_layout(&text, font_style, global, max_width, max_height, &tabs);
+
+  resolve_tab_widths(&layout, font_style, &mut tabs);
+
+  build_text_layout(&text, font_style, global, max_width, max_height, &tabs)
+}
+
+/// Sizes the box of each tab from where it landed in a layout with empty boxes.
+fn resolve_tab_widths(
+  layout: &parley::Layout<()>,
+  font_style: &SizedFontStyle,
+  tabs: &mut [(usize, f32)],
+) {
+  // the advance of the space each tab was replaced with, letter and word spacing included
+  let mut spaces = vec![0.0; tabs.len()];
+
+  for line in layout.lines() {
+    for run in line.runs() {
+      for cluster in run.clusters() {
+        if let Ok(tab) = tabs.binary_search_by_key(&cluster.text_range().start, |(index, _)| *index)
+        {
+          spaces[tab] = cluster.advance();
+        }
+      }
+    }
+  }
+
+  for line in layout.lines() {
+    // the boxes in front of this one on the line push it along once they are sized
+    let mut shift = 0.0;
+
+    for item in line.items() {
+      let PositionedLayoutItem::InlineBox(inline_box) = item else {
+        continue;
+      };
+
+      let Some(tab) = inline_box.id.checked_sub(TAB_BOX_ID) else {
+        continue;
+      };
+
+      let tab = tab as usize;
+
+      let space = spaces[tab];
+      let start = inline_box.x + shift;
+
+      let interval = font_style.resolve_tab_interval(space);
+
+      let end = if interval > 0.0 {
+        let end = ((start / interval).floor() + 1.0) * interval;
+
+        // a stop closer than half a space is skipped, like in CSS
+        if end - start < space / 2.0 {
+          end + interval
+        } else {
+          end
+        }
+      } else {
+        start
+      };
+
+      let width = end - start - space;
+
+      tabs[tab].1 = width;
+      shift += width;
+    }
+  }
+}
+
+fn build_text_layout(
+  text: &str,
+  font_style: &SizedFontStyle,
+  global: &GlobalContext,
+  max_width: f32,
+  max_height: Option<MaxHeight>,
+  tabs: &[(usize, f32)],
+) -> parley::Layout<()> {
+  let mut layout = shape_text(text, font_style, global, max_width, tabs, &[]);
+
+  let Some(reservation) = font_style.line_reservation else {
+    break_lines(&mut layout, max_width, max_height, &[]);
+
+    return layout;
+  };
+
+  // the lines next to a drop cap are broken narrower first to find where they start
+  break_lines(
+    &mut layout,
+    max_width,
+    max_height,
+    &vec![max_width - reservation.width; reservation.lines as usize],
+  );
+
+  let lines = layout
+    .lines()
+    .take(reservation.lines as usize)
+    .map(|line| (line.text_range().start, line.metrics().advance))
+    .collect::<Vec<_>>();
+
+  // then a box in front of each of them takes the room, so they are aligned as whole lines.
+  // Breaking them where they broke before keeps the box of the next line from fitting at the end
+  let line_starts = lines.iter().map(|(start, _)| *start).collect::<Vec<_>>();
+  let line_widths = lines
+    .iter()
+    .enumerate()
+    .map(|(index, (_, advance))| {
+      if index + 1 < lines.len() {
+        reservation.width + advance + 1.0
+      } else {
+        max_width
+      }
+    })
+    .collect::<Vec<_>>();
+
+  let mut layout = shape_text(text, font_style, global, max_width, tabs, &line_starts);
+
+  break_lines(&mut layout, max_width, max_height, &line_widths);
+
+  layout
+}
+
+/// Shapes the text with the inline boxes of the indent, the tabs and the reserved line starts.
+fn shape_text(
+  text: &str,
+  font_style: &SizedFontStyle,
+  global: &GlobalContext,
+  max_width: f32,
+  tabs: &[(usize, f32)],
+  reserved_line_starts: &[usize],
+) -> parley::Layout<()> {
+  global.font_context.create_layout(text, |builder| {
     let font_weight = font_style.parent.font_weight.into();
 
     builder.push_default(StyleProperty::FontSize(font_style.font_size));
@@ -373,11 +1063,28 @@ pub(crate) fn create_text_layout(
       )));
     }
 
-    if let Some(font_feature_settings) = font_style.parent.font_feature_settings.as_ref()
-      && !font_feature_settings.0.is_empty()
-    {
+    let font_features = font_style
+      .parent
+      .font_feature_settings
+      .as_ref()
+      .map(|settings| settings.0.as_slice())
+      .unwrap_or_default();
+
+    if font_style.parent.writing_mode.is_vertical() {
+      // vertical alternates, like the punctuation placed in the top right corner
+      let mut features = vec![Setting {
+        tag: VERTICAL_ALTERNATES_TAG,
+        value: 1,
+      }];
+
+      features.extend_from_slice(font_features);
+
+      builder.push_default(StyleProperty::FontFeatures(parley::FontSettings::List(
+        Cow::Owned(features),
+      )));
+    } else if !font_features.is_empty() {
       builder.push_default(StyleProperty::FontFeatures(parley::FontSettings::List(
-        Cow::Borrowed(&font_feature_settings.0),
+        Cow::Borrowed(font_features),
       )));
     }
 
@@ -399,22 +1106,67 @@ pub(crate) fn create_text_layout(
     builder.push_default(StyleProperty::OverflowWrap(
       font_style.parent.overflow_wrap.into(),
     ));
-  });
 
-  break_lines(&mut layout, max_width, max_height);
+    // the language picks localized glyph forms while shaping and the fallback fonts for it
+    if let Some(lang) = font_style.parent.lang.as_deref() {
+      builder.push_default(StyleProperty::Locale(Some(lang)));
+    }
 
-  layout.align(
-    Some(max_width),
-    font_style.parent.text_align.into(),
-    Default::default(),
-  );
+    // the indent is an empty box in front of the first line, a negative width pulls the line
+    // back for hanging indents
+    let text_indent = font_style.resolve_text_indent(max_width);
 
-  layout
+    if text_indent != 0.0 {
+      builder.push_inline_box(InlineBox {
+        id: INDENT_BOX_ID,
+        index: 0,
+        width: text_indent,
+        height: 0.0,
+      });
+    }
+
+    if let Some(reservation) = font_style.line_reservation {
+      for index in reserved_line_starts {
+        builder.push_inline_box(InlineBox {
+          id: RESERVATION_BOX_ID,
+          index: *index,
+          width: reservation.width,
+          height: 0.0,
+        });
+      }
+    }
+
+    for (tab, (index, width)) in tabs.iter().enumerate() {
+      builder.push_inline_box(InlineBox {
+        id: TAB_BOX_ID + tab as u64,
+        index: *index,
+        width: *width,
+        height: 0.0,
+      });
+    }
+  })
 }
 
-fn break_lines(layout: &mut parley::Layout<()>, max_width: f32, max_height: Option<MaxHeight>) {
-  let Some(max_height) = max_height else {
+/// Breaks the lines in the width, the first lines are broken in `line_widths` instead.
+fn break_lines(
+  layout: &mut parley::Layout<()>,
+  max_width: f32,
+  max_height: Option<MaxHeight>,
+  line_widths: &[f32],
+) {
+  if max_height.is_none() && line_widths.is_empty() {
     return layout.break_all_lines(Some(max_width));
+  }
+
+  let mut line_widths = line_widths.iter().copied();
+  let mut next_width = || line_widths.next().unwrap_or(max_width);
+
+  let Some(max_height) = max_height else {
+    let mut breaker = layout.break_lines();
+
+    while breaker.break_next(next_width()).is_some() {}
+
+    return breaker.finish();
   };
 
   match max_height {
@@ -422,7 +1174,7 @@ fn break_lines(layout: &mut parley::Layout<()>, max_width: f32, max_height: Opti
       let mut breaker = layout.break_lines();
 
       for _ in 0..lines {
-        if breaker.break_next(max_width).is_none() {
+        if breaker.break_next(next_width()).is_none() {
           // no more lines to break
           break;
         };
@@ -435,7 +1187,7 @@ fn break_lines(layout: &mut parley::Layout<()>, max_width: f32, max_height: Opti
       let mut breaker = layout.break_lines();
 
       while total_height < max_height {
-        let Some((_, height)) = breaker.break_next(max_width) else {
+        let Some((_, height)) = breaker.break_next(next_width()) else {
           // no more lines to break
           break;
         };
@@ -460,7 +1212,7 @@ fn break_lines(layout: &mut parley::Layout<()>, max_width: f32, max_height: Opti
           break;
         }
 
-        let Some((_, height)) = breaker.break_next(max_width) else {
+        let Some((_, height)) = breaker.break_next(next_width()) else {
           // no more lines to break
           break;
         };
@@ -479,30 +1231,93 @@ fn break_lines(layout: &mut parley::Layout<()>, max_width: f32, max_height: Opti
 }
 
 /// Applies text transform to the input text.
+///
+/// The case mappings are the full Unicode ones without language tailoring, so `ß` uppercases to
+/// `SS` and a final `Σ` lowercases to `ς`.
 pub fn apply_text_transform<'a>(input: &'a str, transform: TextTransform) -> Cow<'a, str> {
   match transform {
     TextTransform::None => Cow::Borrowed(input),
     TextTransform::Uppercase => Cow::Owned(input.to_uppercase()),
     TextTransform::Lowercase => Cow::Owned(input.to_lowercase()),
-    TextTransform::Capitalize => {
-      let mut result = String::with_capacity(input.len());
-      let mut start_of_word = true;
-      for ch in input.chars() {
-        if ch.is_alphabetic() {
-          if start_of_word {
-            result.extend(ch.to_uppercase());
-            start_of_word = false;
-          } else {
-            result.extend(ch.to_lowercase());
-          }
-        } else {
-          start_of_word = !ch.is_numeric();
-          result.push(ch);
-        }
+    TextTransform::Capitalize => Cow::Owned(capitalize_words(input)),
+  }
+}
+
+/// Titlecases the first letter of every word, the other characters are kept as they are.
+///
+/// Words follow the Unicode word boundaries, so apostrophes and combining marks stay within
+/// their word. Words starting with a digit, like `3rd`, are left alone.
+fn capitalize_words(input: &str) -> String {
+  let mut result = String::with_capacity(input.len());
+
+  for word in input.split_word_bounds() {
+    let mut chars = word.chars();
+
+    match chars.next() {
+      Some(first) if first.is_alphabetic() => {
+        push_titlecase(&mut result, first);
+        result.push_str(chars.as_str());
       }
-      Cow::Owned(result)
+      _ => result.push_str(word),
     }
   }
+
+  result
+}
+
+/// Pushes the titlecase mapping of the character, which only differs from the uppercase one for
+/// digraphs, ligatures, Georgian and some Greek letters.
+fn push_titlecase(output: &mut String, ch: char) {
+  let mapping = match ch {
+    '\u{01C4}'..='\u{01C6}' => "\u{01C5}",
+    '\u{01C7}'..='\u{01C9}' => "\u{01C8}",
+    '\u{01CA}'..='\u{01CC}' => "\u{01CB}",
+    '\u{01F1}'..='\u{01F3}' => "\u{01F2}",
+    'ß' => "Ss",
+    '\u{0587}' => "\u{0535}\u{0582}",
+    // Mkhedruli letters are their own titlecase, uppercasing turns them into Mtavruli
+    '\u{10D0}'..='\u{10FA}' | '\u{10FD}'..='\u{10FF}' => {
+      output.push(ch);
+      return;
+    }
+    // the letters with ypogegrammeni titlecase to the ones with prosgegrammeni
+    '\u{1F80}'..='\u{1FAF}' => {
+      output.extend(char::from_u32(ch as u32 | 0x8));
+      return;
+    }
+    '\u{1FB2}' => "\u{1FBA}\u{0345}",
+    '\u{1FB3}' => "\u{1FBC}",
+    '\u{1FB4}' => "\u{0386}\u{0345}",
+    '\u{1FB7}' => "\u{0391}\u{0342}\u{0345}",
+    '\u{1FBC}' => "\u{1FBC}",
+    '\u{1FC2}' => "\u{1FCA}\u{0345}",
+    '\u{1FC3}' => "\u{1FCC}",
+    '\u{1FC4}' => "\u{0389}\u{0345}",
+    '\u{1FC7}' => "\u{0397}\u{0342}\u{0345}",
+    '\u{1FCC}' => "\u{1FCC}",
+    '\u{1FF2}' => "\u{1FFA}\u{0345}",
+    '\u{1FF3}' => "\u{1FFC}",
+    '\u{1FF4}' => "\u{038F}\u{0345}",
+    '\u{1FF7}' => "\u{03A9}\u{0342}\u{0345}",
+    '\u{1FFC}' => "\u{1FFC}",
+    '\u{FB00}' => "Ff",
+    '\u{FB01}' => "Fi",
+    '\u{FB02}' => "Fl",
+    '\u{FB03}' => "Ffi",
+    '\u{FB04}' => "Ffl",
+    '\u{FB05}' | '\u{FB06}' => "St",
+    '\u{FB13}' => "\u{0544}\u{0576}",
+    '\u{FB14}' => "\u{0544}\u{0565}",
+    '\u{FB15}' => "\u{0544}\u{056B}",
+    '\u{FB16}' => "\u{054E}\u{0576}",
+    '\u{FB17}' => "\u{0544}\u{056D}",
+    _ => {
+      output.extend(ch.to_uppercase());
+      return;
+    }
+  };
+
+  output.push_str(mapping);
 }
 
 /// Construct a new string with an ellipsis appended such that it fits within `max_width`.
@@ -510,12 +1325,27 @@ fn make_ellipsis_text<'s>(
   render_text: &'s str,
   start_index: usize,
   end_index: usize,
+  line_index: usize,
   font_style: &SizedFontStyle,
   global: &GlobalContext,
   max_width: f32,
 ) -> Cow<'s, str> {
   let mut truncated_text = &render_text[start_index..end_index];
 
+  // the last line is laid out on its own, so it only keeps the indent if it's the first line
+  // and the room for a drop cap if it's next to one
+  let font_style = &SizedFontStyle {
+    text_indent: font_style.text_indent.filter(|_| start_index == 0),
+    line_reservation: font_style
+      .line_reservation
+      .filter(|reservation| line_index < reservation.lines as usize)
+      .map(|reservation| LineReservation {
+        lines: 1,
+        ..reservation
+      }),
+    ..font_style.clone()
+  };
+
   while !truncated_text.is_empty() {
     // try to calculate the last line only with the truncated text and ellipsis character
     let mut text_with_ellipsis = String::with_capacity(truncated_text.len() + ELLIPSIS_CHAR.len());
@@ -552,3 +1382,200 @@ fn make_ellipsis_text<'s>(
   // if there's nothing left, returns nothing
   Cow::Borrowed("")
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use crate::layout::style::{LengthUnit, StyleBuilder, TabSize};
+
+  fn transform(input: &str, transform: TextTransform) -> String {
+    apply_text_transform(input, transform).into_owned()
+  }
+
+  #[test]
+  fn test_text_transform_full_case_mapping() {
+    assert_eq!(transform("straße", TextTransform::Uppercase), "STRASSE");
+    assert_eq!(transform("ﬁnal", TextTransform::Uppercase), "FINAL");
+    assert_eq!(
+      transform("ΟΔΟΣ ΣΟΦΟΣ", TextTransform::Lowercase),
+      "οδος σοφος"
+    );
+    assert_eq!(transform("ÉCOLE", TextTransform::Lowercase), "école");
+  }
+
+  #[test]
+  fn test_text_transform_turkish_i() {
+    // without language tailoring the dotted and dotless i map like in every other language
+    assert_eq!(transform("ıi", TextTransform::Uppercase), "II");
+    assert_eq!(transform("İI", TextTransform::Lowercase), "i\u{307}i");
+    assert_eq!(transform("ılık", TextTransform::Capitalize), "Ilık");
+  }
+
+  #[test]
+  fn test_text_transform_capitalize_words() {
+    assert_eq!(
+      transform("don't stop-me now", TextTransform::Capitalize),
+      "Don't Stop-Me Now"
+    );
+    // only the first letter changes, the rest of the word is kept
+    assert_eq!(
+      transform("iPhone mcDonald", TextTransform::Capitalize),
+      "IPhone McDonald"
+    );
+    assert_eq!(
+      transform("3rd place", TextTransform::Capitalize),
+      "3rd Place"
+    );
+    assert_eq!(
+      transform("(hello) «world»", TextTransform::Capitalize),
+      "(Hello) «World»"
+    );
+  }
+
+  #[test]
+  fn test_text_transform_capitalize_multi_byte() {
+    // combining marks stay part of the first letter
+    assert_eq!(
+      transform("e\u{301}cole e\u{301}te\u{301}", TextTransform::Capitalize),
+      "E\u{301}cole E\u{301}te\u{301}"
+    );
+    assert_eq!(
+      transform("élan ñandú", TextTransform::Capitalize),
+      "Élan Ñandú"
+    );
+    assert_eq!(
+      transform("ǆungla ǉubav", TextTransform::Capitalize),
+      "ǅungla ǈubav"
+    );
+    assert_eq!(transform("ßa ﬁsh", TextTransform::Capitalize), "Ssa Fish");
+    assert_eq!(transform("ᾳδω", TextTransform::Capitalize), "ᾼδω");
+    assert_eq!(
+      transform("საქართველო", TextTransform::Capitalize),
+      "საქართველო"
+    );
+    assert_eq!(
+      transform("東京 tokyo", TextTransform::Capitalize),
+      "東京 Tokyo"
+    );
+  }
+
+  #[test]
+  fn test_lang_picks_language_fallback() {
+    const JAPANESE: &[u8] =
+      include_bytes!("../../../assets/fonts/noto-sans/NotoSansJP-Regular.ttf");
+    const CHINESE: &[u8] = include_bytes!("../../../assets/fonts/noto-sans/NotoSansSC-Regular.ttf");
+
+    let global = GlobalContext::default();
+
+    for font in [CHINESE, JAPANESE] {
+      global
+        .font_context
+        .load_and_store(font, None, None)
+        .unwrap();
+    }
+
+    assert!(
+      global
+        .font_context
+        .set_language_fallbacks("ja", &["Noto Sans JP"])
+    );
+    assert!(
+      global
+        .font_context
+        .set_language_fallbacks("zh", &["Noto Sans SC"])
+    );
+    assert!(
+      !global
+        .font_context
+        .set_language_fallbacks("en", &["Noto Sans JP"])
+    );
+
+    let font_data_len = |lang: Option<&str>| {
+      let style = StyleBuilder::default()
+        .lang(lang.map(ToString::to_string))
+        .build()
+        .unwrap();
+
+      let layout = create_root_text_layout(Viewport::new(100, 100), &global, "骨", &style);
+
+      layout
+        .lines()
+        .flat_map(|line| line.items().collect::<Vec<_>>())
+        .find_map(|item| match item {
+          PositionedLayoutItem::GlyphRun(glyph_run) => Some(glyph_run.run().font().data.len()),
+          PositionedLayoutItem::InlineBox(_) => None,
+        })
+    };
+
+    // the shared character is drawn with the font of the language
+    assert_eq!(font_data_len(Some("ja")), Some(JAPANESE.len()));
+    assert_eq!(font_data_len(Some("zh")), Some(CHINESE.len()));
+    assert_eq!(font_data_len(None), Some(CHINESE.len()));
+  }
+
+  /// Returns where the character at the byte index starts on its line.
+  fn cluster_x(layout: &parley::Layout<()>, index: usize) -> Option<f32> {
+    layout.lines().find_map(|line| {
+      line.items().find_map(|item| {
+        let PositionedLayoutItem::GlyphRun(glyph_run) = item else {
+          return None;
+        };
+
+        let mut x = glyph_run.offset();
+
+        glyph_run.run().visual_clusters().find_map(|cluster| {
+          let start = x;
+          x += cluster.advance();
+
+          (cluster.text_range().start == index).then_some(start)
+        })
+      })
+    })
+  }
+
+  #[test]
+  fn test_tab_size_aligns_to_stops() {
+    let global = GlobalContext::default();
+
+    global
+      .font_context
+      .load_and_store(
+        include_bytes!("../../../assets/fonts/noto-sans/NotoSans-Regular.ttf"),
+        None,
+        None,
+      )
+      .unwrap();
+
+    let layout = |text: &str, tab_size: TabSize| {
+      let style = StyleBuilder::default()
+        .tab_size(tab_size)
+        .font_size(LengthUnit::Px(16.0))
+        .build()
+        .unwrap();
+
+      create_root_text_layout(Viewport::new(1000, 100), &global, text, &style)
+    };
+
+    // the text after the tabs starts at the same stop however long the text in front is
+    let columns = layout(
+      "a\tb\nabcd\tc\n\t\td",
+      TabSize::Length(LengthUnit::Px(100.0)),
+    );
+
+    let x = |index| cluster_x(&columns, index).unwrap().round();
+
+    assert_eq!(x(2), 100.0);
+    assert_eq!(x(9), 100.0);
+    assert_eq!(x(13), 200.0);
+
+    // a number of spaces is a multiple of the advance of a space
+    let spaces = layout(" \tx", TabSize::Spaces(4.0));
+    let space = cluster_x(&layout(" x", TabSize::Spaces(4.0)), 1).unwrap();
+
+    assert_eq!(
+      cluster_x(&spaces, 2).unwrap().round(),
+      (space * 4.0).round()
+    );
+  }
+}
@@ -1,19 +1,20 @@
 use std::{
   borrow::Cow,
-  collections::{HashMap, HashSet},
-  num::NonZeroUsize,
+  collections::{BTreeSet, HashMap, HashSet},
   sync::{Arc, Mutex},
 };
 
 use lru::LruCache;
+use serde::Serialize;
 
 use parley::{
-  GenericFamily, Layout, LayoutContext, RangedBuilder, Run,
+  GenericFamily, Layout, LayoutContext, PositionedLayoutItem, RangedBuilder, Run,
   fontique::{Blob, FallbackKey, FontInfoOverride, Script},
 };
 use swash::{
-  FontRef,
+  FontRef, StringId, Tag,
   scale::{ScaleContext, image::Image, outline::Outline},
+  tag_from_bytes,
 };
 
 /// Represents a resolved glyph that can be either a bitmap image or an outline
@@ -49,39 +50,179 @@ pub struct FontScaleCache {
   glyph_cache: GlyphCache,
 }
 
-/// LRU glyph cache for resolved glyphs
+/// The default number of bytes the [`GlyphCache`] may hold, 16 MiB
+pub const DEFAULT_GLYPH_CACHE_BUDGET: usize = 16 * 1024 * 1024;
+
+/// LRU glyph cache for resolved glyphs, bounded by the approximate memory of the glyphs
 pub struct GlyphCache {
-  /// LRU cache with automatic eviction
+  /// LRU cache, evicted by [`GlyphCache::budget`] instead of entry count
   cache: LruCache<GlyphCacheKey, CachedGlyph>,
+  /// Approximate bytes held by the cached glyphs
+  bytes: usize,
+  /// The maximum number of bytes to hold
+  budget: usize,
+  /// Number of lookups that found a cached glyph
+  hits: u64,
+  /// Number of lookups that found nothing
+  misses: u64,
 }
 
 impl Default for GlyphCache {
   fn default() -> Self {
-    Self {
-      cache: LruCache::new(NonZeroUsize::new(1000).unwrap()),
-    }
+    Self::with_budget(DEFAULT_GLYPH_CACHE_BUDGET)
   }
 }
 
+/// Returns the approximate number of bytes a glyph takes in memory.
+///
+/// Bitmaps are counted by their pixel data and outlines by their points and verbs, so a color
+/// emoji weighs as much as hundreds of outlines.
+fn glyph_cost(glyph: &ResolvedGlyph) -> usize {
+  let data = match glyph {
+    ResolvedGlyph::Image(image) => image.data.len(),
+    ResolvedGlyph::Outline(outline) => size_of_val(outline.points()) + size_of_val(outline.verbs()),
+  };
+
+  size_of::<GlyphCacheKey>() + size_of::<ResolvedGlyph>() + data
+}
+
 impl GlyphCache {
-  /// Get a glyph from the cache, updating access order
+  /// Create a cache holding up to `budget` bytes of glyphs
+  pub fn with_budget(budget: usize) -> Self {
+    Self {
+      cache: LruCache::unbounded(),
+      bytes: 0,
+      budget,
+      hits: 0,
+      misses: 0,
+    }
+  }
+
+  /// Change the byte budget, evicting the least recently used glyphs if the cache shrinks
+  pub fn set_budget(&mut self, budget: usize) {
+    self.budget = budget;
+    self.evict(0);
+  }
+
+  /// Get a glyph from the cache, updating access order and the hit counters
   pub fn get(&mut self, key: &GlyphCacheKey) -> Option<CachedGlyph> {
-    self.cache.get(key).cloned()
+    let glyph = self.cache.get(key).cloned();
+
+    if glyph.is_some() {
+      self.hits += 1;
+    } else {
+      self.misses += 1;
+    }
+
+    glyph
+  }
+
+  /// Insert a glyph into the cache with automatic LRU eviction, returning the cached glyph
+  ///
+  /// A glyph larger than the whole budget is returned without being cached.
+  pub fn insert(&mut self, key: GlyphCacheKey, glyph: ResolvedGlyph) -> CachedGlyph {
+    let cost = glyph_cost(&glyph);
+    let glyph = Arc::new(glyph);
+
+    if let Some(previous) = self.cache.pop(&key) {
+      self.bytes -= glyph_cost(&previous);
+    }
+
+    if cost > self.budget {
+      return glyph;
+    }
+
+    self.evict(cost);
+
+    self.cache.put(key, glyph.clone());
+    self.bytes += cost;
+
+    glyph
   }
 
-  /// Insert a glyph into the cache with automatic LRU eviction
-  pub fn insert(&mut self, key: GlyphCacheKey, glyph: ResolvedGlyph) {
-    self.cache.put(key, Arc::new(glyph));
+  /// Evicts the least recently used glyphs until `incoming` more bytes fit in the budget
+  fn evict(&mut self, incoming: usize) {
+    while self.bytes + incoming > self.budget {
+      let Some((_, glyph)) = self.cache.pop_lru() else {
+        break;
+      };
+
+      self.bytes -= glyph_cost(&glyph);
+    }
   }
 
   /// Clear all cached glyphs
   pub fn clear(&mut self) {
     self.cache.clear();
+    self.bytes = 0;
+  }
+
+  /// Get the number of cached glyphs
+  pub fn len(&self) -> usize {
+    self.cache.len()
   }
 
-  /// Get cache statistics
+  /// Returns whether no glyph is cached
+  pub fn is_empty(&self) -> bool {
+    self.cache.is_empty()
+  }
+
+  /// Get cache statistics (bytes used, byte budget)
   pub fn stats(&self) -> (usize, usize) {
-    (self.cache.len(), self.cache.cap().get())
+    (self.bytes, self.budget)
+  }
+
+  /// Get the lookup counters (hits, misses) since the cache was created
+  pub fn hit_stats(&self) -> (u64, u64) {
+    (self.hits, self.misses)
+  }
+}
+
+/// A font family registered in the [`FontContext`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FamilyInfo {
+  /// The family name used in `font-family`
+  pub name: String,
+  /// The weights of the fonts in the family, sorted and deduplicated
+  ///
+  /// Variable fonts report their default weight.
+  pub weights: Vec<f32>,
+  /// The styles of the fonts in the family, e.g. `normal` or `italic`
+  pub styles: Vec<String>,
+}
+
+/// Vertical metrics of a font at a specific size, in pixels
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FontMetrics {
+  /// Distance from the baseline to the top of the em box
+  pub ascent: f32,
+  /// Distance from the baseline to the bottom of the em box, positive downwards
+  pub descent: f32,
+  /// Recommended additional spacing between lines
+  pub line_gap: f32,
+  /// Height of flat capital letters above the baseline
+  pub cap_height: f32,
+  /// Height of flat lowercase letters above the baseline
+  pub x_height: f32,
+}
+
+impl FontMetrics {
+  /// Read the metrics of the font used by a shaped run, scaled to the run's font size
+  pub fn from_run(run: &Run<'_, ()>) -> Self {
+    let font = run.font();
+    let font_ref = FontRef::from_index(font.data.as_ref(), font.index as usize).unwrap();
+    let metrics = font_ref
+      .metrics(run.normalized_coords())
+      .scale(run.font_size());
+
+    Self {
+      ascent: metrics.ascent,
+      descent: metrics.descent,
+      line_gap: metrics.leading,
+      cap_height: metrics.cap_height,
+      x_height: metrics.x_height,
+    }
   }
 }
 
@@ -95,10 +236,16 @@ pub enum FontError {
   Woff(wuff::WuffErr),
   /// Unsupported Font Format
   UnsupportedFormat,
+  /// The font data couldn't be parsed after decoding
+  Malformed,
+  /// The font is missing a table it needs to be rendered, like `cmap` or any outline table
+  MissingTable(&'static str),
+  /// The fetch function passed to [`FontContext::load_and_store_from_url`] failed
+  Fetch(Box<dyn std::error::Error + Send + Sync>),
 }
 
 /// Supported font formats for loading and processing
-#[derive(Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum FontFormat {
   #[cfg(feature = "woff")]
   /// Web Open Font Format (WOFF) - compressed web font format
@@ -138,6 +285,96 @@ pub fn load_font<'source>(
   }
 }
 
+/// The tables every font needs to map characters, lay them out and measure them.
+const REQUIRED_TABLES: [&str; 5] = ["cmap", "head", "hhea", "hmtx", "maxp"];
+
+/// The tables a glyph can be drawn from, a font needs at least one of them.
+const OUTLINE_TABLES: [&str; 5] = ["glyf", "CFF ", "CFF2", "CBDT", "sbix"];
+
+/// The languages that fall back to their own fonts instead of the ones of the whole script,
+/// like Japanese for the Han script.
+const LANGUAGE_FALLBACKS: [(Script, &str); 41] = [
+  (Script(*b"Arab"), "ar-IR"),
+  (Script(*b"Arab"), "fa"),
+  (Script(*b"Arab"), "ks"),
+  (Script(*b"Arab"), "ku-IQ"),
+  (Script(*b"Arab"), "ku-IR"),
+  (Script(*b"Arab"), "la"),
+  (Script(*b"Arab"), "ota"),
+  (Script(*b"Arab"), "pa-PK"),
+  (Script(*b"Arab"), "ps-AF"),
+  (Script(*b"Arab"), "ps-PK"),
+  (Script(*b"Arab"), "sd"),
+  (Script(*b"Arab"), "ug"),
+  (Script(*b"Arab"), "ur"),
+  (Script(*b"Beng"), "as"),
+  (Script(*b"Beng"), "mni"),
+  (Script(*b"Deva"), "bh"),
+  (Script(*b"Deva"), "bho"),
+  (Script(*b"Deva"), "brx"),
+  (Script(*b"Deva"), "doi"),
+  (Script(*b"Deva"), "hne"),
+  (Script(*b"Deva"), "kok"),
+  (Script(*b"Deva"), "mai"),
+  (Script(*b"Deva"), "mr"),
+  (Script(*b"Deva"), "bne"),
+  (Script(*b"Deva"), "sa"),
+  (Script(*b"Deva"), "sat"),
+  (Script(*b"Ethi"), "am"),
+  (Script(*b"Ethi"), "byn"),
+  (Script(*b"Ethi"), "sid"),
+  (Script(*b"Ethi"), "ti-ER"),
+  (Script(*b"Ethi"), "ti-ET"),
+  (Script(*b"Ethi"), "tig"),
+  (Script(*b"Ethi"), "wal"),
+  (Script(*b"Hani"), "ja"),
+  (Script(*b"Hani"), "ko"),
+  (Script(*b"Hani"), "zh-HK"),
+  (Script(*b"Hani"), "zh-MO"),
+  (Script(*b"Hani"), "zh-SG"),
+  (Script(*b"Hani"), "zh-TW"),
+  (Script(*b"Hebr"), "yi"),
+  (Script(*b"Tibt"), "dz"),
+];
+
+fn table_tag(name: &str) -> Tag {
+  let bytes = name.as_bytes();
+
+  tag_from_bytes(&[bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+/// Checks that decoded TTF or OTF data parses and has the tables needed to render it.
+fn validate_font(data: &[u8]) -> Result<FontRef<'_>, FontError> {
+  let font_ref = FontRef::from_index(data, 0).ok_or(FontError::Malformed)?;
+
+  if let Some(table) = REQUIRED_TABLES
+    .into_iter()
+    .find(|table| font_ref.table(table_tag(table)).is_none())
+  {
+    return Err(FontError::MissingTable(table));
+  }
+
+  if OUTLINE_TABLES
+    .into_iter()
+    .all(|table| font_ref.table(table_tag(table)).is_none())
+  {
+    return Err(FontError::MissingTable(OUTLINE_TABLES[0]));
+  }
+
+  Ok(font_ref)
+}
+
+/// What [`FontContext::inspect`] found in font data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FontReport {
+  /// The detected format of the source
+  pub format: FontFormat,
+  /// The family names of the font in every language it has them in, deduplicated
+  pub family_names: Vec<String>,
+  /// The number of glyphs in the font
+  pub glyph_count: u16,
+}
+
 fn guess_font_format(source: &[u8]) -> Result<FontFormat, FontError> {
   if source.len() < 4 {
     return Err(FontError::UnsupportedFormat);
@@ -158,6 +395,10 @@ fn guess_font_format(source: &[u8]) -> Result<FontFormat, FontError> {
 pub struct FontContext {
   layout: Mutex<(parley::FontContext, LayoutContext<()>)>,
   scale_cache: Mutex<FontScaleCache>,
+  /// Characters drawn without a glyph in any font, see [`FontContext::take_missing_codepoints`]
+  missing_codepoints: Mutex<BTreeSet<char>>,
+  /// URLs registered by [`FontContext::load_and_store_from_url`]
+  loaded_urls: Mutex<HashSet<String>>,
 }
 
 impl Default for FontContext {
@@ -198,8 +439,9 @@ impl FontContext {
     run: &Run<'_, ()>,
     glyph_ids: impl Iterator<Item = u16> + Clone,
   ) -> HashMap<u16, CachedGlyph> {
-    // Collect unique glyph IDs to avoid duplicate work
-    let unique_glyph_ids: HashSet<u16> = glyph_ids.collect();
+    // Collect unique glyph IDs to avoid duplicate work, in order so the cache is filled the same
+    // way on every run
+    let unique_glyph_ids: BTreeSet<u16> = glyph_ids.collect();
 
     // Lock both scale and cache together for optimal performance
     let mut scale_cache = self.scale_cache.lock().unwrap();
@@ -240,17 +482,34 @@ impl FontContext {
 
       // Cache and return the result if we got one
       if let Some(glyph) = resolved {
-        scale_cache.glyph_cache.insert(cache_key, glyph);
-        // Get the cached version (now wrapped in Arc)
-        if let Some(cached_glyph) = scale_cache.glyph_cache.get(&cache_key) {
-          result.insert(glyph_id, cached_glyph);
-        }
+        result.insert(glyph_id, scale_cache.glyph_cache.insert(cache_key, glyph));
       }
     }
 
     result
   }
 
+  /// Resolve every glyph of a shaped layout into the glyph cache without drawing it
+  ///
+  /// Returns the number of distinct glyphs that are now cached.
+  pub fn preload_layout_glyphs(&self, layout: &Layout<()>) -> usize {
+    let mut count = 0;
+
+    for line in layout.lines() {
+      for item in line.items() {
+        let PositionedLayoutItem::GlyphRun(glyph_run) = item else {
+          continue;
+        };
+
+        let glyph_ids = glyph_run.positioned_glyphs().map(|glyph| glyph.id);
+
+        count += self.get_or_resolve_glyphs(glyph_run.run(), glyph_ids).len();
+      }
+    }
+
+    count
+  }
+
   /// Get or resolve a single glyph using the cache (backward compatibility)
   pub fn get_or_resolve_glyph(&self, run: &Run<'_, ()>, glyph_id: u16) -> Option<CachedGlyph> {
     self
@@ -289,31 +548,227 @@ impl FontContext {
     scale_cache.glyph_cache.clear();
   }
 
-  /// Get glyph cache statistics (current_entries, max_entries)
+  /// Get glyph cache statistics (bytes_used, byte_budget)
   pub fn glyph_cache_stats(&self) -> (usize, usize) {
     let scale_cache = self.scale_cache.lock().unwrap();
     scale_cache.glyph_cache.stats()
   }
 
+  /// Get the number of glyphs in the glyph cache
+  pub fn glyph_cache_len(&self) -> usize {
+    let scale_cache = self.scale_cache.lock().unwrap();
+    scale_cache.glyph_cache.len()
+  }
+
+  /// Get the glyph cache lookup counters (hits, misses)
+  pub fn glyph_cache_hit_stats(&self) -> (u64, u64) {
+    let scale_cache = self.scale_cache.lock().unwrap();
+    scale_cache.glyph_cache.hit_stats()
+  }
+
+  /// Records characters that were drawn without a glyph in any loaded font
+  pub(crate) fn record_missing_codepoints(&self, codepoints: impl IntoIterator<Item = char>) {
+    self.missing_codepoints.lock().unwrap().extend(codepoints);
+  }
+
+  /// Take the characters drawn without a glyph since the last call, sorted by codepoint
+  ///
+  /// Useful to catch a font that isn't loaded, like missing CJK text in a staging render.
+  pub fn take_missing_codepoints(&self) -> Vec<char> {
+    std::mem::take(&mut *self.missing_codepoints.lock().unwrap())
+      .into_iter()
+      .collect()
+  }
+
+  /// Checks if any font is loaded, text can't be drawn without one
+  pub fn has_fonts(&self) -> bool {
+    let mut lock = self.layout.lock().unwrap();
+
+    lock.0.collection.family_names().next().is_some()
+  }
+
+  /// List the font families loaded with [`FontContext::load_and_store`], sorted by name
+  pub fn loaded_families(&self) -> Vec<FamilyInfo> {
+    let mut lock = self.layout.lock().unwrap();
+    let collection = &mut lock.0.collection;
+
+    let names = collection
+      .family_names()
+      .map(ToString::to_string)
+      .collect::<Vec<_>>();
+
+    let mut families = names
+      .into_iter()
+      .filter_map(|name| {
+        let family = collection.family_by_name(&name)?;
+
+        let mut weights = family
+          .fonts()
+          .iter()
+          .map(|font| font.weight().value())
+          .collect::<Vec<_>>();
+        weights.sort_by(f32::total_cmp);
+        weights.dedup();
+
+        let mut styles = Vec::new();
+        for font in family.fonts() {
+          let style = font.style().to_string();
+
+          if !styles.contains(&style) {
+            styles.push(style);
+          }
+        }
+
+        Some(FamilyInfo {
+          name,
+          weights,
+          styles,
+        })
+      })
+      .collect::<Vec<_>>();
+
+    families.sort_by(|a, b| a.name.cmp(&b.name));
+
+    families
+  }
+
+  /// Change the glyph cache budget in bytes, evicting glyphs if the new budget is smaller
+  pub fn set_glyph_cache_budget(&self, budget: usize) {
+    let mut scale_cache = self.scale_cache.lock().unwrap();
+    scale_cache.glyph_cache.set_budget(budget);
+  }
+
   /// Creates a new font context.
   pub fn new() -> Self {
+    Self::with_glyph_cache_budget(DEFAULT_GLYPH_CACHE_BUDGET)
+  }
+
+  /// Creates a new font context with the fonts loaded, each registered under its family name and
+  /// generic family, like `sans-serif` for the font used when no `font-family` matches.
+  ///
+  /// A new font context has no fonts, rendering text fails with [`crate::Error::FontMissing`]
+  /// until one is loaded, so this gives apps defaults without embedding any in takumi:
+  ///
+  /// ```rust,ignore
+  /// let font_context = FontContext::with_embedded_defaults(&[(
+  ///   include_bytes!("Inter.woff2"),
+  ///   "Inter",
+  ///   GenericFamily::SansSerif,
+  /// )])?;
+  /// ```
+  pub fn with_embedded_defaults(fonts: &[(&[u8], &str, GenericFamily)]) -> Result<Self, FontError> {
+    let font_context = Self::new();
+
+    for (source, family_name, generic_family) in fonts {
+      font_context.load_and_store(
+        source,
+        Some(FontInfoOverride {
+          family_name: Some(family_name),
+          ..Default::default()
+        }),
+        Some(*generic_family),
+      )?;
+    }
+
+    Ok(font_context)
+  }
+
+  /// Creates a new font context with a glyph cache holding up to `budget` bytes of glyphs.
+  pub fn with_glyph_cache_budget(budget: usize) -> Self {
     Self {
       layout: Mutex::new((parley::FontContext::default(), LayoutContext::default())),
       scale_cache: Mutex::new(FontScaleCache {
         scale: ScaleContext::default(),
-        glyph_cache: GlyphCache::default(),
+        glyph_cache: GlyphCache::with_budget(budget),
       }),
+      missing_codepoints: Mutex::new(BTreeSet::new()),
+      loaded_urls: Mutex::new(HashSet::new()),
+    }
+  }
+
+  /// Fetches the font at `url` with `fetch` and registers it like [`FontContext::load_and_store`]
+  ///
+  /// Takumi doesn't ship an HTTP client, `fetch` is the one of your runtime, like `reqwest` on a
+  /// server or the `fetch` API in wasm. A URL is only fetched once per context, later calls return
+  /// `Ok(false)` without calling `fetch`, `Ok(true)` means the font was fetched and registered.
+  pub async fn load_and_store_from_url<F, Fut, B, E>(
+    &self,
+    url: &str,
+    fetch: F,
+  ) -> Result<bool, FontError>
+  where
+    F: FnOnce(&str) -> Fut,
+    Fut: Future<Output = Result<B, E>>,
+    B: AsRef<[u8]>,
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+  {
+    if self.loaded_urls.lock().unwrap().contains(url) {
+      return Ok(false);
+    }
+
+    let source = fetch(url)
+      .await
+      .map_err(|err| FontError::Fetch(err.into()))?;
+
+    // another call may have finished fetching the same url in the meantime
+    if !self.loaded_urls.lock().unwrap().insert(url.to_string()) {
+      return Ok(false);
+    }
+
+    if let Err(err) = self.load_and_store(source.as_ref(), None, None) {
+      self.loaded_urls.lock().unwrap().remove(url);
+
+      return Err(err);
+    }
+
+    Ok(true)
+  }
+
+  /// Decodes and validates font data without registering it
+  ///
+  /// Fails with the same errors as [`FontContext::load_and_store`] would.
+  pub fn inspect(source: &[u8]) -> Result<FontReport, FontError> {
+    let format = guess_font_format(source)?;
+    let data = load_font(source, Some(format))?;
+    let font_ref = validate_font(&data)?;
+
+    let mut family_names = Vec::new();
+
+    for string in font_ref.localized_strings() {
+      if string.id() != StringId::Family {
+        continue;
+      }
+
+      let name = string.to_string();
+
+      if !family_names.contains(&name) {
+        family_names.push(name);
+      }
     }
+
+    Ok(FontReport {
+      format,
+      family_names,
+      glyph_count: font_ref.metrics(&[]).glyph_count,
+    })
   }
 
   /// Loads font into internal font db
+  ///
+  /// The decoded font is validated first, data that can't be rendered is rejected with
+  /// [`FontError::Malformed`] or [`FontError::MissingTable`] instead of being registered.
   pub fn load_and_store(
     &self,
     source: &[u8],
     info_override: Option<FontInfoOverride<'_>>,
     generic_family: Option<GenericFamily>,
   ) -> Result<(), FontError> {
-    let font_data = Blob::new(Arc::new(match load_font(source, None)? {
+    let font_data = load_font(source, None)?;
+
+    // a broken font would register fine and only fail to draw later
+    validate_font(&font_data)?;
+
+    let font_data = Blob::new(Arc::new(match font_data {
       Cow::Owned(vec) => vec,
       Cow::Borrowed(slice) => slice.to_vec(),
     }));
@@ -336,8 +791,188 @@ impl FontContext {
           .collection
           .append_fallbacks(FallbackKey::new(*script, None), std::iter::once(family));
       }
+
+      // text with a `lang` looks up the fallbacks of its language, not the whole script
+      for (script, lang) in LANGUAGE_FALLBACKS {
+        lock
+          .0
+          .collection
+          .append_fallbacks(FallbackKey::from((script, lang)), std::iter::once(family));
+      }
     }
 
     Ok(())
   }
+
+  /// Tries the families first when falling back for text in the `lang` language, like a
+  /// Japanese font for `ja` so the shared Han characters are drawn in the Japanese forms.
+  ///
+  /// Only the languages with their own glyph forms or fonts are tracked, returns `false` if
+  /// the language isn't or none of the families are loaded.
+  pub fn set_language_fallbacks(&self, lang: &str, families: &[&str]) -> bool {
+    let mut lock = self.layout.lock().unwrap();
+    let collection = &mut lock.0.collection;
+
+    let preferred = families
+      .iter()
+      .filter_map(|name| collection.family_id(name))
+      .collect::<Vec<_>>();
+
+    if preferred.is_empty() {
+      return false;
+    }
+
+    let fallbacks = Script::all_samples()
+      .iter()
+      .map(|(script, _)| FallbackKey::from((*script, lang)))
+      .filter(FallbackKey::is_tracked)
+      .map(|key| {
+        let rest = collection
+          .fallback_families(key)
+          .filter(|family| !preferred.contains(family))
+          .collect::<Vec<_>>();
+
+        (key, rest)
+      })
+      .collect::<Vec<_>>();
+
+    if fallbacks.is_empty() {
+      return false;
+    }
+
+    for (key, rest) in fallbacks {
+      collection.set_fallbacks(key, preferred.iter().copied().chain(rest));
+    }
+
+    // the collection keeps the families of the last lookup, looking up a script without
+    // fallbacks drops the ones that were just reordered
+    collection.fallback_families(Script(*b"Zzzz")).count();
+
+    true
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn key(glyph_id: u16) -> GlyphCacheKey {
+    GlyphCacheKey {
+      font_id: 0,
+      glyph_id,
+      font_size: 16,
+      variations_hash: 0,
+    }
+  }
+
+  fn bitmap(pixels: usize) -> ResolvedGlyph {
+    ResolvedGlyph::Image(Image {
+      data: vec![0; pixels * 4],
+      ..Image::new()
+    })
+  }
+
+  #[test]
+  fn test_glyph_cache_evicts_by_bytes() {
+    let small = glyph_cost(&bitmap(1));
+    let large = glyph_cost(&bitmap(100));
+
+    let mut cache = GlyphCache::with_budget(small * 3);
+
+    for glyph_id in 0..3 {
+      cache.insert(key(glyph_id), bitmap(1));
+    }
+
+    assert_eq!(cache.stats(), (small * 3, small * 3));
+
+    // touch the first glyph so it's the most recently used
+    assert!(cache.get(&key(0)).is_some());
+
+    cache.set_budget(small * 2);
+
+    assert_eq!(cache.stats(), (small * 2, small * 2));
+    assert!(cache.get(&key(0)).is_some());
+    assert!(cache.get(&key(2)).is_some());
+    assert!(cache.get(&key(1)).is_none());
+    assert_eq!(cache.hit_stats(), (3, 1));
+
+    // a glyph over the whole budget is handed back without flushing the cache
+    cache.insert(key(3), bitmap(100));
+
+    assert!(large > small * 2);
+    assert_eq!(cache.len(), 2);
+    assert!(cache.get(&key(3)).is_none());
+  }
+
+  #[test]
+  fn test_glyph_cache_replaces_costs() {
+    let mut cache = GlyphCache::with_budget(usize::MAX);
+
+    cache.insert(key(0), bitmap(100));
+    cache.insert(key(0), bitmap(1));
+
+    assert_eq!(cache.stats().0, glyph_cost(&bitmap(1)));
+    assert_eq!(cache.len(), 1);
+
+    cache.clear();
+
+    assert_eq!(cache.stats().0, 0);
+    assert!(cache.is_empty());
+  }
+
+  #[test]
+  fn test_loaded_families() {
+    let context = FontContext::new();
+
+    assert!(context.loaded_families().is_empty());
+
+    context
+      .load_and_store(
+        include_bytes!("../../../assets/fonts/geist/Geist[wght].woff2"),
+        None,
+        None,
+      )
+      .unwrap();
+
+    let families = context.loaded_families();
+
+    assert_eq!(families.len(), 1);
+    assert_eq!(families[0].name, "Geist");
+    assert_eq!(families[0].styles, vec!["normal".to_string()]);
+    assert!(!families[0].weights.is_empty());
+  }
+
+  #[test]
+  fn test_font_context_with_embedded_defaults() {
+    let context = FontContext::with_embedded_defaults(&[(
+      include_bytes!("../../../assets/fonts/geist/Geist[wght].woff2"),
+      "Default Sans",
+      GenericFamily::SansSerif,
+    )])
+    .unwrap();
+
+    assert!(context.has_fonts());
+
+    let families = context.loaded_families();
+
+    assert_eq!(families.len(), 1);
+    assert_eq!(families[0].name, "Default Sans");
+
+    assert!(
+      FontContext::with_embedded_defaults(&[(b"not a font", "Broken", GenericFamily::SansSerif)])
+        .is_err()
+    );
+  }
+
+  #[test]
+  fn test_font_context_glyph_cache_budget() {
+    let context = FontContext::with_glyph_cache_budget(1024);
+
+    assert_eq!(context.glyph_cache_stats(), (0, 1024));
+
+    context.set_glyph_cache_budget(0);
+
+    assert_eq!(context.glyph_cache_stats(), (0, 0));
+    assert_eq!(context.glyph_cache_len(), 0);
+  }
 }
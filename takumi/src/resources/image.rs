@@ -5,14 +5,21 @@
 
 use std::{
   borrow::Cow,
-  collections::HashMap,
-  sync::{Arc, RwLock},
+  fs::read,
+  io::{self, ErrorKind},
+  num::NonZeroUsize,
+  path::{Path, PathBuf},
+  sync::{Arc, Mutex},
+  thread::sleep,
+  time::{Duration, Instant},
 };
 
 use image::{
-  RgbaImage,
+  AnimationDecoder, ImageFormat, RgbaImage,
+  codecs::{gif::GifDecoder, webp::WebPDecoder},
   imageops::{FilterType, resize},
 };
+use lru::LruCache;
 
 /// Represents the state of an image resource.
 pub type ImageResult = Result<Arc<ImageSource>, ImageResourceError>;
@@ -27,24 +34,376 @@ pub enum ImageSource {
   Bitmap(RgbaImage),
 }
 
+/// A source of images referenced by `src` in image nodes.
+///
+/// Implement this trait to plug in your own loader (filesystem, object storage, etc.)
+/// and set it as [`GlobalContext::persistent_image_store`](crate::GlobalContext::persistent_image_store).
+/// Rendering is synchronous, so `fetch` is expected to block until the image is available.
+pub trait ImageStore: Send + Sync {
+  /// Get an image that is already in the store.
+  fn get(&self, src: &str) -> Option<Arc<ImageSource>>;
+
+  /// Insert an image into the store.
+  fn insert(&self, src: &str, image: Arc<ImageSource>);
+
+  /// Clear the store.
+  fn clear(&self);
+
+  /// Loads an image that is not in the store yet, called when [`ImageStore::get`] misses.
+  ///
+  /// The default implementation doesn't load anything.
+  fn fetch(&self, _src: &str) -> ImageResult {
+    Err(ImageResourceError::Unknown)
+  }
+
+  /// Loads an image like [`ImageStore::fetch`], giving up after `timeout`.
+  ///
+  /// Called by [`RetryImageStore`] when it has a timeout. Stores loading over the network should
+  /// pass the timeout to their client and fail with [`ImageResourceError::TimedOut`], a fetch
+  /// can't be interrupted from the outside. The default implementation ignores the timeout.
+  fn fetch_with_timeout(&self, src: &str, _timeout: Duration) -> ImageResult {
+    self.fetch(src)
+  }
+
+  /// Reports the images held in memory by the store.
+  ///
+  /// The default implementation reports an empty store, for stores that don't cache.
+  fn usage(&self) -> ImageStoreUsage {
+    ImageStoreUsage::default()
+  }
+}
+
+/// The images an [`ImageStore`] holds in memory.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImageStoreUsage {
+  /// The number of stored images
+  pub entries: usize,
+  /// The approximate bytes of the stored images, see [`ImageSource::byte_size`]
+  pub bytes: usize,
+}
+
+/// The default number of bytes a [`PersistentImageStore`] may hold, 256 MiB
+pub const DEFAULT_IMAGE_STORE_CAPACITY: usize = 256 * 1024 * 1024;
+
 /// Represents a persistent image store.
-#[derive(Default, Debug)]
-pub struct PersistentImageStore(RwLock<HashMap<String, Arc<ImageSource>>>);
+///
+/// Images are kept until the total of their [`ImageSource::byte_size`] exceeds the capacity, then
+/// the least recently used ones are evicted. Renders holding an evicted image keep their `Arc`
+/// alive, so eviction never frees an image that is in use.
+#[derive(Debug)]
+pub struct PersistentImageStore {
+  images: Mutex<StoredImages>,
+  capacity: usize,
+}
+
+#[derive(Debug)]
+struct StoredImages {
+  cache: LruCache<String, Arc<ImageSource>>,
+  bytes: usize,
+}
+
+impl Default for PersistentImageStore {
+  fn default() -> Self {
+    Self::with_capacity(DEFAULT_IMAGE_STORE_CAPACITY)
+  }
+}
 
 impl PersistentImageStore {
-  /// Get an image from the store.
-  pub fn get(&self, src: &str) -> Option<Arc<ImageSource>> {
-    self.0.read().unwrap().get(src).cloned()
+  /// Creates a store holding up to `capacity` bytes of decoded images.
+  ///
+  /// The most recently inserted image is always kept, even if it's larger than the capacity.
+  pub fn with_capacity(capacity: usize) -> Self {
+    Self {
+      images: Mutex::new(StoredImages {
+        cache: LruCache::unbounded(),
+        bytes: 0,
+      }),
+      capacity,
+    }
   }
 
-  /// Insert an image into the store.
-  pub fn insert(&self, src: &str, image: Arc<ImageSource>) {
-    self.0.write().unwrap().insert(src.to_string(), image);
+  /// Returns the capacity in bytes.
+  pub fn capacity(&self) -> usize {
+    self.capacity
   }
+}
 
-  /// Clear the store.
-  pub fn clear(&self) {
-    self.0.write().unwrap().clear();
+impl ImageStore for PersistentImageStore {
+  fn get(&self, src: &str) -> Option<Arc<ImageSource>> {
+    self.images.lock().unwrap().cache.get(src).cloned()
+  }
+
+  fn insert(&self, src: &str, image: Arc<ImageSource>) {
+    let mut images = self.images.lock().unwrap();
+    let size = image.byte_size();
+
+    if let Some(previous) = images.cache.pop(src) {
+      images.bytes -= previous.byte_size();
+    }
+
+    while images.bytes.saturating_add(size) > self.capacity {
+      let Some((_, evicted)) = images.cache.pop_lru() else {
+        break;
+      };
+
+      images.bytes -= evicted.byte_size();
+    }
+
+    images.cache.put(src.to_string(), image);
+    images.bytes += size;
+  }
+
+  fn clear(&self) {
+    let mut images = self.images.lock().unwrap();
+
+    images.cache.clear();
+    images.bytes = 0;
+  }
+
+  fn usage(&self) -> ImageStoreUsage {
+    let images = self.images.lock().unwrap();
+
+    ImageStoreUsage {
+      entries: images.cache.len(),
+      bytes: images.bytes,
+    }
+  }
+}
+
+/// An image store that loads `file://` sources from a local directory.
+///
+/// Only files inside the root directory can be loaded, paths resolving outside of it
+/// (e.g. with `..` or symlinks) are rejected with [`ImageResourceError::Forbidden`].
+/// Loaded images are cached in memory, up to [`DEFAULT_IMAGE_STORE_CAPACITY`] bytes unless created
+/// with [`FileSystemImageStore::with_capacity`].
+#[derive(Debug)]
+pub struct FileSystemImageStore {
+  root: PathBuf,
+  cache: PersistentImageStore,
+}
+
+impl FileSystemImageStore {
+  /// Creates a store serving files from `root`, the directory must exist.
+  pub fn new(root: impl AsRef<Path>) -> io::Result<Self> {
+    Self::with_capacity(root, DEFAULT_IMAGE_STORE_CAPACITY)
+  }
+
+  /// Creates a store serving files from `root` that caches up to `capacity` bytes of decoded
+  /// images, see [`PersistentImageStore::with_capacity`].
+  pub fn with_capacity(root: impl AsRef<Path>, capacity: usize) -> io::Result<Self> {
+    Ok(Self {
+      root: root.as_ref().canonicalize()?,
+      cache: PersistentImageStore::with_capacity(capacity),
+    })
+  }
+
+  /// Returns the canonicalized root directory.
+  pub fn root(&self) -> &Path {
+    &self.root
+  }
+
+  /// Resolves a `file://` source to a path inside the root directory.
+  ///
+  /// Relative paths are resolved against the root directory.
+  pub fn resolve_path(&self, src: &str) -> Result<PathBuf, ImageResourceError> {
+    let Some(path) = src.strip_prefix("file://") else {
+      return Err(ImageResourceError::Unknown);
+    };
+
+    let path = self.root.join(path);
+
+    let path = path.canonicalize().map_err(|err| match err.kind() {
+      ErrorKind::NotFound => ImageResourceError::NotFound,
+      _ => ImageResourceError::IoError(err),
+    })?;
+
+    if !path.starts_with(&self.root) {
+      return Err(ImageResourceError::Forbidden);
+    }
+
+    Ok(path)
+  }
+}
+
+impl ImageStore for FileSystemImageStore {
+  fn get(&self, src: &str) -> Option<Arc<ImageSource>> {
+    self.cache.get(src)
+  }
+
+  fn insert(&self, src: &str, image: Arc<ImageSource>) {
+    self.cache.insert(src, image);
+  }
+
+  fn clear(&self) {
+    self.cache.clear();
+  }
+
+  fn fetch(&self, src: &str) -> ImageResult {
+    let path = self.resolve_path(src)?;
+    let bytes = read(path).map_err(ImageResourceError::IoError)?;
+    let image = load_image_source_from_bytes(&bytes)?;
+
+    self.cache.insert(src, image.clone());
+
+    Ok(image)
+  }
+
+  fn usage(&self) -> ImageStoreUsage {
+    self.cache.usage()
+  }
+}
+
+/// How long [`RetryImageStore`] remembers a failed fetch by default.
+pub const DEFAULT_FETCH_FAILURE_TTL: Duration = Duration::from_secs(10);
+
+/// How many failed sources [`RetryImageStore`] remembers at most, the least recently failed
+/// ones are forgotten first.
+pub const MAX_REMEMBERED_FETCH_FAILURES: NonZeroUsize = NonZeroUsize::new(1024).unwrap();
+
+/// Wraps an image store and retries failed fetches with an exponential backoff.
+///
+/// When every attempt fails, the error is reported as [`ImageResourceError::FetchFailed`], which
+/// makes [`render`](crate::rendering::render) return an error instead of leaving the image blank.
+///
+/// The failure is remembered for [`DEFAULT_FETCH_FAILURE_TTL`], an image is resolved several
+/// times per render (to measure, check and draw it) and a dead source would otherwise go through
+/// all the retries each time. Up to [`MAX_REMEMBERED_FETCH_FAILURES`] sources are remembered, so
+/// requests with unique dead sources can't grow the store without a bound.
+#[derive(Debug)]
+pub struct RetryImageStore<S: ImageStore> {
+  inner: S,
+  max_retries: u32,
+  backoff: Duration,
+  timeout: Option<Duration>,
+  failure_ttl: Duration,
+  failures: Mutex<LruCache<String, FetchFailure>>,
+}
+
+/// A fetch that failed every attempt, see [`RetryImageStore::with_failure_ttl`].
+#[derive(Debug)]
+struct FetchFailure {
+  expires_at: Instant,
+  attempts: u32,
+  source: Arc<ImageResourceError>,
+}
+
+impl<S: ImageStore> RetryImageStore<S> {
+  /// Creates a store retrying failed fetches of `inner` up to `max_retries` times.
+  ///
+  /// The delay before the n-th retry is `backoff * 2^(n - 1)`.
+  pub fn new(inner: S, max_retries: u32, backoff: Duration) -> Self {
+    Self {
+      inner,
+      max_retries,
+      backoff,
+      timeout: None,
+      failure_ttl: DEFAULT_FETCH_FAILURE_TTL,
+      failures: Mutex::new(LruCache::new(MAX_REMEMBERED_FETCH_FAILURES)),
+    }
+  }
+
+  /// Fetches each attempt with [`ImageStore::fetch_with_timeout`] of the inner store, an attempt
+  /// failing with [`ImageResourceError::TimedOut`] is retried like any other failure.
+  ///
+  /// The inner store has to enforce the timeout itself, e.g. with the timeout of its HTTP client,
+  /// a fetch that never returns blocks the render.
+  #[must_use]
+  pub fn with_timeout(self, timeout: Duration) -> Self {
+    Self {
+      timeout: Some(timeout),
+      ..self
+    }
+  }
+
+  /// Sets how long a failed fetch is remembered, fetching the source again fails right away in
+  /// the meantime. Zero disables remembering failures.
+  #[must_use]
+  pub fn with_failure_ttl(self, failure_ttl: Duration) -> Self {
+    Self {
+      failure_ttl,
+      ..self
+    }
+  }
+
+  fn cached_failure(&self, src: &str) -> Option<ImageResourceError> {
+    let mut failures = self.failures.lock().unwrap();
+
+    let failure = failures.get(src)?;
+
+    if failure.expires_at <= Instant::now() {
+      failures.pop(src);
+      return None;
+    }
+
+    Some(ImageResourceError::FetchFailed {
+      attempts: failure.attempts,
+      source: failure.source.clone(),
+    })
+  }
+
+  fn fetch_attempt(&self, src: &str) -> ImageResult {
+    match self.timeout {
+      Some(timeout) => self.inner.fetch_with_timeout(src, timeout),
+      None => self.inner.fetch(src),
+    }
+  }
+}
+
+impl<S: ImageStore> ImageStore for RetryImageStore<S> {
+  fn get(&self, src: &str) -> Option<Arc<ImageSource>> {
+    self.inner.get(src)
+  }
+
+  fn insert(&self, src: &str, image: Arc<ImageSource>) {
+    self.failures.lock().unwrap().pop(src);
+    self.inner.insert(src, image);
+  }
+
+  fn clear(&self) {
+    self.failures.lock().unwrap().clear();
+    self.inner.clear();
+  }
+
+  fn fetch(&self, src: &str) -> ImageResult {
+    if let Some(failure) = self.cached_failure(src) {
+      return Err(failure);
+    }
+
+    let mut attempts = 0;
+
+    loop {
+      attempts += 1;
+
+      let err = match self.fetch_attempt(src) {
+        Ok(image) => return Ok(image),
+        // the source is not handled by the inner store, retrying won't help
+        Err(ImageResourceError::Unknown) => return Err(ImageResourceError::Unknown),
+        Err(err) => err,
+      };
+
+      if attempts > self.max_retries {
+        let source = Arc::new(err);
+
+        if !self.failure_ttl.is_zero() {
+          self.failures.lock().unwrap().put(
+            src.to_string(),
+            FetchFailure {
+              expires_at: Instant::now() + self.failure_ttl,
+              attempts,
+              source: source.clone(),
+            },
+          );
+        }
+
+        return Err(ImageResourceError::FetchFailed { attempts, source });
+      }
+
+      sleep(self.backoff.saturating_mul(1 << (attempts - 1).min(16)));
+    }
+  }
+
+  fn usage(&self) -> ImageStoreUsage {
+    self.inner.usage()
   }
 }
 
@@ -64,6 +423,18 @@ impl ImageSource {
     }
   }
 
+  /// Get the approximate bytes the image source holds in memory.
+  ///
+  /// Bitmaps are counted by their pixel data, svg trees by their struct only as their nodes
+  /// can't be measured.
+  pub fn byte_size(&self) -> usize {
+    match self {
+      #[cfg(feature = "svg")]
+      ImageSource::Svg(_) => size_of::<resvg::usvg::Tree>(),
+      ImageSource::Bitmap(bitmap) => bitmap.as_raw().len(),
+    }
+  }
+
   /// Render the image source to an RGBA image with the specified dimensions.
   pub fn render_to_rgba_image<'i>(
     &'i self,
@@ -118,6 +489,71 @@ pub fn load_image_source_from_bytes(bytes: &[u8]) -> ImageResult {
   Ok(Arc::new(img.into_rgba8().into()))
 }
 
+/// Try to load an image source from raw bytes, decoding them as the format named by `content_type`.
+///
+/// Meant for stores fetching over HTTP, pass the `Content-Type` header of the response so images
+/// the bytes can't be sniffed from, like svg files starting with an XML declaration, still decode.
+/// Types that aren't image formats, like `application/octet-stream`, and bytes that fail to decode
+/// as the named format fall back to [`load_image_source_from_bytes`].
+pub fn load_image_source_from_bytes_with_content_type(
+  bytes: &[u8],
+  content_type: Option<&str>,
+) -> ImageResult {
+  // the parameters like `; charset=utf-8` don't affect the format
+  let Some(mime_type) = content_type
+    .and_then(|content_type| content_type.split(';').next())
+    .map(|mime_type| mime_type.trim().to_ascii_lowercase())
+  else {
+    return load_image_source_from_bytes(bytes);
+  };
+
+  #[cfg(feature = "svg")]
+  if mime_type == "image/svg+xml" {
+    if let Ok(Ok(svg)) = std::str::from_utf8(bytes).map(parse_svg) {
+      return Ok(svg);
+    }
+  }
+
+  if let Some(format) = ImageFormat::from_mime_type(&mime_type) {
+    if let Ok(image) = image::load_from_memory_with_format(bytes, format) {
+      return Ok(Arc::new(image.into_rgba8().into()));
+    }
+  }
+
+  load_image_source_from_bytes(bytes)
+}
+
+/// Try to load a single frame of an animated image from raw bytes.
+///
+/// Frames are counted from 0, animated GIF and WebP images are supported.
+/// Frame 0 of any image is the same as [`load_image_source_from_bytes`], which always
+/// decodes the first frame of animated images.
+pub fn load_image_frame_from_bytes(bytes: &[u8], frame_index: u32) -> ImageResult {
+  if frame_index == 0 {
+    return load_image_source_from_bytes(bytes);
+  }
+
+  let cursor = io::Cursor::new(bytes);
+
+  let frame = match image::guess_format(bytes) {
+    Ok(ImageFormat::Gif) => GifDecoder::new(cursor)
+      .map_err(ImageResourceError::DecodeError)?
+      .into_frames()
+      .nth(frame_index as usize),
+    Ok(ImageFormat::WebP) => WebPDecoder::new(cursor)
+      .map_err(ImageResourceError::DecodeError)?
+      .into_frames()
+      .nth(frame_index as usize),
+    _ => None,
+  };
+
+  let frame = frame
+    .ok_or(ImageResourceError::FrameNotFound(frame_index))?
+    .map_err(ImageResourceError::DecodeError)?;
+
+  Ok(Arc::new(frame.into_buffer().into()))
+}
+
 /// Check if the bytes are an SVG image.
 pub(crate) fn is_svg(src: &str) -> bool {
   src.trim_start().starts_with("<svg") && src.contains("xmlns=\"http://www.w3.org/2000/svg\"")
@@ -125,12 +561,82 @@ pub(crate) fn is_svg(src: &str) -> bool {
 
 #[cfg(feature = "svg")]
 pub(crate) fn parse_svg(src: &str) -> ImageResult {
-  let tree = resvg::usvg::Tree::from_str(src, &resvg::usvg::Options::default())
-    .map_err(ImageResourceError::SvgParseError)?;
+  use resvg::usvg::{Error, Options, Tree, roxmltree};
+
+  let xml_options = roxmltree::ParsingOptions {
+    allow_dtd: true,
+    ..Default::default()
+  };
+
+  let document = roxmltree::Document::parse_with_options(src, xml_options)
+    .map_err(|err| ImageResourceError::SvgParseError(Error::ParsingFailed(err)))?;
+
+  let tree = match fill_svg_size_from_view_box(src, &document) {
+    Some(src) => Tree::from_str(&src, &Options::default()),
+    None => Tree::from_xmltree(&document, &Options::default()),
+  }
+  .map_err(ImageResourceError::SvgParseError)?;
 
   Ok(Arc::new(ImageSource::Svg(Box::new(tree))))
 }
 
+/// Returns the source with the missing `width` or `height` of the root `<svg>` derived from the
+/// `viewBox` ratio, or `None` if it has both, neither or no `viewBox`.
+///
+/// Like in browsers `<svg width="200" viewBox="0 0 100 50">` is 100px tall, usvg would take the
+/// height of the view box instead and draw the content letterboxed.
+#[cfg(feature = "svg")]
+fn fill_svg_size_from_view_box(
+  src: &str,
+  document: &resvg::usvg::roxmltree::Document,
+) -> Option<String> {
+  let root = document.root_element();
+
+  let view_box = root
+    .attribute("viewBox")?
+    .split(|c: char| c.is_whitespace() || c == ',')
+    .filter(|value| !value.is_empty())
+    .map(str::parse::<f32>)
+    .collect::<Result<Vec<_>, _>>()
+    .ok()?;
+
+  let &[_, _, view_box_width, view_box_height] = view_box.as_slice() else {
+    return None;
+  };
+
+  if view_box_width <= 0.0 || view_box_height <= 0.0 {
+    return None;
+  }
+
+  // percentages and other units are left to usvg
+  let parse_px = |value: &str| -> Option<f32> {
+    let value = value.trim();
+
+    value.strip_suffix("px").unwrap_or(value).parse().ok()
+  };
+
+  let (name, value) = match (root.attribute("width"), root.attribute("height")) {
+    (Some(width), None) => (
+      "height",
+      parse_px(width)? * view_box_height / view_box_width,
+    ),
+    (None, Some(height)) => (
+      "width",
+      parse_px(height)? * view_box_width / view_box_height,
+    ),
+    _ => return None,
+  };
+
+  // right after the tag name, which is followed by whitespace as the element has attributes
+  let start = root.range().start;
+  let name_end = start + src[start..].find(char::is_whitespace)?;
+
+  let mut src = src.to_string();
+  src.insert_str(name_end, &format!(" {name}=\"{value}\""));
+
+  Some(src)
+}
+
 /// Represents the state of an image in the rendering system.
 ///
 /// This enum tracks whether an image has been successfully loaded and decoded,
@@ -153,6 +659,377 @@ pub enum ImageResourceError {
   /// SVG parsing is not supported in this build
   #[cfg(not(feature = "svg"))]
   SvgParseNotSupported,
+  /// The requested frame doesn't exist in the image
+  FrameNotFound(u32),
+  /// The image file doesn't exist
+  NotFound,
+  /// The image file is outside of the allowed directory
+  Forbidden,
+  /// An error occurred while reading the image file
+  IoError(io::Error),
+  /// Every attempt to fetch the image failed, see [`RetryImageStore`]
+  FetchFailed {
+    /// The number of attempts made
+    attempts: u32,
+    /// The error of the last attempt
+    source: Arc<ImageResourceError>,
+  },
+  /// An attempt to fetch the image took longer than the timeout, see
+  /// [`ImageStore::fetch_with_timeout`]
+  TimedOut(Duration),
   /// The image source is unknown
   Unknown,
 }
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    env::temp_dir,
+    fs::{create_dir_all, write},
+    sync::RwLock,
+  };
+
+  use image::Rgba;
+
+  use super::*;
+
+  fn create_asset_dir(name: &str) -> PathBuf {
+    let dir = temp_dir().join(format!("takumi-{name}-{}", std::process::id()));
+    let assets = dir.join("assets");
+
+    create_dir_all(&assets).unwrap();
+
+    let mut bytes = Vec::new();
+    RgbaImage::from_pixel(2, 2, Rgba([255, 0, 0, 255]))
+      .write_to(&mut io::Cursor::new(&mut bytes), image::ImageFormat::Png)
+      .unwrap();
+
+    write(assets.join("logo.png"), &bytes).unwrap();
+    write(dir.join("secret.png"), &bytes).unwrap();
+
+    dir
+  }
+
+  #[test]
+  fn test_content_type_decodes_unsniffable_svg() {
+    let svg = r#"<?xml version="1.0" encoding="UTF-8"?>
+<svg xmlns="http://www.w3.org/2000/svg" width="4" height="3"/>"#;
+
+    assert!(load_image_source_from_bytes(svg.as_bytes()).is_err());
+
+    let image = load_image_source_from_bytes_with_content_type(
+      svg.as_bytes(),
+      Some("image/svg+xml; charset=utf-8"),
+    )
+    .unwrap();
+
+    assert_eq!(image.size(), (4.0, 3.0));
+  }
+
+  #[test]
+  fn test_content_type_falls_back_to_sniffing() {
+    let mut png = Vec::new();
+    RgbaImage::new(2, 1)
+      .write_to(&mut io::Cursor::new(&mut png), ImageFormat::Png)
+      .unwrap();
+
+    for content_type in [None, Some("application/octet-stream"), Some("image/jpeg")] {
+      let image = load_image_source_from_bytes_with_content_type(&png, content_type).unwrap();
+
+      assert_eq!(image.size(), (2.0, 1.0));
+    }
+  }
+
+  #[test]
+  fn test_file_system_store_loads_file() {
+    let dir = create_asset_dir("fs-load");
+    let store = FileSystemImageStore::new(dir.join("assets")).unwrap();
+
+    let src = format!("file://{}", store.root().join("logo.png").display());
+    let image = store.fetch(&src).unwrap();
+
+    assert_eq!(image.size(), (2.0, 2.0));
+    assert!(store.get(&src).is_some());
+    assert!(store.fetch("file://logo.png").is_ok());
+  }
+
+  #[test]
+  fn test_file_system_store_bounds_cache() {
+    assert_eq!(
+      PersistentImageStore::default().capacity(),
+      DEFAULT_IMAGE_STORE_CAPACITY
+    );
+
+    // room for one 2x2 image
+    let dir = create_asset_dir("fs-capacity");
+    let store = FileSystemImageStore::with_capacity(dir.join("assets"), 16).unwrap();
+
+    let src = format!("file://{}", store.root().join("logo.png").display());
+
+    store.fetch(&src).unwrap();
+    store.fetch("file://logo.png").unwrap();
+
+    assert!(store.get(&src).is_none());
+    assert!(store.get("file://logo.png").is_some());
+  }
+
+  #[test]
+  fn test_store_usage_counts_pixel_bytes() {
+    let store = RetryImageStore::new(PersistentImageStore::default(), 0, Duration::ZERO);
+
+    assert_eq!(store.usage(), ImageStoreUsage::default());
+
+    store.insert("a", Arc::new(RgbaImage::new(4, 2).into()));
+    store.insert("b", Arc::new(RgbaImage::new(1, 1).into()));
+
+    assert_eq!(
+      store.usage(),
+      ImageStoreUsage {
+        entries: 2,
+        bytes: (4 * 2 + 1) * 4,
+      }
+    );
+
+    store.clear();
+
+    assert_eq!(store.usage().bytes, 0);
+  }
+
+  #[test]
+  fn test_persistent_store_evicts_least_recently_used() {
+    // room for two 2x2 images
+    let store = PersistentImageStore::with_capacity(32);
+    let image = || Arc::new(ImageSource::from(RgbaImage::new(2, 2)));
+
+    store.insert("a", image());
+    store.insert("b", image());
+
+    // a render still holding the image keeps it alive after eviction
+    let held = store.get("a").unwrap();
+
+    store.insert("c", image());
+
+    assert!(store.get("a").is_some());
+    assert!(store.get("b").is_none());
+    assert!(store.get("c").is_some());
+    assert_eq!(store.usage().bytes, 32);
+    assert_eq!(held.size(), (2.0, 2.0));
+
+    // an image over the capacity replaces everything else
+    store.insert("large", Arc::new(RgbaImage::new(4, 4).into()));
+
+    assert_eq!(
+      store.usage(),
+      ImageStoreUsage {
+        entries: 1,
+        bytes: 64,
+      }
+    );
+  }
+
+  #[test]
+  fn test_load_animated_gif_frame() {
+    use image::{Delay, Frame, codecs::gif::GifEncoder};
+
+    let mut bytes = Vec::new();
+
+    {
+      let mut encoder = GifEncoder::new(&mut bytes);
+      encoder
+        .encode_frames([
+          Frame::from_parts(
+            RgbaImage::from_pixel(2, 2, Rgba([255, 0, 0, 255])),
+            0,
+            0,
+            Delay::from_numer_denom_ms(100, 1),
+          ),
+          Frame::from_parts(
+            RgbaImage::from_pixel(2, 2, Rgba([0, 0, 255, 255])),
+            0,
+            0,
+            Delay::from_numer_denom_ms(100, 1),
+          ),
+        ])
+        .unwrap();
+    }
+
+    let pixel = |frame_index| match &*load_image_frame_from_bytes(&bytes, frame_index).unwrap() {
+      ImageSource::Bitmap(bitmap) => *bitmap.get_pixel(0, 0),
+      #[cfg(feature = "svg")]
+      ImageSource::Svg(_) => unreachable!(),
+    };
+
+    assert_eq!(pixel(0), Rgba([255, 0, 0, 255]));
+    assert_eq!(pixel(1), Rgba([0, 0, 255, 255]));
+    assert!(matches!(
+      load_image_frame_from_bytes(&bytes, 2),
+      Err(ImageResourceError::FrameNotFound(2))
+    ));
+  }
+
+  #[derive(Default)]
+  struct FlakyImageStore {
+    failures: RwLock<u32>,
+  }
+
+  impl ImageStore for FlakyImageStore {
+    fn get(&self, _src: &str) -> Option<Arc<ImageSource>> {
+      None
+    }
+
+    fn insert(&self, _src: &str, _image: Arc<ImageSource>) {}
+
+    fn clear(&self) {}
+
+    fn fetch(&self, _src: &str) -> ImageResult {
+      let mut failures = self.failures.write().unwrap();
+
+      if *failures < 2 {
+        *failures += 1;
+        return Err(ImageResourceError::NotFound);
+      }
+
+      Ok(Arc::new(RgbaImage::new(1, 1).into()))
+    }
+  }
+
+  #[test]
+  fn test_retry_store_retries_until_success() {
+    let store = RetryImageStore::new(FlakyImageStore::default(), 2, Duration::ZERO);
+
+    assert!(store.fetch("flaky").is_ok());
+  }
+
+  #[test]
+  fn test_retry_store_gives_up() {
+    let store = RetryImageStore::new(FlakyImageStore::default(), 1, Duration::ZERO);
+
+    assert!(matches!(
+      store.fetch("flaky"),
+      Err(ImageResourceError::FetchFailed { attempts: 2, .. })
+    ));
+  }
+
+  /// Fails every fetch, counting the attempts and recording the timeouts passed to them.
+  #[derive(Default)]
+  struct DeadImageStore {
+    attempts: Arc<Mutex<u32>>,
+    timeouts: Arc<Mutex<Vec<Duration>>>,
+  }
+
+  impl ImageStore for DeadImageStore {
+    fn get(&self, _src: &str) -> Option<Arc<ImageSource>> {
+      None
+    }
+
+    fn insert(&self, _src: &str, _image: Arc<ImageSource>) {}
+
+    fn clear(&self) {}
+
+    fn fetch(&self, _src: &str) -> ImageResult {
+      *self.attempts.lock().unwrap() += 1;
+
+      Err(ImageResourceError::NotFound)
+    }
+
+    fn fetch_with_timeout(&self, _src: &str, timeout: Duration) -> ImageResult {
+      *self.attempts.lock().unwrap() += 1;
+      self.timeouts.lock().unwrap().push(timeout);
+
+      Err(ImageResourceError::TimedOut(timeout))
+    }
+  }
+
+  #[test]
+  fn test_retry_store_remembers_failures() {
+    let attempts = Arc::new(Mutex::new(0));
+    let dead = || DeadImageStore {
+      attempts: attempts.clone(),
+      ..Default::default()
+    };
+
+    let store = RetryImageStore::new(dead(), 1, Duration::ZERO);
+
+    for _ in 0..3 {
+      assert!(matches!(
+        store.fetch("dead"),
+        Err(ImageResourceError::FetchFailed { attempts: 2, .. })
+      ));
+    }
+
+    // only the first fetch went through the retries
+    assert_eq!(*attempts.lock().unwrap(), 2);
+
+    store.clear();
+    assert!(store.fetch("dead").is_err());
+    assert_eq!(*attempts.lock().unwrap(), 4);
+
+    *attempts.lock().unwrap() = 0;
+
+    let store = RetryImageStore::new(dead(), 1, Duration::ZERO).with_failure_ttl(Duration::ZERO);
+
+    assert!(store.fetch("dead").is_err());
+    assert!(store.fetch("dead").is_err());
+    assert_eq!(*attempts.lock().unwrap(), 4);
+  }
+
+  #[test]
+  fn test_retry_store_bounds_remembered_failures() {
+    let store = RetryImageStore::new(DeadImageStore::default(), 0, Duration::ZERO);
+    let count = MAX_REMEMBERED_FETCH_FAILURES.get() + 10;
+
+    for i in 0..count {
+      assert!(store.fetch(&format!("dead-{i}")).is_err());
+    }
+
+    let failures = store.failures.lock().unwrap();
+
+    assert_eq!(failures.len(), MAX_REMEMBERED_FETCH_FAILURES.get());
+    assert!(!failures.contains("dead-0"));
+    assert!(failures.contains(&format!("dead-{}", count - 1)));
+  }
+
+  #[test]
+  fn test_retry_store_passes_timeout_to_attempts() {
+    let timeouts = Arc::new(Mutex::new(Vec::new()));
+    let store = RetryImageStore::new(
+      DeadImageStore {
+        timeouts: timeouts.clone(),
+        ..Default::default()
+      },
+      1,
+      Duration::ZERO,
+    )
+    .with_timeout(Duration::from_millis(20));
+
+    assert!(matches!(
+      store.fetch("slow"),
+      Err(ImageResourceError::FetchFailed { attempts: 2, source })
+        if matches!(*source, ImageResourceError::TimedOut(_))
+    ));
+    assert_eq!(*timeouts.lock().unwrap(), [Duration::from_millis(20); 2]);
+  }
+
+  #[test]
+  fn test_file_system_store_rejects_traversal() {
+    let dir = create_asset_dir("fs-traversal");
+    let store = FileSystemImageStore::new(dir.join("assets")).unwrap();
+
+    assert!(matches!(
+      store.fetch("file://../secret.png"),
+      Err(ImageResourceError::Forbidden)
+    ));
+    assert!(matches!(
+      store.fetch(&format!("file://{}", dir.join("secret.png").display())),
+      Err(ImageResourceError::Forbidden)
+    ));
+    assert!(matches!(
+      store.fetch("file://missing.png"),
+      Err(ImageResourceError::NotFound)
+    ));
+    assert!(matches!(
+      store.fetch("https://example.com/logo.png"),
+      Err(ImageResourceError::Unknown)
+    ));
+  }
+}
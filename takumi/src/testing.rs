@@ -0,0 +1,295 @@
+use std::{
+  env, fs,
+  path::{Path, PathBuf},
+};
+
+use image::{Rgba, RgbaImage};
+
+use crate::{
+  GlobalContext,
+  layout::{Viewport, node::Node},
+  rendering::render,
+};
+
+/// Set to any value to overwrite the golden images with the renders instead of comparing them.
+pub const UPDATE_SNAPSHOTS_ENV: &str = "TAKUMI_UPDATE_SNAPSHOTS";
+
+/// How far a render may be from its golden image and still match.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+  /// The largest difference of a color channel for a pixel to count as the same
+  pub max_channel_difference: u8,
+  /// The share of pixels allowed to differ, from 0 to 1
+  pub max_differing_ratio: f32,
+}
+
+impl Default for Tolerance {
+  /// Allows rounding differences of anti-aliased edges, but no pixel that differs more.
+  fn default() -> Self {
+    Self {
+      max_channel_difference: 2,
+      max_differing_ratio: 0.0,
+    }
+  }
+}
+
+/// The pixels two images differ in, see [`diff_images`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageDiff {
+  /// The number of pixels that differ by more than the tolerance
+  pub differing_pixels: usize,
+  /// The largest difference of a color channel between the images
+  pub max_channel_difference: u8,
+  /// The expected image faded out, with the differing pixels in red
+  pub image: RgbaImage,
+}
+
+/// Compares two images pixel by pixel, returns `None` if they match within the tolerance.
+///
+/// Images of different sizes never match, the pixels outside of either image count as
+/// differing.
+pub fn diff_images(
+  actual: &RgbaImage,
+  expected: &RgbaImage,
+  tolerance: Tolerance,
+) -> Option<ImageDiff> {
+  let width = actual.width().max(expected.width());
+  let height = actual.height().max(expected.height());
+
+  let mut differing_pixels = 0;
+  let mut max_channel_difference = 0;
+
+  let image = RgbaImage::from_fn(width, height, |x, y| {
+    let (Some(actual), Some(expected)) = (
+      actual.get_pixel_checked(x, y),
+      expected.get_pixel_checked(x, y),
+    ) else {
+      differing_pixels += 1;
+      max_channel_difference = u8::MAX;
+
+      return Rgba([255, 0, 0, 255]);
+    };
+
+    let difference = actual
+      .0
+      .iter()
+      .zip(expected.0)
+      .map(|(actual, expected)| actual.abs_diff(expected))
+      .max()
+      .unwrap_or_default();
+
+    max_channel_difference = max_channel_difference.max(difference);
+
+    if difference > tolerance.max_channel_difference {
+      differing_pixels += 1;
+
+      return Rgba([255, 0, 0, 255]);
+    }
+
+    // a light gray version of the expected pixel, so the differences stand out
+    let [r, g, b, a] = expected.0.map(u32::from);
+    let luma = (r * 299 + g * 587 + b * 114) / 1000 * a / 255 + (255 - a);
+
+    let gray = (191 + luma / 4) as u8;
+
+    Rgba([gray, gray, gray, 255])
+  });
+
+  let allowed = (tolerance.max_differing_ratio * (width * height) as f32) as usize;
+  let same_size = actual.dimensions() == expected.dimensions();
+
+  (!same_size || differing_pixels > allowed).then_some(ImageDiff {
+    differing_pixels,
+    max_channel_difference,
+    image,
+  })
+}
+
+/// Renders the node and asserts it matches the PNG at `golden_path`, with the default
+/// [`Tolerance`].
+///
+/// See [`assert_renders_match_with`].
+pub fn assert_renders_match<Nodes: Node<Nodes>>(
+  viewport: Viewport,
+  global: &GlobalContext,
+  node: Nodes,
+  golden_path: impl AsRef<Path>,
+) {
+  assert_renders_match_with(viewport, global, node, golden_path, Tolerance::default());
+}
+
+/// Renders the node and asserts it matches the PNG at `golden_path` within the tolerance.
+///
+/// A missing golden image is written from the render, so the first run records it. Set
+/// [`UPDATE_SNAPSHOTS_ENV`] to overwrite the existing ones after an intended change.
+///
+/// On a mismatch the render is written next to the golden image with an `.actual.png`
+/// extension, along with a `.diff.png` highlighting the differing pixels, before panicking.
+pub fn assert_renders_match_with<Nodes: Node<Nodes>>(
+  viewport: Viewport,
+  global: &GlobalContext,
+  node: Nodes,
+  golden_path: impl AsRef<Path>,
+  tolerance: Tolerance,
+) {
+  let golden_path = golden_path.as_ref();
+  let actual = render(viewport, global, node).expect("failed to render the node");
+
+  let actual_path = sibling_path(golden_path, "actual");
+  let diff_path = sibling_path(golden_path, "diff");
+
+  if !golden_path.exists() || env::var_os(UPDATE_SNAPSHOTS_ENV).is_some() {
+    if let Some(parent) = golden_path.parent() {
+      fs::create_dir_all(parent).expect("failed to create the golden image directory");
+    }
+
+    actual
+      .save(golden_path)
+      .expect("failed to write the golden image");
+
+    return;
+  }
+
+  let expected = image::open(golden_path)
+    .unwrap_or_else(|error| panic!("failed to read {}: {error}", golden_path.display()))
+    .into_rgba8();
+
+  let Some(diff) = diff_images(&actual, &expected, tolerance) else {
+    // the leftovers of an earlier failure are out of date now
+    let _ = fs::remove_file(&actual_path);
+    let _ = fs::remove_file(&diff_path);
+
+    return;
+  };
+
+  actual
+    .save(&actual_path)
+    .expect("failed to write the actual image");
+  diff
+    .image
+    .save(&diff_path)
+    .expect("failed to write the diff image");
+
+  panic!(
+    "the render doesn't match {}: {} pixels differ, by up to {} per channel, the render is {}x{} \
+     and the golden image {}x{}\n  actual: {}\n  diff: {}",
+    golden_path.display(),
+    diff.differing_pixels,
+    diff.max_channel_difference,
+    actual.width(),
+    actual.height(),
+    expected.width(),
+    expected.height(),
+    actual_path.display(),
+    diff_path.display(),
+  );
+}
+
+/// Returns the path next to the golden image with the suffix before the extension, like
+/// `text.actual.png` for `text.png`.
+fn sibling_path(golden_path: &Path, suffix: &str) -> PathBuf {
+  let stem = golden_path
+    .file_stem()
+    .map(|stem| stem.to_string_lossy())
+    .unwrap_or_default();
+
+  golden_path.with_file_name(format!("{stem}.{suffix}.png"))
+}
+
+#[cfg(test)]
+mod tests {
+  use std::panic::{AssertUnwindSafe, catch_unwind};
+
+  use crate::layout::{
+    node::{ContainerNode, NodeKind},
+    style::{Color, LengthUnit::Percentage, StyleBuilder},
+  };
+
+  use super::*;
+
+  fn square(color: Color) -> NodeKind {
+    ContainerNode {
+      style: StyleBuilder::default()
+        .width(Percentage(100.0))
+        .height(Percentage(100.0))
+        .background_color(color)
+        .build()
+        .unwrap(),
+      children: None,
+    }
+    .into()
+  }
+
+  #[test]
+  fn test_diff_images() {
+    let expected = RgbaImage::from_pixel(4, 4, Rgba([100, 100, 100, 255]));
+
+    let mut actual = expected.clone();
+    actual.put_pixel(0, 0, Rgba([102, 100, 100, 255]));
+
+    assert_eq!(diff_images(&actual, &expected, Tolerance::default()), None);
+
+    actual.put_pixel(1, 1, Rgba([0, 100, 100, 255]));
+
+    let diff = diff_images(&actual, &expected, Tolerance::default()).unwrap();
+
+    assert_eq!(diff.differing_pixels, 1);
+    assert_eq!(diff.max_channel_difference, 100);
+    assert_eq!(diff.image.get_pixel(1, 1).0, [255, 0, 0, 255]);
+
+    let lenient = Tolerance {
+      max_differing_ratio: 0.1,
+      ..Default::default()
+    };
+
+    assert_eq!(diff_images(&actual, &expected, lenient), None);
+
+    // a different size never matches
+    let smaller = RgbaImage::from_pixel(4, 3, Rgba([100, 100, 100, 255]));
+
+    assert_eq!(
+      diff_images(&smaller, &expected, lenient).map(|diff| diff.differing_pixels),
+      Some(4)
+    );
+  }
+
+  #[test]
+  fn test_assert_renders_match() {
+    let context = GlobalContext::default();
+    let viewport = Viewport::new(8, 8);
+
+    let directory = env::temp_dir().join(format!("takumi-snapshot-{}", std::process::id()));
+    let golden_path = directory.join("square.png");
+
+    // the first run records the golden image
+    assert_renders_match(
+      viewport,
+      &context,
+      square(Color([255, 0, 0, 255])),
+      &golden_path,
+    );
+    assert!(golden_path.exists());
+
+    assert_renders_match(
+      viewport,
+      &context,
+      square(Color([255, 0, 0, 255])),
+      &golden_path,
+    );
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+      assert_renders_match(
+        viewport,
+        &context,
+        square(Color([0, 0, 255, 255])),
+        &golden_path,
+      );
+    }));
+
+    assert!(result.is_err());
+    assert!(directory.join("square.actual.png").exists());
+    assert!(directory.join("square.diff.png").exists());
+
+    fs::remove_dir_all(directory).unwrap();
+  }
+}
@@ -0,0 +1,144 @@
+use smallvec::smallvec;
+use takumi::{
+  layout::{
+    Viewport,
+    node::{ContainerNode, NodeKind, TextNode},
+    style::{
+      BackgroundImagesValue, BoxShadow, BoxShadows, Color, Display, FiltersValue, FontWeight,
+      GridTemplateAreas,
+      LengthUnit::{Percentage, Px},
+      Sides, StyleBuilder,
+    },
+  },
+  rendering::render,
+};
+
+mod test_utils;
+use test_utils::{create_test_context, run_style_width_test};
+
+/// The hash of the image rendered from [`create_node`].
+///
+/// Any change to how the tree is drawn changes it, update it with the hash printed by the failing
+/// test after checking the new output.
+const GOLDEN_HASH: u64 = 0xb937d10b5921103b;
+
+/// 64-bit FNV-1a, which is stable across platforms and Rust versions unlike the std hasher.
+fn fnv1a(bytes: &[u8]) -> u64 {
+  bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+    (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+  })
+}
+
+/// A tree touching text shaping, gradients, shadows, filters and named grid areas.
+fn create_node() -> NodeKind {
+  let area = |name: &str, style: StyleBuilder, children: Option<Vec<NodeKind>>| -> NodeKind {
+    let mut style = style;
+
+    ContainerNode {
+      style: style
+        .grid_area(Some(name.to_string()))
+        .border_radius(Sides([Px(16.0); 4]))
+        .padding(Sides([Px(16.0); 4]))
+        .build()
+        .unwrap(),
+      children,
+    }
+    .into()
+  };
+
+  let mut header = StyleBuilder::default();
+  header.background_image(Some(
+    BackgroundImagesValue::Css("linear-gradient(135deg, #6366f1, #ec4899)".to_string())
+      .try_into()
+      .unwrap(),
+  ));
+
+  let mut side = StyleBuilder::default();
+  side
+    .background_image(Some(
+      BackgroundImagesValue::Css("radial-gradient(circle, #fde68a, #f97316)".to_string())
+        .try_into()
+        .unwrap(),
+    ))
+    .box_shadow(Some(BoxShadows(smallvec![BoxShadow {
+      inset: false,
+      offset_x: Px(4.0),
+      offset_y: Px(8.0),
+      blur_radius: Px(12.0),
+      spread_radius: Px(0.0),
+      color: Color([0, 0, 0, 96]),
+    }])));
+
+  let mut main = StyleBuilder::default();
+  main
+    .background_color(Color([240, 253, 244, 255]))
+    .filter(Some(
+      FiltersValue::Css("blur(1px) saturate(120%)".to_string())
+        .try_into()
+        .unwrap(),
+    ));
+
+  let text = |text: &str, weight: f32| -> NodeKind {
+    TextNode {
+      style: StyleBuilder::default()
+        .font_size(Px(36.0))
+        .font_weight(FontWeight::from(weight))
+        .color(Color([17, 24, 39, 255]))
+        .build()
+        .unwrap(),
+      text: text.to_string(),
+    }
+    .into()
+  };
+
+  ContainerNode {
+    style: StyleBuilder::default()
+      .width(Percentage(100.0))
+      .height(Percentage(100.0))
+      .display(Display::Grid)
+      .padding(Sides([Px(24.0); 4]))
+      .grid_template_areas(Some(GridTemplateAreas(vec![
+        vec!["header".to_string(), "header".to_string()],
+        vec!["side".to_string(), "main".to_string()],
+      ])))
+      .background_color(Color::white())
+      .build()
+      .unwrap(),
+    children: Some(vec![
+      area(
+        "main",
+        main,
+        Some(vec![text("Reproducible 🎯 output", 400.0)]),
+      ),
+      area("side", side, Some(vec![text("Side", 700.0)])),
+      area(
+        "header",
+        header,
+        Some(vec![text("The same pixels, every time", 600.0)]),
+      ),
+    ]),
+  }
+  .into()
+}
+
+#[test]
+fn test_render_is_deterministic() {
+  let image = run_style_width_test(create_node(), "tests/fixtures/render_determinism.png");
+
+  // the glyph cache is warm for the second render, a fresh context starts from nothing
+  let viewport = Viewport::new(1200, 630);
+  let context = create_test_context();
+
+  let fresh = render(viewport, &context, create_node()).unwrap();
+  let warm = render(viewport, &context, create_node()).unwrap();
+
+  assert!(image.as_raw() == fresh.as_raw());
+  assert!(image.as_raw() == warm.as_raw());
+
+  let hash = fnv1a(image.as_raw());
+
+  assert_eq!(
+    hash, GOLDEN_HASH,
+    "the rendered image changed, its hash is {hash:#018x}"
+  );
+}
@@ -0,0 +1,191 @@
+use std::io::Cursor;
+
+use image::RgbaImage;
+use takumi::{
+  Error,
+  rendering::{EncodeOptions, ImageOutputFormat, srgb_icc_profile, write_image},
+};
+
+#[test]
+fn test_write_gif() {
+  let image = RgbaImage::from_pixel(4, 4, image::Rgba([255, 0, 0, 128]));
+  let mut buffer = Cursor::new(Vec::new());
+
+  write_image(&image, &mut buffer, ImageOutputFormat::Gif, None).unwrap();
+
+  assert!(buffer.into_inner().starts_with(b"GIF89a"));
+}
+
+#[test]
+fn test_write_progressive_jpeg() {
+  let image = RgbaImage::from_pixel(16, 16, image::Rgba([255, 0, 0, 255]));
+
+  let write = |options: EncodeOptions| {
+    let mut buffer = Cursor::new(Vec::new());
+    write_image(&image, &mut buffer, ImageOutputFormat::Jpeg, options).unwrap();
+    buffer.into_inner()
+  };
+
+  // SOF2 marks a progressive frame, SOF0 a baseline one
+  let has_marker = |bytes: &[u8], marker: u8| bytes.windows(2).any(|w| w == [0xFF, marker]);
+
+  let progressive = write(EncodeOptions {
+    quality: Some(90),
+    progressive: true,
+    ..Default::default()
+  });
+  let baseline = write(90.into());
+
+  assert!(has_marker(&progressive, 0xC2));
+  assert!(!has_marker(&baseline, 0xC2));
+  assert!(image::load_from_memory(&progressive).is_ok());
+}
+
+#[test]
+fn test_write_dpi_metadata() {
+  let image = RgbaImage::from_pixel(4, 4, image::Rgba([255, 0, 0, 255]));
+
+  let write = |format, progressive| {
+    let mut buffer = Cursor::new(Vec::new());
+    let options = EncodeOptions {
+      dpi: Some(144),
+      progressive,
+      ..Default::default()
+    };
+
+    write_image(&image, &mut buffer, format, options).unwrap();
+    buffer.into_inner()
+  };
+
+  let png = write(ImageOutputFormat::Png, false);
+  let decoder = png::Decoder::new(Cursor::new(&png));
+  let info = decoder.read_info().unwrap();
+
+  let pixel_dims = info.info().pixel_dims.unwrap();
+
+  assert_eq!((pixel_dims.xppu, pixel_dims.yppu), (5669, 5669));
+  assert_eq!(pixel_dims.unit, png::Unit::Meter);
+
+  // JFIF APP0: "JFIF\0", version, unit 1 (inches), x density, y density
+  for jpeg in [
+    write(ImageOutputFormat::Jpeg, false),
+    write(ImageOutputFormat::Jpeg, true),
+  ] {
+    let jfif = jpeg.windows(5).position(|w| w == b"JFIF\0").unwrap();
+
+    assert_eq!(jpeg[jfif + 7], 1);
+    assert_eq!(&jpeg[jfif + 8..jfif + 12], &[0, 144, 0, 144]);
+  }
+}
+
+#[test]
+fn test_write_icc_profile() {
+  let image = RgbaImage::from_pixel(4, 4, image::Rgba([255, 0, 0, 255]));
+
+  let write = |format, options: EncodeOptions| {
+    let mut buffer = Cursor::new(Vec::new());
+    write_image(&image, &mut buffer, format, options).unwrap();
+    buffer.into_inner()
+  };
+
+  let srgb = EncodeOptions {
+    embed_srgb_profile: true,
+    ..Default::default()
+  };
+
+  let png = write(ImageOutputFormat::Png, srgb.clone());
+  let info = png::Decoder::new(Cursor::new(&png)).read_info().unwrap();
+
+  assert_eq!(info.info().icc_profile.as_deref(), Some(srgb_icc_profile()));
+
+  // JPEG stores the profile in an APP2 segment, WebP in an ICCP chunk
+  let jpeg = write(ImageOutputFormat::Jpeg, srgb.clone());
+  let progressive_jpeg = write(
+    ImageOutputFormat::Jpeg,
+    EncodeOptions {
+      progressive: true,
+      ..srgb.clone()
+    },
+  );
+  let webp = write(ImageOutputFormat::WebP, srgb);
+
+  let contains = |bytes: &[u8], needle: &[u8]| bytes.windows(needle.len()).any(|w| w == needle);
+
+  assert!(contains(&jpeg, b"ICC_PROFILE\0"));
+  assert!(contains(&progressive_jpeg, b"ICC_PROFILE\0"));
+  assert!(contains(&webp, b"ICCP"));
+  assert!(!contains(
+    &write(ImageOutputFormat::WebP, Default::default()),
+    b"ICCP"
+  ));
+
+  let custom = EncodeOptions {
+    embed_srgb_profile: true,
+    icc_profile: Some(vec![1, 2, 3]),
+    ..Default::default()
+  };
+
+  assert_eq!(custom.resolve_icc_profile(), Some(&[1, 2, 3][..]));
+}
+
+#[test]
+fn test_format_from_content_type() {
+  assert_eq!(
+    ImageOutputFormat::from_content_type("IMAGE/PNG"),
+    Some(ImageOutputFormat::Png)
+  );
+  assert_eq!(ImageOutputFormat::from_content_type("image/svg+xml"), None);
+}
+
+#[test]
+fn test_write_lossless_formats() {
+  let image = RgbaImage::from_pixel(4, 4, image::Rgba([255, 0, 0, 128]));
+
+  for (format, content_type) in [
+    (ImageOutputFormat::Tiff, "image/tiff"),
+    (ImageOutputFormat::Bmp, "image/bmp"),
+  ] {
+    let mut buffer = Cursor::new(Vec::new());
+
+    write_image(&image, &mut buffer, format, None).unwrap();
+
+    let decoded = image::load_from_memory(&buffer.into_inner()).unwrap();
+
+    assert_eq!(format.content_type(), content_type);
+    assert_eq!(decoded.to_rgba8(), image);
+  }
+}
+
+#[test]
+fn test_write_image_rejects_lossy_webp() {
+  let image = RgbaImage::from_pixel(4, 4, image::Rgba([255, 0, 0, 255]));
+
+  let mut buffer = Cursor::new(Vec::new());
+  assert!(write_image(&image, &mut buffer, ImageOutputFormat::WebP, None).is_ok());
+
+  let options = EncodeOptions {
+    lossless: false,
+    ..Default::default()
+  };
+
+  // other formats ignore the option
+  assert!(
+    write_image(
+      &image,
+      &mut Cursor::new(Vec::new()),
+      ImageOutputFormat::Png,
+      options.clone()
+    )
+    .is_ok()
+  );
+
+  assert!(matches!(
+    write_image(
+      &image,
+      &mut Cursor::new(Vec::new()),
+      ImageOutputFormat::WebP,
+      options
+    ),
+    Err(Error::ImageEncodeError(_))
+  ));
+}
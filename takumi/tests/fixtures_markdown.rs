@@ -0,0 +1,37 @@
+#![cfg(feature = "markdown")]
+
+use takumi::layout::{
+  markdown::markdown_to_node,
+  style::{Color, LengthUnit::Px, Sides, StyleBuilder},
+};
+
+mod test_utils;
+use test_utils::run_style_width_test;
+
+#[test]
+fn fixtures_markdown_document() {
+  let node = markdown_to_node(
+    r#"# Release notes
+
+Takumi now renders **Markdown** with *inline* formatting, `inline code` and [links](https://takumi.kane.tw).
+
+- Headings and paragraphs
+- Nested **bold *italic*** text
+
+> Quotes keep their own color.
+
+```
+cargo add takumi --features markdown
+```"#,
+    StyleBuilder::default()
+      .width(Px(1200.0))
+      .height(Px(630.0))
+      .padding(Sides([Px(48.0); 4]))
+      .font_size(Px(28.0))
+      .background_color(Color::white())
+      .build()
+      .unwrap(),
+  );
+
+  run_style_width_test(node, "tests/fixtures/markdown_document.png");
+}
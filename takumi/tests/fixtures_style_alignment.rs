@@ -1,5 +1,5 @@
 use takumi::layout::{
-  node::ContainerNode,
+  node::{ContainerNode, TextNode},
   style::{
     AlignItems, Color, Display, JustifyContent,
     LengthUnit::{Percentage, Px},
@@ -105,3 +105,39 @@ fn test_style_justify_content() {
 
   run_style_width_test(container.into(), "tests/fixtures/style_justify_content.png");
 }
+
+#[test]
+fn test_style_align_items_baseline() {
+  let text = |font_size: f32, color: Color| {
+    TextNode {
+      style: StyleBuilder::default()
+        .font_size(Px(font_size))
+        .background_color(color)
+        .build()
+        .unwrap(),
+      text: "Baseline".to_string(),
+    }
+    .into()
+  };
+
+  let container = ContainerNode {
+    style: StyleBuilder::default()
+      .width(Percentage(100.0))
+      .height(Percentage(100.0))
+      .display(Display::Flex)
+      .align_items(Some(AlignItems::Baseline))
+      .background_color(Color::white())
+      .build()
+      .unwrap(),
+    children: Some(vec![
+      text(16.0, Color([255, 200, 200, 255])),
+      text(32.0, Color([200, 255, 200, 255])),
+      text(64.0, Color([200, 200, 255, 255])),
+    ]),
+  };
+
+  run_style_width_test(
+    container.into(),
+    "tests/fixtures/style_align_items_baseline.png",
+  );
+}
@@ -1,9 +1,9 @@
 use takumi::layout::{
   node::{ContainerNode, NodeKind},
   style::{
-    BackgroundImagesValue, BackgroundPositionsValue, BackgroundRepeat, BackgroundRepeats,
-    BackgroundRepeatsValue, BackgroundSizesValue, Color, CssValue, LengthUnit::Percentage,
-    StyleBuilder,
+    BackgroundBlendModesValue, BackgroundImagesValue, BackgroundNoise, BackgroundPositionsValue,
+    BackgroundRepeat, BackgroundRepeats, BackgroundRepeatsValue, BackgroundSizesValue, Color,
+    CssValue, LengthUnit::Percentage, StyleBuilder,
   },
 };
 
@@ -284,3 +284,97 @@ fn test_background_image_noise_v1_with_gradient() {
     "tests/fixtures/style_background_image_noise_v1_blend.png",
   );
 }
+
+#[test]
+fn test_style_background_noise_over_gradient() {
+  let mut container = create_container(BackgroundImagesValue::Css(
+    "linear-gradient(135deg, #ff7e5f, #feb47b)".to_string(),
+  ));
+
+  container.style.background_noise = Some(BackgroundNoise {
+    opacity: Some(0.15),
+    seed: Some(42),
+    ..Default::default()
+  })
+  .into();
+
+  run_style_width_test(
+    container.into(),
+    "tests/fixtures/style_background_noise_over_gradient.png",
+  );
+}
+
+#[test]
+fn test_style_background_image_repeating_linear_stripes() {
+  let background_images = BackgroundImagesValue::Css(
+    "repeating-linear-gradient(45deg, #1e293b 0px, #1e293b 10px, #facc15 10px, #facc15 20px)"
+      .to_string(),
+  );
+
+  let container = create_container(background_images);
+
+  run_style_width_test(
+    container.into(),
+    "tests/fixtures/style_background_image_repeating_linear_stripes.png",
+  );
+}
+
+#[test]
+fn test_style_background_image_repeating_radial_rings() {
+  let background_images = BackgroundImagesValue::Css(
+    "repeating-radial-gradient(circle at center, #0ea5e9 0px, #0ea5e9 12px, #f8fafc 12px, #f8fafc 24px)"
+      .to_string(),
+  );
+
+  let container = create_container(background_images);
+
+  run_style_width_test(
+    container.into(),
+    "tests/fixtures/style_background_image_repeating_radial_rings.png",
+  );
+}
+
+#[test]
+fn test_style_background_blend_mode_multiply() {
+  let container = ContainerNode {
+    style: StyleBuilder::default()
+      .width(Percentage(100.0))
+      .height(Percentage(100.0))
+      .background_color(Color([226, 232, 240, 255]))
+      .background_image(Some(
+        BackgroundImagesValue::Css(
+          "url(assets/images/yeecord.png), linear-gradient(90deg, #f97316, #8b5cf6)".to_string(),
+        )
+        .try_into()
+        .unwrap(),
+      ))
+      .background_size(Some(
+        BackgroundSizesValue::Css("contain, auto".to_string())
+          .try_into()
+          .unwrap(),
+      ))
+      .background_repeat(Some(
+        BackgroundRepeatsValue::Css("no-repeat".to_string())
+          .try_into()
+          .unwrap(),
+      ))
+      .background_position(Some(
+        BackgroundPositionsValue::Css("center".to_string())
+          .try_into()
+          .unwrap(),
+      ))
+      .background_blend_mode(Some(
+        BackgroundBlendModesValue::Css("normal, multiply".to_string())
+          .try_into()
+          .unwrap(),
+      ))
+      .build()
+      .unwrap(),
+    children: None,
+  };
+
+  run_style_width_test(
+    container.into(),
+    "tests/fixtures/style_background_blend_mode_multiply.png",
+  );
+}
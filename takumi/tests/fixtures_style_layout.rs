@@ -1,8 +1,8 @@
 use takumi::layout::{
   node::ContainerNode,
   style::{
-    Color, Display, FlexDirection, Gap, GridLengthUnit, GridTemplateComponent,
-    GridTemplateComponents, GridTrackSize,
+    Color, Display, FlexDirection, Gap, GridLengthUnit, GridLine, GridPlacement, GridTemplateAreas,
+    GridTemplateComponent, GridTemplateComponents, GridTrackSize,
     LengthUnit::{Percentage, Px},
     StyleBuilder,
   },
@@ -25,7 +25,7 @@ fn test_style_flex_basis() {
     children: Some(vec![
       ContainerNode {
         style: StyleBuilder::default()
-          .flex_basis(Px(100.0))
+          .flex_basis(Some(Px(100.0)))
           .height(Px(50.0))
           .background_color(Color([255, 0, 0, 255]))
           .build()
@@ -35,7 +35,7 @@ fn test_style_flex_basis() {
       .into(),
       ContainerNode {
         style: StyleBuilder::default()
-          .flex_basis(Px(100.0))
+          .flex_basis(Some(Px(100.0)))
           .height(Px(50.0))
           .background_color(Color([0, 255, 0, 255]))
           .build()
@@ -45,7 +45,7 @@ fn test_style_flex_basis() {
       .into(),
       ContainerNode {
         style: StyleBuilder::default()
-          .flex_basis(Px(100.0))
+          .flex_basis(Some(Px(100.0)))
           .height(Px(50.0))
           .background_color(Color([255, 255, 0, 255]))
           .build()
@@ -237,3 +237,92 @@ fn test_style_grid_template_rows() {
     "tests/fixtures/style_grid_template_rows.png",
   );
 }
+
+#[test]
+fn test_style_grid_template_areas() {
+  let area = |name: &str, color: Color| {
+    let line = || {
+      Some(GridLine {
+        start: Some(GridPlacement::Named(name.to_string())),
+        end: None,
+      })
+    };
+
+    ContainerNode {
+      style: StyleBuilder::default()
+        .grid_column(line())
+        .grid_row(line())
+        .background_color(color)
+        .build()
+        .unwrap(),
+      children: None,
+    }
+    .into()
+  };
+
+  let track =
+    || GridTemplateComponent::Single(GridTrackSize::Fixed(GridLengthUnit::Unit(Px(100.0))));
+
+  let container = ContainerNode {
+    style: StyleBuilder::default()
+      .width(Px(200.0))
+      .height(Px(200.0))
+      .display(Display::Grid)
+      .grid_template_columns(Some(GridTemplateComponents(vec![track(), track()])))
+      .grid_template_rows(Some(GridTemplateComponents(vec![track(), track()])))
+      .grid_template_areas(Some(GridTemplateAreas(vec![
+        vec!["side".to_string(), "header".to_string()],
+        vec!["side".to_string(), "main".to_string()],
+      ])))
+      .background_color(Color([0, 0, 255, 255]))
+      .build()
+      .unwrap(),
+    // declared out of order, so auto placement would put them in the wrong cells
+    children: Some(vec![
+      area("main", Color([0, 255, 0, 255])),
+      area("header", Color([255, 0, 0, 255])),
+      area("side", Color([255, 255, 0, 255])),
+    ]),
+  };
+
+  run_style_width_test(
+    container.into(),
+    "tests/fixtures/style_grid_template_areas.png",
+  );
+}
+
+#[test]
+fn test_style_grid_area() {
+  let area = |name: &str, color: Color| {
+    ContainerNode {
+      style: StyleBuilder::default()
+        .grid_area(Some(name.to_string()))
+        .background_color(color)
+        .build()
+        .unwrap(),
+      children: None,
+    }
+    .into()
+  };
+
+  let container = ContainerNode {
+    style: StyleBuilder::default()
+      .width(Px(200.0))
+      .height(Px(200.0))
+      .display(Display::Grid)
+      .grid_template_areas(Some(GridTemplateAreas(vec![
+        vec!["header".to_string(), "header".to_string()],
+        vec!["side".to_string(), "main".to_string()],
+      ])))
+      .background_color(Color([0, 0, 255, 255]))
+      .build()
+      .unwrap(),
+    children: Some(vec![
+      area("main", Color([0, 255, 0, 255])),
+      area("side", Color([255, 255, 0, 255])),
+      area("header", Color([255, 0, 0, 255])),
+    ]),
+  };
+
+  run_style_width_test(container.into(), "tests/fixtures/style_grid_area.png");
+}
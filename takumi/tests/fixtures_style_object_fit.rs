@@ -1,6 +1,8 @@
 use takumi::layout::{
   node::ImageNode,
-  style::{LengthUnit::Percentage, ObjectFit, StyleBuilder},
+  style::{
+    BackgroundRepeat, BackgroundRepeatStyle, LengthUnit::Percentage, ObjectFit, StyleBuilder,
+  },
 };
 
 mod test_utils;
@@ -17,6 +19,7 @@ fn test_style_object_fit_contain() {
       .unwrap(),
     width: None,
     height: None,
+    frame_index: None,
     src: "assets/images/yeecord.png".to_string(),
   };
 
@@ -34,6 +37,7 @@ fn test_style_object_fit_cover() {
       .unwrap(),
     width: None,
     height: None,
+    frame_index: None,
     src: "assets/images/yeecord.png".to_string(),
   };
 
@@ -52,6 +56,7 @@ fn test_style_object_fit_fill() {
     src: "assets/images/yeecord.png".to_string(),
     width: None,
     height: None,
+    frame_index: None,
   };
 
   run_style_width_test(image.into(), "tests/fixtures/style_object_fit_fill.png");
@@ -69,11 +74,80 @@ fn test_style_object_fit_none() {
     src: "assets/images/yeecord.png".to_string(),
     width: None,
     height: None,
+    frame_index: None,
   };
 
   run_style_width_test(image.into(), "tests/fixtures/style_object_fit_none.png");
 }
 
+#[test]
+fn test_style_object_fit_none_repeat() {
+  let image = ImageNode {
+    style: StyleBuilder::default()
+      .width(Percentage(100.0))
+      .height(Percentage(100.0))
+      .object_fit(ObjectFit::None)
+      .object_repeat(BackgroundRepeat::repeat())
+      .build()
+      .unwrap(),
+    src: "assets/images/yeecord.png".to_string(),
+    width: None,
+    height: None,
+    frame_index: None,
+  };
+
+  let rendered = run_style_width_test(
+    image.into(),
+    "tests/fixtures/style_object_fit_none_repeat.png",
+  );
+
+  // the 128px image tiles across the whole box
+  assert_eq!(
+    rendered.get_pixel(600, 300),
+    rendered.get_pixel(600 - 128 * 3, 300)
+  );
+  assert_eq!(
+    rendered.get_pixel(600, 300),
+    rendered.get_pixel(600, 300 - 128 * 2)
+  );
+  assert_eq!(
+    rendered.get_pixel(10, 10),
+    rendered.get_pixel(10 + 128 * 8, 10 + 128 * 4)
+  );
+}
+
+#[test]
+fn test_style_object_fit_none_repeat_x() {
+  let image = ImageNode {
+    style: StyleBuilder::default()
+      .width(Percentage(100.0))
+      .height(Percentage(100.0))
+      .object_fit(ObjectFit::None)
+      .object_repeat(BackgroundRepeat {
+        x: BackgroundRepeatStyle::Repeat,
+        y: BackgroundRepeatStyle::NoRepeat,
+      })
+      .build()
+      .unwrap(),
+    src: "assets/images/yeecord.png".to_string(),
+    width: None,
+    height: None,
+    frame_index: None,
+  };
+
+  let rendered = run_style_width_test(
+    image.into(),
+    "tests/fixtures/style_object_fit_none_repeat_x.png",
+  );
+
+  // a single row of tiles, centered by the default object-position
+  assert_eq!(
+    rendered.get_pixel(600, 315),
+    rendered.get_pixel(600 - 128 * 4, 315)
+  );
+  assert_eq!(rendered.get_pixel(600, 50).0[3], 0);
+}
+
 #[test]
 fn test_style_object_fit_scale_down() {
   let image = ImageNode {
@@ -86,6 +160,7 @@ fn test_style_object_fit_scale_down() {
     src: "assets/images/yeecord.png".to_string(),
     width: None,
     height: None,
+    frame_index: None,
   };
 
   run_style_width_test(
@@ -1,8 +1,9 @@
 use takumi::layout::{
   node::ImageNode,
   style::{
-    BackgroundPosition, LengthUnit::Percentage, ObjectFit, PositionComponent, PositionKeywordX,
-    PositionKeywordY, StyleBuilder,
+    BackgroundPosition,
+    LengthUnit::{Percentage, Px},
+    ObjectFit, PositionComponent, PositionKeywordX, PositionKeywordY, StyleBuilder,
   },
 };
 
@@ -24,6 +25,7 @@ fn test_style_object_position_contain_center() {
       .unwrap(),
     width: None,
     height: None,
+    frame_index: None,
     src: "assets/images/yeecord.png".to_string(),
   };
 
@@ -48,6 +50,7 @@ fn test_style_object_position_contain_top_left() {
       .unwrap(),
     width: None,
     height: None,
+    frame_index: None,
     src: "assets/images/yeecord.png".to_string(),
   };
 
@@ -72,6 +75,7 @@ fn test_style_object_position_contain_bottom_right() {
       .unwrap(),
     width: None,
     height: None,
+    frame_index: None,
     src: "assets/images/yeecord.png".to_string(),
   };
 
@@ -96,6 +100,7 @@ fn test_style_object_position_cover_center() {
       .unwrap(),
     width: None,
     height: None,
+    frame_index: None,
     src: "assets/images/yeecord.png".to_string(),
   };
 
@@ -120,6 +125,7 @@ fn test_style_object_position_cover_top_left() {
       .unwrap(),
     width: None,
     height: None,
+    frame_index: None,
     src: "assets/images/yeecord.png".to_string(),
   };
 
@@ -144,6 +150,7 @@ fn test_style_object_position_none_center() {
       .unwrap(),
     width: None,
     height: None,
+    frame_index: None,
     src: "assets/images/yeecord.png".to_string(),
   };
 
@@ -168,6 +175,7 @@ fn test_style_object_position_none_top_left() {
       .unwrap(),
     width: None,
     height: None,
+    frame_index: None,
     src: "assets/images/yeecord.png".to_string(),
   };
 
@@ -192,6 +200,7 @@ fn test_style_object_position_percentage_25_75() {
       .unwrap(),
     width: None,
     height: None,
+    frame_index: None,
     src: "assets/images/yeecord.png".to_string(),
   };
 
@@ -200,3 +209,61 @@ fn test_style_object_position_percentage_25_75() {
     "tests/fixtures/style_object_position_percentage_25_75.png",
   );
 }
+
+/// A 400×200 image with red, green and blue columns under a yellow band along the top.
+const LANDSCAPE_SVG: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" width="400" height="200"><rect width="100" height="200" fill="#f00"/><rect x="100" width="200" height="200" fill="#0f0"/><rect x="300" width="100" height="200" fill="#00f"/><rect width="400" height="40" fill="#ff0"/></svg>"##;
+
+/// A 200×400 image, red in the top half and blue in the bottom half.
+const PORTRAIT_SVG: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" width="200" height="400"><rect width="200" height="200" fill="#f00"/><rect y="200" width="200" height="200" fill="#00f"/></svg>"##;
+
+fn cover_image(src: &str, x: f32, y: f32) -> ImageNode {
+  ImageNode {
+    style: StyleBuilder::default()
+      .width(Px(200.0))
+      .height(Px(200.0))
+      .object_fit(ObjectFit::Cover)
+      .object_position(BackgroundPosition {
+        x: PositionComponent::Length(Percentage(x)),
+        y: PositionComponent::Length(Percentage(y)),
+      })
+      .build()
+      .unwrap(),
+    width: None,
+    height: None,
+    frame_index: None,
+    src: src.to_string(),
+  }
+}
+
+#[test]
+fn test_style_object_position_cover_focal_point_landscape() {
+  let image = run_style_width_test(
+    cover_image(LANDSCAPE_SVG, 50.0, 0.0).into(),
+    "tests/fixtures/style_object_position_cover_focal_point_landscape.png",
+  );
+
+  // the horizontal overflow is split evenly, only the green column is left
+  assert_eq!(image.get_pixel(5, 100).0, [0, 255, 0, 255]);
+  assert_eq!(image.get_pixel(194, 100).0, [0, 255, 0, 255]);
+  // nothing overflows vertically, so the top band stays in frame
+  assert_eq!(image.get_pixel(100, 5).0, [255, 255, 0, 255]);
+}
+
+#[test]
+fn test_style_object_position_cover_focal_point_portrait() {
+  let top = run_style_width_test(
+    cover_image(PORTRAIT_SVG, 50.0, 0.0).into(),
+    "tests/fixtures/style_object_position_cover_focal_point_top.png",
+  );
+
+  assert_eq!(top.get_pixel(100, 5).0, [255, 0, 0, 255]);
+  assert_eq!(top.get_pixel(100, 194).0, [255, 0, 0, 255]);
+
+  let bottom = run_style_width_test(
+    cover_image(PORTRAIT_SVG, 50.0, 100.0).into(),
+    "tests/fixtures/style_object_position_cover_focal_point_bottom.png",
+  );
+
+  assert_eq!(bottom.get_pixel(100, 5).0, [0, 0, 255, 255]);
+  assert_eq!(bottom.get_pixel(100, 194).0, [0, 0, 255, 255]);
+}
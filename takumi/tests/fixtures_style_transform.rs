@@ -95,6 +95,7 @@ fn create_rotated_container(angle: f32, transform_origin: Option<BackgroundPosit
       .unwrap(),
     width: None,
     height: None,
+    frame_index: None,
     src: "assets/images/yeecord.png".to_string(),
   }
 }
@@ -151,6 +152,7 @@ fn test_style_transform_translate_and_scale() {
           .unwrap(),
         width: None,
         height: None,
+        frame_index: None,
       }
       .into(),
     ]),
@@ -1,10 +1,11 @@
 use smallvec::smallvec;
 use takumi::layout::{
-  node::{ContainerNode, TextNode},
+  node::{ContainerNode, ImageNode, NodeKind, TextNode},
   style::{
-    BoxShadow, BoxShadows, Color, FontWeight,
+    BackgroundImagesValue, BorderRadius, BorderRadiusValue, BoxShadow, BoxShadows, ClipPathValue,
+    Color, FiltersValue, FontWeight, Gap,
     LengthUnit::{Percentage, Px, Rem},
-    LineHeight, Position, Sides, StyleBuilder,
+    LineHeight, MixBlendMode, Outline, OutlineStyle, Position, Sides, StyleBuilder,
   },
 };
 
@@ -117,6 +118,263 @@ fn test_style_border_width_with_radius() {
   );
 }
 
+#[test]
+fn test_style_border_image_gradient() {
+  let container = ContainerNode {
+    style: StyleBuilder::default()
+      .width(Percentage(100.0))
+      .height(Percentage(100.0))
+      .padding(Sides([Rem(4.0); 4]))
+      .background_color(Color::white())
+      .build()
+      .unwrap(),
+    children: Some(vec![
+      ContainerNode {
+        style: StyleBuilder::default()
+          .width(Rem(16.0))
+          .height(Rem(8.0))
+          .border_radius(Sides([Px(16.0); 4]))
+          .border_width(Sides([Px(8.0); 4]))
+          .border_image(Some(
+            BackgroundImagesValue::Css("linear-gradient(90deg, #8b5cf6, #ec4899)".to_string())
+              .try_into()
+              .unwrap(),
+          ))
+          .build()
+          .unwrap(),
+        children: None,
+      }
+      .into(),
+    ]),
+  };
+
+  run_style_width_test(
+    container.into(),
+    "tests/fixtures/style_border_image_gradient.png",
+  );
+}
+
+#[test]
+fn test_style_outline() {
+  let item = |outline: Option<Outline>| -> NodeKind {
+    ContainerNode {
+      style: StyleBuilder::default()
+        .width(Rem(10.0))
+        .height(Rem(6.0))
+        .background_color(Color([59, 130, 246, 255]))
+        .border_radius(Sides([Px(12.0); 4]))
+        .outline(outline)
+        .outline_offset(Px(4.0))
+        .build()
+        .unwrap(),
+      children: None,
+    }
+    .into()
+  };
+
+  let container = ContainerNode {
+    style: StyleBuilder::default()
+      .width(Percentage(100.0))
+      .height(Percentage(100.0))
+      .padding(Sides([Rem(4.0); 4]))
+      .gap(Gap(Px(24.0), Px(24.0)))
+      .background_color(Color::white())
+      .build()
+      .unwrap(),
+    children: Some(vec![
+      item(None),
+      item(Some(Outline {
+        width: Px(3.0),
+        style: OutlineStyle::Solid,
+        color: Some(Color([249, 115, 22, 255]).into()),
+      })),
+      item(None),
+    ]),
+  };
+
+  run_style_width_test(container.into(), "tests/fixtures/style_outline.png");
+}
+
+#[test]
+fn test_style_clip_path() {
+  let item = |clip_path: &str| -> NodeKind {
+    ContainerNode {
+      style: StyleBuilder::default()
+        .width(Rem(10.0))
+        .height(Rem(10.0))
+        .background_image(Some(
+          BackgroundImagesValue::Css("linear-gradient(135deg, #f97316, #8b5cf6)".to_string())
+            .try_into()
+            .unwrap(),
+        ))
+        .clip_path(Some(
+          ClipPathValue::Css(clip_path.to_string())
+            .try_into()
+            .unwrap(),
+        ))
+        .build()
+        .unwrap(),
+      children: None,
+    }
+    .into()
+  };
+
+  let container = ContainerNode {
+    style: StyleBuilder::default()
+      .width(Percentage(100.0))
+      .height(Percentage(100.0))
+      .padding(Sides([Rem(4.0); 4]))
+      .gap(Gap(Px(24.0), Px(24.0)))
+      .background_color(Color::white())
+      .build()
+      .unwrap(),
+    children: Some(vec![
+      item("circle()"),
+      item("polygon(0 0, 100% 0, 100% 100%, 50% 75%, 0 100%)"),
+      item("inset(10% 5% round 24px)"),
+    ]),
+  };
+
+  run_style_width_test(container.into(), "tests/fixtures/style_clip_path.png");
+}
+
+#[test]
+fn test_style_mask_image() {
+  let item = |mask_image: &str| -> NodeKind {
+    ContainerNode {
+      style: StyleBuilder::default()
+        .width(Rem(12.0))
+        .height(Rem(12.0))
+        .background_image(Some(
+          BackgroundImagesValue::Css("linear-gradient(135deg, #f97316, #8b5cf6)".to_string())
+            .try_into()
+            .unwrap(),
+        ))
+        .mask_image(Some(
+          BackgroundImagesValue::Css(mask_image.to_string())
+            .try_into()
+            .unwrap(),
+        ))
+        .build()
+        .unwrap(),
+      children: Some(vec![
+        TextNode {
+          style: StyleBuilder::default()
+            .font_size(Px(48.0))
+            .color(Color::white())
+            .build()
+            .unwrap(),
+          text: "Mask".to_string(),
+        }
+        .into(),
+      ]),
+    }
+    .into()
+  };
+
+  let container = ContainerNode {
+    style: StyleBuilder::default()
+      .width(Percentage(100.0))
+      .height(Percentage(100.0))
+      .padding(Sides([Rem(4.0); 4]))
+      .gap(Gap(Px(24.0), Px(24.0)))
+      .background_color(Color::white())
+      .build()
+      .unwrap(),
+    children: Some(vec![
+      item("linear-gradient(to bottom, black 40%, transparent)"),
+      item(
+        r#"url('<svg xmlns="http://www.w3.org/2000/svg" width="64" height="64" viewBox="0 0 24 24"><path d="M12 2l3 7h7l-5.5 4.5 2 7.5-6.5-4.5-6.5 4.5 2-7.5L2 9h7z"/></svg>')"#,
+      ),
+    ]),
+  };
+
+  run_style_width_test(container.into(), "tests/fixtures/style_mask_image.png");
+}
+
+#[test]
+fn test_style_mix_blend_mode() {
+  let overlay = |background: &str, mix_blend_mode: MixBlendMode| -> NodeKind {
+    ContainerNode {
+      style: StyleBuilder::default()
+        .position(Position::Absolute)
+        .inset(Sides([Px(0.0); 4]))
+        .background_image(Some(
+          BackgroundImagesValue::Css(background.to_string())
+            .try_into()
+            .unwrap(),
+        ))
+        .mix_blend_mode(mix_blend_mode)
+        .build()
+        .unwrap(),
+      children: None,
+    }
+    .into()
+  };
+
+  let item = |background: Color, children: Vec<NodeKind>| -> NodeKind {
+    ContainerNode {
+      style: StyleBuilder::default()
+        .width(Rem(16.0))
+        .height(Rem(16.0))
+        .background_color(background)
+        .build()
+        .unwrap(),
+      children: Some(children),
+    }
+    .into()
+  };
+
+  let container = ContainerNode {
+    style: StyleBuilder::default()
+      .width(Percentage(100.0))
+      .height(Percentage(100.0))
+      .padding(Sides([Rem(4.0); 4]))
+      .gap(Gap(Px(24.0), Px(24.0)))
+      .background_color(Color::white())
+      .build()
+      .unwrap(),
+    children: Some(vec![
+      item(
+        Color::white(),
+        vec![
+          ImageNode {
+            style: StyleBuilder::default()
+              .width(Percentage(100.0))
+              .height(Percentage(100.0))
+              .build()
+              .unwrap(),
+            src: "assets/images/yeecord.png".to_string(),
+            width: None,
+            height: None,
+            frame_index: None,
+          }
+          .into(),
+          overlay(
+            "linear-gradient(135deg, #f97316, #8b5cf6)",
+            MixBlendMode::Multiply,
+          ),
+        ],
+      ),
+      item(
+        Color([15, 23, 42, 255]),
+        vec![
+          overlay(
+            "radial-gradient(circle, #8b5cf6, transparent 70%)",
+            MixBlendMode::Normal,
+          ),
+          overlay(
+            "radial-gradient(circle at 30% 30%, #f97316, transparent 60%)",
+            MixBlendMode::Screen,
+          ),
+        ],
+      ),
+    ]),
+  };
+
+  run_style_width_test(container.into(), "tests/fixtures/style_mix_blend_mode.png");
+}
+
 #[test]
 fn test_style_box_shadow() {
   let container = ContainerNode {
@@ -236,6 +494,46 @@ fn test_style_border_radius_circle() {
   );
 }
 
+#[test]
+fn test_style_border_radius_elliptical() {
+  let item = |radius: &str| -> NodeKind {
+    ContainerNode {
+      style: StyleBuilder::default()
+        .width(Rem(14.0))
+        .height(Rem(8.0))
+        .background_color(Color([59, 130, 246, 255]))
+        .border_width(Sides([Px(4.0); 4]))
+        .border_color(Color([30, 64, 175, 255]))
+        .border_radius(BorderRadius::try_from(BorderRadiusValue::Css(radius.to_string())).unwrap())
+        .build()
+        .unwrap(),
+      children: None,
+    }
+    .into()
+  };
+
+  let container = ContainerNode {
+    style: StyleBuilder::default()
+      .width(Percentage(100.0))
+      .height(Percentage(100.0))
+      .padding(Sides([Rem(4.0); 4]))
+      .gap(Gap(Px(24.0), Px(24.0)))
+      .background_color(Color::white())
+      .build()
+      .unwrap(),
+    children: Some(vec![
+      item("50%"),
+      item("9999px"),
+      item("40px 8px / 16px 48px"),
+    ]),
+  };
+
+  run_style_width_test(
+    container.into(),
+    "tests/fixtures/style_border_radius_elliptical.png",
+  );
+}
+
 // https://github.com/kane50613/takumi/issues/151
 #[test]
 fn test_style_border_radius_width_offset() {
@@ -266,7 +564,7 @@ fn test_style_border_radius_width_offset() {
               .padding(Sides([Rem(4.0); 4]))
               .font_size(Rem(4.0))
               .font_weight(FontWeight::from(500.0))
-              .line_height(LineHeight(Rem(4.0 * 1.5)))
+              .line_height(LineHeight::Length(Rem(4.0 * 1.5)))
               .build()
               .unwrap(),
           }
@@ -282,3 +580,46 @@ fn test_style_border_radius_width_offset() {
     "tests/fixtures/style_border_radius_width_offset.png",
   );
 }
+
+#[test]
+fn test_style_backdrop_filter_blur() {
+  let container = ContainerNode {
+    style: StyleBuilder::default()
+      .width(Percentage(100.0))
+      .height(Percentage(100.0))
+      .padding(Sides([Rem(6.0); 4]))
+      .background_image(Some(
+        BackgroundImagesValue::Css(
+          "repeating-linear-gradient(45deg, #0f172a 0px, #0f172a 20px, #f97316 20px, #f97316 40px)"
+            .to_string(),
+        )
+        .try_into()
+        .unwrap(),
+      ))
+      .build()
+      .unwrap(),
+    children: Some(vec![
+      ContainerNode {
+        style: StyleBuilder::default()
+          .width(Percentage(100.0))
+          .height(Percentage(100.0))
+          .border_radius(Sides([Px(32.0); 4]))
+          .background_color(Color([255, 255, 255, 64]))
+          .backdrop_filter(Some(
+            FiltersValue::Css("blur(12px) saturate(150%)".to_string())
+              .try_into()
+              .unwrap(),
+          ))
+          .build()
+          .unwrap(),
+        children: None,
+      }
+      .into(),
+    ]),
+  };
+
+  run_style_width_test(
+    container.into(),
+    "tests/fixtures/style_backdrop_filter_blur.png",
+  );
+}
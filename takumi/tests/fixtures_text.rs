@@ -1,16 +1,21 @@
 use smallvec::smallvec;
-use takumi::layout::{
-  node::{ContainerNode, NodeKind, TextNode},
-  style::{
-    BackgroundImagesValue, BackgroundPositionsValue, BackgroundRepeatsValue, BackgroundSizesValue,
-    Color, FontWeight,
-    LengthUnit::{Em, Percentage, Px},
-    LineHeight, StyleBuilder, TextAlign, TextOverflow, TextShadow, TextShadows, TextTransform,
+use takumi::{
+  layout::{
+    Viewport,
+    node::{ContainerNode, NodeKind, TextNode},
+    style::{
+      BackgroundImagesValue, BackgroundPositionsValue, BackgroundRepeatsValue,
+      BackgroundSizesValue, Color, FontWeight, Hyphens, InitialLetter,
+      LengthUnit::{Em, Percentage, Px},
+      LineHeight, StyleBuilder, TabSize, TextAlign, TextOverflow, TextShadow, TextShadows,
+      TextTransform, VerticalAlign,
+    },
   },
+  rendering::{compute_layout_tree, font_metrics},
 };
 
 mod test_utils;
-use test_utils::run_style_width_test;
+use test_utils::{create_test_context, run_style_width_test};
 
 // Basic text render with defaults
 #[test]
@@ -67,7 +72,7 @@ fn fixtures_text_typography_line_height_40px() {
     style: StyleBuilder::default()
       .background_color(Color([240, 240, 240, 255]))
       .font_size(Px(24.0))
-      .line_height(LineHeight(Px(40.0)))
+      .line_height(LineHeight::Length(Px(40.0)))
       .build()
       .unwrap(),
     text: "Line height 40px".to_string(),
@@ -97,6 +102,25 @@ fn fixtures_text_typography_letter_spacing_2px() {
   );
 }
 
+#[test]
+fn fixtures_text_typography_letter_spacing_negative() {
+  // the pairs overlap, the box still covers the last glyph
+  let text = TextNode {
+    style: StyleBuilder::default()
+      .background_color(Color([240, 240, 240, 255]))
+      .font_size(Px(72.0))
+      .letter_spacing(Some(Px(-8.0)))
+      .build()
+      .unwrap(),
+    text: "AVAV".to_string(),
+  };
+
+  run_style_width_test(
+    text.into(),
+    "tests/fixtures/text_typography_letter_spacing_negative.png",
+  );
+}
+
 #[test]
 fn fixtures_text_align_start() {
   let text = TextNode {
@@ -155,7 +179,7 @@ Ut enim ad minim veniam, quis nostrud exercitation ullamco laboris nisi ut aliqu
     style: StyleBuilder::default()
       .background_color(Color([240, 240, 240, 255]))
       .font_size(Px(18.0))
-      .line_height(LineHeight(Px(26.0)))
+      .line_height(LineHeight::Length(Px(26.0)))
       .text_align(TextAlign::Justify)
       .text_overflow(TextOverflow::Clip)
       .build()
@@ -177,7 +201,7 @@ Ut enim ad minim veniam, quis nostrud exercitation ullamco laboris nisi ut aliqu
       .width(Em(12.0))
       .background_color(Color([240, 240, 240, 255]))
       .font_size(Px(18.0))
-      .line_height(LineHeight(Px(24.0)))
+      .line_height(LineHeight::Length(Px(24.0)))
       .text_overflow(TextOverflow::Ellipsis)
       .line_clamp(Some(2))
       .build()
@@ -188,6 +212,43 @@ Ut enim ad minim veniam, quis nostrud exercitation ullamco laboris nisi ut aliqu
   run_style_width_test(text.into(), "tests/fixtures/text_ellipsis_line_clamp_2.png");
 }
 
+#[test]
+fn fixtures_text_fade_line_clamp_2() {
+  let long_text = "Lorem ipsum dolor sit amet, consectetur adipiscing elit. \
+Sed do eiusmod tempor incididunt ut labore et dolore magna aliqua. \
+Ut enim ad minim veniam, quis nostrud exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat.";
+
+  let text = TextNode {
+    style: StyleBuilder::default()
+      .width(Em(12.0))
+      .background_color(Color([240, 240, 240, 255]))
+      .font_size(Px(18.0))
+      .line_height(LineHeight::Length(Px(24.0)))
+      .text_overflow(TextOverflow::Fade)
+      .line_clamp(Some(2))
+      .build()
+      .unwrap(),
+    text: long_text.to_string(),
+  };
+
+  let image = run_style_width_test(text.into(), "tests/fixtures/text_fade_line_clamp_2.png");
+
+  let darkest = |columns: std::ops::Range<u32>, rows: std::ops::Range<u32>| {
+    columns
+      .flat_map(|x| rows.clone().map(move |y| (x, y)))
+      .map(|(x, y)| image.get_pixel(x, y).0[0])
+      .min()
+      .unwrap()
+  };
+
+  // the first line and the start of the last line are painted as usual
+  assert_eq!(darkest(140..180, 0..24), 0);
+  assert_eq!(darkest(0..60, 24..48), 0);
+
+  // only the end of the last line is faded towards the background
+  assert!(darkest(140..192, 24..48) > 150);
+}
+
 #[test]
 fn fixtures_text_transform_all() {
   let container = ContainerNode {
@@ -322,3 +383,224 @@ fn fixtures_text_shadow() {
 
   run_style_width_test(text.into(), "tests/fixtures/text_shadow.png");
 }
+
+// Semi-transparent white text over a dark background must not produce darkened edges
+#[test]
+fn fixtures_text_semi_transparent_on_dark() {
+  let background = Color([24, 24, 32, 255]);
+
+  let text = TextNode {
+    style: StyleBuilder::default()
+      .background_color(background)
+      .color(Color([255, 255, 255, 128]))
+      .font_size(Px(64.0))
+      .build()
+      .unwrap(),
+    text: "Soft Edges".to_string(),
+  };
+
+  let image = run_style_width_test(
+    text.into(),
+    "tests/fixtures/text_semi_transparent_on_dark.png",
+  );
+
+  // only the text box is painted, the rest of the viewport stays transparent
+  for pixel in image.pixels().filter(|pixel| pixel.0[3] != 0) {
+    for channel in 0..3 {
+      assert!(
+        pixel.0[channel] >= background.0[channel],
+        "pixel {pixel:?} is darker than the background"
+      );
+    }
+  }
+}
+
+fn create_vertical_align_row(vertical_align: VerticalAlign) -> NodeKind {
+  let icon = |color: Color| -> NodeKind {
+    ContainerNode {
+      style: StyleBuilder::default()
+        .width(Px(32.0))
+        .height(Px(32.0))
+        .background_color(color)
+        .vertical_align(Some(vertical_align))
+        .build()
+        .unwrap(),
+      children: None,
+    }
+    .into()
+  };
+
+  ContainerNode {
+    style: StyleBuilder::default()
+      .width(Percentage(100.0))
+      .height(Percentage(100.0))
+      .font_size(Px(64.0))
+      .background_color(Color([240, 240, 240, 255]))
+      .build()
+      .unwrap(),
+    children: Some(vec![
+      icon(Color([255, 0, 0, 255])),
+      TextNode {
+        style: StyleBuilder::default().build().unwrap(),
+        text: "Baseline xg".to_string(),
+      }
+      .into(),
+      icon(Color([0, 0, 255, 255])),
+    ]),
+  }
+  .into()
+}
+
+#[test]
+fn fixtures_text_vertical_align_baseline() {
+  let context = create_test_context();
+  let node = create_vertical_align_row(VerticalAlign::Baseline);
+
+  let metrics = font_metrics(
+    Viewport::new(1200, 630),
+    &context,
+    "x",
+    &StyleBuilder::default().font_size(Px(64.0)).build().unwrap(),
+  )
+  .unwrap();
+
+  // the default line height is 1.2em, the rest is split above and below the glyphs
+  let half_leading = (64.0 * 1.2 - metrics.ascent - metrics.descent) / 2.0;
+  let baseline = half_leading + metrics.ascent;
+
+  let tree = compute_layout_tree(Viewport::new(1200, 630), &context, node.clone());
+  let icon = tree.get(&[0]).unwrap();
+
+  // parley snaps the baseline to whole pixels
+  assert!((icon.location.y + icon.size.height - baseline).abs() < 1.5);
+
+  run_style_width_test(node, "tests/fixtures/text_vertical_align_baseline.png");
+}
+
+#[test]
+fn fixtures_text_vertical_align_middle() {
+  run_style_width_test(
+    create_vertical_align_row(VerticalAlign::Middle),
+    "tests/fixtures/text_vertical_align_middle.png",
+  );
+}
+
+#[test]
+fn fixtures_text_vertical_align_super_sub() {
+  let context = create_test_context();
+
+  let span = |text: &str, vertical_align: Option<VerticalAlign>| -> NodeKind {
+    TextNode {
+      style: StyleBuilder::default()
+        .vertical_align(vertical_align)
+        .build()
+        .unwrap(),
+      text: text.to_string(),
+    }
+    .into()
+  };
+
+  let node: NodeKind = ContainerNode {
+    style: StyleBuilder::default()
+      .font_size(Px(64.0))
+      .background_color(Color([240, 240, 240, 255]))
+      .build()
+      .unwrap(),
+    children: Some(vec![
+      span("1", None),
+      span("st", Some(VerticalAlign::Super)),
+      span(" place, H", None),
+      span("2", Some(VerticalAlign::Sub)),
+      span("O", None),
+    ]),
+  }
+  .into();
+
+  let tree = compute_layout_tree(Viewport::new(1200, 630), &context, node.clone());
+  let row = tree.get(&[]).unwrap();
+  let text = tree.get(&[0]).unwrap();
+  let superscript = tree.get(&[1]).unwrap();
+  let subscript = tree.get(&[3]).unwrap();
+
+  // the scripts are set smaller, the row grows around them instead of clipping them
+  assert!(superscript.size.height < 64.0 * 1.2);
+  assert!(superscript.location.y >= row.location.y - 0.5);
+  assert!(superscript.location.y < text.location.y);
+  assert!(subscript.location.y > text.location.y);
+  assert!(subscript.location.y + subscript.size.height <= row.location.y + row.size.height + 0.5);
+  // the room made in the row isn't part of the painted boxes
+  assert!((subscript.size.height - superscript.size.height).abs() < 0.5);
+
+  run_style_width_test(node, "tests/fixtures/text_vertical_align_super_sub.png");
+}
+
+fn create_hyphens_column(hyphens: Hyphens) -> NodeKind {
+  TextNode {
+    style: StyleBuilder::default()
+      .width(Px(220.0))
+      .font_size(Px(32.0))
+      .text_align(TextAlign::Justify)
+      .hyphens(hyphens)
+      .background_color(Color([240, 240, 240, 255]))
+      .build()
+      .unwrap(),
+    text: "Incomprehen\u{AD}sibilities of extra\u{AD}ordinary typo\u{AD}graphy".to_string(),
+  }
+  .into()
+}
+
+#[test]
+fn fixtures_text_hyphens_manual() {
+  run_style_width_test(
+    create_hyphens_column(Hyphens::Manual),
+    "tests/fixtures/text_hyphens_manual.png",
+  );
+}
+
+#[test]
+fn fixtures_text_hyphens_none() {
+  run_style_width_test(
+    create_hyphens_column(Hyphens::None),
+    "tests/fixtures/text_hyphens_none.png",
+  );
+}
+
+#[test]
+fn fixtures_text_tab_size() {
+  // the box is sized around the expanded tabs
+  let text = TextNode {
+    style: StyleBuilder::default()
+      .font_size(Px(28.0))
+      .tab_size(TabSize::Length(Em(10.0)))
+      .background_color(Color([240, 240, 240, 255]))
+      .build()
+      .unwrap(),
+    text: "name\tsize\tkind\nCargo.toml\t1.2 KB\tmanifest\nsrc\t-\tdirectory".to_string(),
+  };
+
+  run_style_width_test(text.into(), "tests/fixtures/text_tab_size.png");
+}
+
+#[test]
+fn fixtures_text_initial_letter() {
+  // the letter spans the first three lines, which are shortened to make room for it
+  let long_text = "Lorem ipsum dolor sit amet, consectetur adipiscing elit. \
+Sed do eiusmod tempor incididunt ut labore et dolore magna aliqua. \
+Ut enim ad minim veniam, quis nostrud exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat. \
+Duis aute irure dolor in reprehenderit in voluptate velit esse cillum dolore eu fugiat nulla pariatur.";
+
+  let text = TextNode {
+    style: StyleBuilder::default()
+      .width(Px(600.0))
+      .background_color(Color([240, 240, 240, 255]))
+      .font_size(Px(24.0))
+      .line_height(LineHeight::Length(Px(34.0)))
+      .text_align(TextAlign::Justify)
+      .initial_letter(Some(InitialLetter { size: 3.0, sink: 3 }))
+      .build()
+      .unwrap(),
+    text: long_text.to_string(),
+  };
+
+  run_style_width_test(text.into(), "tests/fixtures/text_initial_letter.png");
+}
@@ -1,6 +1,26 @@
-use std::sync::LazyLock;
+use std::{
+  future::ready,
+  pin::pin,
+  sync::{
+    Arc, LazyLock,
+    atomic::{AtomicUsize, Ordering},
+  },
+  task::{Context as TaskContext, Poll, Waker},
+};
 
-use takumi::{GlobalContext, resources::font::FontError};
+use takumi::{
+  GlobalContext,
+  image::RgbaImage,
+  layout::{
+    Viewport,
+    style::{LengthUnit::Px, StyleBuilder},
+  },
+  rendering::{font_metrics, preload_glyphs},
+  resources::{
+    font::{DEFAULT_GLYPH_CACHE_BUDGET, FontContext, FontError, FontFormat},
+    image::ImageStoreUsage,
+  },
+};
 
 // Include test font data using include_bytes!
 static TTF_FONT: &[u8] = include_bytes!("../../assets/fonts/noto-sans/NotoSans-Regular.ttf");
@@ -65,3 +85,214 @@ fn test_too_short_data() {
   let result = CONTEXT.font_context.load_and_store(short_data, None, None);
   assert!(matches!(result, Err(FontError::UnsupportedFormat)));
 }
+
+#[test]
+fn test_truncated_font_is_rejected() {
+  // a valid header without the tables it points to
+  let result = CONTEXT
+    .font_context
+    .load_and_store(&TTF_FONT[..64], None, None);
+  assert!(matches!(
+    result,
+    Err(FontError::Malformed | FontError::MissingTable(_))
+  ));
+}
+
+#[test]
+fn test_font_missing_table_is_rejected() {
+  let mut data = TTF_FONT.to_vec();
+  let table_count = u16::from_be_bytes([data[4], data[5]]) as usize;
+
+  // rename the cmap entry of the table directory so the font has no character map
+  let entry = (0..table_count)
+    .map(|index| 12 + index * 16)
+    .find(|&offset| &data[offset..offset + 4] == b"cmap")
+    .unwrap();
+  data[entry..entry + 4].copy_from_slice(b"xmap");
+
+  let result = CONTEXT.font_context.load_and_store(&data, None, None);
+  assert!(matches!(result, Err(FontError::MissingTable("cmap"))));
+}
+
+#[test]
+fn test_inspect_font() {
+  let report = FontContext::inspect(TTF_FONT).unwrap();
+  assert_eq!(report.format, FontFormat::Ttf);
+  assert_eq!(report.family_names, vec!["Noto Sans".to_string()]);
+  assert!(report.glyph_count > 0);
+
+  let report = FontContext::inspect(WOFF2_FONT).unwrap();
+  assert_eq!(report.format, FontFormat::Woff2);
+  assert!(!report.family_names.is_empty());
+
+  assert!(matches!(
+    FontContext::inspect(&[0x00, 0x01, 0x02, 0x03]),
+    Err(FontError::UnsupportedFormat)
+  ));
+}
+
+#[test]
+fn test_preload_glyphs() {
+  let context = GlobalContext::default();
+
+  context
+    .font_context
+    .load_and_store(TTF_FONT, None, None)
+    .unwrap();
+
+  let warmed = preload_glyphs(
+    Viewport::new(1200, 630),
+    &context,
+    "Hello",
+    &StyleBuilder::default().font_size(Px(32.0)).build().unwrap(),
+  );
+
+  // "l" is shared, so only 4 distinct glyphs
+  assert_eq!(warmed, 4);
+  assert_eq!(context.font_context.glyph_cache_len(), 4);
+  assert!(context.font_context.glyph_cache_stats().0 > 0);
+}
+
+#[test]
+fn test_memory_report_and_purge_all() {
+  let context = GlobalContext::default();
+
+  context
+    .font_context
+    .load_and_store(TTF_FONT, None, None)
+    .unwrap();
+
+  preload_glyphs(
+    Viewport::new(1200, 630),
+    &context,
+    "Hello",
+    &StyleBuilder::default().font_size(Px(32.0)).build().unwrap(),
+  );
+
+  context
+    .persistent_image_store
+    .insert("logo", Arc::new(RgbaImage::new(10, 10).into()));
+
+  let report = context.memory_report();
+
+  assert_eq!(report.glyph_entries, 4);
+  assert!(report.glyph_bytes > 0);
+  assert_eq!(report.glyph_budget, DEFAULT_GLYPH_CACHE_BUDGET);
+  assert_eq!(
+    report.image_store,
+    ImageStoreUsage {
+      entries: 1,
+      bytes: 400,
+    }
+  );
+
+  context.purge_all();
+
+  let report = context.memory_report();
+
+  assert_eq!(report.glyph_entries, 0);
+  assert_eq!(report.glyph_bytes, 0);
+  assert_eq!(report.image_store, ImageStoreUsage::default());
+
+  // the fonts stay loaded
+  assert!(!context.font_context.loaded_families().is_empty());
+}
+
+#[test]
+fn test_font_metrics() {
+  let context = GlobalContext::default();
+  let style = StyleBuilder::default()
+    .font_size(Px(100.0))
+    .build()
+    .unwrap();
+
+  assert_eq!(
+    font_metrics(Viewport::new(1200, 630), &context, "x", &style),
+    None
+  );
+
+  context
+    .font_context
+    .load_and_store(TTF_FONT, None, None)
+    .unwrap();
+
+  let metrics = font_metrics(Viewport::new(1200, 630), &context, "x", &style).unwrap();
+
+  // Noto Sans: 1069/293 units per 1000 em ascent/descent, 714 cap height, 536 x height
+  assert!((metrics.ascent - 106.9).abs() < 0.1);
+  assert!((metrics.descent - 29.3).abs() < 0.1);
+  assert!((metrics.cap_height - 71.4).abs() < 0.1);
+  assert!((metrics.x_height - 53.6).abs() < 0.1);
+}
+
+/// Polls a future that never waits, like the ready fetches of the tests.
+fn block_on<F: Future>(future: F) -> F::Output {
+  let mut future = pin!(future);
+  let mut context = TaskContext::from_waker(Waker::noop());
+
+  match future.as_mut().poll(&mut context) {
+    Poll::Ready(output) => output,
+    Poll::Pending => panic!("the future should be ready"),
+  }
+}
+
+#[test]
+fn test_load_font_from_url() {
+  let context = GlobalContext::default();
+  let fetches = AtomicUsize::new(0);
+
+  let fetch = |url: &str| {
+    assert_eq!(url, "https://example.com/NotoSans.ttf");
+    fetches.fetch_add(1, Ordering::Relaxed);
+
+    ready(Ok::<_, std::io::Error>(TTF_FONT))
+  };
+
+  let load = || {
+    block_on(
+      context
+        .font_context
+        .load_and_store_from_url("https://example.com/NotoSans.ttf", fetch),
+    )
+  };
+
+  assert!(load().unwrap());
+  assert!(!context.font_context.loaded_families().is_empty());
+
+  // the url is cached, so it isn't fetched again
+  assert!(!load().unwrap());
+  assert_eq!(fetches.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn test_load_font_from_url_errors() {
+  let context = GlobalContext::default();
+
+  let result = block_on(
+    context
+      .font_context
+      .load_and_store_from_url("https://example.com/missing.ttf", |_| {
+        ready(Err::<&[u8], _>("404 Not Found"))
+      }),
+  );
+  assert!(matches!(result, Err(FontError::Fetch(_))));
+
+  // a url that failed to load can be tried again
+  let result = block_on(
+    context
+      .font_context
+      .load_and_store_from_url("https://example.com/invalid.ttf", |_| {
+        ready(Ok::<_, std::io::Error>(b"not a font".as_slice()))
+      }),
+  );
+  assert!(matches!(result, Err(FontError::UnsupportedFormat)));
+
+  let result = block_on(
+    context
+      .font_context
+      .load_and_store_from_url("https://example.com/invalid.ttf", |_| {
+        ready(Ok::<_, std::io::Error>(TTF_FONT))
+      }),
+  );
+  assert!(result.unwrap());
+}
@@ -0,0 +1,1391 @@
+use std::{
+  sync::{
+    Arc,
+    atomic::{AtomicU32, Ordering},
+  },
+  time::Duration,
+};
+
+use image::RgbaImage;
+use parley::GenericFamily;
+use smallvec::smallvec;
+use taffy::{Point, Size};
+use takumi::{
+  Error, GlobalContext,
+  layout::{
+    Viewport,
+    node::{ContainerNode, ImageNode, NodeKind, TextNode},
+    style::{
+      BackgroundImagesValue, BoxShadow, BoxShadows, ClipPathValue, Color, Display, Filters,
+      FiltersValue, FlexDirection, LengthUnit,
+      LengthUnit::{Percentage, Px},
+      LineHeight, ObjectFit, Outline, OutlineStyle, Overflow, Position, Sides, StyleBuilder,
+      Transform, Transforms, Visibility, WritingMode,
+    },
+  },
+  rendering::{
+    DebugOptions, ImageOutputFormat, MissingGlyph, compute_layout_tree, measure_root, render,
+    render_batch, render_batch_encoded, render_into, render_scaled, render_with_stats,
+  },
+  resources::{
+    font::FontContext,
+    image::{ImageResourceError, ImageResult, ImageSource, ImageStore, RetryImageStore},
+  },
+};
+
+/// A 100×100 root holding a child far wider than the canvas.
+fn create_wide_node(mut child_style: StyleBuilder) -> NodeKind {
+  ContainerNode {
+    style: StyleBuilder::default()
+      .width(Px(100.0))
+      .height(Px(100.0))
+      .build()
+      .unwrap(),
+    children: Some(vec![
+      ContainerNode {
+        style: child_style
+          .width(Px(1_000_000.0))
+          .height(Px(50.0))
+          .flex_shrink(Some(0.0))
+          .build()
+          .unwrap(),
+        children: None,
+      }
+      .into(),
+    ]),
+  }
+  .into()
+}
+
+#[test]
+fn test_render_rejects_oversized_node_images() {
+  let context = GlobalContext {
+    max_canvas_pixels: 100 * 100,
+    ..Default::default()
+  };
+  let viewport = Viewport::new(100, 100);
+
+  // nothing is painted at the size of the node
+  assert!(
+    render(
+      viewport,
+      &context,
+      create_wide_node(StyleBuilder::default())
+    )
+    .is_ok()
+  );
+
+  let gradient = || -> Option<_> {
+    Some(
+      BackgroundImagesValue::Css("linear-gradient(90deg, red, blue)".to_string())
+        .try_into()
+        .unwrap(),
+    )
+  };
+
+  let styles = [
+    StyleBuilder::default().background_image(gradient()).clone(),
+    StyleBuilder::default().mask_image(gradient()).clone(),
+    StyleBuilder::default().border_image(gradient()).clone(),
+  ];
+
+  for style in styles {
+    assert!(matches!(
+      render(viewport, &context, create_wide_node(style)),
+      Err(Error::CanvasTooLarge {
+        width: 1_000_000,
+        height: 50,
+        ..
+      })
+    ));
+  }
+
+  // the limit applies to the scaled size
+  let context = GlobalContext {
+    max_canvas_pixels: 200 * 200,
+    ..Default::default()
+  };
+  let viewport = Viewport::new(100, 100).with_scale_factor(2.0);
+  let style = StyleBuilder::default().background_image(gradient()).clone();
+
+  assert!(matches!(
+    render(viewport, &context, create_wide_node(style)),
+    Err(Error::CanvasTooLarge {
+      width: 2_000_000,
+      height: 100,
+      ..
+    })
+  ));
+}
+
+#[test]
+fn test_measure_root_content_height() {
+  let context = GlobalContext::default();
+
+  let child = |height: f32| -> NodeKind {
+    ContainerNode {
+      style: StyleBuilder::default().height(Px(height)).build().unwrap(),
+      children: None,
+    }
+    .into()
+  };
+
+  let node: NodeKind = ContainerNode {
+    style: StyleBuilder::default()
+      .width(Px(600.0))
+      .flex_direction(FlexDirection::Column)
+      .build()
+      .unwrap(),
+    children: Some(vec![child(120.0), child(80.0)]),
+  }
+  .into();
+
+  let size = measure_root(Viewport::new(600, 10), &context, node).unwrap();
+
+  assert_eq!(
+    size,
+    Size {
+      width: 600.0,
+      height: 200.0
+    }
+  );
+}
+
+#[test]
+fn test_text_flex_item_shrinks_and_wraps() {
+  let context = GlobalContext::default();
+
+  context
+    .font_context
+    .load_and_store(
+      include_bytes!("../../assets/fonts/noto-sans/NotoSans-Regular.ttf"),
+      None,
+      None,
+    )
+    .unwrap();
+
+  let node: NodeKind = ContainerNode {
+    style: StyleBuilder::default().width(Px(200.0)).build().unwrap(),
+    children: Some(vec![
+      ContainerNode {
+        style: StyleBuilder::default()
+          .width(Px(100.0))
+          .flex_shrink(Some(0.0))
+          .build()
+          .unwrap(),
+        children: None,
+      }
+      .into(),
+      TextNode {
+        style: StyleBuilder::default().build().unwrap(),
+        text: "a long sentence that has to wrap inside the row".to_string(),
+      }
+      .into(),
+    ]),
+  }
+  .into();
+
+  let tree = compute_layout_tree(Viewport::new(200, 200), &context, node);
+  let text = tree.get(&[1]).unwrap();
+
+  // the automatic minimum size of text is its longest word, so the item shrinks into the
+  // remaining space and wraps instead of overflowing the row
+  assert_eq!(text.size.width, 100.0);
+  assert!(text.size.height > 40.0);
+}
+
+#[test]
+fn test_render_auto_height() {
+  let context = GlobalContext::default();
+
+  let node: NodeKind = ContainerNode {
+    style: StyleBuilder::default()
+      .width(Px(300.0))
+      .flex_direction(FlexDirection::Column)
+      .build()
+      .unwrap(),
+    children: Some(vec![
+      ContainerNode {
+        style: StyleBuilder::default().height(Px(150.5)).build().unwrap(),
+        children: None,
+      }
+      .into(),
+    ]),
+  }
+  .into();
+
+  let image = render(Viewport::auto_height(300), &context, node).unwrap();
+
+  assert_eq!(image.dimensions(), (300, 151));
+}
+
+fn z_index_box(color: Color, position: Position, z_index: Option<i32>) -> NodeKind {
+  ContainerNode {
+    style: StyleBuilder::default()
+      .width(Px(100.0))
+      .height(Px(100.0))
+      .position(position)
+      .z_index(z_index)
+      .background_color(color)
+      .build()
+      .unwrap(),
+    children: None,
+  }
+  .into()
+}
+
+#[test]
+fn test_render_auto_height_resolves_vh_to_zero() {
+  let context = GlobalContext::default();
+
+  let node: NodeKind = ContainerNode {
+    style: StyleBuilder::default()
+      .flex_direction(FlexDirection::Column)
+      .build()
+      .unwrap(),
+    children: Some(vec![
+      ContainerNode {
+        style: StyleBuilder::default()
+          .height(LengthUnit::Vh(50.0))
+          .build()
+          .unwrap(),
+        children: None,
+      }
+      .into(),
+      ContainerNode {
+        style: StyleBuilder::default().height(Px(40.0)).build().unwrap(),
+        children: None,
+      }
+      .into(),
+    ]),
+  }
+  .into();
+
+  let tree = compute_layout_tree(Viewport::auto_height(300), &context, node.clone());
+
+  assert_eq!(tree.get(&[0]).unwrap().size.height, 0.0);
+  assert_eq!(tree.get(&[]).unwrap().size.height, 40.0);
+
+  // with a fixed height it's relative to the viewport as usual
+  let tree = compute_layout_tree(Viewport::new(300, 200), &context, node);
+
+  assert_eq!(tree.get(&[0]).unwrap().size.height, 100.0);
+}
+
+#[test]
+fn test_render_visibility_hidden_keeps_layout_space() {
+  let context = GlobalContext::default();
+
+  let node: NodeKind = ContainerNode {
+    style: StyleBuilder::default()
+      .width(Px(300.0))
+      .height(Px(100.0))
+      .build()
+      .unwrap(),
+    children: Some(vec![
+      ContainerNode {
+        style: StyleBuilder::default()
+          .width(Px(200.0))
+          .height(Px(100.0))
+          .visibility(Visibility::Hidden)
+          .background_color(Color([255, 0, 0, 255]))
+          .build()
+          .unwrap(),
+        children: Some(vec![
+          z_index_box(Color([0, 255, 0, 255]), Position::Relative, None),
+          ContainerNode {
+            style: StyleBuilder::default()
+              .width(Px(100.0))
+              .height(Px(100.0))
+              .visibility(Visibility::Visible)
+              .background_color(Color([0, 0, 255, 255]))
+              .build()
+              .unwrap(),
+            children: None,
+          }
+          .into(),
+        ]),
+      }
+      .into(),
+      z_index_box(Color([0, 0, 0, 255]), Position::Relative, None),
+    ]),
+  }
+  .into();
+
+  let image = render(Viewport::new(300, 100), &context, node).unwrap();
+
+  // the hidden child inherits hidden, the visible one paints inside its hidden parent
+  assert_eq!(image.get_pixel(50, 50).0, [0, 0, 0, 0]);
+  assert_eq!(image.get_pixel(150, 50).0, [0, 0, 255, 255]);
+  // the sibling is still laid out after the hidden node
+  assert_eq!(image.get_pixel(250, 50).0, [0, 0, 0, 255]);
+}
+
+#[test]
+fn test_render_with_stats() {
+  let context = GlobalContext::default();
+
+  context
+    .font_context
+    .load_and_store(
+      include_bytes!("../../assets/fonts/noto-sans/NotoSans-Regular.ttf"),
+      None,
+      None,
+    )
+    .unwrap();
+
+  let node = || -> NodeKind {
+    ContainerNode {
+      style: StyleBuilder::default().width(Px(200.0)).build().unwrap(),
+      children: Some(vec![
+        TextNode {
+          style: Default::default(),
+          text: "Stats".to_string(),
+        }
+        .into(),
+      ]),
+    }
+    .into()
+  };
+
+  let (image, stats) = render_with_stats(Viewport::new(200, 100), &context, node()).unwrap();
+
+  assert_eq!(
+    image,
+    render(Viewport::new(200, 100), &context, node()).unwrap()
+  );
+  assert_eq!(stats.node_count, 2);
+  assert_eq!(stats.encode, Duration::ZERO);
+  assert!(stats.glyph_cache_hits + stats.glyph_cache_misses > 0);
+
+  // the glyphs are cached by the earlier renders
+  let (_, stats) = render_with_stats(Viewport::new(200, 100), &context, node()).unwrap();
+
+  assert_eq!(stats.glyph_cache_misses, 0);
+  assert!(stats.glyph_cache_hits > 0);
+}
+
+#[test]
+fn test_render_missing_glyphs() {
+  let render_text = |missing_glyph: MissingGlyph, text: &str| {
+    let context = GlobalContext {
+      missing_glyph,
+      ..Default::default()
+    };
+
+    context
+      .font_context
+      .load_and_store(
+        include_bytes!("../../assets/fonts/noto-sans/NotoSans-Regular.ttf"),
+        None,
+        None,
+      )
+      .unwrap();
+
+    let node: NodeKind = TextNode {
+      style: StyleBuilder::default().build().unwrap(),
+      text: text.to_string(),
+    }
+    .into();
+
+    let image = render(Viewport::new(100, 40), &context, node).unwrap();
+
+    (image, context.font_context.take_missing_codepoints())
+  };
+
+  let is_blank = |image: &RgbaImage| image.pixels().all(|pixel| pixel[3] == 0);
+
+  // the latin font has no CJK glyphs, so the character is recorded whatever is drawn
+  let (hidden, missing) = render_text(MissingGlyph::Hidden, "a\n一");
+  assert_eq!(missing, vec!['一']);
+
+  let (latin, missing) = render_text(MissingGlyph::Hidden, "a");
+  assert!(missing.is_empty());
+  assert_eq!(hidden, latin);
+
+  let (boxed, _) = render_text(MissingGlyph::Box, "一");
+  assert!(!is_blank(&boxed));
+
+  let (replaced, _) = render_text(MissingGlyph::Replacement('?'), "一");
+  let (question_mark, _) = render_text(MissingGlyph::Notdef, "?");
+  assert_eq!(replaced, question_mark);
+}
+
+#[test]
+fn test_render_debug_spacing_overlay() {
+  let context = GlobalContext {
+    debug: Some(DebugOptions {
+      spacing_overlay: true,
+      ..Default::default()
+    }),
+    ..Default::default()
+  };
+
+  let node: NodeKind = ContainerNode {
+    style: StyleBuilder::default()
+      .width(Px(100.0))
+      .height(Px(100.0))
+      .build()
+      .unwrap(),
+    children: Some(vec![
+      ContainerNode {
+        style: StyleBuilder::default()
+          .width(Px(60.0))
+          .height(Px(60.0))
+          .margin(Sides([Px(10.0); 4]))
+          .padding(Sides([Px(10.0); 4]))
+          .build()
+          .unwrap(),
+        children: None,
+      }
+      .into(),
+    ]),
+  }
+  .into();
+
+  let image = render(Viewport::new(100, 100), &context, node).unwrap();
+
+  let dominant_channel = |x, y| {
+    let [r, g, b, _] = image.get_pixel(x, y).0;
+    [r, g, b]
+      .into_iter()
+      .enumerate()
+      .max_by_key(|(_, value)| *value)
+      .unwrap()
+      .0
+  };
+
+  // margin is orange, padding is green and content is blue
+  assert_eq!(dominant_channel(5, 40), 0);
+  assert_eq!(dominant_channel(15, 40), 1);
+  assert_eq!(dominant_channel(40, 40), 2);
+
+  // the border box stroke is drawn over the overlays
+  assert_eq!(image.get_pixel(10, 40).0, [0, 255, 0, 255]);
+}
+
+#[test]
+fn test_render_display_none_takes_no_space() {
+  let context = GlobalContext::default();
+
+  let grow = |color: Color, display: Display| -> NodeKind {
+    ContainerNode {
+      style: StyleBuilder::default()
+        .display(display)
+        .flex_grow(Some(1.0))
+        .width(Px(100.0))
+        .background_color(color)
+        .build()
+        .unwrap(),
+      children: None,
+    }
+    .into()
+  };
+
+  let node: NodeKind = ContainerNode {
+    style: StyleBuilder::default()
+      .width(Px(300.0))
+      .height(Px(100.0))
+      .build()
+      .unwrap(),
+    children: Some(vec![
+      grow(Color([255, 0, 0, 255]), Display::Flex),
+      grow(Color([0, 255, 0, 255]), Display::None),
+      grow(Color([0, 0, 255, 255]), Display::Flex),
+    ]),
+  }
+  .into();
+
+  let image = render(Viewport::new(300, 100), &context, node).unwrap();
+
+  // the remaining children split the free space as if the hidden one didn't exist
+  assert_eq!(image.get_pixel(140, 50).0, [255, 0, 0, 255]);
+  assert_eq!(image.get_pixel(160, 50).0, [0, 0, 255, 255]);
+}
+
+#[test]
+fn test_render_drop_shadow_follows_content() {
+  let context = GlobalContext::default();
+
+  let node: NodeKind = ContainerNode {
+    style: StyleBuilder::default()
+      .width(Px(100.0))
+      .height(Px(100.0))
+      .filter(Some(
+        Filters::try_from(FiltersValue::Css(
+          "drop-shadow(10px 10px rgb(0 0 255))".to_string(),
+        ))
+        .unwrap(),
+      ))
+      .build()
+      .unwrap(),
+    children: Some(vec![
+      ContainerNode {
+        style: StyleBuilder::default()
+          .width(Px(20.0))
+          .height(Px(20.0))
+          .background_color(Color([255, 0, 0, 255]))
+          .build()
+          .unwrap(),
+        children: None,
+      }
+      .into(),
+    ]),
+  }
+  .into();
+
+  let image = render(Viewport::new(100, 100), &context, node).unwrap();
+
+  // the shadow is cast by the painted child, not the box of the filtered node
+  assert_eq!(image.get_pixel(10, 10).0, [255, 0, 0, 255]);
+  assert_eq!(image.get_pixel(25, 25).0, [0, 0, 255, 255]);
+  assert_eq!(image.get_pixel(60, 60).0[3], 0);
+}
+
+#[test]
+fn test_render_skips_offscreen_nodes() {
+  let context = GlobalContext::default();
+
+  context
+    .font_context
+    .load_and_store(
+      include_bytes!("../../assets/fonts/noto-sans/NotoSans-Regular.ttf"),
+      None,
+      None,
+    )
+    .unwrap();
+
+  let offscreen_box = |top: f32, left: f32, mut style: StyleBuilder| -> NodeKind {
+    ContainerNode {
+      style: style
+        .position(Position::Absolute)
+        .inset(Sides([
+          Px(top),
+          LengthUnit::Auto,
+          LengthUnit::Auto,
+          Px(left),
+        ]))
+        .width(Px(40.0))
+        .height(Px(20.0))
+        .build()
+        .unwrap(),
+      children: None,
+    }
+    .into()
+  };
+
+  let mut shadow_style = StyleBuilder::default();
+  shadow_style.box_shadow(Some(BoxShadows(smallvec![BoxShadow {
+    inset: false,
+    offset_x: Px(60.0),
+    offset_y: Px(0.0),
+    blur_radius: Px(0.0),
+    spread_radius: Px(0.0),
+    color: Color([255, 0, 0, 255]),
+  }])));
+
+  let mut outline_style = StyleBuilder::default();
+  outline_style.outline(Some(Outline {
+    width: Px(6.0),
+    style: OutlineStyle::Solid,
+    color: Some(Color([0, 0, 255, 255]).into()),
+  }));
+
+  let node: NodeKind = ContainerNode {
+    style: StyleBuilder::default()
+      .width(Px(100.0))
+      .height(Px(100.0))
+      .build()
+      .unwrap(),
+    children: Some(vec![
+      TextNode {
+        style: StyleBuilder::default()
+          .position(Position::Absolute)
+          .inset(Sides([
+            Px(200.0),
+            LengthUnit::Auto,
+            LengthUnit::Auto,
+            Px(0.0),
+          ]))
+          .build()
+          .unwrap(),
+        text: "Offscreen".to_string(),
+      }
+      .into(),
+      offscreen_box(10.0, -50.0, shadow_style),
+      offscreen_box(60.0, -42.0, outline_style),
+    ]),
+  }
+  .into();
+
+  let (image, stats) = render_with_stats(Viewport::new(100, 100), &context, node).unwrap();
+
+  // the text below the canvas isn't drawn, it's still laid out
+  assert_eq!(stats.glyph_cache_hits + stats.glyph_cache_misses, 0);
+  assert_eq!(stats.node_count, 4);
+
+  // shadows and outlines reaching into the canvas are drawn even if the box isn't in it
+  assert_eq!(image.get_pixel(20, 20).0, [255, 0, 0, 255]);
+  assert_eq!(image.get_pixel(1, 70).0, [0, 0, 255, 255]);
+}
+
+#[test]
+fn test_render_overflow_hidden_clips_descendants() {
+  let context = GlobalContext::default();
+
+  let node: NodeKind = ContainerNode {
+    style: StyleBuilder::default()
+      .width(Px(100.0))
+      .height(Px(100.0))
+      .build()
+      .unwrap(),
+    children: Some(vec![
+      ContainerNode {
+        style: StyleBuilder::default()
+          .width(Px(50.0))
+          .height(Px(50.0))
+          .flex_shrink(Some(0.0))
+          .overflow(Overflow::Hidden)
+          .build()
+          .unwrap(),
+        children: Some(vec![
+          // lifted by its z-index, still clipped by the parent
+          z_index_box(Color([255, 0, 0, 255]), Position::Relative, Some(10)),
+        ]),
+      }
+      .into(),
+    ]),
+  }
+  .into();
+
+  let image = render(Viewport::new(100, 100), &context, node).unwrap();
+
+  assert_eq!(image.get_pixel(25, 25).0, [255, 0, 0, 255]);
+  assert_eq!(image.get_pixel(75, 25).0[3], 0);
+  assert_eq!(image.get_pixel(25, 75).0[3], 0);
+}
+
+#[test]
+fn test_render_z_index_lifts_absolute_overlay() {
+  let context = GlobalContext::default();
+
+  let node: NodeKind = ContainerNode {
+    style: StyleBuilder::default()
+      .width(Px(100.0))
+      .height(Px(100.0))
+      .build()
+      .unwrap(),
+    children: Some(vec![
+      z_index_box(Color([255, 0, 0, 255]), Position::Absolute, Some(10)),
+      z_index_box(Color([0, 0, 255, 255]), Position::Relative, None),
+    ]),
+  }
+  .into();
+
+  let image = render(Viewport::new(100, 100), &context, node).unwrap();
+
+  assert_eq!(image.get_pixel(50, 50).0, [255, 0, 0, 255]);
+}
+
+#[test]
+fn test_render_z_index_escapes_non_stacking_parent() {
+  let context = GlobalContext::default();
+
+  // the parent has no z-index, so its child is stacked against the parent's later sibling
+  let node: NodeKind = ContainerNode {
+    style: StyleBuilder::default()
+      .width(Px(100.0))
+      .height(Px(100.0))
+      .build()
+      .unwrap(),
+    children: Some(vec![
+      ContainerNode {
+        style: StyleBuilder::default()
+          .position(Position::Absolute)
+          .build()
+          .unwrap(),
+        children: Some(vec![z_index_box(
+          Color([255, 0, 0, 255]),
+          Position::Relative,
+          Some(1),
+        )]),
+      }
+      .into(),
+      z_index_box(Color([0, 0, 255, 255]), Position::Absolute, None),
+      z_index_box(Color([0, 255, 0, 255]), Position::Absolute, Some(-1)),
+    ]),
+  }
+  .into();
+
+  let image = render(Viewport::new(100, 100), &context, node).unwrap();
+
+  assert_eq!(image.get_pixel(50, 50).0, [255, 0, 0, 255]);
+}
+
+#[test]
+fn test_render_batch_keeps_order() {
+  let context = GlobalContext::default();
+
+  let node = |color: Color| -> NodeKind {
+    ContainerNode {
+      style: StyleBuilder::default()
+        .width(Px(20.0))
+        .height(Px(10.0))
+        .background_color(color)
+        .build()
+        .unwrap(),
+      children: None,
+    }
+    .into()
+  };
+
+  let colors = [
+    Color([255, 0, 0, 255]),
+    Color([0, 255, 0, 255]),
+    Color([0, 0, 255, 255]),
+  ];
+
+  let images = render_batch(
+    Viewport::new(20, 10),
+    &context,
+    colors.iter().copied().map(node).collect(),
+  );
+
+  assert_eq!(images.len(), colors.len());
+
+  for (image, color) in images.into_iter().zip(colors) {
+    assert_eq!(image.unwrap().get_pixel(10, 5).0, color.0);
+  }
+
+  let encoded = render_batch_encoded(
+    Viewport::new(20, 10),
+    &context,
+    vec![node(colors[0])],
+    ImageOutputFormat::Png,
+    None,
+  );
+
+  let decoded = image::load_from_memory(encoded[0].as_ref().unwrap()).unwrap();
+  assert_eq!(decoded.to_rgba8().get_pixel(0, 0).0, colors[0].0);
+}
+
+#[test]
+fn test_render_into_reuses_image() {
+  let context = GlobalContext::default();
+
+  let node = |width: f32| -> NodeKind {
+    ContainerNode {
+      style: StyleBuilder::default()
+        .width(Px(width))
+        .height(Px(10.0))
+        .background_color(Color([255, 0, 0, 255]))
+        .build()
+        .unwrap(),
+      children: None,
+    }
+    .into()
+  };
+
+  let mut image = RgbaImage::new(20, 10);
+
+  render_into(&mut image, Viewport::new(20, 10), &context, node(20.0)).unwrap();
+  assert_eq!(image.get_pixel(15, 5).0, [255, 0, 0, 255]);
+
+  // pixels of the previous render are cleared
+  render_into(&mut image, Viewport::new(20, 10), &context, node(10.0)).unwrap();
+  assert_eq!(image.get_pixel(5, 5).0, [255, 0, 0, 255]);
+  assert_eq!(image.get_pixel(15, 5).0, [0, 0, 0, 0]);
+
+  assert!(matches!(
+    render_into(&mut image, Viewport::new(30, 10), &context, node(10.0)),
+    Err(Error::CanvasSizeMismatch {
+      expected: (30, 10),
+      actual: (20, 10),
+    })
+  ));
+  assert_eq!(image.dimensions(), (20, 10));
+  assert_eq!(image.get_pixel(5, 5).0, [255, 0, 0, 255]);
+}
+
+#[test]
+fn test_render_scaled_shares_layout() {
+  let context = GlobalContext::default();
+
+  let square = |color: Color, transform: Option<Transforms>| -> NodeKind {
+    ContainerNode {
+      style: StyleBuilder::default()
+        .width(Px(10.0))
+        .height(Px(10.0))
+        .background_color(color)
+        .transform(transform)
+        .build()
+        .unwrap(),
+      children: None,
+    }
+    .into()
+  };
+
+  let node = || -> NodeKind {
+    ContainerNode {
+      style: StyleBuilder::default()
+        .width(Px(40.0))
+        .height(Px(20.0))
+        .build()
+        .unwrap(),
+      children: Some(vec![
+        square(Color([255, 0, 0, 255]), None),
+        square(
+          Color([0, 0, 255, 255]),
+          Some(Transforms(smallvec![Transform::Translate(
+            Px(10.0),
+            Px(5.0)
+          )])),
+        ),
+      ]),
+    }
+    .into()
+  };
+
+  let images = render_scaled(Viewport::new(40, 20), &context, node(), &[1.0, 2.0, 1.5]).unwrap();
+
+  assert_eq!(
+    images.iter().map(RgbaImage::dimensions).collect::<Vec<_>>(),
+    [(40, 20), (80, 40), (60, 30)]
+  );
+  assert_eq!(
+    images[0],
+    render(Viewport::new(40, 20), &context, node()).unwrap()
+  );
+
+  // the positions follow the scale, including the transformed square
+  let scaled = &images[1];
+  assert_eq!(scaled.get_pixel(10, 10).0, [255, 0, 0, 255]);
+  assert_eq!(scaled.get_pixel(30, 20).0, [0, 0, 0, 0]);
+  assert_eq!(scaled.get_pixel(50, 20).0, [0, 0, 255, 255]);
+  assert_eq!(scaled.get_pixel(50, 5).0, [0, 0, 0, 0]);
+
+  assert!(matches!(
+    render_scaled(Viewport::new(40, 20), &context, node(), &[2.0, 0.0]),
+    Err(Error::InvalidScale(0.0))
+  ));
+}
+
+#[test]
+fn test_render_viewport_scale_factor() {
+  let context = GlobalContext::default();
+
+  // the shadow is drawn in image pixels, its offset follows the scale like the layout
+  let node = || -> NodeKind {
+    ContainerNode {
+      style: StyleBuilder::default()
+        .width(Px(40.0))
+        .height(Px(20.0))
+        .build()
+        .unwrap(),
+      children: Some(vec![
+        ContainerNode {
+          style: StyleBuilder::default()
+            .width(Px(10.0))
+            .height(Px(10.0))
+            .background_color(Color([255, 0, 0, 255]))
+            .box_shadow(Some(BoxShadows(smallvec![BoxShadow {
+              inset: false,
+              offset_x: Px(10.0),
+              offset_y: Px(0.0),
+              blur_radius: Px(0.0),
+              spread_radius: Px(0.0),
+              color: Color([0, 0, 255, 255]),
+            }])))
+            .build()
+            .unwrap(),
+          children: None,
+        }
+        .into(),
+      ]),
+    }
+    .into()
+  };
+
+  let viewport = Viewport::new(40, 20).with_scale_factor(2.0);
+  let image = render(viewport, &context, node()).unwrap();
+
+  assert_eq!(image.dimensions(), (80, 40));
+  assert_eq!(
+    image,
+    render_scaled(Viewport::new(40, 20), &context, node(), &[2.0]).unwrap()[0]
+  );
+  assert_eq!(image.get_pixel(10, 10).0, [255, 0, 0, 255]);
+  assert_eq!(image.get_pixel(30, 10).0, [0, 0, 255, 255]);
+  assert_eq!(image.get_pixel(50, 10).0, [0, 0, 0, 0]);
+
+  let image = render(
+    Viewport::auto_height(40).with_scale_factor(1.5),
+    &context,
+    node(),
+  )
+  .unwrap();
+  assert_eq!(image.dimensions(), (60, 30));
+
+  assert!(matches!(
+    render(viewport.with_scale_factor(f32::NAN), &context, node()),
+    Err(Error::InvalidScale(scale)) if scale.is_nan()
+  ));
+}
+
+/// Fails every fetch, counting the attempts.
+#[derive(Default)]
+struct UnreachableImageStore(Arc<AtomicU32>);
+
+impl ImageStore for UnreachableImageStore {
+  fn get(&self, _src: &str) -> Option<Arc<ImageSource>> {
+    None
+  }
+
+  fn insert(&self, _src: &str, _image: Arc<ImageSource>) {}
+
+  fn clear(&self) {}
+
+  fn fetch(&self, _src: &str) -> ImageResult {
+    self.0.fetch_add(1, Ordering::Relaxed);
+
+    Err(ImageResourceError::NotFound)
+  }
+}
+
+#[test]
+fn test_render_surfaces_failed_image_fetch() {
+  let context = GlobalContext {
+    persistent_image_store: Box::new(RetryImageStore::new(
+      UnreachableImageStore::default(),
+      1,
+      Duration::ZERO,
+    )),
+    ..Default::default()
+  };
+
+  let node: NodeKind = ImageNode {
+    style: StyleBuilder::default().build().unwrap(),
+    src: "https://example.com/broken.png".to_string(),
+    width: Some(10.0),
+    height: Some(10.0),
+    frame_index: None,
+  }
+  .into();
+
+  assert!(matches!(
+    render(Viewport::new(10, 10), &context, node),
+    Err(Error::ImageResolveError(ImageResourceError::FetchFailed {
+      attempts: 2,
+      ..
+    }))
+  ));
+}
+
+#[test]
+fn test_render_retries_failed_image_fetch_once() {
+  let attempts = Arc::new(AtomicU32::new(0));
+
+  let context = GlobalContext {
+    persistent_image_store: Box::new(RetryImageStore::new(
+      UnreachableImageStore(attempts.clone()),
+      2,
+      Duration::ZERO,
+    )),
+    ..Default::default()
+  };
+
+  // without a size the image is resolved to measure it, then again to check it
+  let node = || -> NodeKind {
+    ImageNode {
+      style: StyleBuilder::default().build().unwrap(),
+      src: "https://example.com/broken.png".to_string(),
+      width: None,
+      height: None,
+      frame_index: None,
+    }
+    .into()
+  };
+
+  for _ in 0..2 {
+    assert!(render(Viewport::new(10, 10), &context, node()).is_err());
+  }
+
+  assert_eq!(attempts.load(Ordering::Relaxed), 3);
+}
+
+#[test]
+fn test_aspect_ratio_box_is_covered_by_image() {
+  let context = GlobalContext::default();
+
+  let color = image::Rgba([0, 255, 0, 255]);
+
+  for (src, width, height) in [("portrait", 90, 160), ("landscape", 400, 100)] {
+    context.persistent_image_store.insert(
+      src,
+      Arc::new(ImageSource::Bitmap(RgbaImage::from_pixel(
+        width, height, color,
+      ))),
+    );
+
+    let node: NodeKind = ContainerNode {
+      style: StyleBuilder::default()
+        .width(Px(320.0))
+        .aspect_ratio(Some(16.0 / 9.0))
+        .build()
+        .unwrap(),
+      children: Some(vec![
+        ImageNode {
+          style: StyleBuilder::default()
+            .width(LengthUnit::Percentage(100.0))
+            .height(LengthUnit::Percentage(100.0))
+            .object_fit(ObjectFit::Cover)
+            .build()
+            .unwrap(),
+          src: src.to_string(),
+          width: None,
+          height: None,
+          frame_index: None,
+        }
+        .into(),
+      ]),
+    }
+    .into();
+
+    let image = render(Viewport::new(320, 240), &context, node).unwrap();
+
+    // the image covers the whole 320x180 box and nothing below it
+    for (x, y) in [(0, 0), (319, 0), (0, 179), (319, 179), (160, 90)] {
+      assert_eq!(*image.get_pixel(x, y), color, "{src} at {x},{y}");
+    }
+    assert_eq!(image.get_pixel(160, 200)[3], 0, "{src} below the box");
+  }
+}
+
+#[test]
+fn test_clip_path_circle_clips_node_and_children() {
+  let context = GlobalContext::default();
+
+  let node: NodeKind = ContainerNode {
+    style: StyleBuilder::default()
+      .width(Px(100.0))
+      .height(Px(100.0))
+      .background_color(Color([255, 0, 0, 255]))
+      .clip_path(Some(
+        ClipPathValue::Css("circle()".to_string())
+          .try_into()
+          .unwrap(),
+      ))
+      .build()
+      .unwrap(),
+    children: Some(vec![
+      ContainerNode {
+        style: StyleBuilder::default()
+          .width(Px(100.0))
+          .height(Px(50.0))
+          .background_color(Color([0, 0, 255, 255]))
+          .build()
+          .unwrap(),
+        children: None,
+      }
+      .into(),
+    ]),
+  }
+  .into();
+
+  let image = render(Viewport::new(100, 100), &context, node).unwrap();
+
+  assert_eq!(*image.get_pixel(50, 75), image::Rgba([255, 0, 0, 255]));
+  assert_eq!(*image.get_pixel(50, 25), image::Rgba([0, 0, 255, 255]));
+
+  // the child is clipped by the circle of its parent too
+  assert_eq!(image.get_pixel(2, 2)[3], 0);
+  assert_eq!(image.get_pixel(97, 97)[3], 0);
+
+  // the edge is anti-aliased instead of stepping between in and out
+  let edge = (0..100)
+    .map(|x| image.get_pixel(x, 85)[3])
+    .filter(|alpha| *alpha > 0 && *alpha < 255)
+    .count();
+
+  assert!(edge >= 2, "expected partially covered pixels on the edge");
+}
+
+#[test]
+fn test_render_vertical_rl_text() {
+  let context = GlobalContext::default();
+
+  context
+    .font_context
+    .load_and_store(
+      include_bytes!("../../assets/fonts/noto-sans/NotoSansTC-Bold.woff"),
+      None,
+      None,
+    )
+    .unwrap();
+
+  let node: NodeKind = ContainerNode {
+    style: StyleBuilder::default()
+      .width(Px(200.0))
+      .height(Px(200.0))
+      .font_size(Px(40.0))
+      .line_height(LineHeight::Number(1.0))
+      .writing_mode(WritingMode::VerticalRl)
+      .build()
+      .unwrap(),
+    children: Some(vec![
+      TextNode {
+        style: Default::default(),
+        text: "一一".to_string(),
+      }
+      .into(),
+    ]),
+  }
+  .into();
+
+  let image = render(Viewport::new(200, 200), &context, node).unwrap();
+
+  let painted_bounds = |top: u32, bottom: u32| {
+    let painted = image
+      .enumerate_pixels()
+      .filter(|(_, y, pixel)| (top..bottom).contains(y) && pixel.0[3] > 0);
+
+    painted.fold(
+      (u32::MAX, u32::MAX, 0, 0),
+      |(left, top, right, bottom), (x, y, _)| {
+        (left.min(x), top.min(y), right.max(x), bottom.max(y))
+      },
+    )
+  };
+
+  // the characters run down a single column, measured with the axes swapped
+  let (left, _, right, bottom) = painted_bounds(0, 200);
+  assert!(right < 40, "right = {right}");
+  assert!((40..80).contains(&bottom), "bottom = {bottom}");
+  assert!(left < right);
+
+  // the CJK characters stay upright, so the stroke of the first one lies horizontal
+  let (left, top, right, bottom) = painted_bounds(0, 40);
+  assert!(right - left > (bottom - top) * 4);
+}
+
+#[test]
+fn test_render_glyphs_at_subpixel_offsets() {
+  let context = GlobalContext::default();
+
+  context
+    .font_context
+    .load_and_store(
+      include_bytes!("../../assets/fonts/noto-sans/NotoSans-Regular.ttf"),
+      None,
+      None,
+    )
+    .unwrap();
+
+  // the indent moves the glyph origin without moving the node
+  let render_indented = |text_indent: f32| {
+    let node: NodeKind = TextNode {
+      style: StyleBuilder::default()
+        .font_size(Px(11.0))
+        .text_indent(Some(Px(text_indent)))
+        .build()
+        .unwrap(),
+      text: "l".to_string(),
+    }
+    .into();
+
+    render(Viewport::new(20, 20), &context, node).unwrap()
+  };
+
+  let whole = render_indented(2.0);
+  let half = render_indented(2.5);
+  let next = render_indented(3.0);
+
+  // a whole pixel shifts the same coverage over by one column
+  for (x, y, pixel) in whole.enumerate_pixels() {
+    if x + 1 < next.width() {
+      assert_eq!(pixel, next.get_pixel(x + 1, y));
+    }
+  }
+
+  // half a pixel blends the coverage between the columns instead of snapping to either
+  assert_ne!(half, whole);
+  assert_ne!(half, next);
+}
+
+#[test]
+fn test_render_fixed_position_anchors_to_viewport() {
+  let context = GlobalContext::default();
+
+  let fixed_box = |inset: [LengthUnit; 4], width: LengthUnit, color: Color| -> NodeKind {
+    ContainerNode {
+      style: StyleBuilder::default()
+        .position(Position::Fixed)
+        .inset(Sides(inset))
+        .width(width)
+        .height(Px(10.0))
+        .background_color(color)
+        .build()
+        .unwrap(),
+      children: None,
+    }
+    .into()
+  };
+
+  // nested in an offset box, which an absolute child would be positioned in
+  let node: NodeKind = ContainerNode {
+    style: StyleBuilder::default()
+      .width(Px(100.0))
+      .height(Px(100.0))
+      .padding(Sides([Px(20.0); 4]))
+      .build()
+      .unwrap(),
+    children: Some(vec![
+      ContainerNode {
+        style: StyleBuilder::default()
+          .width(Px(40.0))
+          .height(Px(40.0))
+          .margin(Sides([Px(10.0); 4]))
+          .build()
+          .unwrap(),
+        children: Some(vec![
+          // a corner badge
+          fixed_box(
+            [LengthUnit::Auto, Px(5.0), Px(5.0), LengthUnit::Auto],
+            Px(10.0),
+            Color([255, 0, 0, 255]),
+          ),
+          // a bar stretched across the viewport
+          fixed_box(
+            [Px(0.0), Px(0.0), LengthUnit::Auto, Px(0.0)],
+            LengthUnit::Auto,
+            Color([0, 0, 255, 255]),
+          ),
+          // a percentage of the viewport, not of the parent
+          fixed_box(
+            [
+              Percentage(50.0),
+              LengthUnit::Auto,
+              LengthUnit::Auto,
+              Px(0.0),
+            ],
+            Percentage(20.0),
+            Color([0, 255, 0, 255]),
+          ),
+        ]),
+      }
+      .into(),
+    ]),
+  }
+  .into();
+
+  let tree = compute_layout_tree(Viewport::new(100, 100), &context, node.clone());
+
+  let badge = tree.get(&[0, 0]).unwrap();
+  assert_eq!(badge.location, Point { x: 85.0, y: 85.0 });
+
+  let bar = tree.get(&[0, 1]).unwrap();
+  assert_eq!(bar.location, Point { x: 0.0, y: 0.0 });
+  assert_eq!(bar.size.width, 100.0);
+
+  let scaled = tree.get(&[0, 2]).unwrap();
+  assert_eq!(scaled.location, Point { x: 0.0, y: 50.0 });
+  assert_eq!(scaled.size.width, 20.0);
+
+  let image = render(Viewport::new(100, 100), &context, node).unwrap();
+
+  assert_eq!(image.get_pixel(90, 90).0, [255, 0, 0, 255]);
+  assert_eq!(image.get_pixel(99, 5).0, [0, 0, 255, 255]);
+  assert_eq!(image.get_pixel(10, 55).0, [0, 255, 0, 255]);
+  assert_eq!(image.get_pixel(50, 55).0[3], 0);
+}
+
+#[test]
+fn test_render_text_without_fonts_fails() {
+  let context = GlobalContext::default();
+
+  let text = |text: &str| -> NodeKind {
+    TextNode {
+      style: StyleBuilder::default().build().unwrap(),
+      text: text.to_string(),
+    }
+    .into()
+  };
+
+  let result = render(Viewport::new(100, 100), &context, text("Hello"));
+
+  assert!(matches!(result, Err(Error::FontMissing)));
+  assert_eq!(
+    result.unwrap_err().to_string(),
+    "there is text to draw, but no font is loaded"
+  );
+
+  // nothing to draw, nothing missing
+  assert!(render(Viewport::new(100, 100), &context, text(" ")).is_ok());
+
+  // the defaults are used without a `font-family`
+  let context = GlobalContext {
+    font_context: FontContext::with_embedded_defaults(&[(
+      include_bytes!("../../assets/fonts/geist/Geist[wght].woff2"),
+      "Default Sans",
+      GenericFamily::SansSerif,
+    )])
+    .unwrap(),
+    ..Default::default()
+  };
+
+  let image = render(Viewport::new(100, 100), &context, text("Hello")).unwrap();
+
+  assert!(image.pixels().any(|pixel| pixel.0[3] > 0));
+}
+
+#[test]
+fn test_render_rejects_empty_and_oversized_canvases() {
+  let context = GlobalContext {
+    max_canvas_pixels: 100 * 100,
+    ..Default::default()
+  };
+
+  let node = || -> NodeKind {
+    ContainerNode {
+      style: StyleBuilder::default().build().unwrap(),
+      children: None,
+    }
+    .into()
+  };
+
+  assert!(matches!(
+    render(Viewport::new(0, 100), &context, node()),
+    Err(Error::InvalidViewport {
+      width: 0,
+      height: 100
+    })
+  ));
+
+  // the height follows the empty root
+  assert!(matches!(
+    render(Viewport::auto_height(100), &context, node()),
+    Err(Error::InvalidViewport {
+      width: 100,
+      height: 0
+    })
+  ));
+
+  assert!(render(Viewport::new(100, 100), &context, node()).is_ok());
+
+  let result = render(Viewport::new(200, 100), &context, node());
+
+  assert!(matches!(
+    result,
+    Err(Error::CanvasTooLarge {
+      width: 200,
+      height: 100,
+      max_pixels: 10000
+    })
+  ));
+
+  // a scale that doesn't fit fails before any image is painted
+  assert!(matches!(
+    render_scaled(Viewport::new(100, 100), &context, node(), &[1.0, 2.0]),
+    Err(Error::CanvasTooLarge { .. })
+  ));
+}
@@ -1,12 +1,12 @@
 use std::{path::Path, sync::Arc};
 
-use image::{ColorType::Rgba8, load_from_memory, save_buffer};
-use parley::{GenericFamily, fontique::FontInfoOverride};
+use image::{ColorType::Rgba8, RgbaImage, load_from_memory, save_buffer};
+use parley::GenericFamily;
 use takumi::{
   GlobalContext,
   layout::{Viewport, node::NodeKind},
   rendering::render,
-  resources::image::ImageSource,
+  resources::{font::FontContext, image::ImageSource},
 };
 
 const TEST_FONTS: &[(&[u8], &str, GenericFamily)] = &[
@@ -27,8 +27,11 @@ const TEST_FONTS: &[(&[u8], &str, GenericFamily)] = &[
   ),
 ];
 
-fn create_test_context() -> GlobalContext {
-  let context = GlobalContext::default();
+pub fn create_test_context() -> GlobalContext {
+  let context = GlobalContext {
+    font_context: FontContext::with_embedded_defaults(TEST_FONTS).unwrap(),
+    ..Default::default()
+  };
 
   context.persistent_image_store.insert(
     "assets/images/yeecord.png",
@@ -39,20 +42,6 @@ fn create_test_context() -> GlobalContext {
     )),
   );
 
-  for (font, name, generic) in TEST_FONTS {
-    context
-      .font_context
-      .load_and_store(
-        font,
-        Some(FontInfoOverride {
-          family_name: Some(name),
-          ..Default::default()
-        }),
-        Some(*generic),
-      )
-      .unwrap();
-  }
-
   context
 }
 
@@ -60,8 +49,8 @@ fn create_test_viewport() -> Viewport {
   Viewport::new(1200, 630)
 }
 
-/// Helper function to run style width tests
-pub fn run_style_width_test(node: NodeKind, fixture_path: &str) {
+/// Helper function to run style width tests, returns the rendered image for further assertions
+pub fn run_style_width_test(node: NodeKind, fixture_path: &str) -> RgbaImage {
   let context = create_test_context();
   let viewport = create_test_viewport();
 
@@ -70,4 +59,6 @@ pub fn run_style_width_test(node: NodeKind, fixture_path: &str) {
   let path = Path::new(fixture_path);
 
   save_buffer(path, &image, 1200, 630, Rgba8).expect("Failed to save image");
+
+  image
 }